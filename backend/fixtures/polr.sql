@@ -0,0 +1,66 @@
+-- phpMyAdmin SQL Dump
+-- version 5.2.1
+--
+-- Database: `polr`
+--
+
+SET SQL_MODE = "NO_AUTO_VALUE_ON_ZERO";
+START TRANSACTION;
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `clicks`
+--
+
+CREATE TABLE `clicks` (
+  `id` int(10) UNSIGNED NOT NULL,
+  `ip` varchar(255) NOT NULL,
+  `country` varchar(255) DEFAULT NULL,
+  `referer` varchar(255) DEFAULT NULL,
+  `referer_host` varchar(255) DEFAULT NULL,
+  `user_agent` text DEFAULT NULL,
+  `link_id` int(11) NOT NULL,
+  `created_at` timestamp NULL DEFAULT NULL,
+  `updated_at` timestamp NULL DEFAULT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+--
+-- Dumping data for table `clicks`
+--
+
+INSERT INTO `clicks` (`id`, `ip`, `country`, `referer`, `referer_host`, `user_agent`, `link_id`, `created_at`, `updated_at`) VALUES
+(1, '198.51.100.1', 'DE', 'https://news.example.org/item', 'news.example.org', 'Mozilla/5.0', 1, '2021-06-02 07:00:00', '2021-06-02 07:00:00'),
+(2, '198.51.100.2', NULL, NULL, NULL, 'Mozilla/5.0', 1, '2021-06-03 07:00:00', '2021-06-03 07:00:00');
+
+-- --------------------------------------------------------
+
+--
+-- Table structure for table `links`
+--
+
+CREATE TABLE `links` (
+  `id` int(10) UNSIGNED NOT NULL,
+  `short_url` varchar(255) NOT NULL,
+  `long_url` text NOT NULL,
+  `ip` varchar(255) NOT NULL,
+  `creator` varchar(255) NOT NULL,
+  `clicks` varchar(255) NOT NULL DEFAULT '0',
+  `secret_key` varchar(255) NOT NULL,
+  `is_disabled` tinyint(1) NOT NULL DEFAULT 0,
+  `is_custom` tinyint(1) NOT NULL DEFAULT 0,
+  `is_api` tinyint(1) NOT NULL DEFAULT 0,
+  `created_at` timestamp NULL DEFAULT NULL,
+  `updated_at` timestamp NULL DEFAULT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+--
+-- Dumping data for table `links`
+--
+
+INSERT INTO `links` (`id`, `short_url`, `long_url`, `ip`, `creator`, `clicks`, `secret_key`, `is_disabled`, `is_custom`, `is_api`, `created_at`, `updated_at`) VALUES
+(1, 'polr1', 'https://example.com/polr', '127.0.0.1', 'admin', '2', '', 0, 1, 0, '2021-06-01 12:00:00', '2021-06-01 12:00:00'),
+(2, 'docs', 'https://example.com/other-docs', '127.0.0.1', 'admin', '0', '', 0, 1, 0, '2021-06-01 12:00:00', '2021-06-01 12:00:00'),
+(3, 'nourl', 'example.com', '127.0.0.1', 'admin', '7', '', 0, 1, 0, '2021-06-01 12:00:00', '2021-06-01 12:00:00');
+
+COMMIT;
@@ -0,0 +1,47 @@
+-- MySQL dump 10.13  Distrib 10.11.6-MariaDB, for debian-linux-gnu (x86_64)
+--
+-- Host: localhost    Database: yourls
+-- ------------------------------------------------------
+/*!40101 SET NAMES utf8mb4 */;
+
+--
+-- Table structure for table `yourls_url`
+--
+
+DROP TABLE IF EXISTS `yourls_url`;
+CREATE TABLE `yourls_url` (
+  `keyword` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_bin NOT NULL,
+  `url` text CHARACTER SET utf8mb4 COLLATE utf8mb4_bin NOT NULL,
+  `title` text DEFAULT NULL,
+  `timestamp` timestamp NOT NULL DEFAULT current_timestamp(),
+  `ip` varchar(41) NOT NULL,
+  `clicks` int(10) unsigned NOT NULL,
+  PRIMARY KEY (`keyword`),
+  KEY `ip` (`ip`),
+  KEY `timestamp` (`timestamp`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+LOCK TABLES `yourls_url` WRITE;
+INSERT INTO `yourls_url` VALUES ('docs','https://example.com/docs?a=1;b=2','The docs; \'part\' one','2023-04-01 10:00:00','127.0.0.1',2),('old','https://example.com/old',NULL,'2022-01-01 00:00:00','127.0.0.1',3),('bad code','https://example.com/bad','Bad','2022-01-01 00:00:00','127.0.0.1',0);
+UNLOCK TABLES;
+
+--
+-- Table structure for table `yourls_log`
+--
+
+DROP TABLE IF EXISTS `yourls_log`;
+CREATE TABLE `yourls_log` (
+  `click_id` int(11) NOT NULL AUTO_INCREMENT,
+  `click_time` datetime NOT NULL,
+  `shorturl` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_bin NOT NULL,
+  `referrer` varchar(200) NOT NULL,
+  `user_agent` varchar(255) NOT NULL,
+  `ip_address` varchar(41) NOT NULL,
+  `country_code` char(2) NOT NULL,
+  PRIMARY KEY (`click_id`),
+  KEY `shorturl` (`shorturl`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+LOCK TABLES `yourls_log` WRITE;
+INSERT INTO `yourls_log` VALUES (1,'2023-04-02 08:00:00','docs','https://www.Google.com/search?q=pslink','Mozilla/5.0','203.0.113.5','DE'),(2,'2023-04-03 09:30:00','docs','direct','curl/8.5.0','203.0.113.6','');
+UNLOCK TABLES;
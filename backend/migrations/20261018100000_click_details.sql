@@ -0,0 +1,4 @@
+-- Add migration script here
+
+ALTER TABLE clicks ADD COLUMN referrer TEXT;
+ALTER TABLE clicks ADD COLUMN device TEXT;
@@ -0,0 +1,12 @@
+-- Add migration script here
+
+CREATE TABLE share_tokens (
+    id INTEGER PRIMARY KEY NOT NULL,
+    link INT NOT NULL,
+    nonce TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL,
+    expires_at TIMESTAMP,
+    FOREIGN KEY (link) REFERENCES links (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_share_tokens_link ON share_tokens(link);
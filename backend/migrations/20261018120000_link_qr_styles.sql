@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE link_qr_styles (
+    link INTEGER PRIMARY KEY NOT NULL,
+    style TEXT NOT NULL,
+    FOREIGN KEY (link) REFERENCES links (id) ON DELETE CASCADE
+);
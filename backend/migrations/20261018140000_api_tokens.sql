@@ -0,0 +1,16 @@
+-- Add migration script here
+
+CREATE TABLE api_tokens (
+    id INTEGER PRIMARY KEY NOT NULL,
+    owner INT NOT NULL,
+    name TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    hint TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    expires_at TIMESTAMP,
+    last_used_at TIMESTAMP,
+    FOREIGN KEY (owner) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_tokens_owner ON api_tokens(owner);
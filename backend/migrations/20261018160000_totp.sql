@@ -0,0 +1,24 @@
+-- Add migration script here
+
+CREATE TABLE user_totp (
+    user_id INTEGER PRIMARY KEY NOT NULL,
+    secret TEXT NOT NULL,
+    enabled_at TIMESTAMP,
+    last_step BIGINT,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE TABLE totp_recovery_codes (
+    id INTEGER PRIMARY KEY NOT NULL,
+    owner INT NOT NULL,
+    code_hash TEXT NOT NULL,
+    used_at TIMESTAMP,
+    FOREIGN KEY (owner) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_totp_recovery_codes_owner ON totp_recovery_codes(owner);
+
+CREATE TABLE instance_settings (
+    name TEXT PRIMARY KEY NOT NULL,
+    value TEXT NOT NULL
+);
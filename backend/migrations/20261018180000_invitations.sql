@@ -0,0 +1,16 @@
+-- Add migration script here
+
+CREATE TABLE invitations (
+    id INTEGER PRIMARY KEY NOT NULL,
+    created_by INT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    hint TEXT NOT NULL,
+    email TEXT,
+    role INT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    accepted_at TIMESTAMP,
+    accepted_by INT,
+    FOREIGN KEY (created_by) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (accepted_by) REFERENCES users (id) ON DELETE SET NULL
+);
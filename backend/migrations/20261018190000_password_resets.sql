@@ -0,0 +1,13 @@
+-- Add migration script here
+
+CREATE TABLE password_resets (
+    id INTEGER PRIMARY KEY NOT NULL,
+    user_id INT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    used_at TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_password_resets_user ON password_resets(user_id);
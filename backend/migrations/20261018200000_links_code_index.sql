@@ -0,0 +1,4 @@
+-- Add migration script here
+
+-- links are looked up by their code without regard to case
+CREATE INDEX IF NOT EXISTS idx_links_code_nocase ON links(code COLLATE NOCASE);
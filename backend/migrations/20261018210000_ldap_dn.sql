@@ -0,0 +1,5 @@
+-- Add migration script here
+
+ALTER TABLE users ADD COLUMN ldap_dn TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_ldap_dn ON users(ldap_dn);
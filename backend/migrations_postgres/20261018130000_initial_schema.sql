@@ -0,0 +1,46 @@
+-- The schema of the sqlite migrations up to 20261018120000_link_qr_styles.
+-- All integers are BIGINT as pslink reads and writes them as i64.
+
+CREATE TABLE IF NOT EXISTS users (
+    id BIGSERIAL PRIMARY KEY,
+    username VARCHAR NOT NULL UNIQUE,
+    email VARCHAR NOT NULL UNIQUE,
+    password VARCHAR NOT NULL,
+    role BIGINT DEFAULT 1 NOT NULL,
+    language TEXT NOT NULL DEFAULT 'en'
+);
+
+CREATE TABLE IF NOT EXISTS links (
+    id BIGSERIAL PRIMARY KEY,
+    title VARCHAR NOT NULL,
+    target VARCHAR NOT NULL,
+    code VARCHAR NOT NULL UNIQUE,
+    author BIGINT NOT NULL REFERENCES users (id),
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS clicks (
+    id BIGSERIAL PRIMARY KEY,
+    link BIGINT NOT NULL REFERENCES links (id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL,
+    referrer TEXT,
+    device TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_clicks_link_created_at ON clicks(link, created_at);
+CREATE INDEX IF NOT EXISTS idx_clicks_created_at ON clicks(created_at);
+
+CREATE TABLE IF NOT EXISTS share_tokens (
+    id BIGSERIAL PRIMARY KEY,
+    link BIGINT NOT NULL REFERENCES links (id) ON DELETE CASCADE,
+    nonce TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL,
+    expires_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_share_tokens_link ON share_tokens(link);
+
+CREATE TABLE IF NOT EXISTS link_qr_styles (
+    link BIGINT PRIMARY KEY REFERENCES links (id) ON DELETE CASCADE,
+    style TEXT NOT NULL
+);
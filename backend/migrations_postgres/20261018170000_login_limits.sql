@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS login_failures (
+    kind TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    failures BIGINT NOT NULL,
+    last_failure TIMESTAMP NOT NULL,
+    locked_until TIMESTAMP,
+    PRIMARY KEY (kind, subject)
+);
+
+CREATE TABLE IF NOT EXISTS login_events (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMP NOT NULL,
+    kind TEXT NOT NULL,
+    username TEXT NOT NULL,
+    ip TEXT,
+    detail TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_events_created_at ON login_events(created_at);
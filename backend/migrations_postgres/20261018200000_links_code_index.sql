@@ -0,0 +1,2 @@
+-- links are looked up by lower(code) to ignore the case
+CREATE INDEX IF NOT EXISTS idx_links_lower_code ON links(lower(code));
@@ -0,0 +1,3 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS ldap_dn TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_ldap_dn ON users(ldap_dn);
@@ -0,0 +1,186 @@
+//! Personal API tokens for scripted access.
+//!
+//! A token is a long random string that is shown once when it is created. Only its SHA-256 hash is
+//! stored, the random part is long enough that a slow password hash is not needed. Deleting the token
+//! from the database revokes it.
+use dioxus::logger::tracing::info;
+use dioxus::prelude::ServerFnError;
+use pslink_shared::{
+    apirequests::users::Role,
+    datatypes::{ApiToken, ApiTokenScope, NewApiToken, User},
+};
+
+use crate::models::UserDbOperations as _;
+use crate::storage::{DbRow, query};
+use crate::tokens::{hash_token, random_token};
+
+/// All tokens start with this prefix so they are easy to recognize in configuration files.
+const TOKEN_PREFIX: &str = "pslink_";
+/// The number of random characters of a token.
+const TOKEN_LENGTH: usize = 40;
+/// The number of characters of the token that are stored to tell the tokens apart.
+const HINT_LENGTH: usize = TOKEN_PREFIX.len() + 4;
+
+fn to_api_token(row: &DbRow) -> ApiToken {
+    ApiToken {
+        id: row.get("id"),
+        owner: row.get("owner"),
+        owner_name: row.get("owner_name"),
+        name: row.get("name"),
+        scope: ApiTokenScope::parse(&row.get::<String>("scope")),
+        hint: row.get("hint"),
+        created_at: row.get("created_at"),
+        expires_at: row.get("expires_at"),
+        last_used_at: row.get("last_used_at"),
+    }
+}
+
+/// Create a new token for the user that is valid for `valid_days` days or forever.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the name is empty, the expiry is out of range or the database cannot be acessed.
+pub(crate) async fn create_token(
+    owner: &User,
+    name: &str,
+    scope: ApiTokenScope,
+    valid_days: Option<u32>,
+) -> Result<NewApiToken, ServerFnError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ServerFnError::new("The token needs a name"));
+    }
+    let random = random_token(TOKEN_LENGTH);
+    let secret = format!("{TOKEN_PREFIX}{random}");
+    let created_at = chrono::Local::now().naive_utc();
+    let expires_at = valid_days
+        .map(|days| {
+            created_at
+                .checked_add_signed(chrono::Duration::days(i64::from(days)))
+                .ok_or_else(|| ServerFnError::new(format!("{days} days are too long")))
+        })
+        .transpose()?;
+    let db = crate::get_db().await;
+    let id: i64 = query(
+        "INSERT INTO api_tokens (owner, name, scope, token_hash, hint, created_at, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id",
+    )
+    .bind(owner.id)
+    .bind(name)
+    .bind(scope.as_str())
+    .bind(hash_token(&secret))
+    .bind(&secret[..HINT_LENGTH])
+    .bind(created_at)
+    .bind(expires_at)
+    .fetch_one(&db)
+    .await?
+    .get("id");
+    info!("{} created the API token {}", owner.username, name);
+    Ok(NewApiToken {
+        token: ApiToken {
+            id,
+            owner: owner.id,
+            owner_name: owner.username.clone(),
+            name: name.to_string(),
+            scope,
+            hint: secret[..HINT_LENGTH].to_string(),
+            created_at,
+            expires_at,
+            last_used_at: None,
+        },
+        secret,
+    })
+}
+
+/// List the tokens of one user or of all users if `owner` is `None`.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn list_tokens(owner: Option<i64>) -> Result<Vec<ApiToken>, ServerFnError> {
+    let db = crate::get_db().await;
+    let tokens = query(
+        "SELECT api_tokens.*, users.username AS owner_name
+        FROM api_tokens JOIN users ON api_tokens.owner = users.id
+        WHERE $1 IS NULL OR api_tokens.owner = $1
+        ORDER BY api_tokens.created_at DESC",
+    )
+    .bind(owner)
+    .fetch_all(&db)
+    .await?
+    .iter()
+    .map(to_api_token)
+    .collect();
+    Ok(tokens)
+}
+
+/// Get the id of the user a token belongs to.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed or the token does not exist.
+pub(crate) async fn token_owner(token_id: i64) -> Result<i64, ServerFnError> {
+    let db = crate::get_db().await;
+    let row = query("SELECT owner FROM api_tokens WHERE id = $1")
+        .bind(token_id)
+        .fetch_one(&db)
+        .await?;
+    Ok(row.get("owner"))
+}
+
+/// Revoke a token by deleting it.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn delete_token(token_id: i64) -> Result<(), ServerFnError> {
+    let db = crate::get_db().await;
+    query("DELETE FROM api_tokens WHERE id = $1")
+        .bind(token_id)
+        .execute(&db)
+        .await?;
+    Ok(())
+}
+
+/// The token of an `Authorization: Bearer <token>` header.
+pub(crate) fn bearer_token(headers: &axum::http::HeaderMap) -> Option<&str> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// Find the owner and scope of a valid (existing and not expired) token and remember that it was used.
+///
+/// Tokens of disabled users are not valid.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn authenticate(
+    token: &str,
+) -> Result<Option<(User, ApiTokenScope)>, ServerFnError> {
+    if !token.starts_with(TOKEN_PREFIX) {
+        return Ok(None);
+    }
+    let db = crate::get_db().await;
+    let now = chrono::Local::now().naive_utc();
+    let Some(row) = query(
+        "UPDATE api_tokens SET last_used_at = $1
+        WHERE token_hash = $2
+        AND (expires_at IS NULL OR expires_at > $1)
+        RETURNING owner, scope",
+    )
+    .bind(now)
+    .bind(hash_token(token))
+    .fetch_optional(&db)
+    .await?
+    else {
+        return Ok(None);
+    };
+    let user = User::get_user(row.get("owner")).await?;
+    if user.role == Role::Disabled {
+        return Ok(None);
+    }
+    Ok(Some((
+        user,
+        ApiTokenScope::parse(&row.get::<String>("scope")),
+    )))
+}
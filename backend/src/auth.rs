@@ -1,29 +1,61 @@
+use crate::login_limits::ClientIp;
 #[cfg(feature = "server")]
-use crate::models::UserDbOperations;
+use crate::models::{NewUser, UserDbOperations};
 use axum_extra::extract::Host;
+use dioxus::logger::tracing::info;
 use dioxus::prelude::{ServerFnError, extract};
-use pslink_shared::datatypes::User;
+use pslink_shared::{
+    apirequests::users::Role,
+    datatypes::{ApiTokenScope, User},
+};
 
 #[derive(Debug, Clone)]
 pub(crate) struct AuthAccount {
     user: Option<User>,
+    /// The scope of the API token if the request was authenticated with one instead of a session cookie.
+    token_scope: Option<ApiTokenScope>,
 }
 
 impl AuthAccount {
     pub fn get_user(&self) -> Option<User> {
         self.user.clone()
     }
+
+    pub fn token_scope(&self) -> Option<ApiTokenScope> {
+        self.token_scope
+    }
 }
 
+/// The endpoints of the server functions an API token with [`ApiTokenScope::Read`] may call.
+const READ_ONLY_ENDPOINTS: &[&str] = &[
+    "session",
+    "list_all_links",
+    "list_matching_link_ids",
+    "get_link_simple",
+    "get_link_statistics",
+    "get_link_details",
+    "get_instance_statistics",
+    "get_links_per_month",
+    "get_top_links",
+    "get_top_authors",
+    "get_stale_links",
+    "export_links",
+    "get_qr_code",
+    "get_qr_style",
+    "list_share_tokens",
+    "list_all_users",
+];
+
 #[cfg(feature = "server")]
 #[async_trait::async_trait]
-impl axum_session_auth::Authentication<AuthAccount, i64, sqlx::Pool<sqlx::Sqlite>> for AuthAccount {
+impl axum_session_auth::Authentication<AuthAccount, i64, crate::storage::Storage> for AuthAccount {
     async fn load_user(
         userid: i64,
-        _pool: Option<&sqlx::SqlitePool>,
+        _pool: Option<&crate::storage::Storage>,
     ) -> Result<AuthAccount, anyhow::Error> {
         Ok(Self {
             user: User::get_user(userid).await.ok(),
+            token_scope: None,
         })
     }
 
@@ -43,14 +75,188 @@ impl axum_session_auth::Authentication<AuthAccount, i64, sqlx::Pool<sqlx::Sqlite
 pub type Session = axum_session_auth::AuthSession<
     AuthAccount,
     i64,
-    axum_session_sqlx::SessionSqlitePool,
-    sqlx::SqlitePool,
+    crate::storage::SessionPool,
+    crate::storage::Storage,
 >;
 
+/// Get the session of the request.
+///
+/// If the request has an `Authorization: Bearer` header the user of the API token is the current user
+/// instead of the user of the session cookie.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the token is invalid or its scope does not allow the called endpoint.
 pub async fn get_session() -> Result<Session, ServerFnError> {
-    extract::<_, _>()
+    let mut session: Session = extract::<_, _>()
         .await
-        .map_err(|_| ServerFnError::new("AuthSessionLayer was not found"))
+        .map_err(|_| ServerFnError::new("AuthSessionLayer was not found"))?;
+    let headers: axum::http::HeaderMap = extract::<_, _>()
+        .await
+        .map_err(|_| ServerFnError::new("Headers were not found"))?;
+    let Some(token) = crate::api_tokens::bearer_token(&headers) else {
+        return Ok(session);
+    };
+    let Some((user, scope)) = crate::api_tokens::authenticate(token).await? else {
+        return Err(ServerFnError::new("Invalid or expired API token"));
+    };
+    if scope == ApiTokenScope::Read {
+        let uri: axum::http::Uri = extract::<_, _>()
+            .await
+            .map_err(|_| ServerFnError::new("Uri was not found"))?;
+        let endpoint = uri.path().rsplit('/').next().unwrap_or_default();
+        if !READ_ONLY_ENDPOINTS.contains(&endpoint) {
+            return Err(ServerFnError::new("The API token may only read"));
+        }
+    }
+    session.current_user = Some(AuthAccount {
+        user: Some(user),
+        token_scope: Some(scope),
+    });
+    Ok(session)
+}
+
+/// Get the user of the current session.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if nobody is logged in.
+pub async fn get_authenticated_user() -> Result<User, ServerFnError> {
+    let auth = get_session().await?;
+    auth.current_user
+        .and_then(|account| account.get_user())
+        .ok_or_else(|| ServerFnError::new("Not authenticated".to_owned()))
+}
+
+/// Get the user of the current session and make sure it was not authenticated with an API token.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if nobody is logged in or an API token was used.
+pub async fn get_session_user() -> Result<User, ServerFnError> {
+    let auth = get_session().await?;
+    let account = auth
+        .current_user
+        .ok_or_else(|| ServerFnError::new("Not authenticated".to_owned()))?;
+    if account.token_scope().is_some() {
+        return Err(ServerFnError::new(
+            "This needs a login, API tokens are not accepted",
+        ));
+    }
+    account
+        .get_user()
+        .ok_or_else(|| ServerFnError::new("Not authenticated".to_owned()))
+}
+
+/// Get the address of the client of the request.
+pub(crate) async fn get_client_ip() -> ClientIp {
+    extract::<ClientIp, _>().await.unwrap_or_default()
+}
+
+/// Check the username and password like [`authenticate`] within the limits of failed logins.
+///
+/// The attempt is counted before the password is checked. A failure is answered with the same message
+/// whatever the reason, so the answer does not tell whether the username exists. The caller records the
+/// success once the login is complete.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the login is refused or the credentials are wrong.
+pub(crate) async fn authenticate_limited(
+    username: &str,
+    password: &str,
+    ip: ClientIp,
+) -> Result<User, ServerFnError> {
+    let limits = crate::get_login_limits();
+    let attempt = crate::login_limits::reserve(&limits, username, ip).await?;
+    match authenticate(username, password).await {
+        Ok(user) => {
+            attempt.release().await?;
+            Ok(user)
+        }
+        Err(e) => {
+            attempt.fail(&limits, &e.to_string()).await?;
+            Err(ServerFnError::new(crate::login_limits::LOGIN_FAILED))
+        }
+    }
+}
+
+/// Check the username and password against the directory and the local passwords.
+///
+/// If a directory is configured its [`crate::ldap::LdapOrder`] decides which is asked first.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if neither accepts the password or the user is disabled.
+pub(crate) async fn authenticate(username: &str, password: &str) -> Result<User, ServerFnError> {
+    use crate::ldap::LdapOrder;
+    let user = match crate::get_ldap_settings() {
+        None => check_password(username, password).await?,
+        Some(settings) => match settings.order {
+            LdapOrder::LdapOnly => crate::ldap::authenticate(&settings, username, password)
+                .await?
+                .ok_or_else(|| ServerFnError::new("Unauthorized"))?,
+            LdapOrder::LdapFirst => {
+                match crate::ldap::authenticate(&settings, username, password).await? {
+                    Some(user) => user,
+                    None => check_password(username, password).await?,
+                }
+            }
+            LdapOrder::LocalFirst => match check_password(username, password).await {
+                Ok(user) => user,
+                Err(_) => crate::ldap::authenticate(&settings, username, password)
+                    .await?
+                    .ok_or_else(|| ServerFnError::new("Unauthorized"))?,
+            },
+        },
+    };
+    if user.role == Role::Disabled {
+        return Err(ServerFnError::new("The account is disabled"));
+    }
+    Ok(user)
+}
+
+/// Find the user with this username and check the password.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the username or password is wrong.
+pub(crate) async fn check_password(username: &str, password: &str) -> Result<User, ServerFnError> {
+    use argon2::{Params, PasswordVerifier as _};
+    let secret = crate::get_secret();
+    if username.is_empty() || password.is_empty() {
+        return Err(ServerFnError::new("Username or password cannot be empty"));
+    }
+    info!("Login attempt for user: {}", username);
+    if username.len() > 32 || password.len() > 32 {
+        return Err(ServerFnError::new("Username or password too long"));
+    }
+
+    let Ok(user) = User::get_user_by_name(username).await else {
+        // hash anyway so the answer does not come faster for unknown usernames
+        NewUser::hash_password(password, &secret).ok();
+        return Err(ServerFnError::new("Unauthorized"));
+    };
+    let user_password = user.password.secret.clone().expect("Secret available");
+    let parsed_hash = match argon2::PasswordHash::new(&user_password) {
+        Ok(h) => h,
+        Err(e) => {
+            info!(
+                "Failed to parse password hash for {}: {}",
+                &user.username, e
+            );
+            return Err(ServerFnError::new("Unauthorized"));
+        }
+    };
+    match argon2::Argon2::new_with_secret(
+        &secret.secret.clone().unwrap().into_bytes(),
+        argon2::Algorithm::default(),
+        argon2::Version::default(),
+        Params::default(),
+    )
+    .expect("Failed to create argon2 instance")
+    .verify_password(password.as_bytes(), &parsed_hash)
+    {
+        Ok(_) => Ok(user),
+        Err(e) => {
+            info!("Failed to verify password for {}: {}", &user.username, e);
+            Err(ServerFnError::new("Unauthorized"))
+        }
+    }
 }
 
 pub async fn get_hostname() -> Result<Host, ServerFnError> {
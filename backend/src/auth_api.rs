@@ -1,60 +1,78 @@
 #[cfg(feature = "server")]
 use crate::get_secret;
-#[cfg(feature = "server")]
-use crate::models::UserDbOperations as _;
-#[cfg(feature = "server")]
-use dioxus::logger::tracing::info;
 use dioxus::prelude::{ServerFnError, server, server_fn};
-use pslink_shared::{apirequests::users::SessionInfo, datatypes::User};
+#[cfg(feature = "server")]
+use pslink_shared::apirequests::users::Role;
+use pslink_shared::{
+    apirequests::users::{LoginResult, SessionInfo},
+    datatypes::{LoginEvent, LoginLockout, User},
+};
 
+/// Check the password, users with two-factor authentication are logged in by [`login_totp`].
 #[server(Login, endpoint = "login")]
-pub async fn login(username: String, password: String) -> Result<User, ServerFnError> {
-    use argon2::{Params, PasswordVerifier as _};
+pub async fn login(username: String, password: String) -> Result<LoginResult, ServerFnError> {
     let auth = crate::auth::get_session().await?;
-    let secret = crate::get_secret();
-    if username.is_empty() || password.is_empty() {
-        return Err(ServerFnError::new("Username or password cannot be empty"));
-    }
-    info!("Login attempt for user: {}", username);
-    if username.len() > 32 || password.len() > 32 {
-        return Err(ServerFnError::new("Username or password too long"));
+    let ip = crate::auth::get_client_ip().await;
+    let user = crate::auth::authenticate_limited(&username, &password, ip).await?;
+    let result = crate::totp::begin_login(&auth, user).await?;
+    if matches!(result, LoginResult::LoggedIn(_)) {
+        crate::login_limits::record_success(&username, ip).await?;
     }
+    Ok(result)
+}
 
-    let user = User::get_user_by_name(&username).await;
-    match user {
-        Ok(user) => {
-            let user_password = user.password.secret.clone().expect("Secret available");
-            let parsed_hash = match argon2::PasswordHash::new(&user_password) {
-                Ok(h) => h,
-                Err(e) => {
-                    info!(
-                        "Failed to parse password hash for {}: {}",
-                        &user.username, e
-                    );
-                    return Err(ServerFnError::new("Unauthorized"));
-                }
-            };
-            match argon2::Argon2::new_with_secret(
-                &secret.secret.clone().unwrap().into_bytes(),
-                argon2::Algorithm::default(),
-                argon2::Version::default(),
-                Params::default(),
-            )
-            .expect("Failed to create argon2 instance")
-            .verify_password(password.as_bytes(), &parsed_hash)
-            {
-                Ok(_) => {
-                    auth.login_user(user.id);
-                    Ok(user)
-                }
-                Err(e) => {
-                    info!("Failed to verify password for {}: {}", &user.username, e);
-                    return Err(ServerFnError::new("Unauthorized"));
-                }
-            }
+/// The second factor a login waits for, e.g. after a single sign-on that redirected to the login page.
+#[server(PendingSecondFactor, endpoint = "pending_second_factor")]
+pub async fn pending_second_factor() -> Result<Option<LoginResult>, ServerFnError> {
+    let auth = crate::auth::get_session().await?;
+    Ok(crate::totp::pending_login(&auth).map(|pending| {
+        if pending.enroll {
+            LoginResult::TotpEnrollmentRequired
+        } else {
+            LoginResult::TotpRequired
         }
-        Err(e) => Err(e.into()),
+    }))
+}
+
+/// Finish a login with the one-time code of the authenticator app or a recovery code.
+#[server(LoginTotp, endpoint = "login_totp")]
+pub async fn login_totp(code: String) -> Result<User, ServerFnError> {
+    let auth = crate::auth::get_session().await?;
+    let ip = crate::auth::get_client_ip().await;
+    crate::totp::finish_login(&auth, &code, ip).await
+}
+
+/// Whether forgotten passwords can be reset by email.
+#[server(PasswordResetEnabled, endpoint = "password_reset_enabled")]
+pub async fn password_reset_enabled() -> Result<bool, ServerFnError> {
+    Ok(crate::password_reset::available())
+}
+
+/// Send a link that resets the password to the email address of an account.
+///
+/// The answer is the same whether an account with this username or email address exists.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if passwords cannot be reset by email or access to the database fails.
+#[server(RequestPasswordReset, endpoint = "request_password_reset")]
+pub async fn request_password_reset(username: String) -> Result<(), ServerFnError> {
+    let settings = crate::get_smtp_settings()
+        .filter(|_| crate::password_reset::available())
+        .ok_or_else(|| ServerFnError::new("Passwords cannot be reset by email"))?;
+    crate::password_reset::request_reset(&settings, &username).await
+}
+
+/// Set a new password with the token of a reset link.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the link is invalid or expired or the password is malformed.
+#[server(ResetPassword, endpoint = "reset_password")]
+pub async fn reset_password(token: String, password: String) -> Result<(), ServerFnError> {
+    if !crate::password_reset::available() {
+        return Err(ServerFnError::new("Passwords cannot be reset by email"));
     }
+    crate::password_reset::reset_password(&token, &password).await?;
+    Ok(())
 }
 
 #[server(Logout, endpoint = "logout")]
@@ -94,7 +112,54 @@ pub async fn get_session_info() -> Result<SessionInfo, ServerFnError> {
     })
 }
 
+/// Whether users can log in with the single sign-on of an OpenID Connect provider.
+#[server(SingleSignOn, endpoint = "single_sign_on")]
+pub async fn single_sign_on_enabled() -> Result<bool, ServerFnError> {
+    Ok(crate::get_oidc_settings().is_some())
+}
+
 #[server(Demo, endpoint = "demo")]
 pub async fn demo() -> Result<bool, ServerFnError> {
     Ok(get_secret().is_random)
 }
+
+/// The logged in administrator.
+#[cfg(feature = "server")]
+async fn session_admin() -> Result<User, ServerFnError> {
+    let user = crate::auth::get_session_user().await?;
+    if user.role == Role::Admin {
+        Ok(user)
+    } else {
+        Err(ServerFnError::new("Administrator permissions required"))
+    }
+}
+
+/// The usernames and addresses that are locked after too many failed logins.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is no administrator or access to the database fails.
+#[server(ListLoginLockouts, endpoint = "list_login_lockouts")]
+pub async fn list_login_lockouts() -> Result<Vec<LoginLockout>, ServerFnError> {
+    session_admin().await?;
+    crate::login_limits::lockouts().await
+}
+
+/// Lift the lockout of a username or an address.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is no administrator or access to the database fails.
+#[server(ResetLoginLockout, endpoint = "reset_login_lockout")]
+pub async fn reset_login_lockout(by_ip: bool, subject: String) -> Result<(), ServerFnError> {
+    let admin = session_admin().await?;
+    crate::login_limits::reset(by_ip, &subject, &admin.username).await
+}
+
+/// The newest logins, refused logins and lockouts.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is no administrator or access to the database fails.
+#[server(ListLoginEvents, endpoint = "list_login_events")]
+pub async fn list_login_events() -> Result<Vec<LoginEvent>, ServerFnError> {
+    session_admin().await?;
+    crate::login_limits::events(50).await
+}
@@ -0,0 +1,192 @@
+//! Consistent snapshots of the database.
+//!
+//! Copying the database file while the server writes to it can produce a torn copy and misses the
+//! changes that are still in the WAL. `VACUUM INTO` writes a consistent copy from within sqlite instead.
+//! Only SQLite databases are backed up by pslink, PostgreSQL databases need `pg_dump`.
+//!
+//! The snapshots contain the password hashes, so every file is created readable only by its owner.
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use dioxus::logger::tracing::{error, info, warn};
+use dioxus::prelude::ServerFnError;
+
+/// All backups written into a directory start with this prefix, only those are removed by the retention.
+const BACKUP_PREFIX: &str = "pslink-";
+
+/// Where and how the backups are written.
+#[derive(Debug, Clone)]
+pub struct BackupSettings {
+    pub directory: PathBuf,
+    /// The number of backups that are kept, older ones are deleted.
+    pub keep: usize,
+    pub gzip: bool,
+}
+
+/// Create a new empty file that only the owner can read and write, it must not exist.
+fn create_private_file(path: &Path) -> std::io::Result<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)
+}
+
+/// A new directory in the temporary directory that only the owner can access.
+fn create_private_directory() -> std::io::Result<PathBuf> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let path = std::env::temp_dir().join(format!(
+        "pslink-snapshot-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(&path)?;
+    Ok(path)
+}
+
+/// Write a consistent copy of the database to `destination`, the file must not exist.
+async fn vacuum_into(destination: &Path) -> Result<(), ServerFnError> {
+    let db = crate::get_db().await;
+    if db.is_postgres() {
+        return Err(ServerFnError::new(
+            "PostgreSQL databases are not backed up by pslink, use pg_dump instead",
+        ));
+    }
+    // sqlite writes into an existing empty file and keeps its permissions
+    create_private_file(destination)?;
+    let result = crate::storage::query("VACUUM INTO $1")
+        .bind(destination.to_string_lossy().to_string())
+        .execute(&db)
+        .await;
+    if let Err(e) = result {
+        std::fs::remove_file(destination).ok();
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// A consistent snapshot of the database as bytes, e.g. for the admin download.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the snapshot cannot be written or read.
+pub(crate) async fn snapshot() -> Result<Vec<u8>, ServerFnError> {
+    let directory = create_private_directory()?;
+    let path = directory.join("snapshot.db");
+    let result = match vacuum_into(&path).await {
+        Ok(()) => tokio::fs::read(&path).await.map_err(ServerFnError::from),
+        Err(e) => Err(e),
+    };
+    tokio::fs::remove_dir_all(&directory).await.ok();
+    result
+}
+
+fn gzip_file(source: &Path, destination: &Path) -> std::io::Result<()> {
+    let mut encoder = flate2::write::GzEncoder::new(
+        create_private_file(destination)?,
+        flate2::Compression::default(),
+    );
+    std::io::copy(&mut std::fs::File::open(source)?, &mut encoder)?;
+    encoder.finish()?.sync_all()
+}
+
+/// Write a snapshot to `destination`, gzip compressed if `gzip` is set.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the destination exists or cannot be written.
+pub(crate) async fn write_backup(destination: &Path, gzip: bool) -> Result<(), ServerFnError> {
+    if destination.exists() {
+        return Err(ServerFnError::new(format!(
+            "The backup file {} exists already",
+            destination.display()
+        )));
+    }
+    if !gzip {
+        return vacuum_into(destination).await;
+    }
+    // the uncompressed snapshot is written next to the backup, a leading dot keeps it out of `prune`
+    let path = destination.with_file_name(format!(
+        ".{}.snapshot",
+        destination
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+    ));
+    let result = match vacuum_into(&path).await {
+        Ok(()) => {
+            let (source, destination) = (path.clone(), destination.to_owned());
+            tokio::task::spawn_blocking(move || gzip_file(&source, &destination))
+                .await?
+                .map_err(ServerFnError::from)
+        }
+        Err(e) => Err(e),
+    };
+    tokio::fs::remove_file(&path).await.ok();
+    result
+}
+
+/// Write a new timestamped backup into the backup directory and remove the oldest ones.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the backup cannot be written.
+pub(crate) async fn create_backup(settings: &BackupSettings) -> Result<PathBuf, ServerFnError> {
+    tokio::fs::create_dir_all(&settings.directory).await?;
+    let extension = if settings.gzip { "db.gz" } else { "db" };
+    let destination = settings.directory.join(format!(
+        "{BACKUP_PREFIX}{}.{extension}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    write_backup(&destination, settings.gzip).await?;
+    prune(settings)?;
+    Ok(destination)
+}
+
+/// Delete all but the newest `keep` backups. The timestamp in the name sorts them by age.
+pub(crate) fn prune(settings: &BackupSettings) -> Result<(), ServerFnError> {
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(&settings.directory)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(BACKUP_PREFIX)
+                        && (name.ends_with(".db") || name.ends_with(".db.gz"))
+                })
+        })
+        .collect();
+    backups.sort();
+    let surplus = backups.len().saturating_sub(settings.keep.max(1));
+    for old in &backups[..surplus] {
+        info!("Removing the old backup {}", old.display());
+        if let Err(e) = std::fs::remove_file(old) {
+            warn!("Failed to remove the old backup {}: {}", old.display(), e);
+        }
+    }
+    Ok(())
+}
+
+/// Start the task that writes a backup every `interval`, the first one after one interval.
+pub(crate) fn start(settings: BackupSettings, interval: Duration) {
+    info!(
+        "Scheduled backups every {:?} into {} (keeping {})",
+        interval,
+        settings.directory.display(),
+        settings.keep
+    );
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // the first tick completes immediately
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            match create_backup(&settings).await {
+                Ok(path) => info!("Backup written to {}", path.display()),
+                Err(e) => error!("The scheduled backup failed: {}", e),
+            }
+        }
+    });
+}
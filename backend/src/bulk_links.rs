@@ -0,0 +1,117 @@
+//! Delete, transfer or export many links at once.
+//!
+//! The same permission check as for saving a single link applies to every link: only the author and
+//! administrators may change it. If one link fails the check nothing is changed.
+use std::collections::BTreeSet;
+
+use dioxus::logger::tracing::info;
+use dioxus::prelude::ServerFnError;
+use pslink_shared::{
+    apirequests::{links::BulkLinkOperation, users::Role},
+    datatypes::{Link, User},
+};
+
+use crate::models::{UserDbOperations as _, link_from_row};
+use crate::storage::{Transaction, query};
+
+/// Read the links with their author names and check that the user may edit every one of them.
+async fn editable_links(
+    transaction: &mut Transaction,
+    user: &User,
+    ids: &BTreeSet<i64>,
+) -> Result<Vec<(Link, String)>, ServerFnError> {
+    let mut links = Vec::with_capacity(ids.len());
+    let mut forbidden = Vec::new();
+    for id in ids {
+        let row = query(
+            "SELECT links.*, users.username AS username
+            FROM links JOIN users ON links.author = users.id
+            WHERE links.id = $1",
+        )
+        .bind(*id)
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or_else(|| ServerFnError::new(format!("The link {id} does not exist")))?;
+        let link = link_from_row(&row);
+        if user.role != Role::Admin && link.author != user.id {
+            forbidden.push(link.code.clone());
+        }
+        links.push((link, row.get("username")));
+    }
+    if !forbidden.is_empty() {
+        return Err(ServerFnError::new(format!(
+            "Not authorized to edit the links {}",
+            forbidden.join(", ")
+        )));
+    }
+    Ok(links)
+}
+
+/// Apply the operation to all links in one transaction and return the number of changed links.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if a link does not exist, the user may not edit one of the links,
+/// the new author does not exist or the database cannot be acessed. In that case nothing is changed.
+pub(crate) async fn apply(
+    user: &User,
+    ids: &[i64],
+    operation: &BulkLinkOperation,
+) -> Result<usize, ServerFnError> {
+    let ids: BTreeSet<i64> = ids.iter().copied().collect();
+    let new_author = match operation {
+        BulkLinkOperation::Transfer(username) => {
+            let author = User::get_user_by_name(username)
+                .await
+                .map_err(|_| ServerFnError::new(format!("The user {username} does not exist")))?;
+            if author.role == Role::Disabled {
+                return Err(ServerFnError::new(format!(
+                    "The user {username} is disabled"
+                )));
+            }
+            Some(author.id)
+        }
+        BulkLinkOperation::Delete => None,
+    };
+
+    let db = crate::get_db().await;
+    let mut transaction = db.begin().await?;
+    let links = editable_links(&mut transaction, user, &ids).await?;
+    for (link, _) in &links {
+        let statement = match new_author {
+            Some(author) => query("UPDATE links SET author = $1 WHERE id = $2").bind(author),
+            None => query("DELETE FROM links WHERE id = $1"),
+        };
+        statement.bind(link.id).execute(&mut transaction).await?;
+    }
+    transaction.commit().await?;
+    info!(
+        "{} applied {:?} to {} links",
+        user.username,
+        operation,
+        links.len()
+    );
+    Ok(links.len())
+}
+
+/// Export the links as CSV with the columns of the link import, so the file can be imported again.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if a link does not exist, the user may not edit one of the links
+/// or the database cannot be acessed.
+pub(crate) async fn export(user: &User, ids: &[i64]) -> Result<String, ServerFnError> {
+    let ids: BTreeSet<i64> = ids.iter().copied().collect();
+    let db = crate::get_db().await;
+    let mut transaction = db.begin().await?;
+    let links = editable_links(&mut transaction, user, &ids).await?;
+    transaction.commit().await?;
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["title", "target", "code", "author"])?;
+    for (link, author) in links {
+        writer.write_record([link.title, link.target, link.code, author])?;
+    }
+    let data = writer
+        .into_inner()
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    String::from_utf8(data).map_err(ServerFnError::new)
+}
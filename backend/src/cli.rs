@@ -2,8 +2,7 @@ use clap::{Arg, ArgMatches, Command, command};
 use dioxus::logger::tracing::{error, info, trace, warn};
 use dioxus::prelude::ServerFnError;
 use dotenv::dotenv;
-use pslink_shared::datatypes::{Secret, User};
-use sqlx::migrate::Migrator;
+use pslink_shared::datatypes::{Lang, Secret, User};
 use std::fmt::Display;
 use std::io::IsTerminal;
 use std::str::FromStr;
@@ -13,11 +12,15 @@ use std::{
     path::PathBuf,
 };
 
+use crate::backup::BackupSettings;
+use crate::ldap::{LdapOrder, LdapSettings};
+use crate::login_limits::{LoginLimits, MAX_LOCKOUT_MINUTES};
+use crate::mail::{SmtpSecurity, SmtpSettings};
 use crate::models::{NewLink, NewUser, UserDbOperations as _};
+use crate::oidc::OidcSettings;
+use crate::storage::Storage;
 use crate::{get_db, init_db, init_secret, init_data_download_secret, init_db_path};
 
-static MIGRATOR: Migrator = sqlx::migrate!();
-
 /// The qr-code can contain two different protocolls
 #[derive(Debug, Clone)]
 pub enum Protocol {
@@ -56,10 +59,49 @@ pub struct ServerConfig {
     pub protocol: Protocol,
     pub secret: Secret,
     pub data_download_secret: Secret,
+    pub click_queue_size: usize,
+    pub click_flush_interval: u64,
+    pub backup_dir: Option<PathBuf>,
+    /// The hours between two scheduled backups.
+    pub backup_interval: u64,
+    pub backup_keep: usize,
+    pub backup_gzip: bool,
+    /// The identity provider for single sign-on, if one is configured.
+    pub oidc: Option<OidcSettings>,
+    /// The directory that logins are checked with, if one is configured.
+    pub ldap: Option<LdapSettings>,
+    /// The limits of failed logins.
+    pub login_limits: LoginLimits,
+    /// The server that sends emails, if one is configured.
+    pub smtp: Option<SmtpSettings>,
 }
 
 /// The configuration can be serialized into an environment-file.
 impl ServerConfig {
+    /// Whether the database is a PostgreSQL url instead of a SQLite file.
+    #[must_use]
+    pub(crate) fn uses_postgres(&self) -> bool {
+        Storage::is_postgres_url(&self.db.to_string_lossy())
+    }
+
+    /// The settings of the scheduled backups if a backup directory is configured.
+    ///
+    /// PostgreSQL databases are not backed up by pslink.
+    #[must_use]
+    pub(crate) fn backup_settings(&self) -> Option<BackupSettings> {
+        if self.uses_postgres() {
+            if self.backup_dir.is_some() {
+                warn!("Scheduled backups are disabled for PostgreSQL, use pg_dump instead.");
+            }
+            return None;
+        }
+        self.backup_dir.as_ref().map(|directory| BackupSettings {
+            directory: directory.clone(),
+            keep: self.backup_keep,
+            gzip: self.backup_gzip,
+        })
+    }
+
     #[must_use]
     pub fn to_env_strings(&self) -> Vec<String> {
         vec![
@@ -108,7 +150,7 @@ fn generate_cli() -> Command {
         .arg(
             Arg::new("database")
                 .long("db")
-                .help("The path of the sqlite database")
+                .help("The path of the sqlite database or a postgres:// url")
                 .env("PSLINK_DATABASE")
                 .default_value("./links.db")
                 .global(true),
@@ -207,6 +249,332 @@ fn generate_cli() -> Command {
                 .default_value("")
                 .global(true),
         )
+        .arg(
+            Arg::new("click_queue_size")
+                .long("click-queue-size")
+                .help(concat!(
+                    "The maximum number of clicks waiting to be written to the database.",
+                    " If the queue is full further clicks are still redirected but not recorded."
+                ))
+                .env("PSLINK_CLICK_QUEUE_SIZE")
+                .default_value("10000")
+                .value_parser(clap::value_parser!(usize))
+                .global(true),
+        )
+        .arg(
+            Arg::new("click_flush_interval")
+                .long("click-flush-interval")
+                .help("The maximum time in milliseconds a click waits before it is written to the database.")
+                .env("PSLINK_CLICK_FLUSH_INTERVAL")
+                .default_value("1000")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .global(true),
+        )
+        .arg(
+            Arg::new("backup_dir")
+                .long("backup-dir")
+                .help("The directory of the scheduled backups, no backups are scheduled if it is not set.")
+                .env("PSLINK_BACKUP_DIR")
+                .global(true),
+        )
+        .arg(
+            Arg::new("backup_interval")
+                .long("backup-interval")
+                .help("The hours between two scheduled backups, at most a year.")
+                .env("PSLINK_BACKUP_INTERVAL")
+                .default_value("24")
+                .value_parser(clap::value_parser!(u64).range(1..=8760))
+                .global(true),
+        )
+        .arg(
+            Arg::new("backup_keep")
+                .long("backup-keep")
+                .help("The number of backups that are kept in the backup directory, older ones are deleted.")
+                .env("PSLINK_BACKUP_KEEP")
+                .default_value("7")
+                .value_parser(clap::value_parser!(usize))
+                .global(true),
+        )
+        .arg(
+            Arg::new("backup_gzip")
+                .long("backup-gzip")
+                .help("Compress the backups with gzip.")
+                .env("PSLINK_BACKUP_GZIP")
+                .action(clap::ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("oidc_issuer")
+                .long("oidc-issuer")
+                .help("The issuer url of the OpenID Connect provider, single sign-on is disabled if it is not set.")
+                .env("PSLINK_OIDC_ISSUER")
+                .global(true),
+        )
+        .arg(
+            Arg::new("oidc_client_id")
+                .long("oidc-client-id")
+                .help("The client id of pslink at the OpenID Connect provider.")
+                .env("PSLINK_OIDC_CLIENT_ID")
+                .global(true),
+        )
+        .arg(
+            Arg::new("oidc_client_secret")
+                .long("oidc-client-secret")
+                .help(concat!(
+                    "The client secret of pslink at the OpenID Connect provider, public clients have none.",
+                    " Use the environment variable as command line parameters are visible to all users."
+                ))
+                .env("PSLINK_OIDC_CLIENT_SECRET")
+                .hide_env_values(true)
+                .global(true),
+        )
+        .arg(
+            Arg::new("oidc_scopes")
+                .long("oidc-scopes")
+                .help("The scopes that are requested from the OpenID Connect provider.")
+                .env("PSLINK_OIDC_SCOPES")
+                .default_value("openid profile email")
+                .global(true),
+        )
+        .arg(
+            Arg::new("oidc_username_claim")
+                .long("oidc-username-claim")
+                .help("The claim that contains the username, the email is used if it is missing.")
+                .env("PSLINK_OIDC_USERNAME_CLAIM")
+                .default_value("preferred_username")
+                .global(true),
+        )
+        .arg(
+            Arg::new("oidc_groups_claim")
+                .long("oidc-groups-claim")
+                .help("The claim that contains the groups of the user.")
+                .env("PSLINK_OIDC_GROUPS_CLAIM")
+                .default_value("groups")
+                .global(true),
+        )
+        .arg(
+            Arg::new("oidc_admin_group")
+                .long("oidc-admin-group")
+                .help(concat!(
+                    "Members of this group are administrators and other single sign-on users are not.",
+                    " If it is not set the roles are managed in pslink."
+                ))
+                .env("PSLINK_OIDC_ADMIN_GROUP")
+                .global(true),
+        )
+        .arg(
+            Arg::new("oidc_default_role")
+                .long("oidc-default-role")
+                .help("The role of users that log in with single sign-on for the first time, disabled users have to be enabled by an administrator.")
+                .env("PSLINK_OIDC_DEFAULT_ROLE")
+                .default_value("regular")
+                .value_parser(["regular", "disabled", "admin"])
+                .global(true),
+        )
+        .arg(
+            Arg::new("ldap_url")
+                .long("ldap-url")
+                .help("The url of the LDAP directory like ldap://localhost:389 or ldaps://ad.example.com, LDAP logins are disabled if it is not set.")
+                .env("PSLINK_LDAP_URL")
+                .global(true),
+        )
+        .arg(
+            Arg::new("ldap_starttls")
+                .long("ldap-starttls")
+                .help("Upgrade ldap:// connections with StartTLS.")
+                .env("PSLINK_LDAP_STARTTLS")
+                .action(clap::ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("ldap_bind_dn")
+                .long("ldap-bind-dn")
+                .help("The DN of the service account that searches the users, the search is anonymous if it is not set.")
+                .env("PSLINK_LDAP_BIND_DN")
+                .global(true),
+        )
+        .arg(
+            Arg::new("ldap_bind_password")
+                .long("ldap-bind-password")
+                .help(concat!(
+                    "The password of the service account.",
+                    " Use the environment variable as command line parameters are visible to all users."
+                ))
+                .env("PSLINK_LDAP_BIND_PASSWORD")
+                .hide_env_values(true)
+                .global(true),
+        )
+        .arg(
+            Arg::new("ldap_base_dn")
+                .long("ldap-base-dn")
+                .help("Where the users are searched, e.g. ou=people,dc=example,dc=com.")
+                .env("PSLINK_LDAP_BASE_DN")
+                .global(true),
+        )
+        .arg(
+            Arg::new("ldap_user_filter")
+                .long("ldap-user-filter")
+                .help("The filter that finds a user, {username} is replaced by the escaped username.")
+                .env("PSLINK_LDAP_USER_FILTER")
+                .default_value("(&(objectClass=person)(uid={username}))")
+                .global(true),
+        )
+        .arg(
+            Arg::new("ldap_username_attribute")
+                .long("ldap-username-attribute")
+                .help("The attribute that contains the username, sAMAccountName for Active Directory.")
+                .env("PSLINK_LDAP_USERNAME_ATTRIBUTE")
+                .default_value("uid")
+                .global(true),
+        )
+        .arg(
+            Arg::new("ldap_email_attribute")
+                .long("ldap-email-attribute")
+                .help("The attribute that contains the email address.")
+                .env("PSLINK_LDAP_EMAIL_ATTRIBUTE")
+                .default_value("mail")
+                .global(true),
+        )
+        .arg(
+            Arg::new("ldap_group_attribute")
+                .long("ldap-group-attribute")
+                .help("The attribute that contains the DNs of the groups of a user.")
+                .env("PSLINK_LDAP_GROUP_ATTRIBUTE")
+                .default_value("memberOf")
+                .global(true),
+        )
+        .arg(
+            Arg::new("ldap_admin_group")
+                .long("ldap-admin-group")
+                .help(concat!(
+                    "The DN of the group whose members are administrators, other directory users are not.",
+                    " If it is not set the roles are managed in pslink."
+                ))
+                .env("PSLINK_LDAP_ADMIN_GROUP")
+                .global(true),
+        )
+        .arg(
+            Arg::new("ldap_order")
+                .long("ldap-order")
+                .help(concat!(
+                    "Whether the directory or the local passwords are checked first.",
+                    " With ldap-first local passwords are used if the user is not in the directory or it is not reachable,",
+                    " with ldap-only they are never used."
+                ))
+                .env("PSLINK_LDAP_ORDER")
+                .default_value("ldap-first")
+                .value_parser(LdapOrder::NAMES)
+                .global(true),
+        )
+        .arg(
+            Arg::new("smtp_host")
+                .long("smtp-host")
+                .help("The SMTP server that sends the password reset emails, they are disabled if it is not set.")
+                .env("PSLINK_SMTP_HOST")
+                .global(true),
+        )
+        .arg(
+            Arg::new("smtp_port")
+                .long("smtp-port")
+                .help("The port of the SMTP server.")
+                .env("PSLINK_SMTP_PORT")
+                .default_value("587")
+                .value_parser(clap::value_parser!(u16))
+                .global(true),
+        )
+        .arg(
+            Arg::new("smtp_security")
+                .long("smtp-security")
+                .help(concat!(
+                    "How the connection to the SMTP server is encrypted: starttls usually on port 587,",
+                    " tls usually on port 465 or none for a server on the same host."
+                ))
+                .env("PSLINK_SMTP_SECURITY")
+                .default_value("starttls")
+                .value_parser(SmtpSecurity::NAMES)
+                .global(true),
+        )
+        .arg(
+            Arg::new("smtp_username")
+                .long("smtp-username")
+                .help("The login at the SMTP server, emails are sent without login if it is not set.")
+                .env("PSLINK_SMTP_USERNAME")
+                .global(true),
+        )
+        .arg(
+            Arg::new("smtp_password")
+                .long("smtp-password")
+                .help(concat!(
+                    "The password of the SMTP login.",
+                    " Use the environment variable as command line parameters are visible to all users."
+                ))
+                .env("PSLINK_SMTP_PASSWORD")
+                .hide_env_values(true)
+                .global(true),
+        )
+        .arg(
+            Arg::new("smtp_from")
+                .long("smtp-from")
+                .help("The sender of the emails like \"pslink <pslink@example.com>\".")
+                .env("PSLINK_SMTP_FROM")
+                .global(true),
+        )
+        .arg(
+            Arg::new("login_max_failures")
+                .long("login-max-failures")
+                .help("The failed logins of a username until it is locked for a while, 0 disables the lockout.")
+                .env("PSLINK_LOGIN_MAX_FAILURES")
+                .default_value("5")
+                .value_parser(clap::value_parser!(u32))
+                .global(true),
+        )
+        .arg(
+            Arg::new("login_max_failures_per_ip")
+                .long("login-max-failures-per-ip")
+                .help("The failed logins from one address until it is locked for a while, 0 disables the lockout.")
+                .env("PSLINK_LOGIN_MAX_FAILURES_PER_IP")
+                .default_value("20")
+                .value_parser(clap::value_parser!(u32))
+                .global(true),
+        )
+        .arg(
+            Arg::new("login_backoff_seconds")
+                .long("login-backoff-seconds")
+                .help("The seconds to wait after a failed login, the wait doubles with every further failure.")
+                .env("PSLINK_LOGIN_BACKOFF_SECONDS")
+                .default_value("1")
+                .value_parser(clap::value_parser!(u64))
+                .global(true),
+        )
+        .arg(
+            Arg::new("login_lockout_minutes")
+                .long("login-lockout-minutes")
+                .help("The minutes a username or address is locked after too many failed logins, at most a year.")
+                .env("PSLINK_LOGIN_LOCKOUT_MINUTES")
+                .default_value("15")
+                .value_parser(clap::value_parser!(u64).range(1..=MAX_LOCKOUT_MINUTES))
+                .global(true),
+        )
+        .arg(
+            Arg::new("login_event_days")
+                .long("login-event-days")
+                .help("The days the audit trail of the logins is kept, 0 keeps it forever.")
+                .env("PSLINK_LOGIN_EVENT_DAYS")
+                .default_value("90")
+                .value_parser(clap::value_parser!(u64))
+                .global(true),
+        )
+        .arg(
+            Arg::new("trust_forwarded_for")
+                .long("trust-forwarded-for")
+                .help(concat!(
+                    "Take the client address from the X-Forwarded-For header.",
+                    " Only set this behind a reverse proxy that sets the header, otherwise clients can choose their address."
+                ))
+                .env("PSLINK_TRUST_FORWARDED_FOR")
+                .action(clap::ArgAction::SetTrue)
+                .global(true),
+        )
         .subcommand(
             Command::new("runserver")
                 .about("Run the server")
@@ -227,6 +595,94 @@ fn generate_cli() -> Command {
                 .about("Create an admin user.")
                 .display_order(2),
         )
+        .subcommand(
+            Command::new("import")
+                .about("Import the links and clicks of YOURLS, Shlink, Kutt or Polr.")
+                .display_order(2)
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("The tool that created the file")
+                        .value_parser(crate::foreign_import::ForeignFormat::NAMES)
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("author")
+                        .long("author")
+                        .help("The user that owns the imported links, the first admin by default"),
+                )
+                .arg(
+                    Arg::new("file")
+                        .help("A MySQL dump for YOURLS and Polr, the JSON api response for Shlink and Kutt")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("backup")
+                .about("Write a consistent snapshot of the running database.")
+                .display_order(2)
+                .arg(
+                    Arg::new("dest")
+                        .help(concat!(
+                            "The backup file or a directory. In a directory a timestamped backup is written",
+                            " and only the newest --backup-keep backups are kept."
+                        ))
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Export all users, links and clicks as versioned JSON.")
+                .display_order(2)
+                .arg(
+                    Arg::new("include_passwords")
+                        .long("include-passwords")
+                        .help("Include the password hashes, second factors, API tokens and invitations so users can log in after a restore")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("file")
+                        .help("The JSON file to write")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("restore")
+                .about("Restore a JSON export into a fresh database.")
+                .display_order(2)
+                .arg(
+                    Arg::new("file")
+                        .help("A JSON file written by `pslink export` or the admin export")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("ldap-test")
+                .about("Check the LDAP settings by searching a user and optionally checking the password.")
+                .display_order(2)
+                .arg(
+                    Arg::new("username")
+                        .help("The user that is searched in the directory")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("test-email")
+                .about("Check the SMTP settings by sending a test email.")
+                .display_order(2)
+                .arg(
+                    Arg::new("address")
+                        .help("The address the test email is sent to")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("language")
+                        .long("language")
+                        .help("The language of the test email")
+                        .default_value("en-US")
+                        .value_parser(["en-US", "de-DE"]),
+                ),
+        )
         .subcommand(
             Command::new("demo")
                 .about("Create a database and demo user.")
@@ -255,6 +711,7 @@ async fn parse_args_to_config(config: ArgMatches) -> ServerConfig {
         if secret.is_empty() {
             warn!("No secret was found! Use the environment variable PSLINK_SECRET to set one.");
             warn!("If you change the secret all passwords will be invalid");
+            warn!("and all shared statistics pages will be unavailable");
             warn!("Using an auto generated one for this run.");
         } else {
             warn!("The provided secret was too short. Using an auto generated one.");
@@ -267,7 +724,9 @@ async fn parse_args_to_config(config: ArgMatches) -> ServerConfig {
     };
     let data_download_secret = if data_download_secret.len() < 5 {
         if data_download_secret.is_empty() {
-            warn!("No data download secret was found! Use the environment variable PSLINK_DATA_DOWNLOAD_SECRET to set one.");
+            warn!(
+                "No data download secret was found! Use the environment variable PSLINK_DATA_DOWNLOAD_SECRET to set one."
+            );
             warn!("Using an auto generated one for this run.");
         } else {
             warn!("The provided data download secret was too short. Using an auto generated one.");
@@ -307,6 +766,41 @@ async fn parse_args_to_config(config: ArgMatches) -> ServerConfig {
         .expect("Failed to read the protocol value")
         .parse::<Protocol>()
         .expect("Failed to parse the protocol");
+    let click_queue_size = *config
+        .get_one::<usize>("click_queue_size")
+        .expect("Failed to read the click queue size");
+    let click_flush_interval = *config
+        .get_one::<u64>("click_flush_interval")
+        .expect("Failed to read the click flush interval");
+    let backup_dir = config.get_one::<String>("backup_dir").map(PathBuf::from);
+    let backup_interval = *config
+        .get_one::<u64>("backup_interval")
+        .expect("Failed to read the backup interval");
+    let backup_keep = *config
+        .get_one::<usize>("backup_keep")
+        .expect("Failed to read the number of kept backups");
+    let backup_gzip = config.get_flag("backup_gzip");
+    let oidc = oidc_settings(&config, &format!("{protocol}://{public_url}"));
+    let ldap = ldap_settings(&config);
+    let smtp = smtp_settings(&config);
+    let login_limits = LoginLimits {
+        max_failures: *config
+            .get_one::<u32>("login_max_failures")
+            .expect("Failed to read the maximum of failed logins"),
+        max_failures_per_ip: *config
+            .get_one::<u32>("login_max_failures_per_ip")
+            .expect("Failed to read the maximum of failed logins per address"),
+        backoff_seconds: *config
+            .get_one::<u64>("login_backoff_seconds")
+            .expect("Failed to read the login backoff"),
+        lockout_minutes: *config
+            .get_one::<u64>("login_lockout_minutes")
+            .expect("Failed to read the login lockout"),
+        trust_forwarded_for: config.get_flag("trust_forwarded_for"),
+        event_days: *config
+            .get_one::<u64>("login_event_days")
+            .expect("Failed to read the days of the login events"),
+    };
     info!("Arguments parsed");
     ServerConfig {
         db,
@@ -316,9 +810,119 @@ async fn parse_args_to_config(config: ArgMatches) -> ServerConfig {
         protocol,
         secret,
         data_download_secret,
+        click_queue_size,
+        click_flush_interval,
+        backup_dir,
+        backup_interval,
+        backup_keep,
+        backup_gzip,
+        oidc,
+        ldap,
+        login_limits,
+        smtp,
     }
 }
 
+/// The settings of the OpenID Connect provider if an issuer is configured.
+fn oidc_settings(config: &ArgMatches, public_url: &str) -> Option<OidcSettings> {
+    let issuer = config.get_one::<String>("oidc_issuer")?.to_owned();
+    let Some(client_id) = config.get_one::<String>("oidc_client_id") else {
+        warn!("Single sign-on is disabled because PSLINK_OIDC_CLIENT_ID is not set.");
+        return None;
+    };
+    let get = |name: &str| {
+        config
+            .get_one::<String>(name)
+            .expect("The argument has a default value")
+            .to_owned()
+    };
+    Some(OidcSettings {
+        issuer,
+        client_id: client_id.to_owned(),
+        client_secret: config
+            .get_one::<String>("oidc_client_secret")
+            .filter(|secret| !secret.is_empty())
+            .cloned(),
+        redirect_uri: format!(
+            "{}{}",
+            public_url.trim_end_matches('/'),
+            crate::oidc::CALLBACK_PATH
+        ),
+        scopes: get("oidc_scopes"),
+        username_claim: get("oidc_username_claim"),
+        groups_claim: get("oidc_groups_claim"),
+        admin_group: config.get_one::<String>("oidc_admin_group").cloned(),
+        default_role: crate::oidc::parse_role(&get("oidc_default_role"))
+            .expect("The role is checked by clap"),
+    })
+}
+
+/// The settings of the LDAP directory if an url is configured.
+fn ldap_settings(config: &ArgMatches) -> Option<LdapSettings> {
+    let url = config.get_one::<String>("ldap_url")?.to_owned();
+    let Some(base_dn) = config.get_one::<String>("ldap_base_dn") else {
+        warn!("LDAP logins are disabled because PSLINK_LDAP_BASE_DN is not set.");
+        return None;
+    };
+    let get = |name: &str| {
+        config
+            .get_one::<String>(name)
+            .expect("The argument has a default value")
+            .to_owned()
+    };
+    let user_filter = get("ldap_user_filter");
+    if !user_filter.contains(crate::ldap::USERNAME_PLACEHOLDER) {
+        warn!(
+            "The LDAP user filter {} does not contain {}, every login searches the same entry.",
+            user_filter,
+            crate::ldap::USERNAME_PLACEHOLDER
+        );
+    }
+    Some(LdapSettings {
+        url,
+        starttls: config.get_flag("ldap_starttls"),
+        bind_dn: config.get_one::<String>("ldap_bind_dn").cloned(),
+        bind_password: config.get_one::<String>("ldap_bind_password").cloned(),
+        base_dn: base_dn.to_owned(),
+        user_filter,
+        username_attribute: get("ldap_username_attribute"),
+        email_attribute: get("ldap_email_attribute"),
+        group_attribute: get("ldap_group_attribute"),
+        admin_group: config.get_one::<String>("ldap_admin_group").cloned(),
+        order: LdapOrder::parse(&get("ldap_order")).expect("The order is checked by clap"),
+    })
+}
+
+fn smtp_settings(config: &ArgMatches) -> Option<SmtpSettings> {
+    let host = config.get_one::<String>("smtp_host")?.to_owned();
+    let Some(from) = config.get_one::<String>("smtp_from") else {
+        warn!("Emails are disabled because PSLINK_SMTP_FROM is not set.");
+        return None;
+    };
+    let username = config.get_one::<String>("smtp_username").cloned();
+    let password = config.get_one::<String>("smtp_password").cloned();
+    if username.is_some() != password.is_some() {
+        warn!(
+            "Emails are sent without login because only one of the SMTP username and password is set."
+        );
+    }
+    Some(SmtpSettings {
+        host,
+        port: *config
+            .get_one::<u16>("smtp_port")
+            .expect("The argument has a default value"),
+        security: SmtpSecurity::parse(
+            config
+                .get_one::<String>("smtp_security")
+                .expect("The argument has a default value"),
+        )
+        .expect("The security is checked by clap"),
+        username,
+        password,
+        from: from.to_owned(),
+    })
+}
+
 /// Setup and launch the command
 ///
 /// This function is the entry point for the server. It parses the command line arguments and sets up the database and the server configuration.
@@ -369,14 +973,17 @@ pub async fn setup() -> Result<Option<ServerConfig>, ServerFnError> {
 
     let db = server_config.db.clone();
     trace!("Checking if the database exists at {}", db.display());
-    if db.exists() {
+    if server_config.uses_postgres() || db.exists() {
         init_db(&db.to_string_lossy()).await;
         init_db_path(db.to_string_lossy().to_string());
     } else {
         trace!("No database file found {}", db.display());
         if !(config.subcommand_matches("migrate-database").is_some()
             | config.subcommand_matches("generate-env").is_some()
-            | config.subcommand_matches("demo").is_some())
+            | config.subcommand_matches("demo").is_some()
+            | config.subcommand_matches("restore").is_some()
+            | config.subcommand_matches("ldap-test").is_some()
+            | config.subcommand_matches("test-email").is_some())
         {
             let msg = format!(
                 concat!(
@@ -418,6 +1025,46 @@ pub async fn setup() -> Result<Option<ServerConfig>, ServerFnError> {
         };
     }
 
+    if let Some(import_config) = config.subcommand_matches("import") {
+        return match import_foreign_links(import_config).await {
+            Ok(()) => Ok(None),
+            Err(e) => Err(e),
+        };
+    }
+
+    if let Some(backup_config) = config.subcommand_matches("backup") {
+        return match backup_database(&server_config, backup_config).await {
+            Ok(()) => Ok(None),
+            Err(e) => Err(e),
+        };
+    }
+    if let Some(export_config) = config.subcommand_matches("export") {
+        return match export_data(export_config).await {
+            Ok(()) => Ok(None),
+            Err(e) => Err(e),
+        };
+    }
+    if let Some(restore_config) = config.subcommand_matches("restore") {
+        return match restore_data(&server_config, restore_config).await {
+            Ok(()) => Ok(None),
+            Err(e) => Err(e),
+        };
+    }
+
+    if let Some(ldap_config) = config.subcommand_matches("ldap-test") {
+        return match test_ldap(&server_config, ldap_config).await {
+            Ok(()) => Ok(None),
+            Err(e) => Err(e),
+        };
+    }
+
+    if let Some(email_config) = config.subcommand_matches("test-email") {
+        return match test_email(&server_config, email_config).await {
+            Ok(()) => Ok(None),
+            Err(e) => Err(e),
+        };
+    }
+
     if let Some(_runserver_config) = config.subcommand_matches("demo") {
         return generate_demo_data(server_config).await;
     }
@@ -434,7 +1081,7 @@ pub async fn setup() -> Result<Option<ServerConfig>, ServerFnError> {
             server_config.port = dioxus::cli_config::server_port().expect("Cli should be enabled");
 
             // Check if database exists
-            if !server_config.db.exists() {
+            if !server_config.uses_postgres() && !server_config.db.exists() {
                 error!("Database not found at {}", server_config.db.display());
                 error!("Do you want to create a demo database? If so use the command:");
                 error!("target/dx/web/debug/web/server demo");
@@ -499,6 +1146,50 @@ async fn add_example_links() {
     }
 }
 
+/// Search a user in the directory and check the password if one is entered.
+async fn test_ldap(config: &ServerConfig, ldap_config: &ArgMatches) -> Result<(), ServerFnError> {
+    let Some(settings) = &config.ldap else {
+        return Err(ServerFnError::new(
+            "LDAP is not configured, set at least PSLINK_LDAP_URL and PSLINK_LDAP_BASE_DN"
+                .to_string(),
+        ));
+    };
+    let username = ldap_config
+        .get_one::<String>("username")
+        .expect("The username is required");
+    let password = if io::stdin().is_terminal() {
+        rpassword::prompt_password(format!(
+            "The password of {username} (leave empty to only search): "
+        ))
+        .map_err(|e| ServerFnError::new(format!("Failed to read the password: {e}")))?
+    } else {
+        let mut stdin = BufReader::new(io::stdin());
+        rpassword::read_password_from_bufread(&mut stdin).unwrap_or_default()
+    };
+    crate::ldap::test_configuration(settings, username, &password).await
+}
+
+/// Send a test email with the SMTP settings.
+async fn test_email(config: &ServerConfig, email_config: &ArgMatches) -> Result<(), ServerFnError> {
+    let Some(settings) = &config.smtp else {
+        return Err(ServerFnError::new(
+            "SMTP is not configured, set at least PSLINK_SMTP_HOST and PSLINK_SMTP_FROM"
+                .to_string(),
+        ));
+    };
+    let address = email_config
+        .get_one::<String>("address")
+        .expect("The address is required");
+    let language = email_config
+        .get_one::<String>("language")
+        .expect("The argument has a default value")
+        .parse::<Lang>()
+        .unwrap_or(Lang::EnUS);
+    crate::mail::send(settings, address, &crate::mail::test_email(language)).await?;
+    println!("The test email was sent to {address}.");
+    Ok(())
+}
+
 /// Interactively create a new admin user.
 async fn request_admin_credentials(config: &ServerConfig) -> Result<(), ServerFnError> {
     info!("Creating an admin user.");
@@ -556,14 +1247,16 @@ async fn apply_migrations(config: &ServerConfig) -> Result<(), ServerFnError> {
         "Creating a database file and running the migrations in the file {}:",
         &config.db.display()
     );
-    if config.db.exists() && config.db.metadata().map(|m| m.len() > 0).unwrap_or(false) {
+    if config.uses_postgres() {
+        // the postgres database exists already, only the tables are created.
+        init_db(&config.db.to_string_lossy()).await;
+    } else if config.db.exists() && config.db.metadata().map(|m| m.len() > 0).unwrap_or(false) {
         return Err(ServerFnError::new("The database is not empty aborting because this could mean that creating a demo instance would lead in data loss.".to_string()));
     } else {
         File::create(&config.db)?;
         init_db(&config.db.to_string_lossy()).await;
     }
-    let pool = get_db().await;
-    MIGRATOR.run(&pool).await?;
+    get_db().await.migrate().await?;
     info!("Migrations applied successfully.");
     Ok(())
 }
@@ -596,10 +1289,100 @@ fn generate_env_file(server_config: &ServerConfig) -> Result<(), ServerFnError>
     Ok(())
 }
 
+/// Import the export of another url shortener, see [`crate::foreign_import`].
+async fn import_foreign_links(config: &ArgMatches) -> Result<(), ServerFnError> {
+    let format = config
+        .get_one::<String>("format")
+        .expect("The format is required")
+        .parse::<crate::foreign_import::ForeignFormat>()
+        .map_err(ServerFnError::new)?;
+    let file = config
+        .get_one::<String>("file")
+        .expect("The file is required");
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| ServerFnError::new(format!("Failed to read {file}: {e}")))?;
+    let author = match config.get_one::<String>("author") {
+        Some(name) => User::get_user_by_name(name).await?.id,
+        None => crate::foreign_import::first_admin().await?,
+    };
+    let summary = crate::foreign_import::import(format, &content, author).await?;
+    info!(
+        "Imported {} links and {} clicks from {}",
+        summary.imported, summary.clicks, file
+    );
+    print!("{summary}");
+    Ok(())
+}
+
+/// Write a backup to a file or a timestamped backup into a directory.
+async fn backup_database(
+    server_config: &ServerConfig,
+    config: &ArgMatches,
+) -> Result<(), ServerFnError> {
+    let destination = PathBuf::from(
+        config
+            .get_one::<String>("dest")
+            .expect("The destination is required"),
+    );
+    let path = if destination.is_dir() {
+        crate::backup::create_backup(&BackupSettings {
+            directory: destination,
+            keep: server_config.backup_keep,
+            gzip: server_config.backup_gzip,
+        })
+        .await?
+    } else {
+        crate::backup::write_backup(&destination, server_config.backup_gzip).await?;
+        destination
+    };
+    println!("Backup written to {}", path.display());
+    Ok(())
+}
+
+/// Write the versioned JSON export of all data to a file.
+async fn export_data(config: &ArgMatches) -> Result<(), ServerFnError> {
+    let file = config
+        .get_one::<String>("file")
+        .expect("The file is required");
+    let export = crate::data_export::export(config.get_flag("include_passwords")).await?;
+    std::fs::write(file, serde_json::to_string_pretty(&export)?)?;
+    println!(
+        "Exported {} users, {} links and {} clicks to {}",
+        export.users.len(),
+        export.links.len(),
+        export.clicks.len(),
+        file
+    );
+    Ok(())
+}
+
+/// Restore a JSON export, the database is created if it does not exist.
+async fn restore_data(
+    server_config: &ServerConfig,
+    config: &ArgMatches,
+) -> Result<(), ServerFnError> {
+    let file = config
+        .get_one::<String>("file")
+        .expect("The file is required");
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| ServerFnError::new(format!("Failed to read {file}: {e}")))?;
+    let export: pslink_shared::apirequests::data_export::DataExport =
+        serde_json::from_str(&content)?;
+    if server_config.uses_postgres() || server_config.db.exists() {
+        get_db().await.migrate().await?;
+    } else {
+        apply_migrations(server_config).await?;
+    }
+    let summary = crate::data_export::restore(&export).await?;
+    print!("{summary}");
+    Ok(())
+}
+
 async fn generate_demo_data(
     server_config: ServerConfig,
 ) -> Result<Option<ServerConfig>, ServerFnError> {
-    if server_config.db.exists()
+    if !server_config.uses_postgres()
+        && server_config.db.exists()
         && server_config
             .db
             .metadata()
@@ -0,0 +1,277 @@
+//! Export the clicks of links as CSV or JSON.
+//!
+//! The export is streamed: a background task reads the clicks row by row from the database
+//! and writes them into a pipe that is sent as the response body.
+use axum::{
+    body::Body,
+    extract::Query,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::NaiveDate;
+use dioxus::logger::tracing::{error, info};
+use futures::TryStreamExt as _;
+use pslink_shared::{
+    apirequests::{
+        links::{ClickAggregation, ClickExportFormat, ClickExportQuery},
+        users::Role,
+    },
+    datatypes::{Link, User},
+};
+use serde::Serialize;
+use tokio::io::{AsyncWrite, AsyncWriteExt as _};
+use tokio_util::io::ReaderStream;
+
+use crate::auth::Session;
+use crate::models::LinkDbOperations as _;
+use crate::storage::Storage;
+
+/// The size of the pipe between the database task and the response.
+const PIPE_SIZE: usize = 64 * 1024;
+
+/// A single exported click.
+#[derive(Serialize)]
+struct ExportedClick {
+    link_id: i64,
+    code: String,
+    created_at: String,
+    referrer: Option<String>,
+    device: Option<String>,
+}
+
+/// The number of clicks of a link during one day, week or month.
+#[derive(Serialize)]
+struct ExportedCount {
+    link_id: i64,
+    code: String,
+    period_start: String,
+    clicks: i64,
+}
+
+/// Handle `GET /export/clicks`.
+///
+/// Only links of the logged in user can be exported, administrators can export every link.
+pub async fn export_clicks(session: Session, Query(query): Query<ClickExportQuery>) -> Response {
+    let Some(user) = session.current_user.and_then(|account| account.get_user()) else {
+        return (StatusCode::UNAUTHORIZED, "Not authenticated").into_response();
+    };
+    let link_ids = query.link_ids();
+    if let Err(refused) = ensure_may_export(&user, &link_ids).await {
+        return refused.into_response();
+    }
+    info!(
+        "Exporting clicks of {} for {}",
+        if link_ids.is_empty() {
+            "all links".to_string()
+        } else {
+            format!("the links {:?}", link_ids)
+        },
+        user.username
+    );
+
+    let (reader, writer) = tokio::io::duplex(PIPE_SIZE);
+    let format = query.format;
+    tokio::spawn(async move {
+        if let Err(e) = write_export(writer, &query, &link_ids, user.id).await {
+            error!("Failed to export clicks: {}", e);
+        }
+    });
+
+    let extension = match format {
+        ClickExportFormat::Csv => "csv",
+        ClickExportFormat::Json => "json",
+    };
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"clicks-{}.{}\"",
+                    chrono::Local::now().format("%Y-%m-%d"),
+                    extension
+                ),
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(reader)),
+    )
+        .into_response()
+}
+
+/// Only the author of a link and administrators may export its clicks.
+pub(crate) async fn ensure_may_export(
+    user: &User,
+    link_ids: &[i64],
+) -> Result<(), (StatusCode, &'static str)> {
+    for link_id in link_ids {
+        match Link::get_link_by_id(*link_id).await {
+            Ok(link) if link.author == user.id || user.role == Role::Admin => {}
+            Ok(_) => {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    "Only the author can export the clicks",
+                ));
+            }
+            Err(_) => return Err((StatusCode::NOT_FOUND, "Link not found")),
+        }
+    }
+    Ok(())
+}
+
+/// Build the query selecting the clicks of the requested links in the requested date range.
+///
+/// The placeholders are the author or the link ids followed by the start and the end of the range.
+fn export_sql(db: &Storage, aggregation: ClickAggregation, link_count: usize) -> String {
+    let selection = if link_count == 0 {
+        "links.author = $1".to_string()
+    } else {
+        let placeholders: Vec<String> = (1..=link_count).map(|i| format!("${i}")).collect();
+        format!("links.id IN ({})", placeholders.join(","))
+    };
+    let range = format!(
+        "clicks.created_at >= ${} AND clicks.created_at < ${}",
+        link_count.max(1) + 1,
+        link_count.max(1) + 2
+    );
+    let period = match aggregation {
+        ClickAggregation::Click => {
+            return format!(
+                "SELECT
+                    links.id AS lid,
+                    links.code AS lcode,
+                    {} AS created,
+                    clicks.referrer AS referrer,
+                    clicks.device AS device
+                FROM clicks
+                JOIN links ON clicks.link = links.id
+                WHERE {selection} AND {range}
+                ORDER BY clicks.created_at",
+                db.timestamp_text("clicks.created_at")
+            );
+        }
+        ClickAggregation::Day => db.day("clicks.created_at"),
+        // weeks start on monday
+        ClickAggregation::Week => db.week_start("clicks.created_at"),
+        ClickAggregation::Month => db.month_start("clicks.created_at"),
+    };
+    format!(
+        "SELECT
+            links.id AS lid,
+            links.code AS lcode,
+            {period} AS period_start,
+            COUNT(*) AS clicks
+        FROM clicks
+        JOIN links ON clicks.link = links.id
+        WHERE {selection} AND {range}
+        GROUP BY links.id, period_start
+        ORDER BY period_start, links.id"
+    )
+}
+
+pub(crate) async fn write_export<W: AsyncWrite + Unpin>(
+    mut writer: W,
+    query: &ClickExportQuery,
+    link_ids: &[i64],
+    user_id: i64,
+) -> Result<(), anyhow::Error> {
+    let db = crate::get_db().await;
+    let sql = export_sql(&db, query.aggregation, link_ids.len());
+    let mut sql_query = crate::storage::query(sql);
+    if link_ids.is_empty() {
+        sql_query = sql_query.bind(user_id);
+    } else {
+        for link_id in link_ids {
+            sql_query = sql_query.bind(*link_id);
+        }
+    }
+    let from = query
+        .from
+        .or_else(|| NaiveDate::from_ymd_opt(1, 1, 1))
+        .and_then(|d| d.and_hms_opt(0, 0, 0));
+    // the end date is included
+    let to = query
+        .to
+        .and_then(|d| d.succ_opt())
+        .or_else(|| NaiveDate::from_ymd_opt(9999, 12, 31))
+        .and_then(|d| d.and_hms_opt(0, 0, 0));
+    let sql_query = sql_query.bind(from).bind(to);
+    let mut rows = sql_query.fetch(&db);
+
+    let header = match query.aggregation {
+        ClickAggregation::Click => {
+            csv_line(["link_id", "code", "created_at", "referrer", "device"])?
+        }
+        _ => csv_line(["link_id", "code", "period_start", "clicks"])?,
+    };
+    match query.format {
+        ClickExportFormat::Csv => writer.write_all(header.as_bytes()).await?,
+        ClickExportFormat::Json => writer.write_all(b"[").await?,
+    }
+
+    let mut first = true;
+    while let Some(row) = rows.try_next().await? {
+        let line = match (query.format, query.aggregation) {
+            (ClickExportFormat::Csv, ClickAggregation::Click) => {
+                let referrer: Option<String> = row.get("referrer");
+                let device: Option<String> = row.get("device");
+                csv_line([
+                    row.get::<i64>("lid").to_string(),
+                    row.get("lcode"),
+                    row.get("created"),
+                    referrer.unwrap_or_default(),
+                    device.unwrap_or_default(),
+                ])?
+            }
+            (ClickExportFormat::Csv, _) => csv_line([
+                row.get::<i64>("lid").to_string(),
+                row.get("lcode"),
+                row.get("period_start"),
+                row.get::<i64>("clicks").to_string(),
+            ])?,
+            (ClickExportFormat::Json, ClickAggregation::Click) => {
+                let click = ExportedClick {
+                    link_id: row.get("lid"),
+                    code: row.get("lcode"),
+                    created_at: row.get("created"),
+                    referrer: row.get("referrer"),
+                    device: row.get("device"),
+                };
+                json_entry(&click, first)?
+            }
+            (ClickExportFormat::Json, _) => {
+                let count = ExportedCount {
+                    link_id: row.get("lid"),
+                    code: row.get("lcode"),
+                    period_start: row.get("period_start"),
+                    clicks: row.get("clicks"),
+                };
+                json_entry(&count, first)?
+            }
+        };
+        first = false;
+        // fails if the client closed the connection
+        writer.write_all(line.as_bytes()).await?;
+    }
+
+    if query.format == ClickExportFormat::Json {
+        writer.write_all(b"\n]\n").await?;
+    }
+    writer.shutdown().await?;
+    Ok(())
+}
+
+fn json_entry<T: Serialize>(entry: &T, first: bool) -> Result<String, serde_json::Error> {
+    let separator = if first { "\n" } else { ",\n" };
+    Ok(format!("{}{}", separator, serde_json::to_string(entry)?))
+}
+
+/// One CSV line, fields with characters that have a special meaning in CSV are quoted.
+fn csv_line<I, T>(fields: I) -> Result<String, anyhow::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(fields)?;
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
@@ -0,0 +1,170 @@
+//! Record clicks in the background.
+//!
+//! The redirect handler only pushes the click into a bounded in-process queue and answers immediately.
+//! A background task collects the queued clicks and writes them to the database in batched transactions.
+//! If the queue is full the click is dropped and counted in the [`crate::metrics`].
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use dioxus::logger::tracing::{error, info, trace, warn};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::metrics::CLICKS;
+use crate::models::NewClick;
+
+/// The maximum number of clicks written in one transaction.
+pub(crate) const MAX_BATCH_SIZE: usize = 500;
+
+pub(crate) enum QueueMessage {
+    Click(NewClick),
+    Shutdown(oneshot::Sender<()>),
+}
+
+static CLICK_QUEUE: LazyLock<once_cell::sync::OnceCell<ClickQueue>> =
+    LazyLock::new(|| once_cell::sync::OnceCell::new());
+
+/// The sending end of the queue, the clicks are received by [`run`].
+pub(crate) struct ClickQueue {
+    sender: mpsc::Sender<QueueMessage>,
+}
+
+impl ClickQueue {
+    /// Create a queue for at most `capacity` clicks, nothing is written until [`run`] gets the receiver.
+    pub(crate) fn new(capacity: usize) -> (Self, mpsc::Receiver<QueueMessage>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        (Self { sender }, receiver)
+    }
+
+    /// Queue a click for recording. This never blocks, if the queue is full the click is dropped and
+    /// `false` is returned.
+    pub(crate) fn record(&self, click: NewClick) -> bool {
+        match self.sender.try_send(QueueMessage::Click(click)) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                warn!("The click queue is full, dropping the click");
+                CLICKS.click_dropped();
+                false
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                warn!("The click queue is closed, dropping the click");
+                CLICKS.click_dropped();
+                false
+            }
+        }
+    }
+
+    /// Write all queued clicks to the database and stop the background task.
+    pub(crate) async fn shutdown(&self) {
+        let (done, finished) = oneshot::channel();
+        if self.sender.send(QueueMessage::Shutdown(done)).await.is_ok() {
+            info!("Flushing the click queue");
+            finished.await.ok();
+        }
+    }
+
+    /// The number of queued clicks and the capacity of the queue.
+    pub(crate) fn usage(&self) -> (usize, usize) {
+        (
+            self.sender.max_capacity() - self.sender.capacity(),
+            self.sender.max_capacity(),
+        )
+    }
+}
+
+/// Start the background task that writes the queued clicks to the database.
+///
+/// `capacity` is the maximum number of clicks waiting in the queue, `flush_interval` the maximum time a click waits before it is written.
+pub(crate) fn start(capacity: usize, flush_interval: Duration) {
+    let (queue, receiver) = ClickQueue::new(capacity);
+    if CLICK_QUEUE.set(queue).is_err() {
+        warn!("The click queue was already started");
+        return;
+    }
+    tokio::spawn(run(receiver, flush_interval));
+    info!(
+        "Click queue started (capacity {}, flushing every {:?})",
+        capacity, flush_interval
+    );
+}
+
+/// Queue a click for recording. This never blocks, if the queue is full the click is dropped.
+pub(crate) fn record(click: NewClick) {
+    let Some(queue) = CLICK_QUEUE.get() else {
+        warn!("The click queue is not running, dropping the click");
+        CLICKS.click_dropped();
+        return;
+    };
+    queue.record(click);
+}
+
+/// Write all queued clicks to the database and stop the background task.
+pub(crate) async fn shutdown() {
+    if let Some(queue) = CLICK_QUEUE.get() {
+        queue.shutdown().await;
+    }
+}
+
+/// The number of queued clicks and the capacity of the queue.
+pub(crate) fn usage() -> (usize, usize) {
+    CLICK_QUEUE.get().map_or((0, 0), ClickQueue::usage)
+}
+
+/// Write the received clicks in batches until the queue is shut down.
+pub(crate) async fn run(mut receiver: mpsc::Receiver<QueueMessage>, flush_interval: Duration) {
+    let mut batch = Vec::with_capacity(MAX_BATCH_SIZE);
+    // the first tick is after one interval, not right away
+    let mut ticker =
+        tokio::time::interval_at(tokio::time::Instant::now() + flush_interval, flush_interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            message = receiver.recv() => match message {
+                Some(QueueMessage::Click(click)) => {
+                    batch.push(click);
+                    if batch.len() >= MAX_BATCH_SIZE {
+                        flush(&mut batch).await;
+                    }
+                }
+                Some(QueueMessage::Shutdown(done)) => {
+                    // accept no new clicks but write everything that is already queued.
+                    receiver.close();
+                    while let Some(message) = receiver.recv().await {
+                        if let QueueMessage::Click(click) = message {
+                            batch.push(click);
+                            if batch.len() >= MAX_BATCH_SIZE {
+                                flush(&mut batch).await;
+                            }
+                        }
+                    }
+                    flush(&mut batch).await;
+                    done.send(()).ok();
+                    info!("Click queue stopped");
+                    return;
+                }
+                None => {
+                    flush(&mut batch).await;
+                    return;
+                }
+            },
+            _ = ticker.tick() => flush(&mut batch).await,
+        }
+    }
+}
+
+async fn flush(batch: &mut Vec<NewClick>) {
+    if batch.is_empty() {
+        return;
+    }
+    let clicks = std::mem::take(batch);
+    let count = clicks.len();
+    match NewClick::insert_clicks(clicks).await {
+        Ok(()) => {
+            trace!("Recorded {} clicks", count);
+            CLICKS.batch_written(count);
+        }
+        Err(e) => {
+            error!("Failed to record {} clicks: {}", count, e);
+            CLICKS.batch_failed(count);
+        }
+    }
+}
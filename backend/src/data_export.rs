@@ -0,0 +1,382 @@
+//! Export all data as versioned JSON and restore it into a fresh database.
+use std::fmt::Display;
+
+use dioxus::logger::tracing::info;
+use dioxus::prelude::ServerFnError;
+use pslink_shared::{
+    apirequests::{
+        data_export::{
+            DataExport, ExportedApiToken, ExportedClick, ExportedInvitation, ExportedLink,
+            ExportedRecoveryCode, ExportedShareToken, ExportedTotp, ExportedUser,
+        },
+        users::Role,
+    },
+    qr::QrStyle,
+};
+
+use crate::storage::query;
+
+/// Read all users, links and clicks in one read transaction so that the export is consistent.
+///
+/// Second factors, API tokens and invitations are credentials like the password hashes and only exported with them.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn export(include_passwords: bool) -> Result<DataExport, ServerFnError> {
+    let db = crate::get_db().await;
+    let mut transaction = db.begin().await?;
+
+    let mut totps = std::collections::HashMap::new();
+    if include_passwords {
+        for row in query("SELECT user_id, secret, enabled_at, last_step FROM user_totp")
+            .fetch_all(&mut transaction)
+            .await?
+        {
+            totps.insert(
+                row.get::<i64>("user_id"),
+                ExportedTotp {
+                    secret: row.get("secret"),
+                    enabled_at: row.get("enabled_at"),
+                    last_step: row.get("last_step"),
+                    recovery_codes: Vec::new(),
+                },
+            );
+        }
+        for row in query("SELECT owner, code_hash, used_at FROM totp_recovery_codes ORDER BY id")
+            .fetch_all(&mut transaction)
+            .await?
+        {
+            if let Some(totp) = totps.get_mut(&row.get::<i64>("owner")) {
+                totp.recovery_codes.push(ExportedRecoveryCode {
+                    code_hash: row.get("code_hash"),
+                    used_at: row.get("used_at"),
+                });
+            }
+        }
+    }
+
+    let users = query(
+        "SELECT id, username, email, password, role, language, oidc_subject, ldap_dn
+        FROM users ORDER BY id",
+    )
+    .fetch_all(&mut transaction)
+    .await?
+    .iter()
+    .map(|row| ExportedUser {
+        id: row.get("id"),
+        username: row.get("username"),
+        email: row.get("email"),
+        role: Role::convert(row.get("role")),
+        language: row.get("language"),
+        password: include_passwords.then(|| row.get("password")),
+        oidc_subject: row.get("oidc_subject"),
+        ldap_dn: row.get("ldap_dn"),
+        totp: totps.remove(&row.get::<i64>("id")),
+    })
+    .collect();
+
+    let links = query(
+        "SELECT links.id, title, target, code, author, created_at, style
+        FROM links LEFT JOIN link_qr_styles ON link_qr_styles.link = links.id
+        ORDER BY links.id",
+    )
+    .fetch_all(&mut transaction)
+    .await?
+    .iter()
+    .map(|row| ExportedLink {
+        id: row.get("id"),
+        title: row.get("title"),
+        target: row.get("target"),
+        code: row.get("code"),
+        author: row.get("author"),
+        created_at: row.get("created_at"),
+        qr_style: row
+            .get::<Option<String>>("style")
+            .and_then(|style| serde_json::from_str::<QrStyle>(&style).ok()),
+    })
+    .collect();
+
+    let clicks = query("SELECT link, created_at, referrer, device FROM clicks ORDER BY id")
+        .fetch_all(&mut transaction)
+        .await?
+        .iter()
+        .map(|row| ExportedClick {
+            link: row.get("link"),
+            created_at: row.get("created_at"),
+            referrer: row.get("referrer"),
+            device: row.get("device"),
+        })
+        .collect();
+
+    let share_tokens =
+        query("SELECT link, nonce, created_at, expires_at FROM share_tokens ORDER BY id")
+            .fetch_all(&mut transaction)
+            .await?
+            .iter()
+            .map(|row| ExportedShareToken {
+                link: row.get("link"),
+                nonce: row.get("nonce"),
+                created_at: row.get("created_at"),
+                expires_at: row.get("expires_at"),
+            })
+            .collect();
+
+    let mut api_tokens = Vec::new();
+    let mut invitations = Vec::new();
+    if include_passwords {
+        api_tokens = query(
+            "SELECT owner, name, scope, token_hash, hint, created_at, expires_at, last_used_at
+            FROM api_tokens ORDER BY id",
+        )
+        .fetch_all(&mut transaction)
+        .await?
+        .iter()
+        .map(|row| ExportedApiToken {
+            owner: row.get("owner"),
+            name: row.get("name"),
+            scope: row.get("scope"),
+            token_hash: row.get("token_hash"),
+            hint: row.get("hint"),
+            created_at: row.get("created_at"),
+            expires_at: row.get("expires_at"),
+            last_used_at: row.get("last_used_at"),
+        })
+        .collect();
+        invitations = query(
+            "SELECT created_by, token_hash, hint, email, role, created_at, expires_at, accepted_at, accepted_by
+            FROM invitations ORDER BY id",
+        )
+        .fetch_all(&mut transaction)
+        .await?
+        .iter()
+        .map(|row| ExportedInvitation {
+            created_by: row.get("created_by"),
+            token_hash: row.get("token_hash"),
+            hint: row.get("hint"),
+            email: row.get("email"),
+            role: Role::convert(row.get("role")),
+            created_at: row.get("created_at"),
+            expires_at: row.get("expires_at"),
+            accepted_at: row.get("accepted_at"),
+            accepted_by: row.get("accepted_by"),
+        })
+        .collect();
+    }
+
+    let settings = query("SELECT name, value FROM instance_settings")
+        .fetch_all(&mut transaction)
+        .await?
+        .iter()
+        .map(|row| (row.get("name"), row.get("value")))
+        .collect();
+    transaction.commit().await?;
+
+    Ok(DataExport {
+        version: DataExport::VERSION,
+        exported_at: chrono::Local::now().naive_utc(),
+        users,
+        links,
+        tags: Vec::new(),
+        clicks,
+        api_tokens,
+        share_tokens,
+        invitations,
+        settings,
+    })
+}
+
+/// What was restored.
+#[derive(Debug, Default)]
+pub struct RestoreSummary {
+    pub users: usize,
+    pub links: usize,
+    pub clicks: usize,
+    /// The API tokens, share tokens and invitations.
+    pub tokens: usize,
+    /// The users that were exported without password and cannot log in until a new one is set.
+    pub without_password: Vec<String>,
+}
+
+impl Display for RestoreSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Restored {} users, {} links, {} clicks and {} tokens.",
+            self.users, self.links, self.clicks, self.tokens
+        )?;
+        if !self.without_password.is_empty() {
+            writeln!(
+                f,
+                "These users have no password and need a new one before they can log in:"
+            )?;
+            for username in &self.without_password {
+                writeln!(f, "  {username}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Restore an export into an empty database. The ids are kept so all references stay valid.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the export is invalid, the database is not empty or cannot be acessed.
+/// In that case nothing is written.
+pub(crate) async fn restore(data: &DataExport) -> Result<RestoreSummary, ServerFnError> {
+    data.validate().map_err(ServerFnError::new)?;
+    let db = crate::get_db().await;
+    let mut transaction = db.begin().await?;
+    let existing: i64 =
+        query("SELECT (SELECT count(*) FROM users) + (SELECT count(*) FROM links) AS number")
+            .fetch_one(&mut transaction)
+            .await?
+            .get("number");
+    if existing > 0 {
+        return Err(ServerFnError::new(
+            "The database is not empty, a restore needs a fresh database",
+        ));
+    }
+
+    let mut summary = RestoreSummary::default();
+    for user in &data.users {
+        if user.password.is_none() {
+            summary.without_password.push(user.username.clone());
+        }
+        query(
+            "INSERT INTO users (id, username, email, password, role, language, oidc_subject, ldap_dn)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(user.id)
+        .bind(&user.username)
+        .bind(&user.email)
+        // an empty hash never matches a password
+        .bind(user.password.as_deref().unwrap_or_default())
+        .bind(user.role.to_i64())
+        .bind(&user.language)
+        .bind(&user.oidc_subject)
+        .bind(&user.ldap_dn)
+        .execute(&mut transaction)
+        .await?;
+        if let Some(totp) = &user.totp {
+            query("INSERT INTO user_totp (user_id, secret, enabled_at, last_step) VALUES ($1, $2, $3, $4)")
+                .bind(user.id)
+                .bind(&totp.secret)
+                .bind(totp.enabled_at)
+                .bind(totp.last_step)
+                .execute(&mut transaction)
+                .await?;
+            for code in &totp.recovery_codes {
+                query("INSERT INTO totp_recovery_codes (owner, code_hash, used_at) VALUES ($1, $2, $3)")
+                    .bind(user.id)
+                    .bind(&code.code_hash)
+                    .bind(code.used_at)
+                    .execute(&mut transaction)
+                    .await?;
+            }
+        }
+        summary.users += 1;
+    }
+    for link in &data.links {
+        query(
+            "INSERT INTO links (id, title, target, code, author, created_at) VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(link.id)
+        .bind(&link.title)
+        .bind(&link.target)
+        .bind(&link.code)
+        .bind(link.author)
+        .bind(link.created_at)
+        .execute(&mut transaction)
+        .await?;
+        if let Some(style) = &link.qr_style {
+            query("INSERT INTO link_qr_styles (link, style) VALUES ($1, $2)")
+                .bind(link.id)
+                .bind(serde_json::to_string(style)?)
+                .execute(&mut transaction)
+                .await?;
+        }
+        summary.links += 1;
+    }
+    for click in &data.clicks {
+        query("INSERT INTO clicks (link, created_at, referrer, device) VALUES ($1, $2, $3, $4)")
+            .bind(click.link)
+            .bind(click.created_at)
+            .bind(&click.referrer)
+            .bind(&click.device)
+            .execute(&mut transaction)
+            .await?;
+        summary.clicks += 1;
+    }
+    for token in &data.api_tokens {
+        query(
+            "INSERT INTO api_tokens (owner, name, scope, token_hash, hint, created_at, expires_at, last_used_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(token.owner)
+        .bind(&token.name)
+        .bind(&token.scope)
+        .bind(&token.token_hash)
+        .bind(&token.hint)
+        .bind(token.created_at)
+        .bind(token.expires_at)
+        .bind(token.last_used_at)
+        .execute(&mut transaction)
+        .await?;
+        summary.tokens += 1;
+    }
+    for token in &data.share_tokens {
+        query("INSERT INTO share_tokens (link, nonce, created_at, expires_at) VALUES ($1, $2, $3, $4)")
+            .bind(token.link)
+            .bind(&token.nonce)
+            .bind(token.created_at)
+            .bind(token.expires_at)
+            .execute(&mut transaction)
+            .await?;
+        summary.tokens += 1;
+    }
+    for invitation in &data.invitations {
+        query(
+            "INSERT INTO invitations (created_by, token_hash, hint, email, role, created_at, expires_at, accepted_at, accepted_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(invitation.created_by)
+        .bind(&invitation.token_hash)
+        .bind(&invitation.hint)
+        .bind(&invitation.email)
+        .bind(invitation.role.to_i64())
+        .bind(invitation.created_at)
+        .bind(invitation.expires_at)
+        .bind(invitation.accepted_at)
+        .bind(invitation.accepted_by)
+        .execute(&mut transaction)
+        .await?;
+        summary.tokens += 1;
+    }
+    for (name, value) in &data.settings {
+        // the settings are not part of the empty check and may already be set
+        query(
+            "INSERT INTO instance_settings (name, value) VALUES ($1, $2)
+            ON CONFLICT (name) DO UPDATE SET value = excluded.value",
+        )
+        .bind(name)
+        .bind(value)
+        .execute(&mut transaction)
+        .await?;
+    }
+    if db.is_postgres() {
+        // postgres sequences do not notice explicitly written ids, move them past the restored ones.
+        for table in ["users", "links"] {
+            query(format!(
+                "SELECT setval(pg_get_serial_sequence('{table}', 'id'), COALESCE(MAX(id), 0) + 1, false) FROM {table}"
+            ))
+            .execute(&mut transaction)
+            .await?;
+        }
+    }
+    transaction.commit().await?;
+    info!(
+        "Restored {} users, {} links, {} clicks and {} tokens from an export of version {}",
+        summary.users, summary.links, summary.clicks, summary.tokens, data.version
+    );
+    Ok(summary)
+}
@@ -5,33 +5,32 @@ use crate::get_data_download_secret;
 #[cfg(feature = "server")]
 use dioxus::logger::tracing::info;
 use dioxus::prelude::*;
+use pslink_shared::apirequests::data_export::DataExportRequest;
 #[cfg(feature = "server")]
 use pslink_shared::apirequests::users::Role;
 
-/// Export the database with secret validation and admin authentication
+/// Export a consistent snapshot of the database with secret validation and admin authentication
 ///
 /// # Errors
 /// Fails with [`ServerFnError`] if:
-/// - User is not authenticated as admin
+/// - User is not logged in as admin, API tokens are not accepted
+/// - The admin still has to set up two-factor authentication
 /// - Invalid secret provided
-/// - Database file cannot be read
+/// - The database snapshot cannot be written
 #[server(ExportDatabase, endpoint = "export_database")]
 pub async fn export_database(secret: String) -> Result<Vec<u8>, ServerFnError> {
-    use std::path::Path;
-    use tokio::fs;
-
-    // Check if user is authenticated and is admin
-    let auth = crate::auth::get_session().await?;
-    let user = auth
-        .current_user
-        .ok_or_else(|| ServerFnError::new("Authentication required"))?
-        .get_user()
-        .ok_or_else(|| ServerFnError::new("User information not available"))?;
+    // Check if user is logged in and is admin, an API token must not be enough to copy all data
+    let user = crate::auth::get_session_user().await?;
 
     // Only admin users can access export functionality
     if user.role != Role::Admin {
         return Err(ServerFnError::new("Administrator permissions required"));
     }
+    if crate::totp::needs_enrollment(&user).await? {
+        return Err(ServerFnError::new(
+            "Set up two-factor authentication before exporting the database",
+        ));
+    }
 
     // Validate the secret
     if let Err(e) = validate_export_secret(&secret) {
@@ -39,25 +38,59 @@ pub async fn export_database(secret: String) -> Result<Vec<u8>, ServerFnError> {
         return Err(ServerFnError::new("Invalid secret"));
     }
 
-    // Get database path and read file
-    let db_path = crate::get_db_path().await;
-    
-    if !Path::new(&db_path).exists() {
-        return Err(ServerFnError::new("Database file not found"));
-    }
-
-    match fs::read(&db_path).await {
+    // A snapshot is consistent even while the server writes to the database
+    match crate::backup::snapshot().await {
         Ok(data) => {
-            info!("Database export successful for admin user: {}", user.username);
+            info!(
+                "Database export successful for admin user: {}",
+                user.username
+            );
             Ok(data)
         }
         Err(e) => {
-            info!("Failed to read database file: {}", e);
-            Err(ServerFnError::new("Failed to read database file"))
+            info!("Failed to write the database snapshot: {}", e);
+            Err(ServerFnError::new("Failed to create the database snapshot"))
         }
     }
 }
 
+/// Export all data as versioned JSON that can be restored with `pslink restore`.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if:
+/// - User is not logged in as admin, API tokens are not accepted
+/// - The admin still has to set up two-factor authentication
+/// - Invalid secret provided
+/// - Database cannot be read
+#[server(ExportData, endpoint = "export_data")]
+pub async fn export_data(request: DataExportRequest) -> Result<String, ServerFnError> {
+    let user = crate::auth::get_session_user().await?;
+    if user.role != Role::Admin {
+        return Err(ServerFnError::new("Administrator permissions required"));
+    }
+    if crate::totp::needs_enrollment(&user).await? {
+        return Err(ServerFnError::new(
+            "Set up two-factor authentication before exporting the data",
+        ));
+    }
+    if let Err(e) = validate_export_secret(&request.secret) {
+        info!(
+            "Invalid export secret provided by user {}: {}",
+            user.username, e
+        );
+        return Err(ServerFnError::new("Invalid secret"));
+    }
+
+    let export = crate::data_export::export(request.include_passwords).await?;
+    info!(
+        "JSON export for admin user {} with {} links, password hashes included: {}",
+        user.username,
+        export.links.len(),
+        request.include_passwords
+    );
+    Ok(serde_json::to_string_pretty(&export)?)
+}
+
 /// Validate the secret token for database export
 ///
 /// # Errors  
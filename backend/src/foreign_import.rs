@@ -0,0 +1,580 @@
+//! Import the links of other url shorteners with `pslink import --format <tool> <file>`.
+//!
+//! The supported files are:
+//! * YOURLS: a MySQL dump (`mysqldump` or a phpMyAdmin export) with the tables `yourls_url` and
+//!   `yourls_log`, other table prefixes work as well.
+//! * Polr: a MySQL dump with the tables `links` and `clicks`.
+//! * Shlink: the JSON response of `GET /rest/v3/short-urls` or its `data` array.
+//! * Kutt: the JSON response of `GET /api/v2/links` or its `data` array.
+//!
+//! The links keep their codes and creation dates. Recorded clicks are imported with their time and
+//! referrer. If a tool only knows the number of clicks they are recorded on the day the link was created.
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use dioxus::logger::tracing::info;
+use dioxus::prelude::ServerFnError;
+use pslink_shared::{apirequests::links::code_problems, datatypes::Link};
+
+use crate::models::LinkDbOperations as _;
+use crate::redirect_links::referrer_host;
+use crate::storage::query;
+
+/// The tools whose exports can be imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForeignFormat {
+    Yourls,
+    Shlink,
+    Kutt,
+    Polr,
+}
+
+impl ForeignFormat {
+    pub const NAMES: [&'static str; 4] = ["yourls", "shlink", "kutt", "polr"];
+}
+
+impl FromStr for ForeignFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "yourls" => Ok(Self::Yourls),
+            "shlink" => Ok(Self::Shlink),
+            "kutt" => Ok(Self::Kutt),
+            "polr" => Ok(Self::Polr),
+            _ => Err(format!("Unknown import format: {s}")),
+        }
+    }
+}
+
+/// The clicks of an imported link.
+#[derive(Debug, Default)]
+enum History {
+    #[default]
+    None,
+    /// Only the number of clicks is known.
+    Count(i64),
+    /// The time and the referrer host of every click.
+    Clicks(Vec<(NaiveDateTime, Option<String>)>),
+}
+
+/// A link read from the export of another tool.
+#[derive(Debug)]
+struct ForeignLink {
+    code: String,
+    target: String,
+    title: String,
+    created_at: Option<NaiveDateTime>,
+    history: History,
+}
+
+/// What happened during an import.
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub clicks: i64,
+    /// The code and the reason of entries that were not imported.
+    pub skipped: Vec<(String, String)>,
+    /// Codes that already exist in pslink or appear more than once.
+    pub conflicts: Vec<String>,
+}
+
+impl Display for ImportSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Imported {} links with {} clicks.",
+            self.imported, self.clicks
+        )?;
+        if !self.conflicts.is_empty() {
+            writeln!(
+                f,
+                "{} links were not imported because the code exists:",
+                self.conflicts.len()
+            )?;
+            for code in &self.conflicts {
+                writeln!(f, "  {code}")?;
+            }
+        }
+        if !self.skipped.is_empty() {
+            writeln!(f, "{} entries were skipped:", self.skipped.len())?;
+            for (code, reason) in &self.skipped {
+                writeln!(f, "  {code}: {reason}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A value of a row in a SQL dump.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SqlValue {
+    Null,
+    Text(String),
+}
+
+impl SqlValue {
+    fn text(&self) -> String {
+        match self {
+            Self::Null => String::new(),
+            Self::Text(text) => text.clone(),
+        }
+    }
+}
+
+type SqlRow = HashMap<String, SqlValue>;
+
+/// Split a SQL dump into statements, comments are removed.
+fn sql_statements(dump: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut chars = dump.chars().peekable();
+    let mut quote: Option<char> = None;
+    let mut line_start = true;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            current.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' | '`' => {
+                quote = Some(c);
+                current.push(c);
+            }
+            '-' if line_start && chars.peek() == Some(&'-') => {
+                for skipped in chars.by_ref() {
+                    if skipped == '\n' {
+                        break;
+                    }
+                }
+                line_start = true;
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                let mut previous = ' ';
+                for skipped in chars.by_ref() {
+                    if previous == '*' && skipped == '/' {
+                        break;
+                    }
+                    previous = skipped;
+                }
+            }
+            ';' => statements.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+        line_start = c == '\n' || (line_start && c.is_whitespace());
+    }
+    statements.push(current);
+    statements
+        .into_iter()
+        .map(|statement| statement.trim().to_string())
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+/// The name of a table or column without quotes.
+fn identifier(name: &str) -> String {
+    name.trim().trim_matches(['`', '"']).to_string()
+}
+
+/// Parse the tuples after `VALUES` of an insert statement.
+fn sql_tuples(values: &str) -> Result<Vec<Vec<SqlValue>>, String> {
+    let mut tuples = Vec::new();
+    let mut chars = values.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace() || *c == ',') {
+            chars.next();
+        }
+        match chars.next() {
+            None => return Ok(tuples),
+            Some('(') => {}
+            Some(c) => return Err(format!("Unexpected character {c} in the values")),
+        }
+        let mut tuple = Vec::new();
+        loop {
+            while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                chars.next();
+            }
+            if chars.peek() == Some(&'\'') {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Err("Unterminated string in the values".to_string()),
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('r') => text.push('\r'),
+                            Some('t') => text.push('\t'),
+                            Some('0') => text.push('\0'),
+                            Some(c) => text.push(c),
+                            None => return Err("Unterminated string in the values".to_string()),
+                        },
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            text.push('\'');
+                        }
+                        Some('\'') => break,
+                        Some(c) => text.push(c),
+                    }
+                }
+                tuple.push(SqlValue::Text(text));
+            } else {
+                let mut token = String::new();
+                while let Some(c) = chars.next_if(|c| *c != ',' && *c != ')') {
+                    token.push(c);
+                }
+                let token = token.trim();
+                if token.eq_ignore_ascii_case("null") {
+                    tuple.push(SqlValue::Null);
+                } else {
+                    tuple.push(SqlValue::Text(token.to_string()));
+                }
+            }
+            while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                chars.next();
+            }
+            match chars.next() {
+                Some(',') => {}
+                Some(')') => break,
+                _ => return Err("Unterminated tuple in the values".to_string()),
+            }
+        }
+        tuples.push(tuple);
+    }
+}
+
+/// Read the rows of all tables of a MySQL dump.
+///
+/// The column names are taken from the `CREATE TABLE` statements or the column list of the inserts.
+fn parse_sql_dump(dump: &str) -> Result<HashMap<String, Vec<SqlRow>>, String> {
+    let mut columns: HashMap<String, Vec<String>> = HashMap::new();
+    let mut tables: HashMap<String, Vec<SqlRow>> = HashMap::new();
+    for statement in sql_statements(dump) {
+        let upper = statement.to_uppercase();
+        if upper.starts_with("CREATE TABLE") {
+            let Some((head, body)) = statement.split_once('(') else {
+                continue;
+            };
+            let name = identifier(head.split_whitespace().last().unwrap_or_default());
+            let names = body
+                .lines()
+                .map(str::trim)
+                .filter(|line| line.starts_with('`') || line.starts_with('"'))
+                .filter_map(|line| line.split_whitespace().next())
+                .map(identifier)
+                .collect();
+            columns.insert(name, names);
+        } else if let Some(position) = ["INSERT INTO", "INSERT IGNORE INTO", "REPLACE INTO"]
+            .iter()
+            .find(|start| upper.starts_with(**start))
+            .map(|start| start.len())
+        {
+            let Some(values_position) = upper.find("VALUES") else {
+                continue;
+            };
+            let target = statement[position..values_position].trim();
+            let (name, names) = match target.split_once('(') {
+                Some((name, list)) => (
+                    identifier(name),
+                    list.trim_end_matches(')')
+                        .split(',')
+                        .map(identifier)
+                        .collect(),
+                ),
+                None => (
+                    identifier(target),
+                    columns
+                        .get(&identifier(target))
+                        .cloned()
+                        .unwrap_or_default(),
+                ),
+            };
+            if names.is_empty() {
+                return Err(format!("The columns of the table {name} are unknown"));
+            }
+            let rows = tables.entry(name).or_default();
+            for tuple in sql_tuples(&statement[values_position + "VALUES".len()..])? {
+                rows.push(names.iter().cloned().zip(tuple).collect());
+            }
+        }
+    }
+    Ok(tables)
+}
+
+/// Parse the date formats used by the other tools.
+fn parse_date(date: &str) -> Option<NaiveDateTime> {
+    let date = date.trim();
+    chrono::DateTime::parse_from_rfc3339(date)
+        .map(|date| date.naive_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+}
+
+fn field(row: &SqlRow, name: &str) -> String {
+    row.get(name).map(SqlValue::text).unwrap_or_default()
+}
+
+fn read_yourls(dump: &str) -> Result<Vec<ForeignLink>, String> {
+    let tables = parse_sql_dump(dump)?;
+    let urls = tables
+        .iter()
+        .find(|(name, _)| name.ends_with("url"))
+        .map(|(_, rows)| rows)
+        .ok_or("The dump contains no table yourls_url")?;
+    let mut clicks: HashMap<String, Vec<(NaiveDateTime, Option<String>)>> = HashMap::new();
+    if let Some((_, log)) = tables.iter().find(|(name, _)| name.ends_with("log")) {
+        for row in log {
+            if let Some(time) = parse_date(&field(row, "click_time")) {
+                // yourls logs clicks without a referrer as "direct"
+                let referrer = Some(field(row, "referrer")).filter(|referrer| referrer != "direct");
+                clicks
+                    .entry(field(row, "shorturl"))
+                    .or_default()
+                    .push((time, referrer.as_deref().and_then(referrer_host)));
+            }
+        }
+    }
+    Ok(urls
+        .iter()
+        .map(|row| {
+            let code = field(row, "keyword");
+            let history = match clicks.remove(&code) {
+                Some(clicks) => History::Clicks(clicks),
+                None => History::Count(field(row, "clicks").parse().unwrap_or_default()),
+            };
+            let title = field(row, "title");
+            ForeignLink {
+                target: field(row, "url"),
+                title: if title.is_empty() {
+                    code.clone()
+                } else {
+                    title
+                },
+                created_at: parse_date(&field(row, "timestamp")),
+                history,
+                code,
+            }
+        })
+        .collect())
+}
+
+fn read_polr(dump: &str) -> Result<Vec<ForeignLink>, String> {
+    let tables = parse_sql_dump(dump)?;
+    let links = tables
+        .get("links")
+        .ok_or("The dump contains no table links")?;
+    let mut clicks: HashMap<String, Vec<(NaiveDateTime, Option<String>)>> = HashMap::new();
+    for row in tables.get("clicks").into_iter().flatten() {
+        if let Some(time) = parse_date(&field(row, "created_at")) {
+            let referrer = Some(field(row, "referer_host")).filter(|host| !host.is_empty());
+            clicks
+                .entry(field(row, "link_id"))
+                .or_default()
+                .push((time, referrer));
+        }
+    }
+    Ok(links
+        .iter()
+        .map(|row| {
+            let code = field(row, "short_url");
+            let history = match clicks.remove(&field(row, "id")) {
+                Some(clicks) => History::Clicks(clicks),
+                None => History::Count(field(row, "clicks").parse().unwrap_or_default()),
+            };
+            ForeignLink {
+                target: field(row, "long_url"),
+                // polr links have no title
+                title: code.clone(),
+                created_at: parse_date(&field(row, "created_at")),
+                history,
+                code,
+            }
+        })
+        .collect())
+}
+
+/// The list of links in a JSON api response, either the whole response or only the list.
+fn json_entries(json: &str, path: &[&str]) -> Result<Vec<serde_json::Value>, String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if let serde_json::Value::Array(entries) = value {
+        return Ok(entries);
+    }
+    let mut current = &value;
+    for key in path {
+        current = current.get(key).unwrap_or(current);
+    }
+    match current {
+        serde_json::Value::Array(entries) => Ok(entries.clone()),
+        _ => Err("The file contains no list of links".to_string()),
+    }
+}
+
+fn json_text(value: &serde_json::Value, name: &str) -> String {
+    value
+        .get(name)
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn read_shlink(json: &str) -> Result<Vec<ForeignLink>, String> {
+    Ok(json_entries(json, &["shortUrls", "data"])?
+        .iter()
+        .map(|entry| {
+            let visits = entry
+                .pointer("/visitsSummary/total")
+                .or_else(|| entry.get("visitsCount"))
+                .and_then(serde_json::Value::as_i64)
+                .unwrap_or_default();
+            let code = json_text(entry, "shortCode");
+            let title = json_text(entry, "title");
+            ForeignLink {
+                target: json_text(entry, "longUrl"),
+                title: if title.is_empty() {
+                    code.clone()
+                } else {
+                    title
+                },
+                created_at: parse_date(&json_text(entry, "dateCreated")),
+                history: History::Count(visits),
+                code,
+            }
+        })
+        .collect())
+}
+
+fn read_kutt(json: &str) -> Result<Vec<ForeignLink>, String> {
+    Ok(json_entries(json, &["data"])?
+        .iter()
+        .map(|entry| {
+            let code = json_text(entry, "address");
+            let description = json_text(entry, "description");
+            ForeignLink {
+                target: json_text(entry, "target"),
+                title: if description.is_empty() {
+                    code.clone()
+                } else {
+                    description
+                },
+                created_at: parse_date(&json_text(entry, "created_at")),
+                history: History::Count(
+                    entry
+                        .get("visit_count")
+                        .and_then(serde_json::Value::as_i64)
+                        .unwrap_or_default(),
+                ),
+                code,
+            }
+        })
+        .collect())
+}
+
+/// Import the links of an export of another tool. All links are written in one transaction.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the file cannot be read or the database cannot be acessed.
+pub async fn import(
+    format: ForeignFormat,
+    content: &str,
+    author: i64,
+) -> Result<ImportSummary, ServerFnError> {
+    let links = match format {
+        ForeignFormat::Yourls => read_yourls(content),
+        ForeignFormat::Polr => read_polr(content),
+        ForeignFormat::Shlink => read_shlink(content),
+        ForeignFormat::Kutt => read_kutt(content),
+    }
+    .map_err(ServerFnError::new)?;
+    info!("Read {} links from the {:?} export", links.len(), format);
+
+    let mut summary = ImportSummary::default();
+    let mut seen = HashSet::new();
+    let mut accepted = Vec::with_capacity(links.len());
+    for link in links {
+        if let Some(problem) = code_problems(&link.code).first() {
+            summary.skipped.push((link.code, (*problem).to_string()));
+        } else if !link.target.contains("://") {
+            summary
+                .skipped
+                .push((link.code, "The target is not a URL".to_string()));
+        } else if !seen.insert(link.code.to_lowercase())
+            || Link::get_link_by_code(&link.code).await.is_ok()
+        {
+            summary.conflicts.push(link.code);
+        } else {
+            accepted.push(link);
+        }
+    }
+
+    let db = crate::get_db().await;
+    let mut transaction = db.begin().await?;
+    let now = chrono::Local::now().naive_utc();
+    for link in accepted {
+        let created_at = link.created_at.unwrap_or(now);
+        let row = query(
+            "INSERT INTO links (title, target, code, author, created_at) VALUES ($1, $2, $3, $4, $5)
+            RETURNING id",
+        )
+        .bind(&link.title)
+        .bind(&link.target)
+        .bind(&link.code)
+        .bind(author)
+        .bind(created_at)
+        .fetch_one(&mut transaction)
+        .await?;
+        let link_id: i64 = row.get("id");
+
+        match link.history {
+            History::None => {}
+            History::Count(count) if count > 0 => {
+                query(
+                    "WITH RECURSIVE numbers(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM numbers WHERE n < $1)
+                    INSERT INTO clicks (link, created_at) SELECT $2, $3 FROM numbers",
+                )
+                .bind(count)
+                .bind(link_id)
+                .bind(created_at)
+                .execute(&mut transaction)
+                .await?;
+                summary.clicks += count;
+            }
+            History::Count(_) => {}
+            History::Clicks(clicks) => {
+                for (time, referrer) in &clicks {
+                    query("INSERT INTO clicks (link, created_at, referrer) VALUES ($1, $2, $3)")
+                        .bind(link_id)
+                        .bind(time)
+                        .bind(referrer)
+                        .execute(&mut transaction)
+                        .await?;
+                }
+                summary.clicks += clicks.len() as i64;
+            }
+        }
+        summary.imported += 1;
+    }
+    transaction.commit().await?;
+    Ok(summary)
+}
+
+/// The id of the first administrator, imported links belong to them unless an author is given.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if there is no administrator.
+pub async fn first_admin() -> Result<i64, ServerFnError> {
+    let db = crate::get_db().await;
+    let row = query("SELECT id FROM users WHERE role = 2 ORDER BY id LIMIT 1")
+        .fetch_optional(&db)
+        .await?
+        .ok_or_else(|| ServerFnError::new("There is no administrator, use --author"))?;
+    Ok(row.get("id"))
+}
@@ -0,0 +1,83 @@
+//! Invite new users with single-use links.
+//!
+//! Administrators manage the invitations, the invitation page itself is public and only needs the
+//! token of the url.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use pslink_shared::datatypes::User;
+use pslink_shared::{
+    apirequests::users::{AcceptInvitation, InvitationInfo, LoginResult, Role},
+    datatypes::{Invitation, NewInvitation},
+};
+
+/// The logged in administrator.
+#[cfg(feature = "server")]
+async fn session_admin() -> Result<User, ServerFnError> {
+    let user = crate::auth::get_session_user().await?;
+    if user.role == Role::Admin {
+        Ok(user)
+    } else {
+        Err(ServerFnError::new("Administrator permissions required"))
+    }
+}
+
+/// Create an invitation that is valid for `valid_days` days.
+///
+/// The returned url is only shown this once.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is no administrator, the role cannot be given to new users
+/// or access to the database fails.
+#[server(CreateInvitation, endpoint = "create_invitation")]
+pub async fn create_invitation(
+    email: Option<String>,
+    role: Role,
+    valid_days: u32,
+) -> Result<NewInvitation, ServerFnError> {
+    let admin = session_admin().await?;
+    crate::invitations::create_invitation(&admin, email.as_deref(), role, valid_days).await
+}
+
+/// List the pending and expired invitations.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is no administrator or access to the database fails.
+#[server(ListInvitations, endpoint = "list_invitations")]
+pub async fn list_invitations() -> Result<Vec<Invitation>, ServerFnError> {
+    session_admin().await?;
+    crate::invitations::list_invitations().await
+}
+
+/// Revoke an invitation, its url cannot be used afterwards.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is no administrator or access to the database fails.
+#[server(RevokeInvitation, endpoint = "revoke_invitation")]
+pub async fn revoke_invitation(id: i64) -> Result<(), ServerFnError> {
+    let admin = session_admin().await?;
+    dioxus::logger::tracing::info!("{} revoked the invitation {}", admin.username, id);
+    crate::invitations::delete_invitation(id).await
+}
+
+/// What the invitation page shows, nobody has to be logged in.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the invitation is invalid or expired.
+#[server(GetInvitation, endpoint = "get_invitation")]
+pub async fn get_invitation(token: String) -> Result<InvitationInfo, ServerFnError> {
+    crate::invitations::invitation_info(&token).await
+}
+
+/// Create the account of an invitation and log in with it.
+///
+/// Administrators may have to set up two-factor authentication before the login is finished.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the invitation is invalid, the username is taken or the input is malformed.
+#[server(AcceptInvitationLink, endpoint = "accept_invitation")]
+pub async fn accept_invitation(data: AcceptInvitation) -> Result<LoginResult, ServerFnError> {
+    let session = crate::auth::get_session().await?;
+    let user = crate::invitations::accept_invitation(&data).await?;
+    crate::totp::begin_login(&session, user).await
+}
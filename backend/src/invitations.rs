@@ -0,0 +1,216 @@
+//! Invitation links for new users.
+//!
+//! An administrator creates an invitation with an optional email address and the role of the new
+//! account. The invitation url contains a long random token that is shown once, only its SHA-256 hash
+//! is stored. The invitee chooses username, password and language on the public invitation page. An
+//! invitation can only be accepted once and not after it expired, deleting it revokes it.
+use dioxus::logger::tracing::info;
+use dioxus::prelude::ServerFnError;
+use pslink_shared::{
+    apirequests::users::{AcceptInvitation, InvitationInfo, Role},
+    datatypes::{Invitation, NewInvitation, User},
+};
+
+use crate::models::{NewUser, UserDbOperations as _};
+use crate::storage::{DbRow, query};
+use crate::tokens::{hash_token, random_token};
+
+/// The number of random characters of a token.
+const TOKEN_LENGTH: usize = 32;
+/// The number of characters of the token that are stored to tell the invitations apart.
+const HINT_LENGTH: usize = 4;
+/// The answer for unknown, used, revoked and expired tokens alike.
+const INVALID: &str = "The invitation is invalid or expired";
+
+fn to_invitation(row: &DbRow) -> Invitation {
+    Invitation {
+        id: row.get("id"),
+        created_by: row.get("created_by_name"),
+        hint: row.get("hint"),
+        email: row.get("email"),
+        role: Role::convert(row.get("role")),
+        created_at: row.get("created_at"),
+        expires_at: row.get("expires_at"),
+    }
+}
+
+/// The public url of the page that accepts an invitation.
+fn url(token: &str) -> String {
+    format!("{}/app/invite/{}", crate::get_public_url(), token)
+}
+
+/// Create an invitation that is valid for `valid_days` days.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the role cannot be given to new users, the expiry is out of range or the
+/// database cannot be acessed.
+pub(crate) async fn create_invitation(
+    admin: &User,
+    email: Option<&str>,
+    role: Role,
+    valid_days: u32,
+) -> Result<NewInvitation, ServerFnError> {
+    if role != Role::Regular && role != Role::Admin {
+        return Err(ServerFnError::new(
+            "New users can only be regular users or administrators",
+        ));
+    }
+    if valid_days == 0 {
+        return Err(ServerFnError::new(
+            "An invitation has to be valid for at least one day",
+        ));
+    }
+    let email = email.map(str::trim).filter(|email| !email.is_empty());
+    let token = random_token(TOKEN_LENGTH);
+    let created_at = chrono::Local::now().naive_utc();
+    let expires_at = created_at
+        .checked_add_signed(chrono::Duration::days(i64::from(valid_days)))
+        .ok_or_else(|| ServerFnError::new(format!("{valid_days} days are too long")))?;
+    let db = crate::get_db().await;
+    let id: i64 = query(
+        "INSERT INTO invitations (created_by, token_hash, hint, email, role, created_at, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id",
+    )
+    .bind(admin.id)
+    .bind(hash_token(&token))
+    .bind(&token[..HINT_LENGTH])
+    .bind(email)
+    .bind(role.to_i64())
+    .bind(created_at)
+    .bind(expires_at)
+    .fetch_one(&db)
+    .await?
+    .get("id");
+    info!(
+        "{} created an invitation for a new {:?} user",
+        admin.username, role
+    );
+    Ok(NewInvitation {
+        invitation: Invitation {
+            id,
+            created_by: admin.username.clone(),
+            hint: token[..HINT_LENGTH].to_string(),
+            email: email.map(str::to_string),
+            role,
+            created_at,
+            expires_at,
+        },
+        url: url(&token),
+    })
+}
+
+/// List the invitations that were not accepted yet, pending and expired ones.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn list_invitations() -> Result<Vec<Invitation>, ServerFnError> {
+    let db = crate::get_db().await;
+    let invitations = query(
+        "SELECT invitations.*, users.username AS created_by_name
+        FROM invitations JOIN users ON invitations.created_by = users.id
+        WHERE invitations.accepted_at IS NULL
+        ORDER BY invitations.created_at DESC",
+    )
+    .fetch_all(&db)
+    .await?
+    .iter()
+    .map(to_invitation)
+    .collect();
+    Ok(invitations)
+}
+
+/// Revoke an invitation that was not accepted yet by deleting it.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn delete_invitation(id: i64) -> Result<(), ServerFnError> {
+    let db = crate::get_db().await;
+    query("DELETE FROM invitations WHERE id = $1 AND accepted_at IS NULL")
+        .bind(id)
+        .execute(&db)
+        .await?;
+    Ok(())
+}
+
+/// What the invitation page shows for a valid token.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the invitation is invalid or the database cannot be acessed.
+pub(crate) async fn invitation_info(token: &str) -> Result<InvitationInfo, ServerFnError> {
+    let db = crate::get_db().await;
+    let row = query(
+        "SELECT email, role, expires_at FROM invitations
+        WHERE token_hash = $1 AND accepted_at IS NULL AND expires_at > $2",
+    )
+    .bind(hash_token(token))
+    .bind(chrono::Local::now().naive_utc())
+    .fetch_optional(&db)
+    .await?
+    .ok_or_else(|| ServerFnError::new(INVALID))?;
+    Ok(InvitationInfo {
+        email: row.get("email"),
+        role: Role::convert(row.get("role")),
+        expires_at: row.get("expires_at"),
+    })
+}
+
+/// Create the account of an invitation and mark the invitation as used.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the invitation is invalid, the username is taken or the input is malformed.
+pub(crate) async fn accept_invitation(request: &AcceptInvitation) -> Result<User, ServerFnError> {
+    let username = request.username.trim();
+    let email = request.email.trim();
+    if username.is_empty() || email.is_empty() {
+        return Err(ServerFnError::new("Username and email cannot be empty"));
+    }
+    // longer usernames and passwords are refused at the login
+    if username.len() > 32 || request.password.len() > 32 {
+        return Err(ServerFnError::new("Username or password too long"));
+    }
+    if request.password.len() < 5 {
+        return Err(ServerFnError::new(
+            "The password needs at least 5 characters",
+        ));
+    }
+    if User::get_user_by_name(username).await.is_ok() {
+        return Err(ServerFnError::new("The username is already taken"));
+    }
+    let password = NewUser::hash_password(&request.password, &crate::get_secret())?;
+    let now = chrono::Local::now().naive_utc();
+    let db = crate::get_db().await;
+    let mut transaction = db.begin().await?;
+    // claiming the invitation first makes sure it is only used once
+    let invitation = query(
+        "UPDATE invitations SET accepted_at = $1
+        WHERE token_hash = $2 AND accepted_at IS NULL AND expires_at > $1
+        RETURNING id, role",
+    )
+    .bind(now)
+    .bind(hash_token(&request.token))
+    .fetch_optional(&mut transaction)
+    .await?
+    .ok_or_else(|| ServerFnError::new(INVALID))?;
+    let user_id: i64 = query(
+        "INSERT INTO users (username, email, password, role, language)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id",
+    )
+    .bind(username)
+    .bind(email)
+    .bind(password)
+    .bind(invitation.get::<i64>("role"))
+    .bind(request.language.to_string())
+    .fetch_one(&mut transaction)
+    .await?
+    .get("id");
+    query("UPDATE invitations SET accepted_by = $1 WHERE id = $2")
+        .bind(user_id)
+        .bind(invitation.get::<i64>("id"))
+        .execute(&mut transaction)
+        .await?;
+    transaction.commit().await?;
+    info!("{} accepted an invitation", username);
+    User::get_user(user_id).await
+}
@@ -0,0 +1,391 @@
+//! Authentication against an LDAP directory or Active Directory.
+//!
+//! A login searches the user with the configured filter, either anonymously or bound as a service
+//! account, and then binds as the found entry with the given password. Directory users get a pslink
+//! account on their first login that is kept up to date with the email and, if an admin group is
+//! configured, the admin role of the directory. Their local password is random so they can only log
+//! in with the directory password. Accounts are matched by the DN of the entry, an existing account
+//! with the same username is only linked if it has the email of the entry.
+use std::collections::HashMap;
+use std::time::Duration;
+
+use dioxus::logger::tracing::{info, warn};
+use dioxus::prelude::ServerFnError;
+use ldap3::{Ldap, LdapConnAsync, LdapConnSettings, Scope, SearchEntry};
+use pslink_shared::{apirequests::users::Role, datatypes::User};
+
+use crate::models::{NewUser, UserDbOperations as _};
+use crate::storage::query;
+
+/// The placeholder in the user filter that is replaced by the escaped username.
+pub(crate) const USERNAME_PLACEHOLDER: &str = "{username}";
+
+/// In which order the directory and the local passwords are checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LdapOrder {
+    /// The directory first, local passwords if the user is not in the directory or it is not reachable.
+    LdapFirst,
+    /// Local passwords first, the directory if the local password does not match.
+    LocalFirst,
+    /// Only the directory, local passwords are not accepted.
+    LdapOnly,
+}
+
+impl LdapOrder {
+    pub const NAMES: [&'static str; 3] = ["ldap-first", "local-first", "ldap-only"];
+
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ldap-first" => Some(Self::LdapFirst),
+            "local-first" => Some(Self::LocalFirst),
+            "ldap-only" => Some(Self::LdapOnly),
+            _ => None,
+        }
+    }
+}
+
+/// The settings of the directory, they are read from the command line or environment.
+#[derive(Debug, Clone)]
+pub struct LdapSettings {
+    /// `ldap://host:389` or `ldaps://host:636`.
+    pub url: String,
+    pub starttls: bool,
+    /// The service account that searches the users, the search is anonymous if it is not set.
+    pub bind_dn: Option<String>,
+    pub bind_password: Option<String>,
+    /// Where the users are searched.
+    pub base_dn: String,
+    /// The search filter, [`USERNAME_PLACEHOLDER`] is replaced by the username.
+    pub user_filter: String,
+    pub username_attribute: String,
+    pub email_attribute: String,
+    /// The attribute with the DNs of the groups of a user, e.g. `memberOf`.
+    pub group_attribute: String,
+    /// Members of this group are administrators, if it is not set the roles are managed in pslink.
+    pub admin_group: Option<String>,
+    pub order: LdapOrder,
+}
+
+/// A user as found in the directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LdapEntry {
+    pub dn: String,
+    pub username: String,
+    pub email: String,
+    pub groups: Vec<String>,
+}
+
+/// The result of a login against the directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LdapLogin {
+    Authenticated(LdapEntry),
+    WrongPassword,
+    UnknownUser,
+}
+
+/// The search filter for a username, the special characters of the username are escaped.
+#[must_use]
+pub(crate) fn user_filter(settings: &LdapSettings, username: &str) -> String {
+    settings
+        .user_filter
+        .replace(USERNAME_PLACEHOLDER, &ldap3::ldap_escape(username))
+}
+
+/// Map the attributes of a search result to a user, the attribute names are case insensitive.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the entry has no username or email.
+pub(crate) fn entry_from_attributes(
+    settings: &LdapSettings,
+    dn: &str,
+    attributes: &HashMap<String, Vec<String>>,
+) -> Result<LdapEntry, ServerFnError> {
+    let values = |name: &str| {
+        attributes
+            .iter()
+            .find(|(attribute, _)| attribute.eq_ignore_ascii_case(name))
+            .map(|(_, values)| values.clone())
+            .unwrap_or_default()
+    };
+    let first = |name: &str| {
+        values(name)
+            .into_iter()
+            .map(|value| value.trim().to_string())
+            .find(|value| !value.is_empty())
+            .ok_or_else(|| ServerFnError::new(format!("The entry {dn} has no {name} attribute")))
+    };
+    Ok(LdapEntry {
+        dn: dn.to_string(),
+        username: first(&settings.username_attribute)?,
+        email: first(&settings.email_attribute)?,
+        groups: values(&settings.group_attribute),
+    })
+}
+
+/// Whether the entry is in the admin group, `None` if the roles are not managed by the directory.
+#[must_use]
+pub(crate) fn is_admin(settings: &LdapSettings, entry: &LdapEntry) -> Option<bool> {
+    let admin_group = settings.admin_group.as_ref()?;
+    Some(
+        entry
+            .groups
+            .iter()
+            .any(|group| group.trim().eq_ignore_ascii_case(admin_group.trim())),
+    )
+}
+
+async fn connect(settings: &LdapSettings) -> Result<Ldap, ServerFnError> {
+    let connection_settings = LdapConnSettings::new()
+        .set_conn_timeout(Duration::from_secs(10))
+        .set_starttls(settings.starttls);
+    let (connection, ldap) = LdapConnAsync::with_settings(connection_settings, &settings.url)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to connect to {}: {e}", settings.url)))?;
+    ldap3::drive!(connection);
+    Ok(ldap)
+}
+
+/// Search the user with the service account or anonymously.
+async fn search(
+    ldap: &mut Ldap,
+    settings: &LdapSettings,
+    username: &str,
+) -> Result<Option<LdapEntry>, ServerFnError> {
+    if let Some(bind_dn) = &settings.bind_dn {
+        ldap.simple_bind(
+            bind_dn,
+            settings.bind_password.as_deref().unwrap_or_default(),
+        )
+        .await
+        .and_then(ldap3::LdapResult::success)
+        .map_err(|e| ServerFnError::new(format!("The service account was rejected: {e}")))?;
+    }
+    let attributes = [
+        settings.username_attribute.as_str(),
+        settings.email_attribute.as_str(),
+        settings.group_attribute.as_str(),
+    ];
+    let (entries, _) = ldap
+        .search(
+            &settings.base_dn,
+            Scope::Subtree,
+            &user_filter(settings, username),
+            attributes.to_vec(),
+        )
+        .await
+        .and_then(ldap3::SearchResult::success)
+        .map_err(|e| ServerFnError::new(format!("The search failed: {e}")))?;
+    match entries.as_slice() {
+        [] => Ok(None),
+        [entry] => {
+            let entry = SearchEntry::construct(entry.clone());
+            entry_from_attributes(settings, &entry.dn, &entry.attrs).map(Some)
+        }
+        _ => Err(ServerFnError::new(format!(
+            "The filter matches {} entries for {username}",
+            entries.len()
+        ))),
+    }
+}
+
+/// Check the username and password against the directory.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the directory cannot be reached or the search fails.
+pub(crate) async fn login(
+    settings: &LdapSettings,
+    username: &str,
+    password: &str,
+) -> Result<LdapLogin, ServerFnError> {
+    // a bind with an empty password is an unauthenticated bind that always succeeds
+    if username.is_empty() || password.is_empty() {
+        return Ok(LdapLogin::WrongPassword);
+    }
+    let mut ldap = connect(settings).await?;
+    let Some(entry) = search(&mut ldap, settings, username).await? else {
+        ldap.unbind().await.ok();
+        return Ok(LdapLogin::UnknownUser);
+    };
+    let bound = ldap
+        .simple_bind(&entry.dn, password)
+        .await
+        .map_err(|e| ServerFnError::new(format!("The bind failed: {e}")))?;
+    ldap.unbind().await.ok();
+    if bound.rc == 0 {
+        Ok(LdapLogin::Authenticated(entry))
+    } else {
+        info!("The directory rejected the password of {}", entry.dn);
+        Ok(LdapLogin::WrongPassword)
+    }
+}
+
+/// Find, link or create the pslink user of a directory entry and update the email and role.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the username belongs to an account that may not be linked or the
+/// database cannot be accessed.
+pub(crate) async fn provision_user(
+    settings: &LdapSettings,
+    entry: &LdapEntry,
+) -> Result<User, ServerFnError> {
+    let admin = is_admin(settings, entry);
+    let db = crate::get_db().await;
+    let existing = query("SELECT id FROM users WHERE ldap_dn = $1")
+        .bind(&entry.dn)
+        .fetch_optional(&db)
+        .await?;
+    let mut user = match existing {
+        Some(row) => User::get_user(row.get("id")).await?,
+        None => match User::get_user_by_name(&entry.username).await {
+            Ok(user) => {
+                if !user.email.eq_ignore_ascii_case(&entry.email) {
+                    warn!(
+                        "Refused to link the user {} to the directory entry {}",
+                        entry.username, entry.dn
+                    );
+                    return Err(ServerFnError::new(format!(
+                        "The username {} belongs to an account that is not linked to the directory",
+                        entry.username
+                    )));
+                }
+                let linked =
+                    query("UPDATE users SET ldap_dn = $1 WHERE id = $2 AND ldap_dn IS NULL")
+                        .bind(&entry.dn)
+                        .bind(user.id)
+                        .execute(&db)
+                        .await?;
+                if linked == 0 {
+                    return Err(ServerFnError::new(format!(
+                        "The username {} belongs to another directory entry",
+                        entry.username
+                    )));
+                }
+                info!("Linked the user {} to {}", entry.username, entry.dn);
+                user
+            }
+            Err(_) => {
+                let role = if admin == Some(true) {
+                    Role::Admin
+                } else {
+                    Role::Regular
+                };
+                // the account can only be used with the directory password
+                let random_password = pslink_shared::datatypes::Secret::random();
+                let password = NewUser::hash_password(
+                    random_password.secret.as_deref().unwrap_or_default(),
+                    &crate::get_secret(),
+                )?;
+                let id: i64 = query(
+                    "INSERT INTO users (username, email, password, role, ldap_dn)
+                    VALUES ($1, $2, $3, $4, $5)
+                    RETURNING id",
+                )
+                .bind(&entry.username)
+                .bind(&entry.email)
+                .bind(password)
+                .bind(role.to_i64())
+                .bind(&entry.dn)
+                .fetch_one(&db)
+                .await?
+                .get("id");
+                info!("Created the user {} from the directory", entry.username);
+                return User::get_user(id).await;
+            }
+        },
+    };
+    let mut changed = false;
+    if user.email != entry.email {
+        user.email = entry.email.clone();
+        changed = true;
+    }
+    match admin {
+        Some(true) if user.role == Role::Regular => {
+            user.role = Role::Admin;
+            changed = true;
+        }
+        Some(false) if user.role == Role::Admin => {
+            user.role = Role::Regular;
+            changed = true;
+        }
+        _ => {}
+    }
+    if changed {
+        user.update_user().await?;
+    }
+    Ok(user)
+}
+
+/// Log in with the directory and create or update the pslink user.
+///
+/// `Ok(None)` means the local passwords may be tried, because the user is not in the directory or
+/// the directory is not reachable.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the directory rejects the password.
+pub(crate) async fn authenticate(
+    settings: &LdapSettings,
+    username: &str,
+    password: &str,
+) -> Result<Option<User>, ServerFnError> {
+    match login(settings, username, password).await {
+        Ok(LdapLogin::Authenticated(entry)) => provision_user(settings, &entry).await.map(Some),
+        Ok(LdapLogin::WrongPassword) => Err(ServerFnError::new("Unauthorized")),
+        Ok(LdapLogin::UnknownUser) => Ok(None),
+        Err(e) => {
+            warn!("The directory is not available: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Go through a login step by step and print what happens, for `pslink ldap-test`.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] at the first step that fails.
+pub(crate) async fn test_configuration(
+    settings: &LdapSettings,
+    username: &str,
+    password: &str,
+) -> Result<(), ServerFnError> {
+    println!("Connecting to {}", settings.url);
+    let mut ldap = connect(settings).await?;
+    match &settings.bind_dn {
+        Some(bind_dn) => println!("Searching as {bind_dn}"),
+        None => println!("Searching anonymously"),
+    }
+    let filter = user_filter(settings, username);
+    println!("Searching {} with {}", settings.base_dn, filter);
+    let Some(entry) = search(&mut ldap, settings, username).await? else {
+        return Err(ServerFnError::new(format!("No entry matches {filter}")));
+    };
+    println!("Found {}", entry.dn);
+    println!("  username: {}", entry.username);
+    println!("  email:    {}", entry.email);
+    for group in &entry.groups {
+        println!("  group:    {group}");
+    }
+    match is_admin(settings, &entry) {
+        Some(true) => println!("The user is an administrator"),
+        Some(false) => println!("The user is not an administrator"),
+        None => println!("No admin group is configured, the role is managed in pslink"),
+    }
+    if password.is_empty() {
+        println!("No password was given, the bind is skipped");
+        return Ok(());
+    }
+    let bound = ldap
+        .simple_bind(&entry.dn, password)
+        .await
+        .map_err(|e| ServerFnError::new(format!("The bind failed: {e}")))?;
+    ldap.unbind().await.ok();
+    if bound.rc == 0 {
+        println!("The password is correct");
+        Ok(())
+    } else {
+        Err(ServerFnError::new(format!(
+            "The directory rejected the password: {}",
+            bound.text
+        )))
+    }
+}
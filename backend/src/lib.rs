@@ -1,14 +1,68 @@
 //! This crate contains all shared fullstack server functions.
 #[cfg(feature = "server")]
+mod api_tokens;
+#[cfg(feature = "server")]
 mod auth;
 #[cfg(feature = "server")]
+mod backup;
+#[cfg(feature = "server")]
+mod bulk_links;
+#[cfg(feature = "server")]
 mod cli;
 #[cfg(feature = "server")]
+mod click_export;
+#[cfg(feature = "server")]
+mod click_queue;
+#[cfg(feature = "server")]
+mod data_export;
+#[cfg(feature = "server")]
+mod foreign_import;
+#[cfg(feature = "server")]
+mod invitations;
+#[cfg(feature = "server")]
+mod ldap;
+#[cfg(feature = "server")]
+mod link_import;
+#[cfg(feature = "server")]
+mod login_limits;
+#[cfg(feature = "server")]
+mod mail;
+#[cfg(feature = "server")]
+mod metrics;
+#[cfg(feature = "server")]
 mod models;
+#[cfg(feature = "server")]
+mod oidc;
+#[cfg(feature = "server")]
+mod openapi;
+#[cfg(feature = "server")]
+mod password_reset;
+#[cfg(feature = "server")]
+mod qr_images;
+#[cfg(feature = "server")]
+mod rest_api;
+#[cfg(feature = "server")]
+mod share;
+#[cfg(feature = "server")]
+mod statistics;
+#[cfg(feature = "server")]
+mod storage;
+#[cfg(all(test, feature = "server"))]
+mod tests;
+#[cfg(feature = "server")]
+mod totp;
+#[cfg(feature = "server")]
+mod tokens;
 
 pub mod auth_api;
 pub mod export_api;
+pub mod invitation_api;
 pub mod link_api;
+pub mod metrics_api;
+pub mod qr_api;
+pub mod share_api;
+pub mod token_api;
+pub mod totp_api;
 #[cfg(feature = "server")]
 pub mod redirect_links;
 pub mod user_api;
@@ -17,26 +71,31 @@ pub mod user_api;
 use dioxus::logger::tracing::info;
 #[cfg(feature = "server")]
 use pslink_shared::datatypes::Secret;
-#[cfg(feature = "server")]
-use tokio::sync::OnceCell;
 
 #[cfg(feature = "server")]
 use std::sync::LazyLock;
-#[cfg(feature = "server")]
 
-static DB: LazyLock<OnceCell<sqlx::SqlitePool>> = LazyLock::new(|| OnceCell::new());
+/// The database, it can be replaced so that the tests can run against several databases.
 #[cfg(feature = "server")]
-pub(crate) async fn get_db() -> sqlx::SqlitePool {
-    let db = DB.get().expect("Failed to get DB").clone();
+static DB: std::sync::RwLock<Option<storage::Storage>> = std::sync::RwLock::new(None);
+#[cfg(feature = "server")]
+pub(crate) async fn get_db() -> storage::Storage {
+    let db = DB
+        .read()
+        .expect("Failed to get DB")
+        .clone()
+        .expect("Database not initialized");
     info!("Received DB connection");
     db
 }
 
+/// Connect to the SQLite file or PostgreSQL url.
 #[cfg(feature = "server")]
 async fn init_db(db_url: &str) {
-    DB.get_or_try_init(|| sqlx::SqlitePool::connect(db_url))
+    let storage = storage::Storage::connect(db_url)
         .await
         .expect("Failed to connect to Database");
+    *DB.write().expect("Failed to set DB") = Some(storage);
     info!("Connected to Database");
 }
 
@@ -53,6 +112,27 @@ pub(crate) fn init_secret(secret: Secret) {
     SECRET.set(secret).expect("Failed to initialize secret");
 }
 
+#[cfg(feature = "server")]
+static PUBLIC_URL: LazyLock<once_cell::sync::OnceCell<String>> =
+    LazyLock::new(|| once_cell::sync::OnceCell::new());
+
+/// The start of all short urls e.g. `https://example.com`
+#[cfg(feature = "server")]
+pub(crate) fn get_public_url() -> String {
+    PUBLIC_URL.get().expect("Public url not initialized").clone()
+}
+
+#[cfg(feature = "server")]
+pub(crate) fn init_public_url(protocol: &cli::Protocol, public_url: &str) {
+    PUBLIC_URL
+        .set(format!(
+            "{}://{}",
+            protocol,
+            public_url.trim_end_matches('/')
+        ))
+        .ok(); // Use ok() to ignore if already set
+}
+
 #[cfg(feature = "server")]
 static DATA_DOWNLOAD_SECRET: LazyLock<once_cell::sync::OnceCell<Secret>> =
     LazyLock::new(|| once_cell::sync::OnceCell::new());
@@ -71,6 +151,66 @@ pub(crate) fn init_data_download_secret(secret: Secret) {
         .expect("Failed to initialize data download secret");
 }
 
+#[cfg(feature = "server")]
+static OIDC_SETTINGS: LazyLock<once_cell::sync::OnceCell<oidc::OidcSettings>> =
+    LazyLock::new(|| once_cell::sync::OnceCell::new());
+
+/// The settings of the OpenID Connect provider, `None` if single sign-on is not configured.
+#[cfg(feature = "server")]
+pub(crate) fn get_oidc_settings() -> Option<oidc::OidcSettings> {
+    OIDC_SETTINGS.get().cloned()
+}
+
+#[cfg(feature = "server")]
+pub(crate) fn init_oidc_settings(settings: oidc::OidcSettings) {
+    OIDC_SETTINGS.set(settings).ok(); // Use ok() to ignore if already set
+}
+
+#[cfg(feature = "server")]
+static LDAP_SETTINGS: LazyLock<once_cell::sync::OnceCell<ldap::LdapSettings>> =
+    LazyLock::new(|| once_cell::sync::OnceCell::new());
+
+/// The settings of the LDAP directory, `None` if only local passwords are used.
+#[cfg(feature = "server")]
+pub(crate) fn get_ldap_settings() -> Option<ldap::LdapSettings> {
+    LDAP_SETTINGS.get().cloned()
+}
+
+#[cfg(feature = "server")]
+pub(crate) fn init_ldap_settings(settings: ldap::LdapSettings) {
+    LDAP_SETTINGS.set(settings).ok(); // Use ok() to ignore if already set
+}
+
+#[cfg(feature = "server")]
+static LOGIN_LIMITS: LazyLock<once_cell::sync::OnceCell<login_limits::LoginLimits>> =
+    LazyLock::new(|| once_cell::sync::OnceCell::new());
+
+/// The limits of failed logins, the defaults if they were not configured.
+#[cfg(feature = "server")]
+pub(crate) fn get_login_limits() -> login_limits::LoginLimits {
+    LOGIN_LIMITS.get().cloned().unwrap_or_default()
+}
+
+#[cfg(feature = "server")]
+pub(crate) fn init_login_limits(limits: login_limits::LoginLimits) {
+    LOGIN_LIMITS.set(limits).ok(); // Use ok() to ignore if already set
+}
+
+#[cfg(feature = "server")]
+static SMTP_SETTINGS: LazyLock<once_cell::sync::OnceCell<mail::SmtpSettings>> =
+    LazyLock::new(|| once_cell::sync::OnceCell::new());
+
+/// The settings of the SMTP server, `None` if no emails are sent.
+#[cfg(feature = "server")]
+pub(crate) fn get_smtp_settings() -> Option<mail::SmtpSettings> {
+    SMTP_SETTINGS.get().cloned()
+}
+
+#[cfg(feature = "server")]
+pub(crate) fn init_smtp_settings(settings: mail::SmtpSettings) {
+    SMTP_SETTINGS.set(settings).ok(); // Use ok() to ignore if already set
+}
+
 #[cfg(feature = "server")]
 pub fn launch_pslink(app: fn() -> Result<dioxus::prelude::VNode, dioxus::prelude::RenderError>) {
     dioxus::logger::init(dioxus::logger::tracing::Level::INFO).unwrap();
@@ -92,13 +232,14 @@ pub fn launch_pslink(app: fn() -> Result<dioxus::prelude::VNode, dioxus::prelude
                             .unwrap_or_else(|_| "8080".to_string())
                     );
                     tokio::select! {
-                        _ = tokio::signal::ctrl_c() => {
-                            println!("Received Ctrl+C, shutting down server...");
+                        _ = shutdown_signal() => {
+                            println!("Received shutdown signal, shutting down server...");
                         }
                         _ = server => {
                             println!("Server stopped");
                         }
                     }
+                    click_queue::shutdown().await;
                 }
                 Ok(None) => {
                     std::thread::sleep(std::time::Duration::from_millis(100));
@@ -113,6 +254,26 @@ pub fn launch_pslink(app: fn() -> Result<dioxus::prelude::VNode, dioxus::prelude
         });
 }
 
+/// Wait for Ctrl+C or (on unix) for the SIGTERM sent by e.g. systemd.
+#[cfg(feature = "server")]
+async fn shutdown_signal() {
+    let ctrl_c = tokio::signal::ctrl_c();
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .expect("Failed to install the SIGTERM handler");
+        tokio::select! {
+            _ = ctrl_c => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        ctrl_c.await.ok();
+    }
+}
+
 #[cfg(feature = "server")]
 async fn launch_server(
     app: fn() -> Result<dioxus::prelude::VNode, dioxus::prelude::RenderError>,
@@ -128,13 +289,39 @@ async fn launch_server(
     use dioxus_fullstack::ServeConfigBuilder;
 
     let pool = get_db().await;
+    init_public_url(&server_config.protocol, &server_config.public_url);
+
+    click_queue::start(
+        server_config.click_queue_size,
+        std::time::Duration::from_millis(server_config.click_flush_interval),
+    );
+    if let Some(settings) = server_config.oidc.clone() {
+        info!("Single sign-on with {}", settings.issuer);
+        init_oidc_settings(settings);
+    }
+    if let Some(settings) = server_config.ldap.clone() {
+        info!("Logins are checked with the directory {}", settings.url);
+        init_ldap_settings(settings);
+    }
+    init_login_limits(server_config.login_limits.clone());
+    login_limits::start_pruning(server_config.login_limits.clone());
+    if let Some(settings) = server_config.smtp.clone() {
+        info!("Emails are sent with the server {}", settings.host);
+        init_smtp_settings(settings);
+    }
+    if let Some(settings) = server_config.backup_settings() {
+        backup::start(
+            settings,
+            std::time::Duration::from_secs(server_config.backup_interval.saturating_mul(3600)),
+        );
+    }
 
     //This Defaults as normal Cookies.
     //To enable Private cookies for integrity, and authenticity please check the next Example.
     let session_config = SessionConfig::default().with_table_name("test_table");
     let auth_config = AuthConfig::<i64>::default().with_anonymous_user_id(Some(0));
-    let session_store = SessionStore::<axum_session_sqlx::SessionSqlitePool>::new(
-        Some(pool.clone().into()),
+    let session_store = SessionStore::<storage::SessionPool>::new(
+        Some(storage::SessionPool::from(&pool)),
         session_config,
     )
     .await
@@ -152,14 +339,27 @@ async fn launch_server(
     let axum_route = Router::new()
         // Server side render the application, serve static assets, and register server functions
         .nest("/app/", admin)
+        .route(
+            pslink_shared::apirequests::links::ClickExportQuery::PATH,
+            get(click_export::export_clicks),
+        )
+        .route(
+            pslink_shared::apirequests::links::QrSheetQuery::PATH,
+            get(qr_images::qr_sheet),
+        )
+        .nest(pslink_shared::apirequests::rest::PREFIX, rest_api::router())
+        .route(oidc::LOGIN_PATH, get(oidc::login))
+        .route(oidc::CALLBACK_PATH, get(oidc::callback))
+        .route("/stats/{token}", get(share::shared_statistics))
+        .route("/qr/{file}", get(qr_images::qr_image))
         .route("/{data}", get(redirect_links::redirect))
         .route("/", get(redirect_links::redirect_empty))
         .layer(
             axum_session_auth::AuthSessionLayer::<
                 auth::AuthAccount,
                 i64,
-                axum_session_sqlx::SessionSqlitePool,
-                sqlx::SqlitePool,
+                storage::SessionPool,
+                storage::Storage,
             >::new(Some(pool.clone()))
             .with_config(auth_config),
         )
@@ -177,23 +377,19 @@ async fn launch_server(
     .await
     .unwrap();
 
-    axum::serve(listener, axum_route.into_make_service())
-        .await
-        .unwrap();
+    // the address of the client is needed to limit failed logins
+    axum::serve(
+        listener,
+        axum_route.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
 
 #[cfg(feature = "server")]
 static DB_PATH: LazyLock<once_cell::sync::OnceCell<String>> =
     LazyLock::new(|| once_cell::sync::OnceCell::new());
 
-#[cfg(feature = "server")]
-pub(crate) async fn get_db_path() -> String {
-    DB_PATH
-        .get()
-        .expect("Database path not initialized")
-        .clone()
-}
-
 #[cfg(feature = "server")]
 pub(crate) fn init_db_path(path: String) {
     DB_PATH.set(path).ok(); // Use ok() to ignore if already set
@@ -9,8 +9,14 @@ use dioxus::prelude::{ServerFnError, server, server_fn};
 #[cfg(feature = "server")]
 use enum_map::EnumMap;
 use pslink_shared::{
-    apirequests::links::{LinkDelta, LinkRequestForm},
-    datatypes::{Clicks, FullLink, Item, Link, ListWithOwner},
+    apirequests::links::{
+        BulkLinkRequest, LinkDelta, LinkImportReport, LinkImportRequest, LinkRequestForm,
+        StatisticsPeriod,
+    },
+    datatypes::{
+        AuthorActivity, Clicks, FullLink, Item, Link, LinkClicks, LinkDetails, ListWithOwner,
+        MonthCount, StaleLink, WeekCount,
+    },
 };
 
 #[cfg(feature = "server")]
@@ -41,36 +47,31 @@ pub async fn list_all_allowed(
         .expect("not authenticated")
         .get_user()
         .expect("User is authenticated");
+    let all_links = query_links(&parameters).await?;
+    Ok(ListWithOwner {
+        user,
+        list: all_links,
+    })
+}
+
+/// Load the links matching the filter of the form with their author and number of clicks.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if access to the database fails.
+#[cfg(feature = "server")]
+pub(crate) async fn query_links(
+    parameters: &LinkRequestForm,
+) -> Result<Vec<FullLink>, ServerFnError> {
     let db = crate::get_db().await;
 
-    let mut querystring = "select
-                        links.id as lid,
-                        links.title as ltitle,
-                        links.target as ltarget,
-                        links.code as lcode,
-                        links.author as lauthor,
-                        links.created_at as ldate,
-                        users.id as usid,
-                        users.username as usern,
-                        users.email as uemail,
-                        users.role as urole,
-                        users.language as ulang,
-                        count(clicks.id) as counter
-                    from
-                        links
-                        join users on links.author = users.id
-                        left join clicks on links.id = clicks.link"
-        .to_string();
-    querystring.push_str(&generate_filter_sql(&parameters.filter));
-    querystring.push_str("\n GROUP BY links.id");
-    if let Some(order) = parameters.order {
-        querystring.push_str(&generate_order_sql(&order));
+    let mut querystring = listed_links_sql(&parameters.filter);
+    if let Some(order) = &parameters.order {
+        querystring.push_str(&generate_order_sql(order));
     }
     querystring.push_str(&format!("\n LIMIT {}", parameters.amount));
     querystring.push_str(&format!("\n OFFSET {}", parameters.offset));
 
-    use sqlx::Row;
-    let links = sqlx::query(&querystring)
+    let links = crate::storage::query(querystring)
         .fetch_all(&db)
         .await?
         .into_iter()
@@ -81,7 +82,7 @@ pub async fn list_all_allowed(
                 target: v.get("ltarget"),
                 code: v.get("lcode"),
                 author: v.get("lauthor"),
-                created_at: v.get("ldate"),
+                created_at: v.get_utc("ldate"),
             },
             user: User {
                 id: v.get("usid"),
@@ -95,12 +96,33 @@ pub async fn list_all_allowed(
                 number: v.get("counter"), /* count is never None */
             }),
         });
-    // show all links
-    let all_links: Vec<FullLink> = links.collect();
-    Ok(ListWithOwner {
-        user,
-        list: all_links,
-    })
+    Ok(links.collect())
+}
+
+/// The links with their author and number of clicks, filtered but not ordered or limited.
+#[cfg(feature = "server")]
+fn listed_links_sql(filters: &EnumMap<LinkOverviewColumns, Filter>) -> String {
+    let mut querystring = "select * from (select
+                        links.id as lid,
+                        links.title as ltitle,
+                        links.target as ltarget,
+                        links.code as lcode,
+                        links.author as lauthor,
+                        links.created_at as ldate,
+                        users.id as usid,
+                        users.username as usern,
+                        users.email as uemail,
+                        users.role as urole,
+                        users.language as ulang,
+                        count(clicks.id) as counter
+                    from
+                        links
+                        join users on links.author = users.id
+                        left join clicks on links.id = clicks.link
+                    GROUP BY links.id, users.id) AS listed"
+        .to_string();
+    querystring.push_str(&generate_filter_sql(filters));
+    querystring
 }
 
 /// Generate a filter statement for the SQL-Query according to the parameters...
@@ -113,25 +135,29 @@ fn generate_filter_sql(filters: &EnumMap<LinkOverviewColumns, Filter>) -> String
         .iter()
         .filter_map(|(column, sieve)| {
             // avoid sql injections
-            let sieve: String = sieve.chars().filter(|x| x.is_alphanumeric()).collect();
+            let sieve: String = sieve
+                .chars()
+                .filter(|x| x.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase();
             if sieve.is_empty() {
                 None
             } else {
                 Some(match column {
                     LinkOverviewColumns::Code => {
-                        format!("\n lcode LIKE '%{}%'", sieve)
+                        format!("\n LOWER(lcode) LIKE '%{}%'", sieve)
                     }
                     LinkOverviewColumns::Description => {
-                        format!("\n ltitle LIKE '%{}%'", sieve)
+                        format!("\n LOWER(ltitle) LIKE '%{}%'", sieve)
                     }
                     LinkOverviewColumns::Target => {
-                        format!("\n ltarget LIKE '%{}%'", sieve)
+                        format!("\n LOWER(ltarget) LIKE '%{}%'", sieve)
                     }
                     LinkOverviewColumns::Author => {
-                        format!("\n usern LIKE '%{}%'", sieve)
+                        format!("\n LOWER(usern) LIKE '%{}%'", sieve)
                     }
                     LinkOverviewColumns::Statistics => {
-                        format!("\n counter LIKE '%{}%'", sieve)
+                        format!("\n CAST(counter AS TEXT) LIKE '%{}%'", sieve)
                     }
                 })
             }
@@ -290,3 +316,125 @@ pub async fn get_link_statistics(link_id: i64) -> Result<Clicks, ServerFnError>
 
     Ok(Clicks::Extended(stats))
 }
+
+/// Get the clicks on all links of the instance per week during the last year.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated or access to the database fails.
+#[server(GetInstanceStatistics, endpoint = "get_instance_statistics")]
+pub async fn get_instance_statistics() -> Result<Vec<WeekCount>, ServerFnError> {
+    crate::auth::get_authenticated_user().await?;
+    crate::statistics::clicks_per_week().await
+}
+
+/// Get the number of links created per month during the last two years.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated or access to the database fails.
+#[server(GetLinksPerMonth, endpoint = "get_links_per_month")]
+pub async fn get_links_per_month() -> Result<Vec<MonthCount>, ServerFnError> {
+    crate::auth::get_authenticated_user().await?;
+    crate::statistics::links_per_month().await
+}
+
+/// Get the `amount` most clicked links of the period.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated or access to the database fails.
+#[server(GetTopLinks, endpoint = "get_top_links")]
+pub async fn get_top_links(
+    period: StatisticsPeriod,
+    amount: i64,
+) -> Result<Vec<LinkClicks>, ServerFnError> {
+    crate::auth::get_authenticated_user().await?;
+    crate::statistics::top_links(period, amount.clamp(1, 100)).await
+}
+
+/// Get the `amount` most active authors of the period.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated or access to the database fails.
+#[server(GetTopAuthors, endpoint = "get_top_authors")]
+pub async fn get_top_authors(
+    period: StatisticsPeriod,
+    amount: i64,
+) -> Result<Vec<AuthorActivity>, ServerFnError> {
+    crate::auth::get_authenticated_user().await?;
+    crate::statistics::top_authors(period, amount.clamp(1, 100)).await
+}
+
+/// Get the links that were not clicked during the last `months` months as candidates for a cleanup.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated or access to the database fails.
+#[server(GetStaleLinks, endpoint = "get_stale_links")]
+pub async fn get_stale_links(months: u32, amount: i64) -> Result<Vec<StaleLink>, ServerFnError> {
+    crate::auth::get_authenticated_user().await?;
+    crate::statistics::stale_links(months.max(1), amount.clamp(1, 500)).await
+}
+
+/// Get the detailed statistics of a single link for its detail page.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated, the link does not exist or access to the database fails.
+#[server(GetLinkDetails, endpoint = "get_link_details")]
+pub async fn get_link_details(link_id: i64) -> Result<LinkDetails, ServerFnError> {
+    crate::auth::get_authenticated_user().await?;
+    crate::statistics::link_details(link_id).await
+}
+
+/// Import many links from a CSV or JSON file.
+///
+/// With `dry_run` only the report is created. Otherwise the links are written in a single transaction
+/// if no row is invalid.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated, the file cannot be parsed or access to the database fails.
+#[server(ImportLinks, endpoint = "import_links")]
+pub async fn import_links(request: LinkImportRequest) -> Result<LinkImportReport, ServerFnError> {
+    let user = crate::auth::get_authenticated_user().await?;
+    let links = crate::link_import::parse(request.format, &request.data)?;
+    crate::link_import::import(&user, links, request.policy, request.dry_run).await
+}
+
+/// The ids of all links matching the filter of the form, the ordering, offset and amount are ignored.
+///
+/// Used to select all matching links and not only the loaded ones.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated or access to the database fails.
+#[server(ListMatchingLinkIds, endpoint = "list_matching_link_ids")]
+pub async fn list_matching_link_ids(
+    parameters: LinkRequestForm,
+) -> Result<Vec<i64>, ServerFnError> {
+    crate::auth::get_authenticated_user().await?;
+    let db = crate::get_db().await;
+    let querystring = format!("{}\n ORDER BY lid", listed_links_sql(&parameters.filter));
+    let ids = crate::storage::query(querystring)
+        .fetch_all(&db)
+        .await?
+        .iter()
+        .map(|row| row.get("lid"))
+        .collect();
+    Ok(ids)
+}
+
+/// Delete or transfer many links in one transaction, see [`BulkLinkRequest`]. Returns the number of changed links.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated, may not edit one of the links or access to the database fails.
+#[server(ApplyBulkLinkOperation, endpoint = "bulk_link_operation")]
+pub async fn bulk_link_operation(request: BulkLinkRequest) -> Result<usize, ServerFnError> {
+    let user = crate::auth::get_authenticated_user().await?;
+    crate::bulk_links::apply(&user, &request.links, &request.operation).await
+}
+
+/// Export the links as CSV in the format of the link import.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated, may not edit one of the links or access to the database fails.
+#[server(ExportLinks, endpoint = "export_links")]
+pub async fn export_links(links: Vec<i64>) -> Result<String, ServerFnError> {
+    let user = crate::auth::get_authenticated_user().await?;
+    crate::bulk_links::export(&user, &links).await
+}
@@ -0,0 +1,316 @@
+//! Import many links at once from CSV or JSON files.
+//!
+//! Every import is validated first and the report tells what happens to every row. Only if no row
+//! is invalid and it is not a dry run the links are written, all of them in a single transaction.
+use std::collections::HashSet;
+
+use dioxus::logger::tracing::info;
+use dioxus::prelude::ServerFnError;
+use pslink_shared::{
+    apirequests::{
+        links::{
+            ImportConflictPolicy, ImportRowAction, ImportRowReport, LinkImportFormat,
+            LinkImportReport, code_problems,
+        },
+        users::Role,
+    },
+    datatypes::{Link, User},
+};
+
+use crate::models::{LinkDbOperations as _, UserDbOperations as _};
+use crate::storage::query;
+
+/// A link read from an import file.
+#[derive(Debug, Clone)]
+pub(crate) struct ImportedLink {
+    /// The line in the CSV file or the index in the JSON array, starting with 1.
+    pub row: usize,
+    pub title: String,
+    pub target: String,
+    pub code: String,
+    pub tags: bool,
+    pub author: Option<String>,
+}
+
+/// What is written to the database for a row.
+enum Change {
+    Insert {
+        link: ImportedLink,
+        author: i64,
+    },
+    Update {
+        id: i64,
+        link: ImportedLink,
+        author: i64,
+    },
+    Nothing,
+}
+
+fn parse_csv(data: &str) -> Result<Vec<ImportedLink>, ServerFnError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(data.as_bytes());
+    let headers: Vec<String> = reader.headers()?.iter().map(str::to_lowercase).collect();
+    let column = |name: &str| headers.iter().position(|header| header == name);
+    let (Some(title), Some(target), Some(code)) =
+        (column("title"), column("target"), column("code"))
+    else {
+        return Err(ServerFnError::new(
+            "The CSV file needs the columns title, target and code",
+        ));
+    };
+    let (tags, author) = (column("tags"), column("author"));
+
+    let mut links = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record?;
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|index| record.get(index))
+                .unwrap_or_default()
+                .to_string()
+        };
+        links.push(ImportedLink {
+            // the header is the first line
+            row: i + 2,
+            title: field(Some(title)),
+            target: field(Some(target)),
+            code: field(Some(code)),
+            tags: !field(tags).is_empty(),
+            author: Some(field(author)).filter(|author| !author.is_empty()),
+        });
+    }
+    Ok(links)
+}
+
+fn parse_json(data: &str) -> Result<Vec<ImportedLink>, ServerFnError> {
+    let entries: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_str(data)?;
+    Ok(entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let field = |name: &str| {
+                entry
+                    .get(name)
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            };
+            ImportedLink {
+                row: i + 1,
+                title: field("title"),
+                target: field("target"),
+                code: field("code"),
+                tags: entry.get("tags").is_some_and(|tags| match tags {
+                    serde_json::Value::Array(tags) => !tags.is_empty(),
+                    serde_json::Value::String(tags) => !tags.is_empty(),
+                    _ => false,
+                }),
+                author: Some(field("author")).filter(|author| !author.is_empty()),
+            }
+        })
+        .collect())
+}
+
+/// Read the links of an uploaded file.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the file cannot be parsed or required columns are missing.
+pub(crate) fn parse(
+    format: LinkImportFormat,
+    data: &str,
+) -> Result<Vec<ImportedLink>, ServerFnError> {
+    match format {
+        LinkImportFormat::Csv => parse_csv(data),
+        LinkImportFormat::Json => parse_json(data),
+    }
+}
+
+/// Find a code like `code-2` that is neither in the database nor used by the import.
+///
+/// Codes are resolved without regard to case, so `used` holds lowercase codes.
+async fn free_code(code: &str, used: &HashSet<String>) -> String {
+    let mut number = 2;
+    loop {
+        let candidate = format!("{code}-{number}");
+        if !used.contains(&candidate.to_lowercase())
+            && Link::get_link_by_code(&candidate).await.is_err()
+        {
+            return candidate;
+        }
+        number += 1;
+    }
+}
+
+/// Validate the links and decide what happens to every row.
+async fn plan(
+    user: &User,
+    links: Vec<ImportedLink>,
+    policy: ImportConflictPolicy,
+) -> Result<(Vec<ImportRowReport>, Vec<Change>), ServerFnError> {
+    // `Foo` and `foo` are the same link, so the codes are compared in lowercase
+    let mut used: HashSet<String> = links.iter().map(|link| link.code.to_lowercase()).collect();
+    let mut seen = HashSet::new();
+    let mut reports = Vec::with_capacity(links.len());
+    let mut changes = Vec::with_capacity(links.len());
+
+    for link in links {
+        let mut errors: Vec<String> = code_problems(&link.code)
+            .into_iter()
+            .map(ToString::to_string)
+            .collect();
+        let mut warnings = Vec::new();
+        if !link.target.contains("://") {
+            errors.push("The target is not a URL".to_string());
+        }
+        if !seen.insert(link.code.to_lowercase()) {
+            errors.push("The code appears more than once in the file".to_string());
+        }
+        if link.tags {
+            warnings.push("Tags are not supported and were ignored".to_string());
+        }
+        let author = match &link.author {
+            None => user.id,
+            Some(_) if user.role != Role::Admin => {
+                errors.push("Only administrators can set the author".to_string());
+                user.id
+            }
+            Some(name) => match User::get_user_by_name(name).await {
+                Ok(author) => author.id,
+                Err(_) => {
+                    errors.push(format!("The author {name} does not exist"));
+                    user.id
+                }
+            },
+        };
+
+        let existing = Link::get_link_by_code(&link.code).await.ok();
+        let (action, change) = match existing {
+            _ if !errors.is_empty() => (ImportRowAction::Invalid, Change::Nothing),
+            None => (
+                ImportRowAction::Create,
+                Change::Insert {
+                    link: link.clone(),
+                    author,
+                },
+            ),
+            Some(_) if policy == ImportConflictPolicy::Skip => {
+                warnings.push("A link with this code exists and is kept".to_string());
+                (ImportRowAction::Skip, Change::Nothing)
+            }
+            Some(existing) if policy == ImportConflictPolicy::Overwrite => {
+                if user.role != Role::Admin && existing.author != user.id {
+                    errors.push("The existing link belongs to another user".to_string());
+                    (ImportRowAction::Invalid, Change::Nothing)
+                } else {
+                    (
+                        ImportRowAction::Overwrite,
+                        Change::Update {
+                            id: existing.id,
+                            link: link.clone(),
+                            // keep the author unless a new one is given
+                            author: if link.author.is_some() {
+                                author
+                            } else {
+                                existing.author
+                            },
+                        },
+                    )
+                }
+            }
+            Some(_) => {
+                let code = free_code(&link.code, &used).await;
+                used.insert(code.to_lowercase());
+                let mut renamed = link.clone();
+                renamed.code.clone_from(&code);
+                (
+                    ImportRowAction::Rename(code),
+                    Change::Insert {
+                        link: renamed,
+                        author,
+                    },
+                )
+            }
+        };
+
+        errors.extend(warnings);
+        reports.push(ImportRowReport {
+            row: link.row,
+            code: link.code,
+            title: link.title,
+            target: link.target,
+            action,
+            messages: errors,
+        });
+        changes.push(change);
+    }
+    Ok((reports, changes))
+}
+
+/// Write all changes in a single transaction.
+async fn apply(changes: Vec<Change>) -> Result<(), ServerFnError> {
+    let db = crate::get_db().await;
+    let mut transaction = db.begin().await?;
+    let now = chrono::Local::now().naive_utc();
+    for change in changes {
+        match change {
+            Change::Insert { link, author } => {
+                query(
+                    "INSERT INTO links (title, target, code, author, created_at) VALUES ($1, $2, $3, $4, $5)",
+                )
+                .bind(link.title)
+                .bind(link.target)
+                .bind(link.code)
+                .bind(author)
+                .bind(now)
+                .execute(&mut transaction)
+                .await?;
+            }
+            Change::Update { id, link, author } => {
+                query("UPDATE links SET title = $1, target = $2, author = $3 WHERE id = $4")
+                    .bind(link.title)
+                    .bind(link.target)
+                    .bind(author)
+                    .bind(id)
+                    .execute(&mut transaction)
+                    .await?;
+            }
+            Change::Nothing => {}
+        }
+    }
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Validate the imported links and write them if there are no errors and it is no dry run.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn import(
+    user: &User,
+    links: Vec<ImportedLink>,
+    policy: ImportConflictPolicy,
+    dry_run: bool,
+) -> Result<LinkImportReport, ServerFnError> {
+    let (rows, changes) = plan(user, links, policy).await?;
+    let mut report = LinkImportReport {
+        rows,
+        applied: false,
+    };
+    if !dry_run && !report.has_errors() {
+        apply(changes).await?;
+        report.applied = true;
+        info!(
+            "{} imported {} links",
+            user.username,
+            report
+                .rows
+                .iter()
+                .filter(|row| row.action != ImportRowAction::Skip)
+                .count()
+        );
+    }
+    Ok(report)
+}
@@ -0,0 +1,540 @@
+//! Protection of the login against guessing passwords.
+//!
+//! Failed logins are counted per username and per client address. After every failure the next
+//! attempt has to wait twice as long, after too many failures the username or address is locked for a
+//! while. Counters older than the lockout duration are forgotten. All logins, refusals and lockouts
+//! are written to the `login_events` audit trail, which is pruned after [`LoginLimits::event_days`].
+//!
+//! Every attempt is counted as a failure with [`reserve`] before the password is checked, so parallel
+//! requests cannot try more passwords than the limit allows. A correct password releases the attempt.
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{HeaderMap, request::Parts},
+};
+use dioxus::logger::tracing::{error, info, warn};
+use dioxus::prelude::ServerFnError;
+use pslink_shared::datatypes::{LoginEvent, LoginEventKind, LoginLockout};
+
+use crate::storage::query;
+
+/// The message of every refused login, it does not tell whether the username exists.
+pub(crate) const LOGIN_FAILED: &str = "Wrong username or password";
+/// The message when a login is refused because of earlier failures.
+pub(crate) const LOGIN_BLOCKED: &str = "Too many failed logins, please try again later";
+
+const KIND_USER: &str = "user";
+const KIND_IP: &str = "ip";
+
+/// The longest lockout, one year. Longer durations would overflow the dates of the lockouts.
+pub const MAX_LOCKOUT_MINUTES: u64 = 525_600;
+
+/// The limits of failed logins, they are read from the command line or environment.
+#[derive(Debug, Clone)]
+pub struct LoginLimits {
+    /// Failures of a username until it is locked, `0` disables the limit.
+    pub max_failures: u32,
+    /// Failures from a client address until it is locked, `0` disables the limit.
+    pub max_failures_per_ip: u32,
+    /// The wait after the first failure, it doubles with every further failure.
+    pub backoff_seconds: u64,
+    /// The minutes a username or address stays locked, at most [`MAX_LOCKOUT_MINUTES`].
+    pub lockout_minutes: u64,
+    /// Take the client address from the `X-Forwarded-For` header of a reverse proxy.
+    pub trust_forwarded_for: bool,
+    /// The days the audit trail is kept, `0` keeps it forever.
+    pub event_days: u64,
+}
+
+impl Default for LoginLimits {
+    fn default() -> Self {
+        Self {
+            max_failures: 5,
+            max_failures_per_ip: 20,
+            backoff_seconds: 1,
+            lockout_minutes: 15,
+            trust_forwarded_for: false,
+            event_days: 90,
+        }
+    }
+}
+
+impl LoginLimits {
+    fn lockout(&self) -> chrono::Duration {
+        let minutes = self.lockout_minutes.min(MAX_LOCKOUT_MINUTES);
+        chrono::Duration::minutes(i64::try_from(minutes).unwrap_or_default())
+    }
+
+    /// The wait after `failures` failures, at most the lockout duration.
+    #[must_use]
+    pub(crate) fn backoff(&self, failures: i64) -> chrono::Duration {
+        let exponent = u32::try_from(failures.saturating_sub(1).clamp(0, 30)).unwrap_or(30);
+        let seconds = self.backoff_seconds.saturating_mul(1u64 << exponent);
+        chrono::Duration::seconds(i64::try_from(seconds).unwrap_or(i64::MAX / 1000))
+            .min(self.lockout())
+    }
+}
+
+/// The address of the client that sent the request.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ClientIp(pub Option<IpAddr>);
+
+impl ClientIp {
+    pub(crate) fn from_parts(
+        headers: &HeaderMap,
+        peer: Option<IpAddr>,
+        trust_forwarded_for: bool,
+    ) -> Self {
+        let forwarded = trust_forwarded_for
+            .then(|| headers.get("x-forwarded-for"))
+            .flatten()
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .and_then(|first| first.trim().parse().ok());
+        Self(forwarded.or(peer))
+    }
+
+    fn as_string(self) -> Option<String> {
+        self.0.map(|ip| ip.to_string())
+    }
+}
+
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|info| info.0.ip());
+        Ok(Self::from_parts(
+            &parts.headers,
+            peer,
+            crate::get_login_limits().trust_forwarded_for,
+        ))
+    }
+}
+
+fn now() -> chrono::NaiveDateTime {
+    chrono::Local::now().naive_utc()
+}
+
+/// Usernames are counted case insensitive so variants of a name share the counter.
+fn user_subject(username: &str) -> String {
+    username.trim().to_lowercase()
+}
+
+/// Write an entry of the audit trail, failures to write are only logged.
+pub(crate) async fn record_event(
+    kind: LoginEventKind,
+    username: &str,
+    ip: ClientIp,
+    detail: Option<&str>,
+) {
+    let db = crate::get_db().await;
+    if let Err(e) = query(
+        "INSERT INTO login_events (created_at, kind, username, ip, detail) VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(now())
+    .bind(kind.as_str())
+    .bind(username)
+    .bind(ip.as_string())
+    .bind(detail)
+    .execute(&db)
+    .await
+    {
+        warn!("Failed to write the login event: {}", e);
+    }
+}
+
+/// The time until which the logins of a subject are refused.
+async fn blocked_until(
+    limits: &LoginLimits,
+    kind: &str,
+    subject: &str,
+    max_failures: u32,
+) -> Result<Option<chrono::NaiveDateTime>, ServerFnError> {
+    if max_failures == 0 {
+        return Ok(None);
+    }
+    let db = crate::get_db().await;
+    let Some(row) = query(
+        "SELECT failures, last_failure, locked_until FROM login_failures WHERE kind = $1 AND subject = $2",
+    )
+    .bind(kind)
+    .bind(subject)
+    .fetch_optional(&db)
+    .await?
+    else {
+        return Ok(None);
+    };
+    let failures: i64 = row.get("failures");
+    let last_failure: chrono::NaiveDateTime = row.get("last_failure");
+    let until = row
+        .get::<Option<chrono::NaiveDateTime>>("locked_until")
+        .unwrap_or(last_failure + limits.backoff(failures));
+    Ok((until > now()).then_some(until))
+}
+
+/// Refuse the login if the username or the client address has to wait.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the login is refused or the database cannot be acessed.
+async fn check(limits: &LoginLimits, username: &str, ip: ClientIp) -> Result<(), ServerFnError> {
+    let mut until = blocked_until(
+        limits,
+        KIND_USER,
+        &user_subject(username),
+        limits.max_failures,
+    )
+    .await?;
+    if let Some(address) = ip.as_string() {
+        until =
+            until.max(blocked_until(limits, KIND_IP, &address, limits.max_failures_per_ip).await?);
+    }
+    match until {
+        Some(until) => {
+            record_event(
+                LoginEventKind::Blocked,
+                username,
+                ip,
+                Some(&format!(
+                    "refused until {}",
+                    until.format("%Y-%m-%d %H:%M:%S")
+                )),
+            )
+            .await;
+            Err(ServerFnError::new(LOGIN_BLOCKED))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Count an attempt of a subject and return the number of failures including it.
+///
+/// The counter is increased in one statement so parallel attempts get different numbers. Counters
+/// older than the lockout start again and expired lockouts are cleared, the time of the last failure
+/// is only written once the attempt failed.
+async fn count_attempt(
+    limits: &LoginLimits,
+    kind: &str,
+    subject: &str,
+) -> Result<i64, ServerFnError> {
+    let db = crate::get_db().await;
+    let now = now();
+    let failures = query(
+        "INSERT INTO login_failures (kind, subject, failures, last_failure) VALUES ($1, $2, 1, $3)
+        ON CONFLICT (kind, subject) DO UPDATE SET
+        failures = CASE WHEN login_failures.last_failure > $4 THEN login_failures.failures + 1 ELSE 1 END,
+        last_failure = CASE WHEN login_failures.last_failure > $4
+            THEN login_failures.last_failure ELSE excluded.last_failure END,
+        locked_until = CASE WHEN login_failures.locked_until > excluded.last_failure
+            THEN login_failures.locked_until END
+        RETURNING failures",
+    )
+    .bind(kind)
+    .bind(subject)
+    .bind(now)
+    .bind(now - limits.lockout())
+    .fetch_one(&db)
+    .await?
+    .get("failures");
+    Ok(failures)
+}
+
+/// Lock a subject until `until` unless it is locked already.
+async fn lock(
+    kind: &str,
+    subject: &str,
+    until: chrono::NaiveDateTime,
+) -> Result<(), ServerFnError> {
+    let db = crate::get_db().await;
+    query(
+        "UPDATE login_failures SET locked_until = COALESCE(locked_until, $1)
+        WHERE kind = $2 AND subject = $3",
+    )
+    .bind(until)
+    .bind(kind)
+    .bind(subject)
+    .execute(&db)
+    .await?;
+    Ok(())
+}
+
+/// A login attempt that is counted as a failure until the credentials turn out to be right.
+#[derive(Debug)]
+#[must_use = "the attempt stays counted as a failure until it is released or failed"]
+pub(crate) struct Attempt {
+    username: String,
+    ip: ClientIp,
+    /// The kind, subject, failures including this attempt and the limit of every counter.
+    counted: Vec<(&'static str, String, i64, u32)>,
+}
+
+/// Count a login attempt for the username and the client address before the credentials are checked.
+///
+/// The attempt is refused if the username or address has to wait or if parallel attempts already
+/// used up the limit. In that case the subject is locked.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the login is refused or the database cannot be acessed.
+pub(crate) async fn reserve(
+    limits: &LoginLimits,
+    username: &str,
+    ip: ClientIp,
+) -> Result<Attempt, ServerFnError> {
+    check(limits, username, ip).await?;
+    let subjects = [
+        (KIND_USER, Some(user_subject(username)), limits.max_failures),
+        (KIND_IP, ip.as_string(), limits.max_failures_per_ip),
+    ];
+    let mut attempt = Attempt {
+        username: username.to_string(),
+        ip,
+        counted: Vec::new(),
+    };
+    for (kind, subject, max_failures) in subjects {
+        let Some(subject) = subject.filter(|_| max_failures > 0) else {
+            continue;
+        };
+        let failures = count_attempt(limits, kind, &subject).await?;
+        attempt
+            .counted
+            .push((kind, subject, failures, max_failures));
+    }
+    let exceeded: Vec<_> = attempt
+        .counted
+        .iter()
+        .filter(|(_, _, failures, max_failures)| *failures > i64::from(*max_failures))
+        .collect();
+    if exceeded.is_empty() {
+        return Ok(attempt);
+    }
+    for (kind, subject, _, _) in exceeded {
+        warn!(
+            "Parallel logins exceeded the limit of the {} {}",
+            kind, subject
+        );
+        lock(kind, subject, now() + limits.lockout()).await?;
+    }
+    record_event(
+        LoginEventKind::Blocked,
+        username,
+        ip,
+        Some("too many parallel attempts"),
+    )
+    .await;
+    Err(ServerFnError::new(LOGIN_BLOCKED))
+}
+
+impl Attempt {
+    /// The credentials were right, the attempt is not counted as a failure.
+    ///
+    /// # Errors
+    /// Fails with [`ServerFnError`] if the database cannot be acessed.
+    pub(crate) async fn release(self) -> Result<(), ServerFnError> {
+        let db = crate::get_db().await;
+        for (kind, subject, _, _) in &self.counted {
+            query("UPDATE login_failures SET failures = failures - 1 WHERE kind = $1 AND subject = $2")
+                .bind(*kind)
+                .bind(subject)
+                .execute(&db)
+                .await?;
+            query("DELETE FROM login_failures WHERE kind = $1 AND subject = $2 AND failures <= 0")
+                .bind(*kind)
+                .bind(subject)
+                .execute(&db)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// The credentials were wrong, the attempt stays counted and locks the username or address if
+    /// there were too many.
+    ///
+    /// # Errors
+    /// Fails with [`ServerFnError`] if the database cannot be acessed.
+    pub(crate) async fn fail(
+        self,
+        limits: &LoginLimits,
+        reason: &str,
+    ) -> Result<(), ServerFnError> {
+        record_event(
+            LoginEventKind::Failure,
+            &self.username,
+            self.ip,
+            Some(reason),
+        )
+        .await;
+        let db = crate::get_db().await;
+        let now = now();
+        for (kind, subject, failures, max_failures) in &self.counted {
+            let locked = *failures >= i64::from(*max_failures);
+            query(
+                "UPDATE login_failures SET last_failure = $1, locked_until = COALESCE(locked_until, $2)
+                WHERE kind = $3 AND subject = $4",
+            )
+            .bind(now)
+            .bind(locked.then(|| now + limits.lockout()))
+            .bind(*kind)
+            .bind(subject)
+            .execute(&db)
+            .await?;
+            if locked {
+                let what = if *kind == KIND_IP {
+                    "address"
+                } else {
+                    "username"
+                };
+                warn!(
+                    "The {} {} is locked after too many failed logins",
+                    what, subject
+                );
+                record_event(
+                    LoginEventKind::Lockout,
+                    &self.username,
+                    self.ip,
+                    Some(&format!("{what} locked")),
+                )
+                .await;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Forget the failures of the username after a successful login.
+///
+/// The failures of the address are kept, a valid account must not reset the limit for guessing others.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn record_success(username: &str, ip: ClientIp) -> Result<(), ServerFnError> {
+    let db = crate::get_db().await;
+    query("DELETE FROM login_failures WHERE kind = $1 AND subject = $2")
+        .bind(KIND_USER)
+        .bind(user_subject(username))
+        .execute(&db)
+        .await?;
+    record_event(LoginEventKind::Success, username, ip, None).await;
+    Ok(())
+}
+
+/// The usernames and addresses that are currently locked.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn lockouts() -> Result<Vec<LoginLockout>, ServerFnError> {
+    let db = crate::get_db().await;
+    let lockouts = query(
+        "SELECT kind, subject, failures, locked_until FROM login_failures
+        WHERE locked_until > $1
+        ORDER BY locked_until DESC",
+    )
+    .bind(now())
+    .fetch_all(&db)
+    .await?
+    .iter()
+    .map(|row| LoginLockout {
+        by_ip: row.get::<String>("kind") == KIND_IP,
+        subject: row.get("subject"),
+        failures: row.get("failures"),
+        locked_until: row.get("locked_until"),
+    })
+    .collect();
+    Ok(lockouts)
+}
+
+/// Lift the lockout of a username or address.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn reset(by_ip: bool, subject: &str, admin: &str) -> Result<(), ServerFnError> {
+    let (kind, subject) = if by_ip {
+        (KIND_IP, subject.trim().to_string())
+    } else {
+        (KIND_USER, user_subject(subject))
+    };
+    let db = crate::get_db().await;
+    query("DELETE FROM login_failures WHERE kind = $1 AND subject = $2")
+        .bind(kind)
+        .bind(&subject)
+        .execute(&db)
+        .await?;
+    info!("{} lifted the login lockout of {}", admin, subject);
+    let detail = format!("{kind} {subject} unlocked by {admin}");
+    let ip = ClientIp(by_ip.then(|| subject.parse().ok()).flatten());
+    let username = if by_ip { "" } else { subject.as_str() };
+    record_event(LoginEventKind::Reset, username, ip, Some(&detail)).await;
+    Ok(())
+}
+
+/// The newest entries of the audit trail.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn events(limit: i64) -> Result<Vec<LoginEvent>, ServerFnError> {
+    let db = crate::get_db().await;
+    let events = query("SELECT * FROM login_events ORDER BY id DESC LIMIT $1")
+        .bind(limit)
+        .fetch_all(&db)
+        .await?
+        .iter()
+        .map(|row| LoginEvent {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            kind: LoginEventKind::parse(&row.get::<String>("kind")),
+            username: row.get("username"),
+            ip: row.get("ip"),
+            detail: row.get("detail"),
+        })
+        .collect();
+    Ok(events)
+}
+
+/// Delete the audit trail older than [`LoginLimits::event_days`] and the counters that are forgotten.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn prune(limits: &LoginLimits) -> Result<u64, ServerFnError> {
+    let db = crate::get_db().await;
+    let now = now();
+    query(
+        "DELETE FROM login_failures WHERE last_failure < $1
+        AND (locked_until IS NULL OR locked_until < $2)",
+    )
+    .bind(now - limits.lockout())
+    .bind(now)
+    .execute(&db)
+    .await?;
+    if limits.event_days == 0 {
+        return Ok(0);
+    }
+    // a century is forever for an audit trail and keeps the date in range
+    let days = i64::try_from(limits.event_days.min(36_500)).unwrap_or(36_500);
+    let before = now - chrono::Duration::days(days);
+    let deleted = query("DELETE FROM login_events WHERE created_at < $1")
+        .bind(before)
+        .execute(&db)
+        .await?;
+    Ok(deleted)
+}
+
+/// Start the task that prunes the audit trail once a day, the first time right away.
+pub(crate) fn start_pruning(limits: LoginLimits) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(24 * 3600));
+        loop {
+            ticker.tick().await;
+            match prune(&limits).await {
+                Ok(deleted) if deleted > 0 => info!("Deleted {} old login events", deleted),
+                Ok(_) => {}
+                Err(e) => error!("Failed to prune the login events: {}", e),
+            }
+        }
+    });
+}
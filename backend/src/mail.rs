@@ -0,0 +1,167 @@
+//! Emails that are sent through an SMTP server.
+//!
+//! The texts come from the Fluent translations of the web interface, an email is written in the
+//! language of its recipient and falls back to english for missing translations.
+use std::time::Duration;
+
+use dioxus::logger::tracing::info;
+use dioxus::prelude::ServerFnError;
+use fluent_bundle::{FluentArgs, FluentResource, concurrent::FluentBundle};
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport as _, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use pslink_shared::datatypes::Lang;
+use unic_langid::LanguageIdentifier;
+
+/// The time to wait for the SMTP server.
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How the connection to the SMTP server is encrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Upgrade the connection with `STARTTLS`, usually on port 587.
+    StartTls,
+    /// Connect with TLS from the start, usually on port 465.
+    Tls,
+    /// No encryption, only for a server on the same host or in tests.
+    None,
+}
+
+impl SmtpSecurity {
+    pub const NAMES: [&'static str; 3] = ["starttls", "tls", "none"];
+
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "starttls" => Some(Self::StartTls),
+            "tls" => Some(Self::Tls),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+/// The settings of the SMTP server, they are read from the command line or environment.
+#[derive(Debug, Clone)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    /// The login at the server, emails are sent without login if it is not set.
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// The sender like `pslink <pslink@example.com>`.
+    pub from: String,
+}
+
+/// A translated email.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Email {
+    pub subject: String,
+    pub body: String,
+}
+
+fn translations(lang: Lang) -> (&'static str, &'static str) {
+    match lang {
+        Lang::EnUS => ("en-US", include_str!("../../translations/en-US.ftl")),
+        Lang::DeDE => ("de-DE", include_str!("../../translations/de-DE.ftl")),
+    }
+}
+
+fn format_message(lang: Lang, key: &str, args: &FluentArgs) -> Option<String> {
+    let (id, source) = translations(lang);
+    let id: LanguageIdentifier = id.parse().ok()?;
+    let resource =
+        FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, _)| resource);
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // the unicode isolation marks only help in html
+    bundle.set_use_isolating(false);
+    bundle.add_resource(resource).ok()?;
+    let pattern = bundle.get_message(key)?.value()?;
+    let mut errors = vec![];
+    Some(
+        bundle
+            .format_pattern(pattern, Some(args), &mut errors)
+            .into_owned(),
+    )
+}
+
+/// Translate a message, the english text is used if the translation is missing.
+pub(crate) fn translate(lang: Lang, key: &str, args: &FluentArgs) -> String {
+    format_message(lang, key, args)
+        .or_else(|| format_message(Lang::EnUS, key, args))
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// The email with the link that resets a forgotten password.
+pub(crate) fn password_reset_email(lang: Lang, username: &str, url: &str, minutes: i64) -> Email {
+    let mut args = FluentArgs::new();
+    args.set("username", username);
+    args.set("minutes", minutes);
+    Email {
+        subject: translate(lang, "email-reset-subject", &args),
+        body: format!(
+            "{}\n\n{}\n\n{}\n\n{}\n",
+            translate(lang, "email-greeting", &args),
+            translate(lang, "email-reset-text", &args),
+            url,
+            translate(lang, "email-reset-ignore", &args),
+        ),
+    }
+}
+
+/// The email that checks the SMTP settings.
+pub(crate) fn test_email(lang: Lang) -> Email {
+    let args = FluentArgs::new();
+    Email {
+        subject: translate(lang, "email-test-subject", &args),
+        body: format!("{}\n", translate(lang, "email-test-text", &args)),
+    }
+}
+
+/// Send an email to one recipient.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if an address is invalid or the SMTP server does not accept the email.
+pub(crate) async fn send(
+    settings: &SmtpSettings,
+    to: &str,
+    email: &Email,
+) -> Result<(), ServerFnError> {
+    let from = settings.from.parse::<Mailbox>().map_err(|e| {
+        ServerFnError::new(format!("Invalid sender address {}: {e}", settings.from))
+    })?;
+    let recipient = to
+        .parse::<Mailbox>()
+        .map_err(|e| ServerFnError::new(format!("Invalid recipient address {to}: {e}")))?;
+    let message = Message::builder()
+        .from(from)
+        .to(recipient)
+        .subject(email.subject.clone())
+        .header(ContentType::TEXT_PLAIN)
+        .body(email.body.clone())
+        .map_err(|e| ServerFnError::new(format!("Failed to write the email: {e}")))?;
+    let builder = match settings.security {
+        SmtpSecurity::StartTls => {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.host)
+        }
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&settings.host),
+        SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+            &settings.host,
+        )),
+    }
+    .map_err(|e| ServerFnError::new(format!("Invalid SMTP server {}: {e}", settings.host)))?;
+    let mut builder = builder.port(settings.port).timeout(Some(TIMEOUT));
+    if let (Some(username), Some(password)) = (&settings.username, &settings.password) {
+        builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+    builder
+        .build()
+        .send(message)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to send the email: {e}")))?;
+    info!("Sent the email \"{}\" to {}", email.subject, to);
+    Ok(())
+}
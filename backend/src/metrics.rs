@@ -0,0 +1,56 @@
+//! Process wide counters of the running server.
+//!
+//! The counters only live in memory and are reset on every restart.
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use pslink_shared::datatypes::ServerMetrics;
+
+/// Counters about the recording of clicks.
+pub(crate) struct ClickMetrics {
+    recorded: AtomicU64,
+    dropped: AtomicU64,
+    failed: AtomicU64,
+    batches: AtomicU64,
+}
+
+impl ClickMetrics {
+    const fn new() -> Self {
+        Self {
+            recorded: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            batches: AtomicU64::new(0),
+        }
+    }
+
+    /// A batch of `count` clicks was written to the database.
+    pub(crate) fn batch_written(&self, count: usize) {
+        self.recorded.fetch_add(count as u64, Ordering::Relaxed);
+        self.batches.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A batch of `count` clicks could not be written to the database.
+    pub(crate) fn batch_failed(&self, count: usize) {
+        self.failed.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// A click was not queued because the queue was full or closed.
+    pub(crate) fn click_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+pub(crate) static CLICKS: ClickMetrics = ClickMetrics::new();
+
+/// Take a snapshot of all counters.
+pub(crate) fn snapshot() -> ServerMetrics {
+    let (click_queue_length, click_queue_capacity) = crate::click_queue::usage();
+    ServerMetrics {
+        clicks_recorded: CLICKS.recorded.load(Ordering::Relaxed),
+        clicks_dropped: CLICKS.dropped.load(Ordering::Relaxed),
+        clicks_failed: CLICKS.failed.load(Ordering::Relaxed),
+        click_batches: CLICKS.batches.load(Ordering::Relaxed),
+        click_queue_length,
+        click_queue_capacity,
+    }
+}
@@ -0,0 +1,26 @@
+//! Runtime metrics of the server for admin users
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use pslink_shared::apirequests::users::Role;
+use pslink_shared::datatypes::ServerMetrics;
+
+/// Get the counters of the running server.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated as admin.
+#[server(GetServerMetrics, endpoint = "server_metrics")]
+pub async fn get_server_metrics() -> Result<ServerMetrics, ServerFnError> {
+    let auth = crate::auth::get_session().await?;
+    let user = auth
+        .current_user
+        .ok_or_else(|| ServerFnError::new("Authentication required"))?
+        .get_user()
+        .ok_or_else(|| ServerFnError::new("User information not available"))?;
+
+    if user.role != Role::Admin {
+        return Err(ServerFnError::new("Administrator permissions required"));
+    }
+
+    Ok(crate::metrics::snapshot())
+}
@@ -1,18 +1,17 @@
 use std::str::FromStr;
 
-use chrono::NaiveDate;
 use dioxus::{
     logger::tracing::{error, info},
     prelude::ServerFnError,
 };
 use serde::{Deserialize, Serialize};
 
+use crate::storage::{DbRow, Storage, Transaction};
 use argon2::PasswordHasher as _;
 use pslink_shared::{
     apirequests::{links::LinkDelta, users::Role},
-    datatypes::{Count, Lang, Link, Secret, Statistics, User, WeekCount},
+    datatypes::{Count, DeviceClass, Lang, Link, Secret, Statistics, User},
 };
-use sqlx::Row;
 
 /// The operations a User should support.
 
@@ -28,6 +27,29 @@ pub trait UserDbOperations<T> {
     async fn delete_user(id: i64, current_user_id: i64) -> Result<(), ServerFnError>;
 }
 
+/// A row of the users table as the checked queries of both backends return it.
+struct UserRow {
+    id: i64,
+    username: String,
+    email: String,
+    password: String,
+    role: i64,
+    language: String,
+}
+
+impl From<UserRow> for User {
+    fn from(row: UserRow) -> Self {
+        Self {
+            id: row.id,
+            username: row.username,
+            email: row.email,
+            password: Secret::new(row.password),
+            role: Role::convert(row.role),
+            language: Lang::from_str(&row.language).expect("Should parse"),
+        }
+    }
+}
+
 impl UserDbOperations<Self> for User {
     /// get a user by its id
     ///
@@ -35,19 +57,24 @@ impl UserDbOperations<Self> for User {
     /// fails with [`ServerFnError`] if the user does not exist or the database cannot be acessed.
 
     async fn get_user(id: i64) -> Result<Self, ServerFnError> {
-        let db = crate::get_db().await;
-        let user = sqlx::query!("Select * from users where id = ? ", id)
-            .fetch_one(&db)
-            .await
-            .map(|row| Self {
-                id: row.id,
-                username: row.username,
-                email: row.email,
-                password: Secret::new(row.password),
-                role: Role::convert(row.role),
-                language: Lang::from_str(&row.language).expect("Should parse"),
-            });
-        user.map_err(|e| e.into())
+        let user =
+            match crate::get_db().await {
+                Storage::Sqlite(pool) => sqlx::query_as!(
+                    UserRow,
+                    "SELECT id, username, email, password, role, language FROM users WHERE id = ?",
+                    id
+                )
+                .fetch_one(&pool)
+                .await,
+                Storage::Postgres(pool) => sqlx::query_as!(
+                    UserRow,
+                    "SELECT id, username, email, password, role, language FROM users WHERE id = $1",
+                    id
+                )
+                .fetch_one(&pool)
+                .await,
+            };
+        user.map(Self::from).map_err(|e| e.into())
     }
 
     /// get a user by its username
@@ -56,19 +83,27 @@ impl UserDbOperations<Self> for User {
     /// fails with [`ServerFnError`] if the user does not exist or the database cannot be acessed.
 
     async fn get_user_by_name(name: &str) -> Result<Self, ServerFnError> {
-        let db = crate::get_db().await;
-        let user = sqlx::query!("Select * from users where username = ? ", name)
-            .fetch_one(&db)
-            .await
-            .map(|row| Self {
-                id: row.id,
-                username: row.username,
-                email: row.email,
-                password: Secret::new(row.password),
-                role: Role::convert(row.role),
-                language: Lang::from_str(&row.language).expect("Should parse"),
-            });
-        user.map_err(|e| e.into())
+        let user = match crate::get_db().await {
+            Storage::Sqlite(pool) => {
+                sqlx::query_as!(
+                    UserRow,
+                    "SELECT id, username, email, password, role, language FROM users WHERE username = ?",
+                    name
+                )
+                .fetch_one(&pool)
+                .await
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query_as!(
+                    UserRow,
+                    "SELECT id, username, email, password, role, language FROM users WHERE username = $1",
+                    name
+                )
+                .fetch_one(&pool)
+                .await
+            }
+        };
+        user.map(Self::from).map_err(|e| e.into())
     }
 
     /// get all users
@@ -77,24 +112,27 @@ impl UserDbOperations<Self> for User {
     /// fails with [`ServerFnError`] if the database cannot be acessed.
 
     async fn get_all_users() -> Result<Vec<Self>, ServerFnError> {
-        let db = crate::get_db().await;
-        let user = sqlx::query("Select * from users")
-            .fetch_all(&db)
-            .await
-            .map(|row| {
-                row.into_iter()
-                    .map(|r| Self {
-                        id: r.get("id"),
-                        username: r.get("username"),
-                        email: r.get("email"),
-                        password: Secret::new(r.get("password")),
-                        role: Role::convert(r.get("role")),
-                        language: Lang::from_str(r.get("language"))
-                            .expect("should parse correctly"),
-                    })
-                    .collect()
-            });
-        user.map_err(|e| e.into())
+        let users = match crate::get_db().await {
+            // rowid is the same as id but unknown to postgres, which keeps the
+            // cached descriptions of both queries apart (see `crate::storage`).
+            Storage::Sqlite(pool) => {
+                sqlx::query_as!(
+                    UserRow,
+                    "SELECT id, username, email, password, role, language FROM users ORDER BY rowid"
+                )
+                .fetch_all(&pool)
+                .await
+            }
+            Storage::Postgres(pool) => sqlx::query_as!(
+                UserRow,
+                "SELECT id, username, email, password, role, language FROM users ORDER BY users.id"
+            )
+            .fetch_all(&pool)
+            .await,
+        };
+        users
+            .map(|users| users.into_iter().map(Self::from).collect())
+            .map_err(|e| e.into())
     }
 
     /// change a user
@@ -103,22 +141,41 @@ impl UserDbOperations<Self> for User {
     /// fails with [`ServerFnError`] if the user does not exist, some constraints are not satisfied or the database cannot be acessed.
 
     async fn update_user(&self) -> Result<(), ServerFnError> {
-        let db = crate::get_db().await;
         let role_i64 = self.role.to_i64();
-        sqlx::query!(
-            "UPDATE users SET
-            username = ?,
-            email = ?,
-            password = ?,
-            role = ? where id = ?",
-            self.username,
-            self.email,
-            self.password.secret,
-            role_i64,
-            self.id
-        )
-        .execute(&db)
-        .await?;
+        match crate::get_db().await {
+            Storage::Sqlite(pool) => {
+                sqlx::query!(
+                    "UPDATE users SET
+                    username = ?,
+                    email = ?,
+                    password = ?,
+                    role = ? where id = ?",
+                    self.username,
+                    self.email,
+                    self.password.secret,
+                    role_i64,
+                    self.id
+                )
+                .execute(&pool)
+                .await?;
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query!(
+                    "UPDATE users SET
+                    username = $1,
+                    email = $2,
+                    password = $3,
+                    role = $4 where id = $5",
+                    self.username,
+                    self.email,
+                    self.password.secret,
+                    role_i64,
+                    self.id
+                )
+                .execute(&pool)
+                .await?;
+            }
+        }
         Ok(())
     }
 
@@ -135,9 +192,22 @@ impl UserDbOperations<Self> for User {
             Role::Admin => Role::Regular,
         };
         let role_i64 = new_role.to_i64();
-        sqlx::query!("UPDATE users SET role = ? where id = ?", role_i64, self.id)
-            .execute(&db)
-            .await?;
+        match db {
+            Storage::Sqlite(pool) => {
+                sqlx::query!("UPDATE users SET role = ? where id = ?", role_i64, self.id)
+                    .execute(&pool)
+                    .await?;
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query!(
+                    "UPDATE users SET role = $1 where id = $2",
+                    role_i64,
+                    self.id
+                )
+                .execute(&pool)
+                .await?;
+            }
+        }
         Ok(())
     }
 
@@ -147,15 +217,27 @@ impl UserDbOperations<Self> for User {
     /// fails with [`ServerFnError`] if the user does not exist or the database cannot be acessed.
 
     async fn set_language(self, new_language: Lang) -> Result<(), ServerFnError> {
-        let db = crate::get_db().await;
         let lang_code = new_language.to_string();
-        sqlx::query!(
-            "UPDATE users SET language = ? where id = ?",
-            lang_code,
-            self.id
-        )
-        .execute(&db)
-        .await?;
+        match crate::get_db().await {
+            Storage::Sqlite(pool) => {
+                sqlx::query!(
+                    "UPDATE users SET language = ? where id = ?",
+                    lang_code,
+                    self.id
+                )
+                .execute(&pool)
+                .await?;
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query!(
+                    "UPDATE users SET language = $1 where id = $2",
+                    lang_code,
+                    self.id
+                )
+                .execute(&pool)
+                .await?;
+            }
+        }
         Ok(())
     }
 
@@ -167,11 +249,26 @@ impl UserDbOperations<Self> for User {
     /// fails with [`ServerFnError`] if the database cannot be acessed.
 
     async fn count_admins() -> Result<Count, ServerFnError> {
-        let db = crate::get_db().await;
-        let num = sqlx::query_as!(Count, "select count(*) as number from users where role = 2")
-            .fetch_one(&db)
-            .await?;
-        Ok(num)
+        let admin = Role::Admin.to_i64();
+        let number = match crate::get_db().await {
+            Storage::Sqlite(pool) => {
+                sqlx::query_scalar!(
+                    r#"select count(*) as "number!: i64" from users where role = ?"#,
+                    admin
+                )
+                .fetch_one(&pool)
+                .await?
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query_scalar!(
+                    r#"select count(*) as "number!: i64" from users where role = $1"#,
+                    admin
+                )
+                .fetch_one(&pool)
+                .await?
+            }
+        };
+        Ok(Count { number })
     }
 
     /// Delete a user by their ID and reassign their links to another user
@@ -182,17 +279,32 @@ impl UserDbOperations<Self> for User {
     /// # Errors
     /// fails with [`ServerFnError`] if the database cannot be accessed or the user is not found.
     async fn delete_user(id: i64, current_user_id: i64) -> Result<(), ServerFnError> {
-        let db = crate::get_db().await;
-        sqlx::query!(
-            "UPDATE links SET author = ? where author = ?",
-            current_user_id,
-            id
-        )
-        .execute(&db)
-        .await?;
-        sqlx::query!("DELETE from users where id = ? ", id)
-            .execute(&db)
-            .await?;
+        match crate::get_db().await {
+            Storage::Sqlite(pool) => {
+                sqlx::query!(
+                    "UPDATE links SET author = ? where author = ?",
+                    current_user_id,
+                    id
+                )
+                .execute(&pool)
+                .await?;
+                sqlx::query!("DELETE from users where id = ?", id)
+                    .execute(&pool)
+                    .await?;
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query!(
+                    "UPDATE links SET author = $1 where author = $2",
+                    current_user_id,
+                    id
+                )
+                .execute(&pool)
+                .await?;
+                sqlx::query!("DELETE from users where id = $1", id)
+                    .execute(&pool)
+                    .await?;
+            }
+        }
         Ok(())
     }
 }
@@ -257,19 +369,36 @@ impl NewUser {
     /// fails with [`ServerFnError`] if the database cannot be acessed.
 
     pub async fn insert_user(&self) -> Result<(), ServerFnError> {
-        let db = crate::get_db().await;
-        sqlx::query!(
-            "Insert into users (
-            username,
-            email,
-            password,
-            role) VALUES (?,?,?,1)",
-            self.username,
-            self.email,
-            self.password,
-        )
-        .execute(&db)
-        .await?;
+        match crate::get_db().await {
+            Storage::Sqlite(pool) => {
+                sqlx::query!(
+                    "Insert into users (
+                    username,
+                    email,
+                    password,
+                    role) VALUES (?,?,?,1)",
+                    self.username,
+                    self.email,
+                    self.password,
+                )
+                .execute(&pool)
+                .await?;
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query!(
+                    "Insert into users (
+                    username,
+                    email,
+                    password,
+                    role) VALUES ($1,$2,$3,1)",
+                    self.username,
+                    self.email,
+                    self.password,
+                )
+                .execute(&pool)
+                .await?;
+            }
+        }
         Ok(())
     }
 }
@@ -284,6 +413,24 @@ pub trait LinkDbOperations<T> {
     async fn get_statistics(link_id: i64) -> Result<Statistics, ServerFnError>;
 }
 
+/// Read a link from a row of the links table.
+pub(crate) fn link_from_row(row: &DbRow) -> Link {
+    Link {
+        id: row.get("id"),
+        title: row.get("title"),
+        target: row.get("target"),
+        code: row.get("code"),
+        author: row.get("author"),
+        created_at: row.get_utc("created_at"),
+    }
+}
+
+/// The time of a link as it is stored in the `TIMESTAMP` columns, in UTC without offset.
+fn stored_time(time: time::OffsetDateTime) -> time::PrimitiveDateTime {
+    let utc = time.to_offset(time::UtcOffset::UTC);
+    time::PrimitiveDateTime::new(utc.date(), utc.time())
+}
+
 impl LinkDbOperations<Self> for Link {
     /// Get a link statistics by its id
     ///
@@ -292,71 +439,39 @@ impl LinkDbOperations<Self> for Link {
 
     async fn get_statistics(link_id: i64) -> Result<Statistics, ServerFnError> {
         let db = crate::get_db().await;
-        // Verify that the code exists to avoid injections in the next query
-        let link = sqlx::query!("select id,code from links where id=?", link_id)
-            .fetch_one(&db)
-            .await?;
-        // Use proper parameter binding for SQLx static analysis
-        let qry = "WITH all_weeks AS (
-  WITH RECURSIVE weeks(date_value) AS (
-    SELECT date('now', 'start of month', '-1 year')
-    UNION ALL
-    SELECT date(date_value, '+7 days')
-    FROM weeks
-    WHERE date_value < date('now')
-  )
-  SELECT 
-    date_value AS full_date,
-    strftime('%Y.%W', date_value) AS year_week,
-    strftime('%W', date_value) AS week
-  FROM weeks
-),
-clicks_per_week AS (
-  SELECT
-    strftime('%Y.%W', created_at) AS year_week,
-    COUNT(*) AS total
-  FROM clicks
-  WHERE link = ?
-    AND created_at > date('now', 'start of month', '-1 year')
-  GROUP BY year_week
-)
-SELECT
-  aw.full_date,
-  aw.week,
-  COALESCE(cpw.total, 0) AS total
-FROM all_weeks aw
-LEFT JOIN clicks_per_week cpw ON cpw.year_week = aw.year_week
-ORDER BY aw.full_date";
-        let code = link.code;
-        // Execute and map the query to the desired type
-        let values: Vec<WeekCount> = sqlx::query(qry)
-            .bind(link.id)
-            .fetch_all(&db)
-            .await?
-            .into_iter()
-            .map(|c| {
-                // Get the date as a string
-                let date_str: String = c.get("full_date");
-                // Setze Offset auf UTC
-                let full_date = date_str.parse::<NaiveDate>().expect("failed to parse date");
-                // Get week as string and parse to i32
-                let week_str: String = c.get("week");
-                let week: i32 = week_str.parse().unwrap_or(0); // or error handling as needed
-
-                WeekCount {
-                    full_date,
-                    total: Count {
-                        number: c.get("total"),
-                    },
-                    week,
-                }
-            })
-            .collect();
-        let total = sqlx::query_as!(
-            Count,
-            "select count(*) as number from clicks join links on clicks.link = links.id where links.code = ?",
-            code
-        ).fetch_one(&db).await?;
+        // Verify that the link exists
+        let link_id = match &db {
+            Storage::Sqlite(pool) => {
+                sqlx::query_scalar!("select id from links where id=?", link_id)
+                    .fetch_one(pool)
+                    .await?
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query_scalar!("select id from links where id=$1", link_id)
+                    .fetch_one(pool)
+                    .await?
+            }
+        };
+        let values = crate::statistics::weekly_clicks(Some(link_id)).await?;
+        let number = match &db {
+            Storage::Sqlite(pool) => {
+                sqlx::query_scalar!(
+                    r#"select count(*) as "number!: i64" from clicks where link = ?"#,
+                    link_id
+                )
+                .fetch_one(pool)
+                .await?
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query_scalar!(
+                    r#"select count(*) as "number!: i64" from clicks where link = $1"#,
+                    link_id
+                )
+                .fetch_one(pool)
+                .await?
+            }
+        };
+        let total = Count { number };
         let res = Statistics {
             link_id,
             total,
@@ -371,14 +486,29 @@ ORDER BY aw.full_date";
     /// fails with [`ServerFnError`] if the database cannot be acessed or the link is not found.
 
     async fn get_link_by_code(code: &str) -> Result<Self, ServerFnError> {
-        let db = crate::get_db().await;
-        let link = sqlx::query_as!(
-            Self,
-            "Select * from links where code = ? COLLATE NOCASE",
-            code
-        )
-        .fetch_one(&db)
-        .await;
+        // codes are case insensitive, both backends have an index for the lookup
+        let link = match crate::get_db().await {
+            Storage::Sqlite(pool) => {
+                sqlx::query_as!(
+                    Self,
+                    "SELECT id, title, target, code, author, created_at FROM links
+                    WHERE code = ? COLLATE NOCASE",
+                    code
+                )
+                .fetch_one(&pool)
+                .await
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query_as!(
+                    Self,
+                    r#"SELECT id, title, target, code, author, created_at AT TIME ZONE 'UTC' AS "created_at!"
+                    FROM links WHERE lower(code) = lower($1)"#,
+                    code
+                )
+                .fetch_one(&pool)
+                .await
+            }
+        };
         info!("Found link: {:?}", &link);
         link.map_err(|e| e.into())
     }
@@ -389,10 +519,27 @@ ORDER BY aw.full_date";
     /// fails with [`ServerFnError`] if the database cannot be acessed or the link is not found.
 
     async fn get_link_by_id(id: i64) -> Result<Self, ServerFnError> {
-        let db = crate::get_db().await;
-        let link = sqlx::query_as!(Self, "Select * from links where id = ? ", id)
-            .fetch_one(&db)
-            .await;
+        let link = match crate::get_db().await {
+            Storage::Sqlite(pool) => {
+                sqlx::query_as!(
+                    Self,
+                    "SELECT id, title, target, code, author, created_at FROM links WHERE id = ?",
+                    id
+                )
+                .fetch_one(&pool)
+                .await
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query_as!(
+                    Self,
+                    r#"SELECT id, title, target, code, author, created_at AT TIME ZONE 'UTC' AS "created_at!"
+                    FROM links WHERE id = $1"#,
+                    id
+                )
+                .fetch_one(&pool)
+                .await
+            }
+        };
         info!("Found link: {:?}", &link);
         link.map_err(|e| e.into())
     }
@@ -403,10 +550,18 @@ ORDER BY aw.full_date";
     /// fails with [`ServerFnError`] if the database cannot be acessed or the link is not found.
 
     async fn delete_link_by_code(code: &str) -> Result<(), ServerFnError> {
-        let db = crate::get_db().await;
-        sqlx::query!("DELETE from links where code = ? ", code)
-            .execute(&db)
-            .await?;
+        match crate::get_db().await {
+            Storage::Sqlite(pool) => {
+                sqlx::query!("DELETE from links where code = ?", code)
+                    .execute(&pool)
+                    .await?;
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query!("DELETE from links where code = $1", code)
+                    .execute(&pool)
+                    .await?;
+            }
+        }
         Ok(())
     }
 
@@ -417,26 +572,47 @@ ORDER BY aw.full_date";
     /// fails with [`ServerFnError`] if the database cannot be acessed or the link is not found.
 
     async fn update_link(&self) -> Result<(), ServerFnError> {
-        let db = crate::get_db().await;
         info!("{:?}", self);
-        let qry = sqlx::query!(
-            "UPDATE links SET
-            title = ?,
-            target = ?,
-            code = ?,
-            author = ?,
-            created_at = ? where id = ?",
-            self.title,
-            self.target,
-            self.code,
-            self.author,
-            self.created_at,
-            self.id
-        );
-        match qry.execute(&db).await {
-            Ok(_) => Ok(()),
+        let created_at = stored_time(self.created_at);
+        let result = match crate::get_db().await {
+            Storage::Sqlite(pool) => sqlx::query!(
+                "UPDATE links SET
+                title = ?,
+                target = ?,
+                code = ?,
+                author = ?,
+                created_at = ? where id = ?",
+                self.title,
+                self.target,
+                self.code,
+                self.author,
+                created_at,
+                self.id
+            )
+            .execute(&pool)
+            .await
+            .map(|_| ()),
+            Storage::Postgres(pool) => sqlx::query!(
+                "UPDATE links SET
+                title = $1,
+                target = $2,
+                code = $3,
+                author = $4,
+                created_at = $5 where id = $6",
+                self.title,
+                self.target,
+                self.code,
+                self.author,
+                created_at,
+                self.id
+            )
+            .execute(&pool)
+            .await
+            .map(|_| ()),
+        };
+        match result {
+            Ok(()) => Ok(()),
             Err(e) => {
-                //error!("{}", qry);
                 error!("{}", e);
                 Err(e.into())
             }
@@ -471,31 +647,55 @@ impl NewLink {
     /// # Errors
     /// fails with [`ServerFnError`] if the database cannot be acessed or constraints are not met.
     pub async fn insert(self) -> Result<(), ServerFnError> {
-        let db = crate::get_db().await;
-        sqlx::query!(
-            "Insert into links (
-                title,
-                target,
-                code,
-                author,
-                created_at) VALUES (?,?,?,?,?)",
-            self.title,
-            self.target,
-            self.code,
-            self.author,
-            self.created_at,
-        )
-        .execute(&db)
-        .await?;
+        match crate::get_db().await {
+            Storage::Sqlite(pool) => {
+                sqlx::query!(
+                    "Insert into links (
+                    title,
+                    target,
+                    code,
+                    author,
+                    created_at) VALUES (?,?,?,?,?)",
+                    self.title,
+                    self.target,
+                    self.code,
+                    self.author,
+                    self.created_at,
+                )
+                .execute(&pool)
+                .await?;
+            }
+            Storage::Postgres(pool) => {
+                sqlx::query!(
+                    "Insert into links (
+                    title,
+                    target,
+                    code,
+                    author,
+                    created_at) VALUES ($1,$2,$3,$4,$5)",
+                    self.title,
+                    self.target,
+                    self.code,
+                    self.author,
+                    self.created_at as _,
+                )
+                .execute(&pool)
+                .await?;
+            }
+        }
         Ok(())
     }
 }
 
 /// Whenever a link is clicked the click is registered for statistical purposes.
+///
+/// Only the host of the referrer and the class of the device are stored to not collect more personal data than necessary.
 #[derive(Serialize)]
 pub struct NewClick {
     pub link: i64,
     pub created_at: chrono::NaiveDateTime,
+    pub referrer: Option<String>,
+    pub device: Option<DeviceClass>,
 }
 
 #[allow(dead_code)]
@@ -505,20 +705,66 @@ impl NewClick {
         Self {
             link: link_id,
             created_at: chrono::Local::now().naive_utc(),
+            referrer: None,
+            device: None,
         }
     }
 
-    pub(crate) async fn insert_click(self) -> Result<(), ServerFnError> {
+    /// Add the referrer host and the device class of the request.
+    #[must_use]
+    pub fn with_request_details(
+        mut self,
+        referrer: Option<String>,
+        device: Option<DeviceClass>,
+    ) -> Self {
+        self.referrer = referrer;
+        self.device = device;
+        self
+    }
+
+    /// Insert several clicks in one transaction
+    ///
+    /// # Errors
+    /// fails with [`ServerFnError`] if the database cannot be acessed. In that case none of the clicks is recorded.
+    pub(crate) async fn insert_clicks(clicks: Vec<Self>) -> Result<(), ServerFnError> {
         let db = crate::get_db().await;
-        sqlx::query!(
-            "Insert into clicks (
-            link,
-            created_at) VALUES (?,?)",
-            self.link,
-            self.created_at,
-        )
-        .execute(&db)
-        .await?;
+        let mut transaction = db.begin().await?;
+        for click in clicks {
+            let device = click.device.map(|device| device.as_ref().to_owned());
+            match &mut transaction {
+                Transaction::Sqlite(transaction) => {
+                    sqlx::query!(
+                        "Insert into clicks (
+                        link,
+                        created_at,
+                        referrer,
+                        device) VALUES (?,?,?,?)",
+                        click.link,
+                        click.created_at,
+                        click.referrer,
+                        device
+                    )
+                    .execute(&mut **transaction)
+                    .await?;
+                }
+                Transaction::Postgres(transaction) => {
+                    sqlx::query!(
+                        "Insert into clicks (
+                        link,
+                        created_at,
+                        referrer,
+                        device) VALUES ($1,$2,$3,$4)",
+                        click.link,
+                        click.created_at as _,
+                        click.referrer,
+                        device
+                    )
+                    .execute(&mut **transaction)
+                    .await?;
+                }
+            }
+        }
+        transaction.commit().await?;
         Ok(())
     }
 }
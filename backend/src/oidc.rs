@@ -0,0 +1,519 @@
+//! Single sign-on with an OpenID Connect identity provider.
+//!
+//! `/auth/oidc/login` redirects to the provider with an authorization code request secured by PKCE,
+//! `/auth/oidc/callback` exchanges the code for the tokens, maps the claims to a [`User`] and logs
+//! the user in. Users with a second factor enter their one-time code afterwards like after a password.
+//! Users are matched by the `sub` claim, on their first login a new account is created with the
+//! configured role. An existing account with the same username is only linked if the provider has
+//! verified that the email belongs to the user and it is the email of the account, otherwise anybody
+//! who can choose the username at the provider could take the account over.
+//!
+//! The id token is fetched directly from the token endpoint of the provider over https, so as
+//! allowed by the OpenID Connect core spec (3.1.3.7) its issuer is validated by the connection and
+//! not by its signature. The audience, expiry and nonce are checked.
+use std::str::FromStr as _;
+
+use axum::{
+    extract::Query,
+    http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
+};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use dioxus::logger::tracing::{info, warn};
+use dioxus::prelude::ServerFnError;
+use pslink_shared::{
+    apirequests::users::{LoginResult, Role},
+    datatypes::{Lang, User},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest as _, Sha256};
+
+use crate::auth::Session;
+use crate::models::{NewUser, UserDbOperations as _};
+use crate::storage::query;
+use crate::tokens::random_token;
+
+/// The path the provider redirects back to, it has to be registered as redirect uri at the provider.
+pub(crate) const CALLBACK_PATH: &str = "/auth/oidc/callback";
+/// The path that starts a single sign-on.
+pub(crate) const LOGIN_PATH: &str = "/auth/oidc/login";
+/// The key of the pending login in the session.
+const FLOW_KEY: &str = "oidc_flow";
+
+/// The settings of the identity provider, they are read from the command line or environment.
+#[derive(Debug, Clone)]
+pub struct OidcSettings {
+    /// The issuer url, the provider configuration is read from `<issuer>/.well-known/openid-configuration`.
+    pub issuer: String,
+    pub client_id: String,
+    /// Public clients only use PKCE and have no secret.
+    pub client_secret: Option<String>,
+    /// The full url of [`CALLBACK_PATH`] on this server.
+    pub redirect_uri: String,
+    /// The requested scopes separated by spaces.
+    pub scopes: String,
+    /// The claim that contains the username.
+    pub username_claim: String,
+    /// The claim that contains the list of groups.
+    pub groups_claim: String,
+    /// Members of this group are administrators, if it is not set the roles are managed in pslink.
+    pub admin_group: Option<String>,
+    /// The role of new users, [`Role::Disabled`] if an administrator has to enable them first.
+    pub default_role: Role,
+}
+
+/// The parts of the provider configuration that are used.
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: Option<String>,
+}
+
+/// The random values of a login that has been started but not finished yet.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct OidcFlow {
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
+}
+
+impl OidcFlow {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: random_token(32),
+            nonce: random_token(32),
+            code_verifier: random_token(64),
+        }
+    }
+
+    /// The S256 PKCE challenge of the verifier.
+    pub(crate) fn code_challenge(&self) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(self.code_verifier.as_bytes()))
+    }
+}
+
+/// Parse the role of new users, `regular`, `disabled` or `admin`.
+pub(crate) fn parse_role(role: &str) -> Option<Role> {
+    match role {
+        "regular" => Some(Role::Regular),
+        "disabled" => Some(Role::Disabled),
+        "admin" => Some(Role::Admin),
+        _ => None,
+    }
+}
+
+fn http_client() -> Result<reqwest::Client, ServerFnError> {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| ServerFnError::new(format!("Failed to create the http client: {e}")))
+}
+
+async fn discover(settings: &OidcSettings) -> Result<ProviderMetadata, ServerFnError> {
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        settings.issuer.trim_end_matches('/')
+    );
+    let metadata: ProviderMetadata = http_client()?
+        .get(&url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| ServerFnError::new(format!("Failed to read {url}: {e}")))?
+        .json()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Invalid provider configuration: {e}")))?;
+    if metadata.issuer.trim_end_matches('/') != settings.issuer.trim_end_matches('/') {
+        return Err(ServerFnError::new(format!(
+            "The provider configuration is for the issuer {}",
+            metadata.issuer
+        )));
+    }
+    Ok(metadata)
+}
+
+/// The url of the provider the browser is sent to.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the provider configuration cannot be read.
+pub(crate) async fn authorization_url(
+    settings: &OidcSettings,
+    flow: &OidcFlow,
+) -> Result<String, ServerFnError> {
+    let metadata = discover(settings).await?;
+    let code_challenge = flow.code_challenge();
+    let url = reqwest::Url::parse_with_params(
+        &metadata.authorization_endpoint,
+        [
+            ("response_type", "code"),
+            ("client_id", settings.client_id.as_str()),
+            ("redirect_uri", settings.redirect_uri.as_str()),
+            ("scope", settings.scopes.as_str()),
+            ("state", flow.state.as_str()),
+            ("nonce", flow.nonce.as_str()),
+            ("code_challenge", code_challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .map_err(|e| ServerFnError::new(format!("Invalid authorization endpoint: {e}")))?;
+    Ok(url.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    id_token: String,
+}
+
+fn claim<'a>(claims: &'a Map<String, Value>, name: &str) -> Option<&'a str> {
+    claims
+        .get(name)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Decode the claims of the id token and check that it was issued for this login.
+fn id_token_claims(
+    id_token: &str,
+    settings: &OidcSettings,
+    issuer: &str,
+    nonce: &str,
+) -> Result<Map<String, Value>, ServerFnError> {
+    let invalid = |reason: &str| ServerFnError::new(format!("Invalid id token: {reason}"));
+    let payload = id_token
+        .split('.')
+        .nth(1)
+        .ok_or_else(|| invalid("not a JWT"))?;
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .map_err(|_| invalid("the payload is not base64"))?;
+    let claims: Map<String, Value> =
+        serde_json::from_slice(&payload).map_err(|_| invalid("the payload is not json"))?;
+    if claim(&claims, "iss") != Some(issuer) {
+        return Err(invalid("wrong issuer"));
+    }
+    let audience_matches = match claims.get("aud") {
+        Some(Value::String(audience)) => audience == &settings.client_id,
+        Some(Value::Array(audiences)) => audiences
+            .iter()
+            .any(|audience| audience.as_str() == Some(settings.client_id.as_str())),
+        _ => false,
+    };
+    if !audience_matches {
+        return Err(invalid("wrong audience"));
+    }
+    let expires = claims.get("exp").and_then(Value::as_i64).unwrap_or(0);
+    if expires < chrono::Utc::now().timestamp() {
+        return Err(invalid("expired"));
+    }
+    if claim(&claims, "nonce") != Some(nonce) {
+        return Err(invalid("wrong nonce"));
+    }
+    if claim(&claims, "sub").is_none() {
+        return Err(invalid("no subject"));
+    }
+    Ok(claims)
+}
+
+/// Exchange the code for the tokens and return the claims of the id token and the userinfo endpoint.
+async fn fetch_claims(
+    settings: &OidcSettings,
+    flow: &OidcFlow,
+    code: &str,
+) -> Result<Map<String, Value>, ServerFnError> {
+    let metadata = discover(settings).await?;
+    let client = http_client()?;
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", settings.redirect_uri.as_str()),
+        ("client_id", settings.client_id.as_str()),
+        ("code_verifier", flow.code_verifier.as_str()),
+    ];
+    if let Some(secret) = &settings.client_secret {
+        form.push(("client_secret", secret.as_str()));
+    }
+    let tokens: TokenResponse = client
+        .post(&metadata.token_endpoint)
+        .form(&form)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| ServerFnError::new(format!("The code was not accepted: {e}")))?
+        .json()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Invalid token response: {e}")))?;
+    let mut claims = id_token_claims(&tokens.id_token, settings, &metadata.issuer, &flow.nonce)?;
+    if let Some(userinfo_endpoint) = &metadata.userinfo_endpoint {
+        let userinfo: Map<String, Value> = client
+            .get(userinfo_endpoint)
+            .bearer_auth(&tokens.access_token)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| ServerFnError::new(format!("Failed to read the userinfo: {e}")))?
+            .json()
+            .await
+            .map_err(|e| ServerFnError::new(format!("Invalid userinfo: {e}")))?;
+        if claim(&userinfo, "sub") != claim(&claims, "sub") {
+            return Err(ServerFnError::new("The userinfo is about another subject"));
+        }
+        for (name, value) in userinfo {
+            claims.entry(name).or_insert(value);
+        }
+    }
+    Ok(claims)
+}
+
+/// The language of a `locale` claim like `de`, `de-DE` or `de_AT`.
+fn language(locale: &str) -> Option<Lang> {
+    Lang::from_str(locale).ok().or_else(|| {
+        locale
+            .split(['-', '_'])
+            .next()
+            .and_then(|prefix| Lang::from_str(&prefix.to_lowercase()).ok())
+    })
+}
+
+/// Whether the provider has verified the email, some providers send the flag as string.
+fn email_verified(claims: &Map<String, Value>) -> bool {
+    match claims.get("email_verified") {
+        Some(Value::Bool(verified)) => *verified,
+        Some(Value::String(verified)) => verified == "true",
+        _ => false,
+    }
+}
+
+/// Whether the claims list the admin group, `None` if the roles are not managed by the provider.
+fn is_admin(settings: &OidcSettings, claims: &Map<String, Value>) -> Option<bool> {
+    let admin_group = settings.admin_group.as_ref()?;
+    let member = match claims.get(&settings.groups_claim) {
+        Some(Value::Array(groups)) => groups
+            .iter()
+            .any(|group| group.as_str() == Some(admin_group.as_str())),
+        Some(Value::String(groups)) => groups
+            .split([' ', ','])
+            .any(|group| group == admin_group.as_str()),
+        _ => false,
+    };
+    Some(member)
+}
+
+/// Find, link or create the user of the claims and update the email, language and role.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the claims lack a username or email, the username belongs to an account
+/// that may not be linked or the database cannot be accessed.
+pub(crate) async fn provision_user(
+    settings: &OidcSettings,
+    claims: &Map<String, Value>,
+) -> Result<User, ServerFnError> {
+    let subject = claim(claims, "sub").ok_or_else(|| ServerFnError::new("No subject claim"))?;
+    let email = claim(claims, "email")
+        .ok_or_else(|| ServerFnError::new("The identity provider did not send an email"))?;
+    let username = claim(claims, &settings.username_claim)
+        .unwrap_or(email)
+        .to_string();
+    let admin = is_admin(settings, claims);
+    let db = crate::get_db().await;
+
+    let existing = query("SELECT id FROM users WHERE oidc_subject = $1")
+        .bind(subject)
+        .fetch_optional(&db)
+        .await?;
+    let mut user = match existing {
+        Some(row) => User::get_user(row.get("id")).await?,
+        None => match User::get_user_by_name(&username).await {
+            Ok(user) => {
+                if !email_verified(claims) || !user.email.eq_ignore_ascii_case(email) {
+                    warn!(
+                        "Refused to link the user {} to the single sign-on subject {}",
+                        username, subject
+                    );
+                    return Err(ServerFnError::new(format!(
+                        "The username {username} belongs to an account that is not linked to single sign-on"
+                    )));
+                }
+                let linked = query(
+                    "UPDATE users SET oidc_subject = $1 WHERE id = $2 AND oidc_subject IS NULL",
+                )
+                .bind(subject)
+                .bind(user.id)
+                .execute(&db)
+                .await?;
+                if linked == 0 {
+                    return Err(ServerFnError::new(format!(
+                        "The username {username} belongs to another single sign-on account"
+                    )));
+                }
+                info!("Linked the user {} to single sign-on", username);
+                user
+            }
+            Err(_) => {
+                let role = match admin {
+                    Some(true) => Role::Admin,
+                    _ => settings.default_role,
+                };
+                // the account can only be used with single sign-on
+                let password = NewUser::hash_password(&random_token(32), &crate::get_secret())?;
+                let id: i64 = query(
+                    "INSERT INTO users (username, email, password, role, language, oidc_subject)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    RETURNING id",
+                )
+                .bind(&username)
+                .bind(email)
+                .bind(password)
+                .bind(role.to_i64())
+                .bind(
+                    claim(claims, "locale")
+                        .and_then(language)
+                        .unwrap_or(Lang::EnUS)
+                        .to_string(),
+                )
+                .bind(subject)
+                .fetch_one(&db)
+                .await?
+                .get("id");
+                info!("Created the user {} from single sign-on", username);
+                return User::get_user(id).await;
+            }
+        },
+    };
+
+    let mut changed = false;
+    if user.email != email {
+        user.email = email.to_string();
+        changed = true;
+    }
+    match admin {
+        Some(true) if user.role == Role::Regular => {
+            user.role = Role::Admin;
+            changed = true;
+        }
+        Some(false) if user.role == Role::Admin => {
+            user.role = Role::Regular;
+            changed = true;
+        }
+        _ => {}
+    }
+    if changed {
+        user.update_user().await?;
+    }
+    if let Some(lang) = claim(claims, "locale").and_then(language) {
+        if lang != user.language {
+            user.clone().set_language(lang).await?;
+            user.language = lang;
+        }
+    }
+    Ok(user)
+}
+
+/// Finish a login: check the state, fetch the claims and provision the user.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the state does not match, the provider rejects the code or the user is disabled.
+pub(crate) async fn finish_login(
+    settings: &OidcSettings,
+    flow: &OidcFlow,
+    state: &str,
+    code: &str,
+) -> Result<User, ServerFnError> {
+    if state != flow.state {
+        return Err(ServerFnError::new("The login state does not match"));
+    }
+    let claims = fetch_claims(settings, flow, code).await?;
+    let user = provision_user(settings, &claims).await?;
+    if user.role == Role::Disabled {
+        return Err(ServerFnError::new(
+            "The account is disabled, an administrator has to enable it",
+        ));
+    }
+    Ok(user)
+}
+
+fn error_page(status: StatusCode, message: &str) -> Response {
+    (status, format!("Single sign-on failed: {message}")).into_response()
+}
+
+/// `GET /auth/oidc/login`
+pub async fn login(session: Session) -> Response {
+    let Some(settings) = crate::get_oidc_settings() else {
+        return error_page(StatusCode::NOT_FOUND, "single sign-on is not configured");
+    };
+    let flow = OidcFlow::new();
+    match authorization_url(&settings, &flow).await {
+        Ok(url) => {
+            session.session.set(FLOW_KEY, flow);
+            Redirect::to(&url).into_response()
+        }
+        Err(e) => {
+            warn!("Failed to start the single sign-on: {}", e);
+            error_page(
+                StatusCode::BAD_GATEWAY,
+                "the identity provider is not available",
+            )
+        }
+    }
+}
+
+/// The query parameters the provider redirects back with.
+#[derive(Debug, Deserialize)]
+pub struct CallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// `GET /auth/oidc/callback`
+pub async fn callback(session: Session, Query(query): Query<CallbackQuery>) -> Response {
+    let Some(settings) = crate::get_oidc_settings() else {
+        return error_page(StatusCode::NOT_FOUND, "single sign-on is not configured");
+    };
+    let Some(flow) = session.session.get::<OidcFlow>(FLOW_KEY) else {
+        return error_page(StatusCode::BAD_REQUEST, "no login was started");
+    };
+    session.session.remove(FLOW_KEY);
+    if let Some(error) = query.error {
+        let description = query.error_description.unwrap_or_default();
+        return error_page(StatusCode::UNAUTHORIZED, &format!("{error} {description}"));
+    }
+    let (Some(code), Some(state)) = (query.code, query.state) else {
+        return error_page(StatusCode::BAD_REQUEST, "the code is missing");
+    };
+    let user = match finish_login(&settings, &flow, &state, &code).await {
+        Ok(user) => user,
+        Err(e) => {
+            warn!("Single sign-on failed: {}", e);
+            return error_page(StatusCode::UNAUTHORIZED, &e.to_string());
+        }
+    };
+    let username = user.username.clone();
+    // a second factor is asked for like after a password, the login page shows the code field
+    match crate::totp::begin_login(&session, user).await {
+        Ok(LoginResult::LoggedIn(_)) => {
+            info!("{} logged in with single sign-on", username);
+            Redirect::to("/app/").into_response()
+        }
+        Ok(_) => {
+            info!(
+                "{} has to enter a second factor after single sign-on",
+                username
+            );
+            Redirect::to("/app/login").into_response()
+        }
+        Err(e) => {
+            warn!("Single sign-on failed: {}", e);
+            error_page(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "the login could not be stored",
+            )
+        }
+    }
+}
@@ -0,0 +1,585 @@
+//! The OpenAPI 3.0 description of the REST api in [`crate::rest_api`].
+//!
+//! The paths are generated from [`ROUTES`], the schemas describe the json encoding of the shared types.
+//! A test calls every route in the table so that it cannot describe routes that do not exist.
+use pslink_shared::apirequests::rest::{MAX_AMOUNT, PREFIX};
+use serde_json::{Map, Value, json};
+
+/// A parameter of a route.
+pub(crate) struct Parameter {
+    pub name: &'static str,
+    /// `path` or `query`
+    pub location: &'static str,
+    /// The json schema type, `string`, `integer` or `boolean`.
+    pub kind: &'static str,
+    pub description: &'static str,
+}
+
+/// What a successful request returns.
+pub(crate) enum Body {
+    /// Nothing, `204 No Content`.
+    Empty,
+    Schema(&'static str),
+    List(&'static str),
+    /// An image in the requested format.
+    Image,
+}
+
+/// A route of the REST api.
+pub(crate) struct Route {
+    pub method: &'static str,
+    /// The path below [`PREFIX`] with `{name}` for path parameters.
+    pub path: &'static str,
+    pub tag: &'static str,
+    pub summary: &'static str,
+    /// The scope the API token needs, `None` if the route is public.
+    pub scope: Option<&'static str>,
+    pub parameters: &'static [Parameter],
+    pub request: Option<&'static str>,
+    pub status: u16,
+    pub response: Body,
+}
+
+const CODE: Parameter = Parameter {
+    name: "code",
+    location: "path",
+    kind: "string",
+    description: "The code of the link, it is matched case insensitive",
+};
+
+const USER_ID: Parameter = Parameter {
+    name: "id",
+    location: "path",
+    kind: "integer",
+    description: "The id of the user",
+};
+
+const AMOUNT: Parameter = Parameter {
+    name: "amount",
+    location: "query",
+    kind: "integer",
+    description: "The number of entries, at most 1000 and 100 if not given",
+};
+
+pub(crate) const ROUTES: &[Route] = &[
+    Route {
+        method: "post",
+        path: "/session",
+        tag: "session",
+        summary: "Log in and get a session cookie that authenticates the following requests",
+        scope: None,
+        parameters: &[],
+        request: Some("LoginUser"),
+        status: 200,
+        response: Body::Schema("User"),
+    },
+    Route {
+        method: "delete",
+        path: "/session",
+        tag: "session",
+        summary: "Log out",
+        scope: None,
+        parameters: &[],
+        request: None,
+        status: 204,
+        response: Body::Empty,
+    },
+    Route {
+        method: "get",
+        path: "/links",
+        tag: "links",
+        summary: "List the links, the filters match case insensitive parts",
+        scope: Some("read"),
+        parameters: &[
+            Parameter {
+                name: "code",
+                location: "query",
+                kind: "string",
+                description: "Filter by code",
+            },
+            Parameter {
+                name: "title",
+                location: "query",
+                kind: "string",
+                description: "Filter by title",
+            },
+            Parameter {
+                name: "target",
+                location: "query",
+                kind: "string",
+                description: "Filter by target",
+            },
+            Parameter {
+                name: "author",
+                location: "query",
+                kind: "string",
+                description: "Filter by the username of the author",
+            },
+            Parameter {
+                name: "offset",
+                location: "query",
+                kind: "integer",
+                description: "The number of links to skip",
+            },
+            AMOUNT,
+            Parameter {
+                name: "order",
+                location: "query",
+                kind: "string",
+                description: "Order by Code (default), Description, Target, Author or Statistics",
+            },
+            Parameter {
+                name: "descending",
+                location: "query",
+                kind: "boolean",
+                description: "Reverse the order",
+            },
+        ],
+        request: None,
+        status: 200,
+        response: Body::List("FullLink"),
+    },
+    Route {
+        method: "post",
+        path: "/links",
+        tag: "links",
+        summary: "Create a link, the owner of the token is the author",
+        scope: Some("write"),
+        parameters: &[],
+        request: Some("LinkDelta"),
+        status: 201,
+        response: Body::Schema("Link"),
+    },
+    Route {
+        method: "get",
+        path: "/links/{code}",
+        tag: "links",
+        summary: "Get a link",
+        scope: Some("read"),
+        parameters: &[CODE],
+        request: None,
+        status: 200,
+        response: Body::Schema("Link"),
+    },
+    Route {
+        method: "put",
+        path: "/links/{code}",
+        tag: "links",
+        summary: "Change the code, title and target of a link, only the author and administrators may",
+        scope: Some("write"),
+        parameters: &[CODE],
+        request: Some("LinkDelta"),
+        status: 200,
+        response: Body::Schema("Link"),
+    },
+    Route {
+        method: "delete",
+        path: "/links/{code}",
+        tag: "links",
+        summary: "Delete a link and its clicks, only the author and administrators may",
+        scope: Some("write"),
+        parameters: &[CODE],
+        request: None,
+        status: 204,
+        response: Body::Empty,
+    },
+    Route {
+        method: "get",
+        path: "/links/{code}/stats",
+        tag: "statistics",
+        summary: "The clicks of a link per day, referrer, device, weekday and hour",
+        scope: Some("read"),
+        parameters: &[CODE],
+        request: None,
+        status: 200,
+        response: Body::Schema("LinkDetails"),
+    },
+    Route {
+        method: "get",
+        path: "/links/{code}/qr",
+        tag: "links",
+        summary: "The qr-code of a link",
+        scope: Some("read"),
+        parameters: &[
+            CODE,
+            Parameter {
+                name: "format",
+                location: "query",
+                kind: "string",
+                description: "svg (default), png, pdf or eps",
+            },
+            Parameter {
+                name: "size",
+                location: "query",
+                kind: "integer",
+                description: "The width in pixels (points for pdf and eps)",
+            },
+            Parameter {
+                name: "dpi",
+                location: "query",
+                kind: "integer",
+                description: "The resolution stored in png images",
+            },
+            Parameter {
+                name: "label",
+                location: "query",
+                kind: "boolean",
+                description: "Print the title and short url below pdf and eps codes",
+            },
+        ],
+        request: None,
+        status: 200,
+        response: Body::Image,
+    },
+    Route {
+        method: "get",
+        path: "/stats/clicks",
+        tag: "statistics",
+        summary: "The clicks on all links per week during the last year",
+        scope: Some("read"),
+        parameters: &[],
+        request: None,
+        status: 200,
+        response: Body::List("WeekCount"),
+    },
+    Route {
+        method: "get",
+        path: "/stats/top-links",
+        tag: "statistics",
+        summary: "The most clicked links of a period",
+        scope: Some("read"),
+        parameters: &[
+            Parameter {
+                name: "period",
+                location: "query",
+                kind: "string",
+                description: "Week, Month (default), Quarter, Year or All",
+            },
+            Parameter {
+                name: "amount",
+                location: "query",
+                kind: "integer",
+                description: "The number of links, at most 100 and 10 if not given",
+            },
+        ],
+        request: None,
+        status: 200,
+        response: Body::List("LinkClicks"),
+    },
+    Route {
+        method: "get",
+        path: "/users",
+        tag: "users",
+        summary: "List the users ordered by id, users that are not administrators only get themselves",
+        scope: Some("read"),
+        parameters: &[
+            Parameter {
+                name: "username",
+                location: "query",
+                kind: "string",
+                description: "Filter by username",
+            },
+            Parameter {
+                name: "email",
+                location: "query",
+                kind: "string",
+                description: "Filter by email",
+            },
+            AMOUNT,
+        ],
+        request: None,
+        status: 200,
+        response: Body::List("User"),
+    },
+    Route {
+        method: "post",
+        path: "/users",
+        tag: "users",
+        summary: "Create a regular user, only administrators may",
+        scope: Some("write"),
+        parameters: &[],
+        request: Some("UserDelta"),
+        status: 201,
+        response: Body::Schema("User"),
+    },
+    Route {
+        method: "get",
+        path: "/users/me",
+        tag: "users",
+        summary: "The owner of the token",
+        scope: Some("read"),
+        parameters: &[],
+        request: None,
+        status: 200,
+        response: Body::Schema("User"),
+    },
+    Route {
+        method: "get",
+        path: "/users/{id}",
+        tag: "users",
+        summary: "Get a user, users that are not administrators only get themselves",
+        scope: Some("read"),
+        parameters: &[USER_ID],
+        request: None,
+        status: 200,
+        response: Body::Schema("User"),
+    },
+    Route {
+        method: "put",
+        path: "/users/{id}",
+        tag: "users",
+        summary: "Change a user, the password, email and role need a login and only administrators change roles",
+        scope: Some("write"),
+        parameters: &[USER_ID],
+        request: Some("UserDelta"),
+        status: 200,
+        response: Body::Schema("User"),
+    },
+    Route {
+        method: "delete",
+        path: "/users/{id}",
+        tag: "users",
+        summary: "Delete a user and give their links to the administrator, only administrators may",
+        scope: Some("write"),
+        parameters: &[USER_ID],
+        request: None,
+        status: 204,
+        response: Body::Empty,
+    },
+];
+
+fn reference(schema: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{schema}") })
+}
+
+fn json_content(schema: Value) -> Value {
+    json!({ "application/json": { "schema": schema } })
+}
+
+fn error(description: &str) -> Value {
+    json!({ "description": description, "content": json_content(reference("Message")) })
+}
+
+fn operation(route: &Route) -> Value {
+    let parameters: Vec<Value> = route
+        .parameters
+        .iter()
+        .map(|parameter| {
+            json!({
+                "name": parameter.name,
+                "in": parameter.location,
+                "required": parameter.location == "path",
+                "description": parameter.description,
+                "schema": { "type": parameter.kind },
+            })
+        })
+        .collect();
+    let success = match route.response {
+        Body::Empty => json!({ "description": "Done" }),
+        Body::Schema(schema) => {
+            json!({ "description": "Success", "content": json_content(reference(schema)) })
+        }
+        Body::List(schema) => json!({
+            "description": "Success",
+            "content": json_content(json!({ "type": "array", "items": reference(schema) })),
+        }),
+        Body::Image => json!({
+            "description": "The image",
+            "content": {
+                "image/svg+xml": {},
+                "image/png": {},
+                "application/pdf": {},
+                "application/postscript": {},
+            },
+        }),
+    };
+    let mut responses = Map::new();
+    responses.insert(route.status.to_string(), success);
+    responses.insert(
+        "401".to_string(),
+        error("The API token or login is missing, invalid or expired"),
+    );
+    responses.insert(
+        "403".to_string(),
+        error("The token or its owner may not do this"),
+    );
+    if route.parameters.iter().any(|p| p.location == "path") {
+        responses.insert("404".to_string(), error("Not found"));
+    }
+    if route.request.is_some() {
+        responses.insert(
+            "409".to_string(),
+            error("The code or username is already used"),
+        );
+        responses.insert("422".to_string(), error("The request is invalid"));
+    }
+    let mut operation = json!({
+        "tags": [route.tag],
+        "summary": route.summary,
+        "parameters": parameters,
+        "responses": responses,
+    });
+    operation["security"] = match route.scope {
+        Some(scope) => json!([{ "token": [scope] }, { "session": [] }]),
+        None => json!([]),
+    };
+    if let Some(schema) = route.request {
+        operation["requestBody"] =
+            json!({ "required": true, "content": json_content(reference(schema)) });
+    }
+    operation
+}
+
+fn schemas() -> Value {
+    let timestamp = json!({
+        "type": "array",
+        "items": { "type": "integer" },
+        "description": "year, day of the year, hour, minute, second, nanosecond and the utc offset in hours, minutes and seconds",
+    });
+    let count = json!({ "type": "object", "properties": { "number": { "type": "integer" } } });
+    json!({
+        "Message": {
+            "type": "object",
+            "properties": { "message": { "type": "string" } },
+        },
+        "LoginUser": {
+            "type": "object",
+            "required": ["username", "password"],
+            "properties": {
+                "username": { "type": "string" },
+                "password": { "type": "string" },
+                "totp": {
+                    "type": "string",
+                    "description": "The one-time code or a recovery code if two-factor authentication is enabled",
+                },
+            },
+        },
+        "Link": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer" },
+                "title": { "type": "string" },
+                "target": { "type": "string" },
+                "code": { "type": "string" },
+                "author": { "type": "integer", "description": "The id of the author" },
+                "created_at": timestamp,
+            },
+        },
+        "LinkDelta": {
+            "type": "object",
+            "required": ["title", "target", "code"],
+            "properties": {
+                "title": { "type": "string" },
+                "target": { "type": "string" },
+                "code": { "type": "string", "description": "Letters, digits, '-', '_' and '.'" },
+            },
+        },
+        "FullLink": {
+            "type": "object",
+            "properties": {
+                "link": reference("Link"),
+                "user": reference("User"),
+                "clicks": {
+                    "type": "object",
+                    "properties": { "Count": count },
+                    "description": "The number of clicks",
+                },
+            },
+        },
+        "User": {
+            "type": "object",
+            "properties": {
+                "id": { "type": "integer" },
+                "username": { "type": "string" },
+                "email": { "type": "string" },
+                "password": { "type": "string", "description": "Always hidden" },
+                "role": reference("Role"),
+                "language": { "type": "string", "enum": ["EnUS", "DeDE"] },
+            },
+        },
+        "UserDelta": {
+            "type": "object",
+            "required": ["username", "email", "role"],
+            "properties": {
+                "username": { "type": "string" },
+                "email": { "type": "string" },
+                "password": { "type": "string", "nullable": true, "description": "Required for new users, unchanged if empty" },
+                "role": reference("Role"),
+            },
+        },
+        "Role": { "type": "string", "enum": ["Disabled", "Regular", "Admin"] },
+        "WeekCount": {
+            "type": "object",
+            "properties": {
+                "full_date": { "type": "string", "format": "date" },
+                "total": count,
+                "week": { "type": "integer" },
+            },
+        },
+        "LinkClicks": {
+            "type": "object",
+            "properties": { "link": reference("Link"), "clicks": count },
+        },
+        "LinkDetails": {
+            "type": "object",
+            "properties": {
+                "link": reference("FullLink"),
+                "daily": {
+                    "type": "array",
+                    "description": "The clicks per day during the last year",
+                    "items": {
+                        "type": "object",
+                        "properties": { "date": { "type": "string", "format": "date" }, "total": count },
+                    },
+                },
+                "referrers": {
+                    "type": "array",
+                    "description": "The hosts of the most common referrers, direct clicks are named \"\"",
+                    "items": {
+                        "type": "object",
+                        "properties": { "name": { "type": "string" }, "total": count },
+                    },
+                },
+                "devices": {
+                    "type": "array",
+                    "description": "Pairs of the device class (Desktop, Mobile, Tablet, Bot or Unknown) and the count",
+                    "items": { "type": "array" },
+                },
+                "heatmap": {
+                    "type": "array",
+                    "description": "The clicks by day of week (0 = sunday) and hour of the day (UTC)",
+                    "items": { "type": "array", "items": { "type": "integer" } },
+                },
+            },
+        },
+    })
+}
+
+/// The OpenAPI document of the REST api.
+pub(crate) fn document() -> Value {
+    let mut paths = Map::new();
+    for route in ROUTES {
+        let path = paths
+            .entry(format!("{PREFIX}{}", route.path))
+            .or_insert_with(|| json!({}));
+        path[route.method] = operation(route);
+    }
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Pslink",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": format!(
+                "Manage short links, their statistics and the users of pslink. Send a personal API token as `Authorization: Bearer <token>`, read tokens may only use GET routes. Alternatively log in at /api/v1/session and send the session cookie. Lists return at most {MAX_AMOUNT} entries."
+            ),
+        },
+        "paths": paths,
+        "components": {
+            "schemas": schemas(),
+            "securitySchemes": {
+                "token": { "type": "http", "scheme": "bearer" },
+                "session": { "type": "apiKey", "in": "cookie", "name": "session" },
+            },
+        },
+    })
+}
@@ -0,0 +1,176 @@
+//! Reset forgotten passwords by email.
+//!
+//! A user asks for a reset with the username or email address. If the account exists a link with a
+//! random token is sent to its email address, only the SHA-256 hash of the token is stored. The link
+//! is valid for [`VALIDITY_MINUTES`] minutes and only once. The answer to the request does not depend
+//! on whether the account exists, so it cannot be used to find accounts. The email is sent in the
+//! background so the time of the answer does not tell either.
+//!
+//! Accounts that log in by single sign-on or with the directory have no local password to reset.
+use dioxus::logger::tracing::{info, warn};
+use dioxus::prelude::ServerFnError;
+use pslink_shared::{apirequests::users::Role, datatypes::User};
+
+use crate::ldap::LdapOrder;
+use crate::mail::SmtpSettings;
+use crate::models::{NewUser, UserDbOperations as _};
+use crate::storage::query;
+use crate::tokens::{hash_token, random_token};
+
+/// The number of random characters of a token.
+const TOKEN_LENGTH: usize = 32;
+/// The minutes a reset link is valid.
+pub(crate) const VALIDITY_MINUTES: i64 = 60;
+/// Another email is only sent to the same account after this many minutes.
+const RESEND_MINUTES: i64 = 2;
+/// The answer for unknown, used and expired tokens alike.
+const INVALID: &str = "The link is invalid or expired, please request a new one";
+
+/// Whether passwords can be reset, it needs an SMTP server and local passwords.
+pub(crate) fn available() -> bool {
+    crate::get_smtp_settings().is_some()
+        && !crate::get_ldap_settings().is_some_and(|settings| settings.order == LdapOrder::LdapOnly)
+}
+
+/// Whether the account logs in by single sign-on or with the directory.
+async fn is_external(user_id: i64) -> Result<bool, ServerFnError> {
+    let db = crate::get_db().await;
+    let row = query("SELECT oidc_subject, ldap_dn FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(&db)
+        .await?;
+    Ok(row.get::<Option<String>>("oidc_subject").is_some()
+        || row.get::<Option<String>>("ldap_dn").is_some())
+}
+
+/// The account with this username or, if the address is unique, with this email address.
+async fn find_user(name_or_email: &str) -> Result<Option<User>, ServerFnError> {
+    if let Ok(user) = User::get_user_by_name(name_or_email).await {
+        return Ok(Some(user));
+    }
+    let db = crate::get_db().await;
+    let rows = query("SELECT id FROM users WHERE lower(email) = lower($1)")
+        .bind(name_or_email)
+        .fetch_all(&db)
+        .await?;
+    match rows.as_slice() {
+        [row] => Ok(Some(User::get_user(row.get("id")).await?)),
+        _ => Ok(None),
+    }
+}
+
+/// Send a reset link to the account with this username or email address.
+///
+/// Unknown accounts, accounts of single sign-on or the directory and failures to send are only logged
+/// so the answer does not tell whether the account exists.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn request_reset(
+    settings: &SmtpSettings,
+    name_or_email: &str,
+) -> Result<(), ServerFnError> {
+    let name_or_email = name_or_email.trim();
+    let Some(user) = find_user(name_or_email).await? else {
+        info!("A password reset was requested for the unknown account {name_or_email}");
+        return Ok(());
+    };
+    if user.role == Role::Disabled || user.email.trim().is_empty() || is_external(user.id).await? {
+        info!("No password reset is sent to {}", user.username);
+        return Ok(());
+    }
+    let now = chrono::Local::now().naive_utc();
+    let db = crate::get_db().await;
+    let recent: i64 = query(
+        "SELECT COUNT(*) AS number FROM password_resets WHERE user_id = $1 AND created_at > $2",
+    )
+    .bind(user.id)
+    .bind(now - chrono::Duration::minutes(RESEND_MINUTES))
+    .fetch_one(&db)
+    .await?
+    .get("number");
+    if recent > 0 {
+        info!("A password reset was sent to {} just now", user.username);
+        return Ok(());
+    }
+    let token = random_token(TOKEN_LENGTH);
+    // only the newest link can be used
+    query("DELETE FROM password_resets WHERE user_id = $1")
+        .bind(user.id)
+        .execute(&db)
+        .await?;
+    query(
+        "INSERT INTO password_resets (user_id, token_hash, created_at, expires_at)
+        VALUES ($1, $2, $3, $4)",
+    )
+    .bind(user.id)
+    .bind(hash_token(&token))
+    .bind(now)
+    .bind(now + chrono::Duration::minutes(VALIDITY_MINUTES))
+    .execute(&db)
+    .await?;
+    let url = format!("{}/app/reset-password/{}", crate::get_public_url(), token);
+    let email =
+        crate::mail::password_reset_email(user.language, &user.username, &url, VALIDITY_MINUTES);
+    let settings = settings.clone();
+    tokio::spawn(async move {
+        if let Err(e) = crate::mail::send(&settings, &user.email, &email).await {
+            warn!(
+                "The password reset for {} was not sent: {}",
+                user.username, e
+            );
+        }
+    });
+    Ok(())
+}
+
+/// Set a new password with the token of a reset link, the token cannot be used again.
+///
+/// A lockout of the username after failed logins is lifted.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the token is invalid, the account was linked to single sign-on or
+/// the directory since the link was sent, the password is malformed or the database cannot be acessed.
+pub(crate) async fn reset_password(token: &str, password: &str) -> Result<User, ServerFnError> {
+    // longer passwords are refused at the login
+    if password.len() > 32 {
+        return Err(ServerFnError::new("Username or password too long"));
+    }
+    if password.len() < 5 {
+        return Err(ServerFnError::new(
+            "The password needs at least 5 characters",
+        ));
+    }
+    let hashed = NewUser::hash_password(password, &crate::get_secret())?;
+    let now = chrono::Local::now().naive_utc();
+    let db = crate::get_db().await;
+    let mut transaction = db.begin().await?;
+    let user_id: i64 = query(
+        "UPDATE password_resets SET used_at = $1
+        WHERE token_hash = $2 AND used_at IS NULL AND expires_at > $1
+        RETURNING user_id",
+    )
+    .bind(now)
+    .bind(hash_token(token))
+    .fetch_optional(&mut transaction)
+    .await?
+    .ok_or_else(|| ServerFnError::new(INVALID))?
+    .get("user_id");
+    let updated = query(
+        "UPDATE users SET password = $1
+        WHERE id = $2 AND oidc_subject IS NULL AND ldap_dn IS NULL",
+    )
+    .bind(hashed)
+    .bind(user_id)
+    .execute(&mut transaction)
+    .await?;
+    if updated == 0 {
+        // the transaction is rolled back, the link stays unused
+        return Err(ServerFnError::new(INVALID));
+    }
+    transaction.commit().await?;
+    let user = User::get_user(user_id).await?;
+    info!("The password of {} was reset by email", user.username);
+    crate::login_limits::reset(false, &user.username, "password reset").await?;
+    Ok(user)
+}
@@ -0,0 +1,63 @@
+//! Generate the qr-codes of links on the server.
+
+use dioxus::prelude::*;
+use pslink_shared::{
+    apirequests::links::{QrCodeRequest, QrCodeResponse},
+    qr::QrStyle,
+};
+#[cfg(feature = "server")]
+use pslink_shared::{
+    apirequests::{
+        links::{QrCodeFormat, SvgQrCodeResponse},
+        users::Role,
+    },
+    datatypes::Link,
+};
+
+#[cfg(feature = "server")]
+use crate::models::LinkDbOperations as _;
+
+/// Get the qr-code of a link in the requested format.
+///
+/// The same images are available without the ui at `/qr/<code>.svg`, `.png`, `.pdf` and `.eps`.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the link does not exist or the qr-code cannot be generated.
+#[server(GetQrCode, endpoint = "get_qr_code")]
+pub async fn get_qr_code(request: QrCodeRequest) -> Result<QrCodeResponse, ServerFnError> {
+    let image = crate::qr_images::render(&request).await?;
+    Ok(match request.format {
+        QrCodeFormat::Svg => QrCodeResponse::Svg(SvgQrCodeResponse {
+            svg: String::from_utf8(image)?,
+        }),
+        QrCodeFormat::Png => QrCodeResponse::Png(image),
+        QrCodeFormat::Pdf => QrCodeResponse::Pdf(image),
+        QrCodeFormat::Eps => QrCodeResponse::Eps(String::from_utf8(image)?),
+    })
+}
+
+/// Get the stored qr-code style of a link, the default style if none was saved.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not authenticated or access to the database fails.
+#[server(GetQrStyle, endpoint = "get_qr_style")]
+pub async fn get_qr_style(link_id: i64) -> Result<QrStyle, ServerFnError> {
+    crate::auth::get_authenticated_user().await?;
+    crate::qr_images::link_style(link_id).await
+}
+
+/// Store the qr-code style of a link so all downloads look the same.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not the author of the link or access to the database fails.
+#[server(SaveQrStyle, endpoint = "save_qr_style")]
+pub async fn save_qr_style(link_id: i64, style: QrStyle) -> Result<(), ServerFnError> {
+    let user = crate::auth::get_authenticated_user().await?;
+    let link = Link::get_link_by_id(link_id).await?;
+    if user.role != Role::Admin && link.author != user.id {
+        return Err(ServerFnError::new(
+            "Only the author can change the qr-code of a link",
+        ));
+    }
+    crate::qr_images::save_link_style(link_id, &style.sanitized()).await
+}
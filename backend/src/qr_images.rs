@@ -0,0 +1,257 @@
+//! Serve the qr-codes of the links as images, e.g. `/qr/mycode.svg`, `/qr/mycode.png?size=512&dpi=300`
+//! or `/qr/mycode.pdf?label=true`.
+//!
+//! The images are public like the short links themselves so they can be embedded in documents and emails.
+use axum::{
+    extract::{Path, Query},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use dioxus::logger::tracing::info;
+use dioxus::prelude::ServerFnError;
+use pslink_shared::{
+    apirequests::links::{QrCodeFormat, QrCodeRequest, QrSheetQuery},
+    datatypes::Link,
+    qr::{
+        QrRenderOptions, QrStyle,
+        print::{QrSheetEntry, pdf_sheet},
+    },
+};
+use serde::Deserialize;
+use sha2::{Digest as _, Sha256};
+
+use crate::auth::Session;
+use crate::models::LinkDbOperations as _;
+use crate::storage::query;
+
+/// How long clients and proxies may cache the images.
+const CACHE_SECONDS: u32 = 24 * 60 * 60;
+
+#[derive(Deserialize)]
+pub struct QrImageParameters {
+    size: Option<u32>,
+    dpi: Option<u32>,
+    #[serde(default)]
+    label: bool,
+}
+
+fn short_url(link: &Link) -> String {
+    format!("{}/{}", crate::get_public_url(), link.code)
+}
+
+fn render_options(request: &QrCodeRequest) -> QrRenderOptions {
+    QrRenderOptions {
+        size: request.size,
+        dpi: request.dpi,
+        label: request.label,
+    }
+}
+
+/// Get the stored qr-code style of a link or the default style.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn link_style(link_id: i64) -> Result<QrStyle, ServerFnError> {
+    let db = crate::get_db().await;
+    let row = query("SELECT style FROM link_qr_styles WHERE link = $1")
+        .bind(link_id)
+        .fetch_optional(&db)
+        .await?;
+    Ok(row
+        .and_then(|row| serde_json::from_str::<QrStyle>(row.get("style")).ok())
+        .unwrap_or_default())
+}
+
+/// Store the qr-code style of a link.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn save_link_style(link_id: i64, style: &QrStyle) -> Result<(), ServerFnError> {
+    let db = crate::get_db().await;
+    query(
+        "INSERT INTO link_qr_styles (link, style) VALUES ($1, $2)
+        ON CONFLICT(link) DO UPDATE SET style = excluded.style",
+    )
+    .bind(link_id)
+    .bind(serde_json::to_string(style)?)
+    .execute(&db)
+    .await?;
+    Ok(())
+}
+
+/// The hex encoded start of the SHA-256 hash of the parts, every part is ended by a zero byte.
+fn etag_hash(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.finalize()[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Render the qr-code of a link.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the link does not exist or the qr-code cannot be generated.
+pub(crate) async fn render(request: &QrCodeRequest) -> Result<Vec<u8>, ServerFnError> {
+    let link = Link::get_link_by_code(&request.link_id).await?;
+    let style = link_style(link.id).await?;
+    pslink_shared::qr::render(
+        &short_url(&link),
+        &link.title,
+        &style,
+        request.format,
+        &render_options(request),
+    )
+    .ok_or_else(|| ServerFnError::new("Failed to create the QR code"))
+}
+
+/// Handle `GET /qr/{code}.{svg|png|pdf|eps}`.
+pub async fn qr_image(
+    Path(file): Path<String>,
+    Query(parameters): Query<QrImageParameters>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(request) = file.rsplit_once('.').and_then(|(code, extension)| {
+        QrCodeFormat::from_extension(extension).map(|format| QrCodeRequest {
+            link_id: code.to_string(),
+            format,
+            size: parameters.size,
+            dpi: parameters.dpi,
+            label: parameters.label,
+        })
+    }) else {
+        return (
+            StatusCode::NOT_FOUND,
+            "Use /qr/<code>.svg, /qr/<code>.png, /qr/<code>.pdf or /qr/<code>.eps",
+        )
+            .into_response();
+    };
+
+    let Ok(link) = Link::get_link_by_code(&request.link_id).await else {
+        return (StatusCode::NOT_FOUND, "Link not found").into_response();
+    };
+    let url = short_url(&link);
+    let Ok(style) = link_style(link.id).await else {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to load the QR code style",
+        )
+            .into_response();
+    };
+
+    // the image only depends on the url, the title, the style and the parameters, the hash has to
+    // stay the same across releases so the cached images stay valid
+    let etag = format!(
+        "\"{}\"",
+        etag_hash(&[
+            &url,
+            &link.title,
+            &serde_json::to_string(&style).unwrap_or_default(),
+            request.format.extension(),
+            &format!("{:?}", request.size),
+            &format!("{:?}", request.dpi),
+            &request.label.to_string(),
+        ])
+    );
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag))
+    {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+
+    match pslink_shared::qr::render(
+        &url,
+        &link.title,
+        &style,
+        request.format,
+        &render_options(&request),
+    ) {
+        Some(image) => (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    request.format.content_type().to_string(),
+                ),
+                (
+                    header::CACHE_CONTROL,
+                    format!("public, max-age={}", CACHE_SECONDS),
+                ),
+                (header::ETAG, etag),
+            ],
+            image,
+        )
+            .into_response(),
+        None => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to create the QR code",
+        )
+            .into_response(),
+    }
+}
+
+/// Handle `GET /export/qr-sheet` and build a printable pdf with the qr-codes of the selected links.
+///
+/// The qr-codes are public, so every logged in user can print the codes of every link.
+pub async fn qr_sheet(session: Session, Query(query): Query<QrSheetQuery>) -> Response {
+    let Some(user) = session.current_user.and_then(|account| account.get_user()) else {
+        return (StatusCode::UNAUTHORIZED, "Not authenticated").into_response();
+    };
+    let link_ids = query.link_ids();
+    if link_ids.is_empty() {
+        return (StatusCode::BAD_REQUEST, "No links selected").into_response();
+    }
+    let mut entries = Vec::with_capacity(link_ids.len());
+    for link_id in link_ids {
+        let Ok(link) = Link::get_link_by_id(link_id).await else {
+            return (StatusCode::NOT_FOUND, "Link not found").into_response();
+        };
+        let Ok(style) = link_style(link.id).await else {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load the QR code style",
+            )
+                .into_response();
+        };
+        entries.push(QrSheetEntry {
+            url: short_url(&link),
+            title: link.title,
+            style,
+        });
+    }
+    info!(
+        "Printing a qr-code sheet of {} links for {}",
+        entries.len(),
+        user.username
+    );
+
+    match pdf_sheet(&entries, &query) {
+        Some(pdf) => (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    QrCodeFormat::Pdf.content_type().to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"qr-codes-{}.pdf\"",
+                        chrono::Local::now().format("%Y-%m-%d")
+                    ),
+                ),
+            ],
+            pdf,
+        )
+            .into_response(),
+        None => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to create the QR codes",
+        )
+            .into_response(),
+    }
+}
@@ -1,22 +1,35 @@
 use axum::body::Body;
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode, header};
 use axum::{
     extract::Path,
     response::{Html, IntoResponse, Redirect, Response},
 };
 use dioxus::logger::tracing::info;
 use dioxus::prelude::*;
-use pslink_shared::datatypes::Link;
+use pslink_shared::datatypes::{DeviceClass, Link};
 
 use crate::models::{LinkDbOperations as _, NewClick};
 
-pub async fn redirect(Path(data): Path<String>) -> Result<impl IntoResponse, StatusCode> {
+pub async fn redirect(
+    Path(data): Path<String>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, StatusCode> {
     info!("Redirecting to {:?}", data);
     let link = Link::get_link_by_code(&data).await;
     info!("link: {:?}", link);
     match link {
         Ok(link) => {
-            NewClick::new(link.id).insert_click().await.unwrap();
+            let referrer = headers
+                .get(header::REFERER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(referrer_host);
+            let device = headers
+                .get(header::USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(DeviceClass::from_user_agent);
+            crate::click_queue::record(
+                NewClick::new(link.id).with_request_details(referrer, device),
+            );
             Ok(redirect_builder(&link.target))
         }
         Err(_e) => {
@@ -31,8 +44,24 @@ pub async fn redirect(Path(data): Path<String>) -> Result<impl IntoResponse, Sta
     }
 }
 
-pub async fn redirect_empty() -> impl IntoResponse {
-    redirect(Path("".to_string())).await.unwrap()
+pub async fn redirect_empty(headers: HeaderMap) -> impl IntoResponse {
+    redirect(Path("".to_string()), headers).await.unwrap()
+}
+
+/// Extract the host of a referrer url, the rest of the url is not stored.
+pub(crate) fn referrer_host(referrer: &str) -> Option<String> {
+    let without_scheme = referrer
+        .split_once("://")
+        .map_or(referrer, |(_, rest)| rest);
+    let host = without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .rsplit('@')
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    if host.is_empty() { None } else { Some(host) }
 }
 fn redirect_builder(target: &str) -> Response {
     Redirect::temporary(target).into_response()
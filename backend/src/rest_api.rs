@@ -0,0 +1,499 @@
+//! The versioned REST api under `/api/v1`.
+//!
+//! The server functions of the web interface are an internal rpc whose encoding may change with every
+//! release. This api is meant for scripts and other programs: it uses json bodies with the types of
+//! [`pslink_shared::apirequests`], answers with proper http status codes and is described by the
+//! OpenAPI document at `/api/v1/openapi.json`. Requests authenticate with a personal API token in the
+//! `Authorization: Bearer` header, tokens with the read scope may only use `GET` routes. Programs that
+//! act for a person can instead log in at `/api/v1/session` and send the session cookie.
+use axum::{
+    Json, Router,
+    extract::{FromRequestParts, Path, Query},
+    http::{HeaderMap, Method, StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use dioxus::logger::tracing::{info, warn};
+use dioxus::prelude::ServerFnError;
+use pslink_shared::{
+    apirequests::{
+        general::Message,
+        links::{LinkDelta, QrCodeFormat, QrCodeRequest, code_problems},
+        rest::{LinkListQuery, TopLinksQuery, UserListQuery},
+        users::{LoginUser, Role, UserDelta},
+    },
+    datatypes::{ApiTokenScope, FullLink, Link, LinkClicks, LinkDetails, Secret, User, WeekCount},
+};
+use serde::Deserialize;
+
+use crate::auth::Session;
+use crate::login_limits::ClientIp;
+use crate::models::{LinkDbOperations as _, NewLink, NewUser, UserDbOperations as _};
+
+/// An error with the http status code it is answered with, the body is a json [`Message`].
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, message)
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    fn invalid(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNPROCESSABLE_ENTITY, message)
+    }
+}
+
+impl From<ServerFnError> for ApiError {
+    fn from(e: ServerFnError) -> Self {
+        warn!("REST api request failed: {}", e);
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(Message {
+                message: self.message,
+            }),
+        )
+            .into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+fn unauthorized(message: impl Into<String>) -> ApiError {
+    ApiError::new(StatusCode::UNAUTHORIZED, message)
+}
+
+/// The owner of the API token the request was sent with or the user of the session cookie.
+pub(crate) struct ApiUser(User);
+
+impl<S> FromRequestParts<S> for ApiUser
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Some(token) = crate::api_tokens::bearer_token(&parts.headers) else {
+            let user = Session::from_request_parts(parts, state)
+                .await
+                .ok()
+                .and_then(|session| session.current_user)
+                .and_then(|account| account.get_user())
+                .ok_or_else(|| {
+                    unauthorized("An API token in the Authorization header or a login is required")
+                })?;
+            if user.role == Role::Disabled {
+                return Err(ApiError::forbidden("The account is disabled"));
+            }
+            return Ok(Self(user));
+        };
+        match crate::api_tokens::authenticate(token).await? {
+            Some((_, ApiTokenScope::Read)) if parts.method != Method::GET => {
+                Err(ApiError::forbidden("The API token may only read"))
+            }
+            Some((user, _)) => Ok(Self(user)),
+            None => Err(unauthorized("Invalid or expired API token")),
+        }
+    }
+}
+
+/// The routes of the api, they are nested under [`pslink_shared::apirequests::rest::PREFIX`].
+pub(crate) fn router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/openapi.json", get(openapi))
+        .route("/session", post(login).delete(logout))
+        .route("/links", get(list_links).post(create_link))
+        .route(
+            "/links/{code}",
+            get(get_link).put(update_link).delete(delete_link),
+        )
+        .route("/links/{code}/stats", get(link_statistics))
+        .route("/links/{code}/qr", get(link_qr))
+        .route("/stats/clicks", get(instance_statistics))
+        .route("/stats/top-links", get(top_links))
+        .route("/users", get(list_users).post(create_user))
+        .route("/users/me", get(current_user))
+        .route(
+            "/users/{id}",
+            get(get_user).put(update_user).delete(delete_user),
+        )
+}
+
+/// The OpenAPI document is public so that clients can be generated without a token.
+async fn openapi() -> Json<serde_json::Value> {
+    Json(crate::openapi::document())
+}
+
+/// `POST /session`, log in and set the session cookie.
+///
+/// Refused logins are counted like in the web interface, too many failures are answered with
+/// `429 Too Many Requests`.
+async fn login(
+    session: Session,
+    ip: ClientIp,
+    Json(data): Json<LoginUser>,
+) -> ApiResult<Json<User>> {
+    let limits = crate::get_login_limits();
+    // the password and the one-time code are checked with one counted attempt
+    let attempt = crate::login_limits::reserve(&limits, &data.username, ip)
+        .await
+        .map_err(|e| ApiError::new(StatusCode::TOO_MANY_REQUESTS, e.to_string()))?;
+    let user = match crate::auth::authenticate(&data.username, &data.password).await {
+        Ok(user) => user,
+        Err(e) => {
+            attempt.fail(&limits, &e.to_string()).await?;
+            return Err(unauthorized(crate::login_limits::LOGIN_FAILED));
+        }
+    };
+    let totp_enabled = crate::totp::is_enabled(user.id).await?;
+    if totp_enabled && !crate::totp::verify(user.id, &data.totp.unwrap_or_default()).await? {
+        attempt.fail(&limits, "wrong one-time code").await?;
+        return Err(unauthorized("A valid one-time code is required"));
+    }
+    attempt.release().await?;
+    if !totp_enabled && crate::totp::needs_enrollment(&user).await? {
+        return Err(ApiError::forbidden(
+            "Set up two-factor authentication in the web interface first",
+        ));
+    }
+    crate::login_limits::record_success(&user.username, ip).await?;
+    session.login_user(user.id);
+    Ok(Json(user))
+}
+
+/// `DELETE /session`
+async fn logout(session: Session) -> StatusCode {
+    session.logout_user();
+    StatusCode::NO_CONTENT
+}
+
+async fn find_link(code: &str) -> ApiResult<Link> {
+    Link::get_link_by_code(code)
+        .await
+        .map_err(|_| ApiError::not_found(format!("The link {code} does not exist")))
+}
+
+async fn find_user(id: i64) -> ApiResult<User> {
+    User::get_user(id)
+        .await
+        .map_err(|_| ApiError::not_found(format!("The user {id} does not exist")))
+}
+
+/// Only the author of a link and administrators may change it.
+fn ensure_may_edit(user: &User, link: &Link) -> ApiResult<()> {
+    if user.role != Role::Admin && link.author != user.id {
+        return Err(ApiError::forbidden("Only the author can change this link"));
+    }
+    Ok(())
+}
+
+fn validate_link(data: &LinkDelta) -> ApiResult<()> {
+    let mut problems = code_problems(&data.code);
+    if data.target.trim().is_empty() {
+        problems.push("The target is empty");
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::invalid(problems.join(", ")))
+    }
+}
+
+/// `GET /links`
+async fn list_links(
+    ApiUser(_): ApiUser,
+    Query(query): Query<LinkListQuery>,
+) -> ApiResult<Json<Vec<FullLink>>> {
+    let links = crate::link_api::query_links(&(&query).into()).await?;
+    Ok(Json(links))
+}
+
+/// `POST /links`, the author is the owner of the token.
+async fn create_link(
+    ApiUser(user): ApiUser,
+    Json(data): Json<LinkDelta>,
+) -> ApiResult<(StatusCode, Json<Link>)> {
+    validate_link(&data)?;
+    if Link::get_link_by_code(&data.code).await.is_ok() {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            format!("The code {} is already used", data.code),
+        ));
+    }
+    let code = data.code.clone();
+    NewLink::from_link_delta(data, user.id).insert().await?;
+    info!(
+        "{} created the link {} with the REST api",
+        user.username, code
+    );
+    Ok((StatusCode::CREATED, Json(find_link(&code).await?)))
+}
+
+/// `GET /links/{code}`
+async fn get_link(ApiUser(_): ApiUser, Path(code): Path<String>) -> ApiResult<Json<Link>> {
+    Ok(Json(find_link(&code).await?))
+}
+
+/// `PUT /links/{code}`, changes the code, title and target.
+async fn update_link(
+    ApiUser(user): ApiUser,
+    Path(code): Path<String>,
+    Json(data): Json<LinkDelta>,
+) -> ApiResult<Json<Link>> {
+    let mut link = find_link(&code).await?;
+    ensure_may_edit(&user, &link)?;
+    validate_link(&data)?;
+    if let Ok(existing) = Link::get_link_by_code(&data.code).await {
+        if existing.id != link.id {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                format!("The code {} is already used", data.code),
+            ));
+        }
+    }
+    link.code = data.code;
+    link.title = data.title;
+    link.target = data.target;
+    link.update_link().await?;
+    Ok(Json(link))
+}
+
+/// `DELETE /links/{code}`
+async fn delete_link(ApiUser(user): ApiUser, Path(code): Path<String>) -> ApiResult<StatusCode> {
+    let link = find_link(&code).await?;
+    ensure_may_edit(&user, &link)?;
+    Link::delete_link_by_code(&link.code).await?;
+    info!(
+        "{} deleted the link {} with the REST api",
+        user.username, link.code
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `GET /links/{code}/stats`
+async fn link_statistics(
+    ApiUser(_): ApiUser,
+    Path(code): Path<String>,
+) -> ApiResult<Json<LinkDetails>> {
+    let link = find_link(&code).await?;
+    Ok(Json(crate::statistics::link_details(link.id).await?))
+}
+
+#[derive(Deserialize)]
+struct QrParameters {
+    format: Option<String>,
+    size: Option<u32>,
+    dpi: Option<u32>,
+    #[serde(default)]
+    label: bool,
+}
+
+/// `GET /links/{code}/qr?format=svg`
+async fn link_qr(
+    ApiUser(_): ApiUser,
+    Path(code): Path<String>,
+    Query(parameters): Query<QrParameters>,
+) -> ApiResult<Response> {
+    let link = find_link(&code).await?;
+    let extension = parameters.format.as_deref().unwrap_or("svg");
+    let format = QrCodeFormat::from_extension(extension).ok_or_else(|| {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "The format must be svg, png, pdf or eps",
+        )
+    })?;
+    let image = crate::qr_images::render(&QrCodeRequest {
+        link_id: link.code,
+        format,
+        size: parameters.size,
+        dpi: parameters.dpi,
+        label: parameters.label,
+    })
+    .await?;
+    Ok(([(header::CONTENT_TYPE, format.content_type())], image).into_response())
+}
+
+/// `GET /stats/clicks`
+async fn instance_statistics(ApiUser(_): ApiUser) -> ApiResult<Json<Vec<WeekCount>>> {
+    Ok(Json(crate::statistics::clicks_per_week().await?))
+}
+
+/// `GET /stats/top-links`
+async fn top_links(
+    ApiUser(_): ApiUser,
+    Query(query): Query<TopLinksQuery>,
+) -> ApiResult<Json<Vec<LinkClicks>>> {
+    let amount = query.amount.unwrap_or(10).clamp(1, 100);
+    Ok(Json(
+        crate::statistics::top_links(query.period, amount).await?,
+    ))
+}
+
+/// `GET /users`, users that are not administrators only see themselves.
+async fn list_users(
+    ApiUser(user): ApiUser,
+    Query(query): Query<UserListQuery>,
+) -> ApiResult<Json<Vec<User>>> {
+    if user.role == Role::Admin {
+        Ok(Json(crate::user_api::query_users(&(&query).into()).await?))
+    } else {
+        Ok(Json(vec![user]))
+    }
+}
+
+/// `GET /users/me`
+async fn current_user(ApiUser(user): ApiUser) -> Json<User> {
+    Json(user)
+}
+
+/// `GET /users/{id}`
+async fn get_user(ApiUser(user): ApiUser, Path(id): Path<i64>) -> ApiResult<Json<User>> {
+    if user.role != Role::Admin && user.id != id {
+        return Err(ApiError::forbidden(
+            "Only administrators can see other users",
+        ));
+    }
+    Ok(Json(find_user(id).await?))
+}
+
+/// New passwords need the same length as in the web interface.
+fn validate_password(password: &str) -> ApiResult<()> {
+    if password.len() > 4 {
+        Ok(())
+    } else {
+        Err(ApiError::invalid(
+            "The password must be longer than 4 characters",
+        ))
+    }
+}
+
+/// `POST /users`, only administrators can create users.
+async fn create_user(
+    ApiUser(user): ApiUser,
+    Json(data): Json<UserDelta>,
+) -> ApiResult<(StatusCode, Json<User>)> {
+    if user.role != Role::Admin {
+        return Err(ApiError::forbidden("Only administrators can create users"));
+    }
+    let Some(password) = data.password.as_deref().filter(|p| !p.is_empty()) else {
+        return Err(ApiError::invalid("A new user requires a password"));
+    };
+    validate_password(password)?;
+    if data.username.trim().is_empty() {
+        return Err(ApiError::invalid("The username is empty"));
+    }
+    if User::get_user_by_name(&data.username).await.is_ok() {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            format!("The user {} already exists", data.username),
+        ));
+    }
+    NewUser::new(
+        data.username.clone(),
+        data.email.clone(),
+        password,
+        &crate::get_secret(),
+    )?
+    .insert_user()
+    .await?;
+    info!(
+        "{} created the user {} with the REST api",
+        user.username, data.username
+    );
+    let new_user = User::get_user_by_name(&data.username).await?;
+    Ok((StatusCode::CREATED, Json(new_user)))
+}
+
+/// `PUT /users/{id}`, the password is only changed if a new one is given and only administrators can
+/// change the role.
+///
+/// The password, email and role can only be changed with a login, a leaked API token must not take over
+/// an account.
+async fn update_user(
+    ApiUser(user): ApiUser,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+    Json(data): Json<UserDelta>,
+) -> ApiResult<Json<User>> {
+    if user.role != Role::Admin && user.id != id {
+        return Err(ApiError::forbidden(
+            "Only administrators can change other users",
+        ));
+    }
+    let unmodified = find_user(id).await?;
+    if crate::api_tokens::bearer_token(&headers).is_some()
+        && (data.password.as_deref().is_some_and(|p| !p.is_empty())
+            || data.email != unmodified.email
+            || data.role != unmodified.role)
+    {
+        return Err(ApiError::forbidden(
+            "The password, email and role can only be changed with a login",
+        ));
+    }
+    if let Ok(existing) = User::get_user_by_name(&data.username).await {
+        if existing.id != id {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                format!("The user {} already exists", data.username),
+            ));
+        }
+    }
+    let password = match data.password.as_deref() {
+        Some(password) if !password.is_empty() => {
+            validate_password(password)?;
+            Secret::new(NewUser::hash_password(password, &crate::get_secret())?)
+        }
+        _ => unmodified.password,
+    };
+    User {
+        id,
+        username: data.username,
+        email: data.email,
+        password,
+        role: if user.role == Role::Admin {
+            data.role
+        } else {
+            unmodified.role
+        },
+        language: unmodified.language,
+    }
+    .update_user()
+    .await?;
+    Ok(Json(find_user(id).await?))
+}
+
+/// `DELETE /users/{id}`, only administrators can delete users, their links are given to the administrator.
+async fn delete_user(ApiUser(user): ApiUser, Path(id): Path<i64>) -> ApiResult<StatusCode> {
+    if user.role != Role::Admin {
+        return Err(ApiError::forbidden("Only administrators can delete users"));
+    }
+    if user.id == id {
+        return Err(ApiError::invalid("Can not delete yourself"));
+    }
+    find_user(id).await?;
+    User::delete_user(id, user.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,373 @@
+//! Public statistics pages of single links.
+//!
+//! The owner of a link can create share tokens. A token consists of a random nonce that is stored in
+//! the database and a signature of the nonce with the server secret, so guessed or altered tokens are
+//! rejected without touching the database. Deleting the token from the database revokes it.
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+};
+use dioxus::logger::tracing::info;
+use dioxus::prelude::*;
+use hmac::{Hmac, Mac as _};
+use pslink_shared::datatypes::{Clicks, DeviceClass, LinkDetails, ShareToken};
+use sha2::Sha256;
+
+use crate::redirect_links::generate_error_page;
+use crate::storage::{DbRow, query};
+use crate::tokens::random_token;
+
+/// The number of random characters of a token.
+const NONCE_LENGTH: usize = 24;
+/// The number of signature bytes that are included in the token.
+const SIGNATURE_LENGTH: usize = 16;
+
+fn mac(nonce: &str) -> Hmac<Sha256> {
+    let secret = crate::get_secret();
+    let mut mac = Hmac::<Sha256>::new_from_slice(
+        secret
+            .secret
+            .as_deref()
+            .expect("Secret not initialized")
+            .as_bytes(),
+    )
+    .expect("HMAC accepts keys of any size");
+    mac.update(b"share-token:");
+    mac.update(nonce.as_bytes());
+    mac
+}
+
+/// Create the public token of a nonce.
+fn sign(nonce: &str) -> String {
+    let signature = mac(nonce).finalize().into_bytes();
+    let hex: String = signature[..SIGNATURE_LENGTH]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("{}.{}", nonce, hex)
+}
+
+/// Check the signature of a token and return the nonce if it is valid.
+fn verify(token: &str) -> Option<&str> {
+    let (nonce, hex) = token.split_once('.')?;
+    if hex.len() != SIGNATURE_LENGTH * 2 {
+        return None;
+    }
+    let signature = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    mac(nonce).verify_truncated_left(&signature).ok()?;
+    Some(nonce)
+}
+
+fn to_share_token(row: &DbRow) -> ShareToken {
+    let nonce: String = row.get("nonce");
+    ShareToken {
+        id: row.get("id"),
+        link: row.get("link"),
+        token: sign(&nonce),
+        created_at: row.get("created_at"),
+        expires_at: row.get("expires_at"),
+    }
+}
+
+/// Create a new token for the link that is valid for `valid_days` days or forever.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the expiry is out of range or the database cannot be acessed.
+pub(crate) async fn create_token(
+    link_id: i64,
+    valid_days: Option<u32>,
+) -> Result<ShareToken, ServerFnError> {
+    let db = crate::get_db().await;
+    let nonce = random_token(NONCE_LENGTH);
+    let created_at = chrono::Local::now().naive_utc();
+    let expires_at = valid_days
+        .map(|days| {
+            created_at
+                .checked_add_signed(chrono::Duration::days(i64::from(days)))
+                .ok_or_else(|| ServerFnError::new(format!("{days} days are too long")))
+        })
+        .transpose()?;
+    let row = query(
+        "INSERT INTO share_tokens (link, nonce, created_at, expires_at)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, link, nonce, created_at, expires_at",
+    )
+    .bind(link_id)
+    .bind(&nonce)
+    .bind(created_at)
+    .bind(expires_at)
+    .fetch_one(&db)
+    .await?;
+    Ok(to_share_token(&row))
+}
+
+/// List all tokens of a link.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn list_tokens(link_id: i64) -> Result<Vec<ShareToken>, ServerFnError> {
+    let db = crate::get_db().await;
+    let tokens = query(
+        "SELECT id, link, nonce, created_at, expires_at
+        FROM share_tokens
+        WHERE link = $1
+        ORDER BY created_at DESC",
+    )
+    .bind(link_id)
+    .fetch_all(&db)
+    .await?
+    .iter()
+    .map(to_share_token)
+    .collect();
+    Ok(tokens)
+}
+
+/// Get the id of the link a token belongs to.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed or the token does not exist.
+pub(crate) async fn token_link(token_id: i64) -> Result<i64, ServerFnError> {
+    let db = crate::get_db().await;
+    let row = query("SELECT link FROM share_tokens WHERE id = $1")
+        .bind(token_id)
+        .fetch_one(&db)
+        .await?;
+    Ok(row.get("link"))
+}
+
+/// Revoke a token by deleting it.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn delete_token(token_id: i64) -> Result<(), ServerFnError> {
+    let db = crate::get_db().await;
+    query("DELETE FROM share_tokens WHERE id = $1")
+        .bind(token_id)
+        .execute(&db)
+        .await?;
+    Ok(())
+}
+
+/// Find the link of a valid (correctly signed, not revoked and not expired) token.
+async fn valid_token_link(token: &str) -> Result<Option<i64>, ServerFnError> {
+    let Some(nonce) = verify(token) else {
+        return Ok(None);
+    };
+    let db = crate::get_db().await;
+    let row = query(
+        "SELECT link FROM share_tokens
+        WHERE nonce = $1
+        AND (expires_at IS NULL OR expires_at > $2)",
+    )
+    .bind(nonce)
+    .bind(chrono::Local::now().naive_utc())
+    .fetch_optional(&db)
+    .await?;
+    Ok(row.map(|row| row.get("link")))
+}
+
+/// Handle `GET /stats/{token}` and render the statistics of the shared link.
+pub async fn shared_statistics(Path(token): Path<String>) -> Response {
+    let link_id = match valid_token_link(&token).await {
+        Ok(Some(link_id)) => link_id,
+        Ok(None) => {
+            return generate_error_page(
+                "This statistics page does not exist or is no longer shared.",
+                StatusCode::NOT_FOUND,
+            );
+        }
+        Err(e) => {
+            info!("Failed to check a share token: {}", e);
+            return generate_error_page(
+                "The statistics could not be loaded.",
+                StatusCode::INTERNAL_SERVER_ERROR,
+            );
+        }
+    };
+    match crate::statistics::link_details(link_id).await {
+        Ok(details) => statistics_page(&details),
+        Err(e) => {
+            info!("Failed to load the shared statistics: {}", e);
+            generate_error_page(
+                "The statistics could not be loaded.",
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        }
+    }
+}
+
+/// Draw the clicks per week as a svg bar chart.
+fn weekly_chart(details: &LinkDetails) -> Element {
+    let weeks: Vec<(String, i64)> = details
+        .daily
+        .chunks(7)
+        .map(|week| {
+            (
+                week[0].date.format("%d.%m.%Y").to_string(),
+                week.iter().map(|day| day.total.number).sum(),
+            )
+        })
+        .collect();
+    let maximum = weeks.iter().map(|(_, c)| *c).max().unwrap_or(0).max(1);
+    let slot = 600.0 / weeks.len().max(1) as f64;
+    rsx! {
+        svg {
+            view_box: "0 0 600 160",
+            style: "width: 100%; height: auto;",
+            line {
+                x1: "0",
+                y1: "150",
+                x2: "600",
+                y2: "150",
+                stroke: "#b5b5b5",
+            }
+            for (i , (label , count)) in weeks.into_iter().enumerate() {
+                rect {
+                    x: "{i as f64 * slot + slot * 0.1}",
+                    y: "{150.0 - count as f64 / maximum as f64 * 140.0}",
+                    width: "{slot * 0.8}",
+                    height: "{count as f64 / maximum as f64 * 140.0}",
+                    fill: "green",
+                    title { "{label}: {count}" }
+                }
+            }
+            text {
+                x: "2",
+                y: "10",
+                font_size: "10",
+                "{maximum}"
+            }
+        }
+    }
+}
+
+/// A table of named counts.
+fn count_table(title: &str, entries: Vec<(String, i64)>) -> Element {
+    rsx! {
+        div { class: "box",
+            h2 { class: "title is-5", "{title}" }
+            if entries.is_empty() {
+                p { "No clicks yet." }
+            }
+            table { class: "table is-fullwidth is-narrow",
+                tbody {
+                    for (name , count) in entries {
+                        tr {
+                            td { "{name}" }
+                            td { class: "has-text-right", "{count}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn device_name(device: DeviceClass) -> &'static str {
+    match device {
+        DeviceClass::Desktop => "Desktop",
+        DeviceClass::Mobile => "Mobile",
+        DeviceClass::Tablet => "Tablet",
+        DeviceClass::Bot => "Bot",
+        DeviceClass::Unknown => "Unknown",
+    }
+}
+
+/// Render the read-only statistics page. Nothing about the author is shown.
+fn statistics_page(details: &LinkDetails) -> Response {
+    let bulma_path = pslink_shared::BULMA_CSS
+        .bundled()
+        .bundled_path()
+        .to_string();
+    let brand_name = std::env::var("PSLINK_BRAND_NAME").unwrap_or_else(|_| "Pslink".to_string());
+    let link = &details.link.link;
+    let total = match &details.link.clicks {
+        Clicks::Count(count) => count.number,
+        Clicks::Extended(statistics) => statistics.total.number,
+    };
+    let referrers = details
+        .referrers
+        .iter()
+        .map(|r| {
+            let name = if r.name.is_empty() {
+                "Direct / unknown".to_string()
+            } else {
+                r.name.clone()
+            };
+            (name, r.total.number)
+        })
+        .collect();
+    let devices = details
+        .devices
+        .iter()
+        .map(|(device, count)| (device_name(*device).to_string(), count.number))
+        .collect();
+    let content = rsx! {
+        section { class: "section",
+            div { class: "container",
+                h3 { class: "subtitle is-5",
+                    img { src: "/favicon.ico", alt: "{brand_name}" }
+                    " {brand_name}"
+                }
+                h1 { class: "title is-2", "{link.title}" }
+                div { class: "box",
+                    table { class: "table is-fullwidth",
+                        tbody {
+                            tr {
+                                th { "Code" }
+                                td { "{link.code}" }
+                            }
+                            tr {
+                                th { "Target" }
+                                td { style: "word-wrap:anywhere;", "{link.target}" }
+                            }
+                            tr {
+                                th { "Created" }
+                                td { "{link.created_at.date()}" }
+                            }
+                            tr {
+                                th { "Total clicks" }
+                                td { "{total}" }
+                            }
+                        }
+                    }
+                }
+                div { class: "box",
+                    h2 { class: "title is-5", "Clicks per week during the last year" }
+                    {weekly_chart(details)}
+                }
+                div { class: "columns",
+                    div { class: "column is-half", {count_table("Referrers", referrers)} }
+                    div { class: "column is-half", {count_table("Devices", devices)} }
+                }
+            }
+        }
+    };
+    let content = dioxus::ssr::render_element(content);
+    // the title is inserted into the html without the escaping of the rsx
+    let title = link
+        .title
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    let html_page = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <meta name="robots" content="noindex">
+    <title>Statistics - {title}</title>
+    <link rel="stylesheet" href="/app/assets/{bulma_path}">
+</head>
+<body>
+{content}
+</body>
+</html>"#
+    );
+    Html(html_page).into_response()
+}
@@ -0,0 +1,60 @@
+//! Manage the tokens that share the statistics of a link publicly.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use pslink_shared::apirequests::users::Role;
+use pslink_shared::datatypes::ShareToken;
+#[cfg(feature = "server")]
+use pslink_shared::datatypes::{Link, User};
+
+#[cfg(feature = "server")]
+use crate::models::LinkDbOperations as _;
+
+/// Only the author of a link and administrators may share its statistics.
+#[cfg(feature = "server")]
+async fn ensure_may_share(user: &User, link_id: i64) -> Result<(), ServerFnError> {
+    let link = Link::get_link_by_id(link_id).await?;
+    if user.role != Role::Admin && link.author != user.id {
+        return Err(ServerFnError::new(
+            "Only the author can share the statistics of a link",
+        ));
+    }
+    Ok(())
+}
+
+/// Create a token for the public statistics page of a link, it expires after `valid_days` days if given.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not the author of the link or access to the database fails.
+#[server(CreateShareToken, endpoint = "create_share_token")]
+pub async fn create_share_token(
+    link_id: i64,
+    valid_days: Option<u32>,
+) -> Result<ShareToken, ServerFnError> {
+    let user = crate::auth::get_authenticated_user().await?;
+    ensure_may_share(&user, link_id).await?;
+    crate::share::create_token(link_id, valid_days.filter(|days| *days > 0)).await
+}
+
+/// List the share tokens of a link.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not the author of the link or access to the database fails.
+#[server(ListShareTokens, endpoint = "list_share_tokens")]
+pub async fn list_share_tokens(link_id: i64) -> Result<Vec<ShareToken>, ServerFnError> {
+    let user = crate::auth::get_authenticated_user().await?;
+    ensure_may_share(&user, link_id).await?;
+    crate::share::list_tokens(link_id).await
+}
+
+/// Revoke a share token, the public page is no longer available afterwards.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is not the author of the link or access to the database fails.
+#[server(RevokeShareToken, endpoint = "revoke_share_token")]
+pub async fn revoke_share_token(token_id: i64) -> Result<(), ServerFnError> {
+    let user = crate::auth::get_authenticated_user().await?;
+    let link_id = crate::share::token_link(token_id).await?;
+    ensure_may_share(&user, link_id).await?;
+    crate::share::delete_token(token_id).await
+}
@@ -0,0 +1,429 @@
+//! Aggregated statistics over all links of the instance.
+use std::collections::HashMap;
+use std::str::FromStr as _;
+
+use crate::models::LinkDbOperations as _;
+use crate::storage::query;
+use chrono::{Datelike as _, Months, NaiveDate, NaiveDateTime, NaiveTime};
+use dioxus::prelude::ServerFnError;
+use pslink_shared::{
+    apirequests::{links::StatisticsPeriod, users::Role},
+    datatypes::{
+        AuthorActivity, Clicks, Count, DayCount, DeviceClass, FullLink, Lang, Link, LinkClicks,
+        LinkDetails, MonthCount, NamedCount, Secret, StaleLink, User, WeekCount,
+    },
+};
+
+/// The number of referrers listed in the link details.
+const REFERRER_AMOUNT: i64 = 10;
+
+/// The earliest creation date that belongs to the period.
+fn period_start(period: StatisticsPeriod) -> NaiveDateTime {
+    match period.days() {
+        Some(days) => chrono::Utc::now().naive_utc() - chrono::Duration::days(days.into()),
+        None => NaiveDateTime::default(),
+    }
+}
+
+/// Read the number of clicks per day since `since`, of a single link or of all links.
+async fn clicks_per_day(
+    since: NaiveDate,
+    link: Option<i64>,
+) -> Result<HashMap<NaiveDate, i64>, ServerFnError> {
+    let db = crate::get_db().await;
+    let link_filter = if link.is_some() { "AND link = $2" } else { "" };
+    let mut qry = query(format!(
+        "SELECT {} AS day, COUNT(*) AS total
+        FROM clicks
+        WHERE created_at >= $1 {link_filter}
+        GROUP BY day",
+        db.day("created_at")
+    ))
+    .bind(since.and_time(NaiveTime::MIN));
+    if let Some(link) = link {
+        qry = qry.bind(link);
+    }
+    Ok(qry
+        .fetch_all(&db)
+        .await?
+        .iter()
+        .filter_map(|row| {
+            let day: String = row.get("day");
+            Some((day.parse::<NaiveDate>().ok()?, row.get("total")))
+        })
+        .collect())
+}
+
+/// Get the clicks per week during the last year, of a single link or of all links.
+///
+/// The weeks start at the first day of the month a year ago and are at most the current week.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn weekly_clicks(link: Option<i64>) -> Result<Vec<WeekCount>, ServerFnError> {
+    let today = chrono::Utc::now().date_naive();
+    let first = today
+        .with_day(1)
+        .and_then(|day| day.checked_sub_months(Months::new(12)))
+        .unwrap_or(today);
+    let days = clicks_per_day(first, link).await?;
+    let mut totals: HashMap<String, i64> = HashMap::new();
+    for (day, total) in &days {
+        *totals.entry(day.format("%Y.%W").to_string()).or_default() += total;
+    }
+    let mut values = Vec::new();
+    let mut full_date = first;
+    loop {
+        values.push(WeekCount {
+            full_date,
+            total: Count {
+                number: totals
+                    .get(&full_date.format("%Y.%W").to_string())
+                    .copied()
+                    .unwrap_or(0),
+            },
+            week: full_date.format("%W").to_string().parse().unwrap_or(0),
+        });
+        if full_date >= today {
+            break;
+        }
+        full_date += chrono::Duration::days(7);
+    }
+    Ok(values)
+}
+
+/// Get the clicks on all links per week during the last year.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn clicks_per_week() -> Result<Vec<WeekCount>, ServerFnError> {
+    weekly_clicks(None).await
+}
+
+/// Get the number of links created per month during the last two years.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn links_per_month() -> Result<Vec<MonthCount>, ServerFnError> {
+    let db = crate::get_db().await;
+    let this_month = chrono::Utc::now()
+        .date_naive()
+        .with_day(1)
+        .unwrap_or_default();
+    let first = this_month
+        .checked_sub_months(Months::new(23))
+        .unwrap_or(this_month);
+    let totals: HashMap<String, i64> = query(format!(
+        "SELECT {} AS month, COUNT(*) AS total
+        FROM links
+        WHERE created_at >= $1
+        GROUP BY month",
+        db.month_start("created_at")
+    ))
+    .bind(first.and_time(NaiveTime::MIN))
+    .fetch_all(&db)
+    .await?
+    .iter()
+    .map(|row| (row.get("month"), row.get("total")))
+    .collect();
+    let values = (0..24)
+        .filter_map(|offset| first.checked_add_months(Months::new(offset)))
+        .map(|month| MonthCount {
+            month,
+            total: Count {
+                number: totals
+                    .get(&month.format("%Y-%m-%d").to_string())
+                    .copied()
+                    .unwrap_or(0),
+            },
+        })
+        .collect();
+    Ok(values)
+}
+
+/// Get the most clicked links of the period.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn top_links(
+    period: StatisticsPeriod,
+    amount: i64,
+) -> Result<Vec<LinkClicks>, ServerFnError> {
+    let db = crate::get_db().await;
+    let values = query(
+        "SELECT
+            links.id AS lid,
+            links.title AS ltitle,
+            links.target AS ltarget,
+            links.code AS lcode,
+            links.author AS lauthor,
+            links.created_at AS ldate,
+            COUNT(clicks.id) AS number
+        FROM clicks
+        JOIN links ON clicks.link = links.id
+        WHERE clicks.created_at > $1
+        GROUP BY links.id
+        ORDER BY number DESC
+        LIMIT $2",
+    )
+    .bind(period_start(period))
+    .bind(amount)
+    .fetch_all(&db)
+    .await?
+    .into_iter()
+    .map(|v| LinkClicks {
+        link: Link {
+            id: v.get("lid"),
+            title: v.get("ltitle"),
+            target: v.get("ltarget"),
+            code: v.get("lcode"),
+            author: v.get("lauthor"),
+            created_at: v.get_utc("ldate"),
+        },
+        clicks: Count {
+            number: v.get("number"),
+        },
+    })
+    .collect();
+    Ok(values)
+}
+
+/// Get the authors that created the most links and received the most clicks in the period.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn top_authors(
+    period: StatisticsPeriod,
+    amount: i64,
+) -> Result<Vec<AuthorActivity>, ServerFnError> {
+    let db = crate::get_db().await;
+    let since = period_start(period);
+    let values = query(
+        "SELECT * FROM (
+            SELECT
+                users.id AS uid,
+                users.username AS username,
+                (SELECT COUNT(*) FROM links
+                    WHERE links.author = users.id
+                    AND links.created_at > $1) AS links_created,
+                (SELECT COUNT(*) FROM clicks
+                    JOIN links ON clicks.link = links.id
+                    WHERE links.author = users.id
+                    AND clicks.created_at > $1) AS clicks
+            FROM users
+        ) AS activity
+        WHERE links_created > 0 OR clicks > 0
+        ORDER BY links_created DESC, clicks DESC
+        LIMIT $2",
+    )
+    .bind(since)
+    .bind(amount)
+    .fetch_all(&db)
+    .await?
+    .into_iter()
+    .map(|v| AuthorActivity {
+        user_id: v.get("uid"),
+        username: v.get("username"),
+        links_created: Count {
+            number: v.get("links_created"),
+        },
+        clicks: Count {
+            number: v.get("clicks"),
+        },
+    })
+    .collect();
+    Ok(values)
+}
+
+/// Get the links that were not clicked during the last `months` months, the ones unused for the longest time first.
+///
+/// Links that were created during that time are not included.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn stale_links(months: u32, amount: i64) -> Result<Vec<StaleLink>, ServerFnError> {
+    let db = crate::get_db().await;
+    let before = chrono::Utc::now()
+        .naive_utc()
+        .checked_sub_months(Months::new(months))
+        .unwrap_or_default();
+    let values = query(
+        "SELECT
+            links.id AS lid,
+            links.title AS ltitle,
+            links.target AS ltarget,
+            links.code AS lcode,
+            links.author AS lauthor,
+            links.created_at AS ldate,
+            users.username AS usern,
+            (SELECT MAX(clicks.created_at) FROM clicks WHERE clicks.link = links.id) AS last_click
+        FROM links
+        JOIN users ON links.author = users.id
+        WHERE links.created_at < $1
+        AND NOT EXISTS (
+            SELECT 1 FROM clicks
+            WHERE clicks.link = links.id
+            AND clicks.created_at > $1
+        )
+        ORDER BY last_click ASC NULLS FIRST, links.created_at ASC
+        LIMIT $2",
+    )
+    .bind(before)
+    .bind(amount)
+    .fetch_all(&db)
+    .await?
+    .into_iter()
+    .map(|v| StaleLink {
+        link: Link {
+            id: v.get("lid"),
+            title: v.get("ltitle"),
+            target: v.get("ltarget"),
+            code: v.get("lcode"),
+            author: v.get("lauthor"),
+            created_at: v.get_utc("ldate"),
+        },
+        author: v.get("usern"),
+        last_click: v.get("last_click"),
+    })
+    .collect();
+    Ok(values)
+}
+
+/// Get the detailed statistics of a single link.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed or the link is not found.
+pub(crate) async fn link_details(link_id: i64) -> Result<LinkDetails, ServerFnError> {
+    let db = crate::get_db().await;
+    let v = query(
+        "SELECT
+            links.id AS lid,
+            links.title AS ltitle,
+            links.target AS ltarget,
+            links.code AS lcode,
+            links.author AS lauthor,
+            links.created_at AS ldate,
+            users.id AS usid,
+            users.username AS usern,
+            users.email AS uemail,
+            users.role AS urole,
+            users.language AS ulang
+        FROM links
+        JOIN users ON links.author = users.id
+        WHERE links.id = $1",
+    )
+    .bind(link_id)
+    .fetch_one(&db)
+    .await?;
+    let link = FullLink {
+        link: Link {
+            id: v.get("lid"),
+            title: v.get("ltitle"),
+            target: v.get("ltarget"),
+            code: v.get("lcode"),
+            author: v.get("lauthor"),
+            created_at: v.get_utc("ldate"),
+        },
+        user: User {
+            id: v.get("usid"),
+            username: v.get("usern"),
+            email: v.get("uemail"),
+            password: Secret::new("invalid".to_string()),
+            role: Role::convert(v.get("urole")),
+            language: Lang::from_str(v.get("ulang")).expect("Should parse"),
+        },
+        clicks: Clicks::Extended(Link::get_statistics(link_id).await?),
+    };
+
+    let today = chrono::Utc::now().date_naive();
+    let first = today - chrono::Duration::days(364);
+    let days = clicks_per_day(first, Some(link_id)).await?;
+    let daily = first
+        .iter_days()
+        .take_while(|date| *date <= today)
+        .map(|date| DayCount {
+            date,
+            total: Count {
+                number: days.get(&date).copied().unwrap_or(0),
+            },
+        })
+        .collect();
+
+    let referrers = query(
+        "SELECT COALESCE(referrer, '') AS name, COUNT(*) AS total
+        FROM clicks
+        WHERE link = $1
+        GROUP BY name
+        ORDER BY total DESC
+        LIMIT $2",
+    )
+    .bind(link_id)
+    .bind(REFERRER_AMOUNT)
+    .fetch_all(&db)
+    .await?
+    .into_iter()
+    .map(|c| NamedCount {
+        name: c.get("name"),
+        total: Count {
+            number: c.get("total"),
+        },
+    })
+    .collect();
+
+    let devices = query(
+        // clicks recorded before the device was stored are counted as unknown.
+        "SELECT COALESCE(device, 'unknown') AS device_class, COUNT(*) AS total
+        FROM clicks
+        WHERE link = $1
+        GROUP BY device_class
+        ORDER BY total DESC",
+    )
+    .bind(link_id)
+    .fetch_all(&db)
+    .await?
+    .into_iter()
+    .map(|c| {
+        let device: String = c.get("device_class");
+        (
+            DeviceClass::from_str(&device).unwrap_or(DeviceClass::Unknown),
+            Count {
+                number: c.get("total"),
+            },
+        )
+    })
+    .collect();
+
+    let mut heatmap = vec![vec![0_i64; 24]; 7];
+    let rows = query(format!(
+        "SELECT
+            {} AS weekday,
+            {} AS hour,
+            COUNT(*) AS total
+        FROM clicks
+        WHERE link = $1
+        GROUP BY weekday, hour",
+        db.weekday("created_at"),
+        db.hour("created_at")
+    ))
+    .bind(link_id)
+    .fetch_all(&db)
+    .await?;
+    for row in rows {
+        let weekday: i64 = row.get("weekday");
+        let hour: i64 = row.get("hour");
+        if let Some(cell) = heatmap
+            .get_mut(weekday as usize)
+            .and_then(|day| day.get_mut(hour as usize))
+        {
+            *cell = row.get("total");
+        }
+    }
+
+    Ok(LinkDetails {
+        link,
+        daily,
+        referrers,
+        devices,
+        heatmap,
+    })
+}
@@ -0,0 +1,540 @@
+//! The databases pslink can store its data in.
+//!
+//! SQLite is used by default, a `postgres://` (or `postgresql://`) database url selects PostgreSQL.
+//! Queries are written once with `$1`, `$2`, … placeholders which are understood by both backends.
+//! The few date functions that differ between them are generated by the methods of [`Storage`].
+//!
+//! The user and link queries of `crate::models` are checked at compile time instead, one query per backend.
+//! SQLite queries use `?` placeholders and PostgreSQL queries `$1`, … so that each text is only valid for one
+//! backend, the descriptions of both are kept together in the `.sqlx` directory of the workspace.
+//! After changing one of them run `cargo make prepare-queries` (needs `sqlx-cli` and a PostgreSQL server given by
+//! `PSLINK_PREPARE_POSTGRES`) and commit the regenerated `.sqlx` directory.
+use axum_session::DatabasePool;
+use chrono::NaiveDateTime;
+use futures::{StreamExt as _, TryStreamExt as _, stream::BoxStream};
+use sqlx::{Database, Decode, Postgres, Sqlite, Type, migrate::Migrator};
+
+static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+static POSTGRES_MIGRATOR: Migrator = sqlx::migrate!("./migrations_postgres");
+
+/// A connection pool to the database.
+#[derive(Debug, Clone)]
+pub enum Storage {
+    Sqlite(sqlx::SqlitePool),
+    Postgres(sqlx::PgPool),
+}
+
+impl Storage {
+    /// Whether the database setting is the url of a PostgreSQL server rather than the path of a SQLite file.
+    #[must_use]
+    pub fn is_postgres_url(database: &str) -> bool {
+        database.starts_with("postgres://") || database.starts_with("postgresql://")
+    }
+
+    /// Connect to a PostgreSQL url or a SQLite file.
+    ///
+    /// # Errors
+    /// Fails with [`sqlx::Error`] if the database cannot be opened.
+    pub async fn connect(database: &str) -> Result<Self, sqlx::Error> {
+        if Self::is_postgres_url(database) {
+            Ok(Self::Postgres(sqlx::PgPool::connect(database).await?))
+        } else {
+            Ok(Self::Sqlite(sqlx::SqlitePool::connect(database).await?))
+        }
+    }
+
+    #[must_use]
+    pub const fn is_postgres(&self) -> bool {
+        matches!(self, Self::Postgres(_))
+    }
+
+    /// Apply the pending migrations of the backend, every backend has its own set of migrations.
+    ///
+    /// # Errors
+    /// Fails with [`sqlx::migrate::MigrateError`] if a migration fails.
+    pub async fn migrate(&self) -> Result<(), sqlx::migrate::MigrateError> {
+        match self {
+            Self::Sqlite(pool) => SQLITE_MIGRATOR.run(pool).await,
+            Self::Postgres(pool) => POSTGRES_MIGRATOR.run(pool).await,
+        }
+    }
+
+    /// Start a transaction, it is rolled back if it is dropped without [`Transaction::commit`].
+    ///
+    /// # Errors
+    /// Fails with [`sqlx::Error`] if the database cannot be acessed.
+    pub async fn begin(&self) -> Result<Transaction, sqlx::Error> {
+        Ok(match self {
+            Self::Sqlite(pool) => Transaction::Sqlite(pool.begin().await?),
+            Self::Postgres(pool) => Transaction::Postgres(pool.begin().await?),
+        })
+    }
+
+    /// The date of a timestamp column as `YYYY-MM-DD` text.
+    #[must_use]
+    pub fn day(&self, column: &str) -> String {
+        match self {
+            Self::Sqlite(_) => format!("date({column})"),
+            Self::Postgres(_) => format!("to_char({column}, 'YYYY-MM-DD')"),
+        }
+    }
+
+    /// The monday of the week of a timestamp column as `YYYY-MM-DD` text.
+    #[must_use]
+    pub fn week_start(&self, column: &str) -> String {
+        match self {
+            Self::Sqlite(_) => format!("date({column}, '-6 days', 'weekday 1')"),
+            Self::Postgres(_) => format!("to_char(date_trunc('week', {column}), 'YYYY-MM-DD')"),
+        }
+    }
+
+    /// The first day of the month of a timestamp column as `YYYY-MM-DD` text.
+    #[must_use]
+    pub fn month_start(&self, column: &str) -> String {
+        match self {
+            Self::Sqlite(_) => format!("date({column}, 'start of month')"),
+            Self::Postgres(_) => format!("to_char(date_trunc('month', {column}), 'YYYY-MM-DD')"),
+        }
+    }
+
+    /// A timestamp column as `YYYY-MM-DD HH:MM:SS` text.
+    #[must_use]
+    pub fn timestamp_text(&self, column: &str) -> String {
+        match self {
+            Self::Sqlite(_) => format!("strftime('%Y-%m-%d %H:%M:%S', {column})"),
+            Self::Postgres(_) => format!("to_char({column}, 'YYYY-MM-DD HH24:MI:SS')"),
+        }
+    }
+
+    /// The day of the week of a timestamp column as integer, 0 is sunday.
+    #[must_use]
+    pub fn weekday(&self, column: &str) -> String {
+        match self {
+            Self::Sqlite(_) => format!("CAST(strftime('%w', {column}) AS INTEGER)"),
+            Self::Postgres(_) => format!("CAST(EXTRACT(DOW FROM {column}) AS BIGINT)"),
+        }
+    }
+
+    /// The hour of a timestamp column as integer.
+    #[must_use]
+    pub fn hour(&self, column: &str) -> String {
+        match self {
+            Self::Sqlite(_) => format!("CAST(strftime('%H', {column}) AS INTEGER)"),
+            Self::Postgres(_) => format!("CAST(EXTRACT(HOUR FROM {column}) AS BIGINT)"),
+        }
+    }
+}
+
+/// A running transaction of either backend.
+#[derive(Debug)]
+pub enum Transaction {
+    Sqlite(sqlx::Transaction<'static, Sqlite>),
+    Postgres(sqlx::Transaction<'static, Postgres>),
+}
+
+impl Transaction {
+    /// Write all changes of the transaction.
+    ///
+    /// # Errors
+    /// Fails with [`sqlx::Error`] if the changes cannot be written, in that case nothing is written.
+    pub async fn commit(self) -> Result<(), sqlx::Error> {
+        match self {
+            Self::Sqlite(transaction) => transaction.commit().await,
+            Self::Postgres(transaction) => transaction.commit().await,
+        }
+    }
+}
+
+/// Where a query is executed, either on the pool or inside a transaction.
+pub enum Executor<'a> {
+    Pool(&'a Storage),
+    Transaction(&'a mut Transaction),
+}
+
+impl<'a> From<&'a Storage> for Executor<'a> {
+    fn from(storage: &'a Storage) -> Self {
+        Self::Pool(storage)
+    }
+}
+
+impl<'a> From<&'a mut Transaction> for Executor<'a> {
+    fn from(transaction: &'a mut Transaction) -> Self {
+        Self::Transaction(transaction)
+    }
+}
+
+/// A value bound to a placeholder of a query.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Integer(Option<i64>),
+    Text(Option<String>),
+    Timestamp(Option<NaiveDateTime>),
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Self::Integer(Some(value))
+    }
+}
+
+impl From<Option<i64>> for Value {
+    fn from(value: Option<i64>) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::Text(Some(value))
+    }
+}
+
+impl From<&String> for Value {
+    fn from(value: &String) -> Self {
+        Self::Text(Some(value.clone()))
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::Text(Some(value.to_owned()))
+    }
+}
+
+impl From<Option<String>> for Value {
+    fn from(value: Option<String>) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&Option<String>> for Value {
+    fn from(value: &Option<String>) -> Self {
+        Self::Text(value.clone())
+    }
+}
+
+impl From<Option<&str>> for Value {
+    fn from(value: Option<&str>) -> Self {
+        Self::Text(value.map(ToOwned::to_owned))
+    }
+}
+
+impl From<NaiveDateTime> for Value {
+    fn from(value: NaiveDateTime) -> Self {
+        Self::Timestamp(Some(value))
+    }
+}
+
+impl From<&NaiveDateTime> for Value {
+    fn from(value: &NaiveDateTime) -> Self {
+        Self::Timestamp(Some(*value))
+    }
+}
+
+impl From<Option<NaiveDateTime>> for Value {
+    fn from(value: Option<NaiveDateTime>) -> Self {
+        Self::Timestamp(value)
+    }
+}
+
+/// A row returned by either backend.
+#[derive(Debug)]
+pub enum DbRow {
+    Sqlite(sqlx::sqlite::SqliteRow),
+    Postgres(sqlx::postgres::PgRow),
+}
+
+impl DbRow {
+    /// Read a column, panics if the column does not exist or has a different type like [`sqlx::Row::get`].
+    #[must_use]
+    pub fn get<'r, T>(&'r self, column: &str) -> T
+    where
+        T: Decode<'r, Sqlite> + Type<Sqlite> + Decode<'r, Postgres> + Type<Postgres>,
+    {
+        match self {
+            Self::Sqlite(row) => sqlx::Row::get(row, column),
+            Self::Postgres(row) => sqlx::Row::get(row, column),
+        }
+    }
+
+    /// Read a `TIMESTAMP` column as UTC time.
+    ///
+    /// PostgreSQL only decodes timestamps without time zone into naive times, they are stored in UTC.
+    #[must_use]
+    pub fn get_utc(&self, column: &str) -> time::OffsetDateTime {
+        self.get::<time::PrimitiveDateTime>(column).assume_utc()
+    }
+
+    /// Read a column.
+    ///
+    /// # Errors
+    /// Fails with [`sqlx::Error`] if the column does not exist or has a different type.
+    pub fn try_get<'r, T>(&'r self, column: &str) -> Result<T, sqlx::Error>
+    where
+        T: Decode<'r, Sqlite> + Type<Sqlite> + Decode<'r, Postgres> + Type<Postgres>,
+    {
+        match self {
+            Self::Sqlite(row) => sqlx::Row::try_get(row, column),
+            Self::Postgres(row) => sqlx::Row::try_get(row, column),
+        }
+    }
+}
+
+/// A query with its bound values that can run on either backend, see [`query`].
+#[derive(Debug, Clone)]
+pub struct Query {
+    sql: String,
+    arguments: Vec<Value>,
+}
+
+/// Create a query, the placeholders are `$1`, `$2`, … in the order of the [`Query::bind`] calls.
+pub fn query(sql: impl Into<String>) -> Query {
+    Query {
+        sql: sql.into(),
+        arguments: Vec::new(),
+    }
+}
+
+type BackendQuery<'q, DB> = sqlx::query::Query<'q, DB, <DB as Database>::Arguments<'q>>;
+
+impl Query {
+    /// Bind the value of the next placeholder.
+    #[must_use]
+    pub fn bind(mut self, value: impl Into<Value>) -> Self {
+        self.arguments.push(value.into());
+        self
+    }
+
+    fn sqlite(&self) -> BackendQuery<'_, Sqlite> {
+        self.arguments
+            .iter()
+            .fold(sqlx::query(&self.sql), |query, value| match value {
+                Value::Integer(value) => query.bind(*value),
+                Value::Text(value) => query.bind(value.as_deref()),
+                Value::Timestamp(value) => query.bind(*value),
+            })
+    }
+
+    fn postgres(&self) -> BackendQuery<'_, Postgres> {
+        self.arguments
+            .iter()
+            .fold(sqlx::query(&self.sql), |query, value| match value {
+                Value::Integer(value) => query.bind(*value),
+                Value::Text(value) => query.bind(value.as_deref()),
+                Value::Timestamp(value) => query.bind(*value),
+            })
+    }
+
+    /// Execute the query and return the number of affected rows.
+    ///
+    /// # Errors
+    /// Fails with [`sqlx::Error`] if the query fails.
+    pub async fn execute<'a>(self, executor: impl Into<Executor<'a>>) -> Result<u64, sqlx::Error> {
+        Ok(match executor.into() {
+            Executor::Pool(Storage::Sqlite(pool)) => self.sqlite().execute(pool).await?,
+            Executor::Pool(Storage::Postgres(pool)) => self.postgres().execute(pool).await?,
+            Executor::Transaction(Transaction::Sqlite(transaction)) => {
+                self.sqlite().execute(&mut **transaction).await?
+            }
+            Executor::Transaction(Transaction::Postgres(transaction)) => {
+                self.postgres().execute(&mut **transaction).await?
+            }
+        }
+        .rows_affected())
+    }
+
+    /// Fetch exactly one row.
+    ///
+    /// # Errors
+    /// Fails with [`sqlx::Error`] if the query fails or returns no row.
+    pub async fn fetch_one<'a>(
+        self,
+        executor: impl Into<Executor<'a>>,
+    ) -> Result<DbRow, sqlx::Error> {
+        Ok(match executor.into() {
+            Executor::Pool(Storage::Sqlite(pool)) => {
+                DbRow::Sqlite(self.sqlite().fetch_one(pool).await?)
+            }
+            Executor::Pool(Storage::Postgres(pool)) => {
+                DbRow::Postgres(self.postgres().fetch_one(pool).await?)
+            }
+            Executor::Transaction(Transaction::Sqlite(transaction)) => {
+                DbRow::Sqlite(self.sqlite().fetch_one(&mut **transaction).await?)
+            }
+            Executor::Transaction(Transaction::Postgres(transaction)) => {
+                DbRow::Postgres(self.postgres().fetch_one(&mut **transaction).await?)
+            }
+        })
+    }
+
+    /// Fetch at most one row.
+    ///
+    /// # Errors
+    /// Fails with [`sqlx::Error`] if the query fails.
+    pub async fn fetch_optional<'a>(
+        self,
+        executor: impl Into<Executor<'a>>,
+    ) -> Result<Option<DbRow>, sqlx::Error> {
+        Ok(match executor.into() {
+            Executor::Pool(Storage::Sqlite(pool)) => {
+                self.sqlite().fetch_optional(pool).await?.map(DbRow::Sqlite)
+            }
+            Executor::Pool(Storage::Postgres(pool)) => self
+                .postgres()
+                .fetch_optional(pool)
+                .await?
+                .map(DbRow::Postgres),
+            Executor::Transaction(Transaction::Sqlite(transaction)) => self
+                .sqlite()
+                .fetch_optional(&mut **transaction)
+                .await?
+                .map(DbRow::Sqlite),
+            Executor::Transaction(Transaction::Postgres(transaction)) => self
+                .postgres()
+                .fetch_optional(&mut **transaction)
+                .await?
+                .map(DbRow::Postgres),
+        })
+    }
+
+    /// Fetch all rows.
+    ///
+    /// # Errors
+    /// Fails with [`sqlx::Error`] if the query fails.
+    pub async fn fetch_all<'a>(
+        self,
+        executor: impl Into<Executor<'a>>,
+    ) -> Result<Vec<DbRow>, sqlx::Error> {
+        Ok(match executor.into() {
+            Executor::Pool(Storage::Sqlite(pool)) => self
+                .sqlite()
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(DbRow::Sqlite)
+                .collect(),
+            Executor::Pool(Storage::Postgres(pool)) => self
+                .postgres()
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(DbRow::Postgres)
+                .collect(),
+            Executor::Transaction(Transaction::Sqlite(transaction)) => self
+                .sqlite()
+                .fetch_all(&mut **transaction)
+                .await?
+                .into_iter()
+                .map(DbRow::Sqlite)
+                .collect(),
+            Executor::Transaction(Transaction::Postgres(transaction)) => self
+                .postgres()
+                .fetch_all(&mut **transaction)
+                .await?
+                .into_iter()
+                .map(DbRow::Postgres)
+                .collect(),
+        })
+    }
+
+    /// Stream the rows one by one instead of loading all of them into memory.
+    pub fn fetch<'a>(&'a self, storage: &'a Storage) -> BoxStream<'a, Result<DbRow, sqlx::Error>> {
+        match storage {
+            Storage::Sqlite(pool) => self.sqlite().fetch(pool).map_ok(DbRow::Sqlite).boxed(),
+            Storage::Postgres(pool) => self.postgres().fetch(pool).map_ok(DbRow::Postgres).boxed(),
+        }
+    }
+}
+
+/// The session store in the database of the configured backend.
+#[derive(Debug, Clone)]
+pub enum SessionPool {
+    Sqlite(axum_session_sqlx::SessionSqlitePool),
+    Postgres(axum_session_sqlx::SessionPgPool),
+}
+
+impl From<&Storage> for SessionPool {
+    fn from(storage: &Storage) -> Self {
+        match storage {
+            Storage::Sqlite(pool) => Self::Sqlite(pool.clone().into()),
+            Storage::Postgres(pool) => Self::Postgres(pool.clone().into()),
+        }
+    }
+}
+
+/// Forward a method of the session store to the store of the backend.
+macro_rules! forward {
+    ($self:ident.$method:ident($($argument:expr),*)) => {
+        match $self {
+            Self::Sqlite(pool) => pool.$method($($argument),*).await,
+            Self::Postgres(pool) => pool.$method($($argument),*).await,
+        }
+    };
+}
+
+#[async_trait::async_trait]
+impl DatabasePool for SessionPool {
+    async fn initiate(&self, table_name: &str) -> Result<(), axum_session::DatabaseError> {
+        forward!(self.initiate(table_name))
+    }
+
+    async fn delete_by_expiry(
+        &self,
+        table_name: &str,
+    ) -> Result<Vec<String>, axum_session::DatabaseError> {
+        forward!(self.delete_by_expiry(table_name))
+    }
+
+    async fn count(&self, table_name: &str) -> Result<i64, axum_session::DatabaseError> {
+        forward!(self.count(table_name))
+    }
+
+    async fn store(
+        &self,
+        id: &str,
+        session: &str,
+        expires: i64,
+        table_name: &str,
+    ) -> Result<(), axum_session::DatabaseError> {
+        forward!(self.store(id, session, expires, table_name))
+    }
+
+    async fn load(
+        &self,
+        id: &str,
+        table_name: &str,
+    ) -> Result<Option<String>, axum_session::DatabaseError> {
+        forward!(self.load(id, table_name))
+    }
+
+    async fn delete_one_by_id(
+        &self,
+        id: &str,
+        table_name: &str,
+    ) -> Result<(), axum_session::DatabaseError> {
+        forward!(self.delete_one_by_id(id, table_name))
+    }
+
+    async fn exists(
+        &self,
+        id: &str,
+        table_name: &str,
+    ) -> Result<bool, axum_session::DatabaseError> {
+        forward!(self.exists(id, table_name))
+    }
+
+    async fn delete_all(&self, table_name: &str) -> Result<(), axum_session::DatabaseError> {
+        forward!(self.delete_all(table_name))
+    }
+
+    async fn get_ids(&self, table_name: &str) -> Result<Vec<String>, axum_session::DatabaseError> {
+        forward!(self.get_ids(table_name))
+    }
+
+    fn auto_handles_expiry(&self) -> bool {
+        match self {
+            Self::Sqlite(pool) => pool.auto_handles_expiry(),
+            Self::Postgres(pool) => pool.auto_handles_expiry(),
+        }
+    }
+}
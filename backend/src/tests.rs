@@ -0,0 +1,2808 @@
+#![cfg(test)]
+//! The database tests run the same suite against SQLite and PostgreSQL.
+//!
+//! The PostgreSQL suite is ignored by default as it needs a local server. Set `PSLINK_TEST_POSTGRES_URL` to an
+//! empty database like `postgres://postgres@localhost/pslink_test` and run `cargo test -- --ignored` to run it.
+//! Its schema is dropped before the suite runs.
+use pslink_shared::{
+    apirequests::{links::StatisticsPeriod, users::Role},
+    datatypes::{ApiTokenScope, DeviceClass, Link, User},
+};
+
+use crate::models::{LinkDbOperations as _, NewClick, NewLink, NewUser, UserDbOperations as _};
+use crate::storage::query;
+
+/// The database is a global, the suites must not run at the same time.
+static DATABASE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Lock the database and replace it with a new migrated in-memory SQLite database.
+async fn fresh_db() -> tokio::sync::MutexGuard<'static, ()> {
+    let lock = DATABASE_LOCK.lock().await;
+    reset_db().await;
+    lock
+}
+
+/// Replace the database with a new migrated in-memory SQLite database, the lock has to be held.
+async fn reset_db() {
+    crate::init_db("sqlite::memory:").await;
+    crate::get_db()
+        .await
+        .migrate()
+        .await
+        .expect("Failed to migrate");
+    crate::SECRET.get_or_init(|| pslink_shared::datatypes::Secret::new("test".to_string()));
+}
+
+/// Insert a regular user with the password `password`.
+async fn add_user(username: &str) -> User {
+    NewUser::new(
+        username.to_string(),
+        format!("{username}@example.com"),
+        "password",
+        &crate::get_secret(),
+    )
+    .expect("Hash")
+    .insert_user()
+    .await
+    .expect("Failed to insert user");
+    User::get_user_by_name(username).await.expect("User exists")
+}
+
+/// Insert a link to `https://example.com`.
+async fn add_link(code: &str, author: &User) -> Link {
+    NewLink {
+        title: code.to_string(),
+        target: "https://example.com".to_string(),
+        code: code.to_string(),
+        author: author.id,
+        created_at: chrono::Utc::now().naive_utc(),
+    }
+    .insert()
+    .await
+    .expect("Failed to insert link");
+    Link::get_link_by_code(code).await.expect("Link exists")
+}
+
+async fn suite() {
+    let db = crate::get_db().await;
+    db.migrate().await.expect("Failed to migrate");
+
+    // users
+    NewUser {
+        username: "admin".to_string(),
+        email: "admin@example.com".to_string(),
+        password: "hash".to_string(),
+    }
+    .insert_user()
+    .await
+    .expect("Failed to insert user");
+    let admin = User::get_user_by_name("admin").await.expect("User exists");
+    admin
+        .clone()
+        .toggle_admin()
+        .await
+        .expect("Failed to toggle");
+    assert_eq!(
+        User::get_user(admin.id).await.expect("User exists").role,
+        Role::Admin
+    );
+    assert_eq!(User::count_admins().await.expect("Count").number, 1);
+    assert!(User::get_user_by_name("missing").await.is_err());
+
+    // links, the code is found case insensitive
+    NewLink {
+        title: "Pslink".to_string(),
+        target: "https://github.com/enaut/pslink".to_string(),
+        code: "PsLink".to_string(),
+        author: admin.id,
+        created_at: chrono::Utc::now().naive_utc() - chrono::Duration::days(400),
+    }
+    .insert()
+    .await
+    .expect("Failed to insert link");
+    let link = Link::get_link_by_code("pslink").await.expect("Link exists");
+    assert_eq!(link.code, "PsLink");
+    assert_eq!(
+        Link::get_link_by_id(link.id)
+            .await
+            .expect("Link exists")
+            .title,
+        "Pslink"
+    );
+
+    // clicks and statistics
+    NewClick::insert_clicks(vec![
+        NewClick::new(link.id)
+            .with_request_details(Some("example.com".to_string()), Some(DeviceClass::Mobile)),
+        NewClick::new(link.id),
+        NewClick::new(link.id),
+    ])
+    .await
+    .expect("Failed to insert clicks");
+    let statistics = Link::get_statistics(link.id).await.expect("Statistics");
+    assert_eq!(statistics.total.number, 3);
+    assert_eq!(
+        statistics
+            .values
+            .iter()
+            .map(|w| w.total.number)
+            .sum::<i64>(),
+        3
+    );
+    let details = crate::statistics::link_details(link.id)
+        .await
+        .expect("Details");
+    assert_eq!(details.daily.len(), 365);
+    assert_eq!(details.daily.last().map(|d| d.total.number), Some(3));
+    assert_eq!(details.referrers.first().map(|r| r.total.number), Some(2));
+    assert_eq!(details.heatmap.iter().flatten().sum::<i64>(), 3);
+    let months = crate::statistics::links_per_month().await.expect("Months");
+    assert_eq!(months.len(), 24);
+    let top = crate::statistics::top_links(StatisticsPeriod::Week, 5)
+        .await
+        .expect("Top links");
+    assert_eq!(top.first().map(|t| t.clicks.number), Some(3));
+    let authors = crate::statistics::top_authors(StatisticsPeriod::All, 5)
+        .await
+        .expect("Top authors");
+    assert_eq!(authors.first().map(|a| a.links_created.number), Some(1));
+    assert!(
+        crate::statistics::stale_links(1, 5)
+            .await
+            .expect("Stale links")
+            .is_empty()
+    );
+
+    // api tokens are found by their secret until they are revoked
+    let created = crate::api_tokens::create_token(&admin, "ci", ApiTokenScope::Read, Some(30))
+        .await
+        .expect("Failed to create token");
+    let (owner, scope) = crate::api_tokens::authenticate(&created.secret)
+        .await
+        .expect("Authenticate")
+        .expect("Token is valid");
+    assert_eq!((owner.id, scope), (admin.id, ApiTokenScope::Read));
+    let tokens = crate::api_tokens::list_tokens(Some(admin.id))
+        .await
+        .expect("Tokens");
+    assert_eq!(tokens.len(), 1);
+    assert!(tokens[0].last_used_at.is_some());
+    assert!(
+        crate::api_tokens::create_token(&admin, "forever", ApiTokenScope::Read, Some(u32::MAX))
+            .await
+            .is_err()
+    );
+    // only the hash of the secret is stored
+    let stored: String = query("SELECT token_hash FROM api_tokens WHERE id = $1")
+        .bind(created.token.id)
+        .fetch_one(&crate::get_db().await)
+        .await
+        .expect("Token exists")
+        .get("token_hash");
+    assert_eq!(stored, crate::tokens::hash_token(&created.secret));
+    assert_eq!(stored.len(), 64);
+    assert_ne!(
+        crate::tokens::random_token(40),
+        crate::tokens::random_token(40)
+    );
+    crate::api_tokens::delete_token(created.token.id)
+        .await
+        .expect("Failed to revoke");
+    assert!(
+        crate::api_tokens::authenticate(&created.secret)
+            .await
+            .expect("Authenticate")
+            .is_none()
+    );
+
+    // share tokens expire, a validity beyond the range of dates is refused
+    let share = crate::share::create_token(link.id, Some(7))
+        .await
+        .expect("Failed to create share token");
+    assert!(share.expires_at.is_some());
+    assert!(
+        crate::share::create_token(link.id, Some(u32::MAX))
+            .await
+            .is_err()
+    );
+
+    // deleting a link deletes its clicks
+    Link::delete_link_by_code("PsLink")
+        .await
+        .expect("Failed to delete");
+    let remaining: i64 = query("SELECT count(*) AS number FROM clicks")
+        .fetch_one(&db)
+        .await
+        .expect("Count")
+        .get("number");
+    assert_eq!(remaining, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sqlite_storage() {
+    let _lock = DATABASE_LOCK.lock().await;
+    let path = std::env::temp_dir().join(format!("pslink-test-{}.db", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    crate::init_db(&format!("sqlite://{}?mode=rwc", path.display())).await;
+    suite().await;
+    std::fs::remove_file(&path).ok();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs a PostgreSQL server in PSLINK_TEST_POSTGRES_URL"]
+async fn test_postgres_storage() {
+    let url = std::env::var("PSLINK_TEST_POSTGRES_URL")
+        .expect("PSLINK_TEST_POSTGRES_URL has to be set to run the PostgreSQL tests");
+    let _lock = DATABASE_LOCK.lock().await;
+    crate::init_db(&url).await;
+    let db = crate::get_db().await;
+    for statement in ["DROP SCHEMA public CASCADE", "CREATE SCHEMA public"] {
+        query(statement)
+            .execute(&db)
+            .await
+            .expect("Failed to reset the schema");
+    }
+    suite().await;
+}
+
+/// Send a request to the REST api and return the status and the body.
+async fn rest_request(
+    method: &str,
+    path: &str,
+    token: Option<&str>,
+    body: Option<serde_json::Value>,
+) -> (axum::http::StatusCode, Vec<u8>) {
+    use tower::ServiceExt as _;
+
+    let mut request = axum::http::Request::builder().method(method).uri(format!(
+        "{}{path}",
+        pslink_shared::apirequests::rest::PREFIX
+    ));
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {token}"));
+    }
+    let request = match body {
+        Some(body) => request
+            .header("Content-Type", "application/json")
+            .body(axum::body::Body::from(body.to_string())),
+        None => request.body(axum::body::Body::empty()),
+    }
+    .expect("Valid request");
+    let response = axum::Router::new()
+        .nest(
+            pslink_shared::apirequests::rest::PREFIX,
+            crate::rest_api::router(),
+        )
+        .oneshot(request)
+        .await
+        .expect("Response");
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("Body");
+    (status, body.to_vec())
+}
+
+fn json(body: &[u8]) -> serde_json::Value {
+    serde_json::from_slice(body).expect("Json body")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rest_api() {
+    use axum::http::StatusCode;
+    use serde_json::json;
+
+    let _lock = fresh_db().await;
+    crate::init_public_url(&crate::cli::Protocol::Https, "example.com");
+
+    let mut tokens = Vec::new();
+    for username in ["admin", "alice", "bob"] {
+        NewUser::new(
+            username.to_string(),
+            format!("{username}@example.com"),
+            "password",
+            &crate::get_secret(),
+        )
+        .expect("Hash")
+        .insert_user()
+        .await
+        .expect("Failed to insert user");
+        let user = User::get_user_by_name(username).await.expect("User exists");
+        let token = crate::api_tokens::create_token(&user, "test", ApiTokenScope::Write, None)
+            .await
+            .expect("Failed to create token");
+        tokens.push(token.secret);
+    }
+    User::get_user_by_name("admin")
+        .await
+        .expect("User exists")
+        .toggle_admin()
+        .await
+        .expect("Failed to toggle");
+    let alice = User::get_user_by_name("alice").await.expect("User exists");
+    let reader = crate::api_tokens::create_token(&alice, "read", ApiTokenScope::Read, None)
+        .await
+        .expect("Failed to create token")
+        .secret;
+    let (admin, alice_token, bob) = (
+        Some(tokens[0].as_str()),
+        Some(tokens[1].as_str()),
+        Some(tokens[2].as_str()),
+    );
+
+    // every documented route exists, unknown links and users are answered by the handlers
+    for route in crate::openapi::ROUTES {
+        let path = route
+            .path
+            .replace("{code}", "missing")
+            .replace("{id}", "999");
+        let body = route.request.map(|_| json!({}));
+        let (status, response) =
+            rest_request(&route.method.to_uppercase(), &path, admin, body).await;
+        assert_ne!(status, StatusCode::METHOD_NOT_ALLOWED, "{path}");
+        if status == StatusCode::NOT_FOUND {
+            assert!(
+                serde_json::from_slice::<serde_json::Value>(&response).is_ok(),
+                "{path}"
+            );
+        }
+    }
+    let (status, document) = rest_request("GET", "/openapi.json", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(json(&document)["paths"]["/api/v1/links/{code}"]["put"].is_object());
+
+    // authentication and scopes
+    let link =
+        json!({ "title": "Pslink", "target": "https://github.com/enaut/pslink", "code": "pslink" });
+    assert_eq!(
+        rest_request("GET", "/links", None, None).await.0,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        rest_request("GET", "/links", Some("pslink_invalid"), None)
+            .await
+            .0,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        rest_request("POST", "/links", Some(&reader), Some(link.clone()))
+            .await
+            .0,
+        StatusCode::FORBIDDEN
+    );
+
+    // links
+    let (status, created) = rest_request("POST", "/links", alice_token, Some(link.clone())).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(json(&created)["author"], json!(alice.id));
+    assert_eq!(
+        rest_request("POST", "/links", bob, Some(link.clone()))
+            .await
+            .0,
+        StatusCode::CONFLICT
+    );
+    assert_eq!(
+        rest_request(
+            "POST",
+            "/links",
+            bob,
+            Some(json!({ "title": "", "target": "https://example.com", "code": "qr" }))
+        )
+        .await
+        .0,
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    let (status, list) = rest_request("GET", "/links?code=PSL", Some(&reader), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json(&list).as_array().map(Vec::len), Some(1));
+    let renamed = json!({ "title": "Renamed", "target": "https://example.com", "code": "pslink" });
+    assert_eq!(
+        rest_request("PUT", "/links/pslink", bob, Some(renamed.clone()))
+            .await
+            .0,
+        StatusCode::FORBIDDEN
+    );
+    let (status, updated) = rest_request("PUT", "/links/pslink", admin, Some(renamed)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json(&updated)["title"], json!("Renamed"));
+    let (status, details) = rest_request("GET", "/links/PsLink/stats", bob, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json(&details)["daily"].as_array().map(Vec::len), Some(365));
+    let (status, svg) = rest_request("GET", "/links/pslink/qr?format=svg", bob, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(String::from_utf8_lossy(&svg).contains("<svg"));
+    assert_eq!(
+        rest_request("GET", "/links/pslink/qr?format=gif", bob, None)
+            .await
+            .0,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        rest_request("GET", "/stats/top-links?period=Week", bob, None)
+            .await
+            .0,
+        StatusCode::OK
+    );
+
+    // users
+    let (_, users) = rest_request("GET", "/users", bob, None).await;
+    assert_eq!(json(&users).as_array().map(Vec::len), Some(1));
+    let (_, users) = rest_request("GET", "/users", admin, None).await;
+    assert_eq!(json(&users).as_array().map(Vec::len), Some(3));
+    let (_, me) = rest_request("GET", "/users/me", alice_token, None).await;
+    assert_eq!(json(&me)["username"], json!("alice"));
+    let carol = json!({ "username": "carol", "email": "carol@example.com", "password": "secret", "role": "Regular" });
+    assert_eq!(
+        rest_request("POST", "/users", bob, Some(carol.clone()))
+            .await
+            .0,
+        StatusCode::FORBIDDEN
+    );
+    let short = json!({ "username": "dave", "email": "dave@example.com", "password": "x", "role": "Regular" });
+    assert_eq!(
+        rest_request("POST", "/users", admin, Some(short)).await.0,
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    let (status, created) = rest_request("POST", "/users", admin, Some(carol)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let carol_id = json(&created)["id"].as_i64().expect("Id");
+    let rename = json!({ "username": "alice", "email": "carol@example.com", "role": "Regular" });
+    assert_eq!(
+        rest_request("PUT", &format!("/users/{carol_id}"), admin, Some(rename))
+            .await
+            .0,
+        StatusCode::CONFLICT
+    );
+
+    // a write token cannot take over an account, the password, email and role need a login
+    let carol_user = User::get_user(carol_id).await.expect("User exists");
+    let carol_token =
+        crate::api_tokens::create_token(&carol_user, "write", ApiTokenScope::Write, None)
+            .await
+            .expect("Failed to create token")
+            .secret;
+    let takeover = json!({ "username": "carol", "email": "carol@example.com", "password": "stolen", "role": "Regular" });
+    assert_eq!(
+        rest_request(
+            "PUT",
+            &format!("/users/{carol_id}"),
+            Some(&carol_token),
+            Some(takeover)
+        )
+        .await
+        .0,
+        StatusCode::FORBIDDEN
+    );
+    assert!(crate::auth::check_password("carol", "secret").await.is_ok());
+    assert!(
+        crate::auth::check_password("carol", "stolen")
+            .await
+            .is_err()
+    );
+    let promote = json!({ "username": "carol", "email": "carol@example.com", "role": "Admin" });
+    assert_eq!(
+        rest_request("PUT", &format!("/users/{carol_id}"), admin, Some(promote))
+            .await
+            .0,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        rest_request("DELETE", &format!("/users/{carol_id}"), admin, None)
+            .await
+            .0,
+        StatusCode::NO_CONTENT
+    );
+
+    // deleting
+    assert_eq!(
+        rest_request("DELETE", "/links/pslink", bob, None).await.0,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        rest_request("DELETE", "/links/pslink", alice_token, None)
+            .await
+            .0,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        rest_request("GET", "/links/pslink", alice_token, None)
+            .await
+            .0,
+        StatusCode::NOT_FOUND
+    );
+}
+
+/// Serve the REST api with sessions on a random local port and return its url.
+async fn serve_rest_api() -> String {
+    let router = with_sessions(axum::Router::new().nest(
+        pslink_shared::apirequests::rest::PREFIX,
+        crate::rest_api::router(),
+    ))
+    .await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind");
+    let address = listener.local_addr().expect("Local address");
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    format!("http://{address}")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rest_client() {
+    use pslink_client::{Client, Error};
+    use pslink_shared::apirequests::{
+        general::{Operation, Ordering},
+        links::{LinkDelta, LinkOverviewColumns, LinkRequestForm},
+        rest::UserListQuery,
+    };
+
+    let _lock = fresh_db().await;
+    crate::init_public_url(&crate::cli::Protocol::Https, "example.com");
+    let url = serve_rest_api().await;
+    let alice = add_user("alice").await;
+    let bob = add_user("bob").await;
+    let token = |user: User, scope: ApiTokenScope| async move {
+        crate::api_tokens::create_token(&user, "client", scope, None)
+            .await
+            .expect("Token")
+            .secret
+    };
+    let client = Client::with_token(&url, &token(alice.clone(), ApiTokenScope::Write).await)
+        .expect("Client");
+    let reader =
+        Client::with_token(&url, &token(alice.clone(), ApiTokenScope::Read).await).expect("Client");
+    let bob_client =
+        Client::with_token(&url, &token(bob, ApiTokenScope::Write).await).expect("Client");
+
+    // the token authenticates the requests
+    assert_eq!(
+        client.current_user().await.expect("Current user").id,
+        alice.id
+    );
+    let invalid = Client::with_token(&url, "pslink_invalid").expect("Client");
+    assert!(matches!(
+        invalid.current_user().await,
+        Err(Error::Unauthorized(_))
+    ));
+    assert!(matches!(
+        Client::with_token("not a url", "pslink_invalid"),
+        Err(Error::InvalidUrl(_))
+    ));
+
+    // the error statuses are mapped to the errors
+    let link = |title: &str, code: &str| LinkDelta {
+        title: title.to_string(),
+        target: "https://example.com".to_string(),
+        code: code.to_string(),
+        ..LinkDelta::default()
+    };
+    for (title, code) in [
+        ("Tom & Jerry ü", "tom"),
+        ("Plain", "plain"),
+        ("Zebra", "zebra"),
+    ] {
+        client
+            .create_link(link(title, code))
+            .await
+            .expect("Created");
+    }
+    let conflict = client
+        .create_link(link("Again", "tom"))
+        .await
+        .expect_err("Conflict");
+    assert!(matches!(conflict, Error::Conflict(_)));
+    assert_eq!(conflict.status(), Some(409));
+    assert!(matches!(
+        client.create_link(link("", "empty")).await,
+        Err(Error::Invalid(_))
+    ));
+    assert!(matches!(
+        reader.create_link(link("Read", "read")).await,
+        Err(Error::Forbidden(_))
+    ));
+    assert!(matches!(
+        bob_client.delete_link("tom").await,
+        Err(Error::Forbidden(_))
+    ));
+    let missing = client.get_link("missing").await.expect_err("Not found");
+    assert!(matches!(missing, Error::NotFound(ref message) if !message.is_empty()));
+
+    // the filters and the order of the form are sent as query parameters
+    let mut form = LinkRequestForm::default();
+    form.filter[LinkOverviewColumns::Description].sieve = "tom & j".to_string();
+    let found = reader.list_links(&form).await.expect("List");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].link.code, "tom");
+    let form = LinkRequestForm {
+        order: Some(Operation {
+            column: LinkOverviewColumns::Description,
+            value: Ordering::Descending,
+        }),
+        amount: 2,
+        ..LinkRequestForm::default()
+    };
+    let titles: Vec<String> = reader
+        .list_links(&form)
+        .await
+        .expect("List")
+        .into_iter()
+        .map(|link| link.link.title)
+        .collect();
+    assert_eq!(titles, vec!["Zebra", "Tom & Jerry ü"]);
+
+    // a login keeps the session cookie until the logout
+    let session = Client::login(&url, "alice", "password")
+        .await
+        .expect("Logged in");
+    assert_eq!(session.current_user().await.expect("Session").id, alice.id);
+    session.logout().await.expect("Logged out");
+    assert!(matches!(
+        session.current_user().await,
+        Err(Error::Unauthorized(_))
+    ));
+    assert!(matches!(
+        Client::login(&url, "mallory", "wrong").await,
+        Err(Error::Unauthorized(_))
+    ));
+    // other statuses keep their code, the next attempt has to wait
+    let limited = Client::login(&url, "mallory", "wrong")
+        .await
+        .expect_err("Too many requests");
+    assert!(matches!(limited, Error::Server { status: 429, .. }));
+
+    // the blocking client runs its own runtime outside of the async one
+    let token = token(alice.clone(), ApiTokenScope::Write).await;
+    let (code, users, me) = tokio::task::spawn_blocking(move || {
+        let client = pslink_client::blocking::Client::with_token(&url, &token).expect("Client");
+        let code = client.get_link("zebra").expect("Link").code;
+        let users = client
+            .list_users(&UserListQuery::default())
+            .expect("Users")
+            .len();
+        let session =
+            pslink_client::blocking::Client::login(&url, "bob", "password").expect("Logged in");
+        (
+            code,
+            users,
+            session.current_user().expect("Session").username,
+        )
+    })
+    .await
+    .expect("Blocking client");
+    assert_eq!((code.as_str(), users, me.as_str()), ("zebra", 1, "bob"));
+}
+
+type Shared<T> = std::sync::Arc<std::sync::Mutex<T>>;
+
+/// A minimal OpenID Connect provider, `/authorize` logs in whoever has the claims in `next`.
+#[derive(Clone, Default)]
+struct MockIdp {
+    issuer: String,
+    next: Shared<serde_json::Value>,
+    /// The parameters of the authorization request and the claims by code.
+    codes: Shared<
+        std::collections::HashMap<
+            String,
+            (std::collections::HashMap<String, String>, serde_json::Value),
+        >,
+    >,
+    /// The claims by access token.
+    userinfo: Shared<std::collections::HashMap<String, serde_json::Value>>,
+}
+
+async fn start_mock_idp() -> MockIdp {
+    use axum::{
+        Form, Json,
+        extract::{Query, State},
+        http::{HeaderMap, StatusCode},
+        response::{IntoResponse as _, Redirect},
+        routing::{get, post},
+    };
+    use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+    use serde_json::json;
+    use sha2::{Digest as _, Sha256};
+    use std::collections::HashMap;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind the mock provider");
+    let idp = MockIdp {
+        issuer: format!("http://{}", listener.local_addr().expect("Address")),
+        ..Default::default()
+    };
+    let router = axum::Router::new()
+        .route(
+            "/.well-known/openid-configuration",
+            get(|State(idp): State<MockIdp>| async move {
+                Json(json!({
+                    "issuer": idp.issuer,
+                    "authorization_endpoint": format!("{}/authorize", idp.issuer),
+                    "token_endpoint": format!("{}/token", idp.issuer),
+                    "userinfo_endpoint": format!("{}/userinfo", idp.issuer),
+                }))
+            }),
+        )
+        .route(
+            "/authorize",
+            get(
+                |State(idp): State<MockIdp>, Query(params): Query<HashMap<String, String>>| async move {
+                    let mut codes = idp.codes.lock().expect("Lock");
+                    let code = format!("code-{}", codes.len());
+                    let redirect = format!(
+                        "{}?code={code}&state={}",
+                        params["redirect_uri"], params["state"]
+                    );
+                    codes.insert(code, (params, idp.next.lock().expect("Lock").clone()));
+                    Redirect::to(&redirect)
+                },
+            ),
+        )
+        .route(
+            "/token",
+            post(
+                |State(idp): State<MockIdp>, Form(form): Form<HashMap<String, String>>| async move {
+                    let Some((params, claims)) = idp.codes.lock().expect("Lock").remove(&form["code"])
+                    else {
+                        return StatusCode::BAD_REQUEST.into_response();
+                    };
+                    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(form["code_verifier"].as_bytes()));
+                    if challenge != params["code_challenge"]
+                        || form["client_id"] != "pslink"
+                        || form["redirect_uri"] != params["redirect_uri"]
+                    {
+                        return StatusCode::BAD_REQUEST.into_response();
+                    }
+                    let id_token = json!({
+                        "iss": idp.issuer,
+                        "aud": ["pslink"],
+                        "exp": chrono::Utc::now().timestamp() + 300,
+                        "nonce": params["nonce"],
+                        "sub": claims["sub"],
+                        "preferred_username": claims["preferred_username"],
+                    });
+                    let access_token = format!("access-{}", form["code"]);
+                    idp.userinfo
+                        .lock()
+                        .expect("Lock")
+                        .insert(access_token.clone(), claims);
+                    Json(json!({
+                        "access_token": access_token,
+                        "token_type": "Bearer",
+                        "id_token": format!("e30.{}.", URL_SAFE_NO_PAD.encode(id_token.to_string())),
+                    }))
+                    .into_response()
+                },
+            ),
+        )
+        .route(
+            "/userinfo",
+            get(|State(idp): State<MockIdp>, headers: HeaderMap| async move {
+                let claims = crate::api_tokens::bearer_token(&headers)
+                    .and_then(|token| idp.userinfo.lock().expect("Lock").get(token).cloned());
+                match claims {
+                    Some(mut claims) => {
+                        claims.as_object_mut().expect("Object").remove("preferred_username");
+                        Json(claims).into_response()
+                    }
+                    None => StatusCode::UNAUTHORIZED.into_response(),
+                }
+            }),
+        )
+        .with_state(idp.clone());
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    idp
+}
+
+/// Follow the redirect of the mock provider like a browser and return the flow and the callback parameters.
+async fn start_sso(
+    idp: &MockIdp,
+    settings: &crate::oidc::OidcSettings,
+    claims: serde_json::Value,
+) -> (crate::oidc::OidcFlow, String, String) {
+    *idp.next.lock().expect("Lock") = claims;
+    let flow = crate::oidc::OidcFlow::new();
+    let url = crate::oidc::authorization_url(settings, &flow)
+        .await
+        .expect("Authorization url");
+    let response = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("Client")
+        .get(url)
+        .send()
+        .await
+        .expect("Redirect");
+    let location = response.headers()[reqwest::header::LOCATION]
+        .to_str()
+        .expect("Location");
+    assert!(location.starts_with(&settings.redirect_uri));
+    let callback: std::collections::HashMap<String, String> = reqwest::Url::parse(location)
+        .expect("Callback url")
+        .query_pairs()
+        .into_owned()
+        .collect();
+    (flow, callback["state"].clone(), callback["code"].clone())
+}
+
+async fn sso_login(
+    idp: &MockIdp,
+    settings: &crate::oidc::OidcSettings,
+    claims: serde_json::Value,
+) -> Result<User, dioxus::prelude::ServerFnError> {
+    let (flow, state, code) = start_sso(idp, settings, claims).await;
+    crate::oidc::finish_login(settings, &flow, &state, &code).await
+}
+
+/// The routes wrapped in the session layers of the server.
+async fn with_sessions(routes: axum::Router) -> axum::Router {
+    let pool = crate::get_db().await;
+    let session_store = axum_session::SessionStore::<crate::storage::SessionPool>::new(
+        Some(crate::storage::SessionPool::from(&pool)),
+        axum_session::SessionConfig::default().with_table_name("test_table"),
+    )
+    .await
+    .expect("Session store");
+    routes
+        .layer(
+            axum_session_auth::AuthSessionLayer::<
+                crate::auth::AuthAccount,
+                i64,
+                crate::storage::SessionPool,
+                crate::storage::Storage,
+            >::new(Some(pool))
+            .with_config(
+                axum_session_auth::AuthConfig::<i64>::default().with_anonymous_user_id(Some(0)),
+            ),
+        )
+        .layer(axum_session::SessionLayer::new(session_store))
+}
+
+/// Request the uri like a browser that keeps the cookies.
+async fn browse(
+    router: &axum::Router,
+    cookies: &mut Vec<String>,
+    uri: &str,
+) -> axum::response::Response {
+    use axum::http::header::{COOKIE, SET_COOKIE};
+    use tower::ServiceExt as _;
+
+    let mut request = axum::http::Request::builder().uri(uri);
+    if !cookies.is_empty() {
+        request = request.header(COOKIE, cookies.join("; "));
+    }
+    let response = router
+        .clone()
+        .oneshot(
+            request
+                .body(axum::body::Body::empty())
+                .expect("Valid request"),
+        )
+        .await
+        .expect("Response");
+    for cookie in response.headers().get_all(SET_COOKIE) {
+        let cookie = cookie.to_str().expect("Cookie");
+        let cookie = cookie.split(';').next().unwrap_or_default().to_string();
+        let name = cookie.split('=').next().unwrap_or_default().to_string();
+        cookies.retain(|known| !known.starts_with(&format!("{name}=")));
+        cookies.push(cookie);
+    }
+    response
+}
+
+fn location(response: &axum::response::Response) -> String {
+    response.headers()[axum::http::header::LOCATION]
+        .to_str()
+        .expect("Location")
+        .to_string()
+}
+
+/// Go through the single sign-on routes of the server, returns where the callback redirects to and
+/// the ids of the user waiting for a second factor and of the logged in user.
+async fn sso_callback(
+    idp: &MockIdp,
+    router: &axum::Router,
+    claims: serde_json::Value,
+) -> (String, (Option<i64>, Option<i64>)) {
+    *idp.next.lock().expect("Lock") = claims;
+    let mut cookies = Vec::new();
+    let login = browse(router, &mut cookies, crate::oidc::LOGIN_PATH).await;
+    let authorized = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("Client")
+        .get(location(&login))
+        .send()
+        .await
+        .expect("Redirect");
+    let callback = reqwest::Url::parse(
+        authorized.headers()[reqwest::header::LOCATION]
+            .to_str()
+            .expect("Location"),
+    )
+    .expect("Callback url");
+    let path = format!(
+        "{}?{}",
+        callback.path(),
+        callback.query().unwrap_or_default()
+    );
+    let response = browse(router, &mut cookies, &path).await;
+    let state = browse(router, &mut cookies, "/state").await;
+    let state = axum::body::to_bytes(state.into_body(), usize::MAX)
+        .await
+        .expect("Body");
+    (
+        location(&response),
+        serde_json::from_slice(&state).expect("Json body"),
+    )
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_oidc_login() {
+    use pslink_shared::datatypes::Lang;
+    use serde_json::json;
+
+    let _lock = fresh_db().await;
+    let idp = start_mock_idp().await;
+    let mut settings = crate::oidc::OidcSettings {
+        issuer: idp.issuer.clone(),
+        client_id: "pslink".to_string(),
+        client_secret: Some("client-secret".to_string()),
+        redirect_uri: format!("https://example.com{}", crate::oidc::CALLBACK_PATH),
+        scopes: "openid profile email".to_string(),
+        username_claim: "preferred_username".to_string(),
+        groups_claim: "groups".to_string(),
+        admin_group: Some("pslink-admins".to_string()),
+        default_role: Role::Regular,
+    };
+
+    // new users are created with the claims, the admin group makes them administrators
+    let carol = json!({
+        "sub": "sub-carol",
+        "preferred_username": "carol",
+        "email": "carol@example.com",
+        "locale": "de-AT",
+        "groups": ["staff", "pslink-admins"],
+    });
+    let user = sso_login(&idp, &settings, carol).await.expect("Login");
+    assert_eq!(user.username, "carol");
+    assert_eq!(user.email, "carol@example.com");
+    assert_eq!(user.language, Lang::DeDE);
+    assert_eq!(user.role, Role::Admin);
+
+    // the next login updates the email and takes the admin role away
+    let again = json!({
+        "sub": "sub-carol",
+        "preferred_username": "carol",
+        "email": "carol@example.org",
+        "groups": ["staff"],
+    });
+    let updated = sso_login(&idp, &settings, again).await.expect("Login");
+    assert_eq!(updated.id, user.id);
+    assert_eq!(updated.email, "carol@example.org");
+    assert_eq!(updated.role, Role::Regular);
+    assert_eq!(updated.language, Lang::DeDE);
+
+    // an existing account is only linked with its verified email and only to one subject
+    NewUser::new(
+        "dave".to_string(),
+        "dave@example.com".to_string(),
+        "password",
+        &crate::get_secret(),
+    )
+    .expect("Hash")
+    .insert_user()
+    .await
+    .expect("Failed to insert user");
+    let dave = User::get_user_by_name("dave").await.expect("User exists");
+    let claims = |sub: &str, email: &str, verified: bool| json!({ "sub": sub, "preferred_username": "dave", "email": email, "email_verified": verified });
+    for colliding in [
+        claims("sub-mallory", "mallory@example.com", true),
+        claims("sub-mallory", "dave@example.com", false),
+        json!({ "sub": "sub-mallory", "preferred_username": "dave", "email": "dave@example.com" }),
+    ] {
+        assert!(sso_login(&idp, &settings, colliding).await.is_err());
+    }
+    let unlinked = User::get_user_by_name("dave").await.expect("User exists");
+    assert_eq!(unlinked.email, "dave@example.com");
+    assert!(
+        query("SELECT id FROM users WHERE oidc_subject = $1")
+            .bind("sub-mallory")
+            .fetch_optional(&crate::get_db().await)
+            .await
+            .expect("Query")
+            .is_none()
+    );
+    let linked = sso_login(
+        &idp,
+        &settings,
+        claims("sub-dave", "Dave@Example.com", true),
+    )
+    .await
+    .expect("Login");
+    assert_eq!(linked.id, dave.id);
+    assert!(
+        sso_login(
+            &idp,
+            &settings,
+            claims("sub-mallory", "dave@example.com", true)
+        )
+        .await
+        .is_err()
+    );
+
+    // the state, the PKCE verifier and the nonce have to match and a code can only be used once
+    let erin =
+        json!({ "sub": "sub-erin", "preferred_username": "erin", "email": "erin@example.com" });
+    let (flow, _, code) = start_sso(&idp, &settings, erin.clone()).await;
+    assert!(
+        crate::oidc::finish_login(&settings, &flow, "forged", &code)
+            .await
+            .is_err()
+    );
+    let (flow, state_2, code_2) = start_sso(&idp, &settings, erin.clone()).await;
+    let other_verifier = crate::oidc::OidcFlow {
+        code_verifier: crate::oidc::OidcFlow::new().code_verifier,
+        ..flow.clone()
+    };
+    assert!(
+        crate::oidc::finish_login(&settings, &other_verifier, &state_2, &code_2)
+            .await
+            .is_err()
+    );
+    let (flow, state_3, code_3) = start_sso(&idp, &settings, erin.clone()).await;
+    let other_nonce = crate::oidc::OidcFlow {
+        nonce: "replayed".to_string(),
+        ..flow.clone()
+    };
+    assert!(
+        crate::oidc::finish_login(&settings, &other_nonce, &state_3, &code_3)
+            .await
+            .is_err()
+    );
+    let (flow, state_4, code_4) = start_sso(&idp, &settings, erin.clone()).await;
+    assert!(
+        crate::oidc::finish_login(&settings, &flow, &state_4, &code_4)
+            .await
+            .is_ok()
+    );
+    assert!(
+        crate::oidc::finish_login(&settings, &flow, &state_4, &code_4)
+            .await
+            .is_err()
+    );
+
+    // the callback logs users in or, if they have a second factor, lets the login wait for it
+    crate::init_oidc_settings(settings.clone());
+    let router = with_sessions(
+        axum::Router::new()
+            .route(
+                crate::oidc::LOGIN_PATH,
+                axum::routing::get(crate::oidc::login),
+            )
+            .route(
+                crate::oidc::CALLBACK_PATH,
+                axum::routing::get(crate::oidc::callback),
+            )
+            .route(
+                "/state",
+                axum::routing::get(|session: crate::auth::Session| async move {
+                    let pending =
+                        crate::totp::pending_login(&session).map(|pending| pending.user_id);
+                    let logged_in = session
+                        .current_user
+                        .as_ref()
+                        .and_then(crate::auth::AuthAccount::get_user)
+                        .map(|user| user.id);
+                    axum::Json((pending, logged_in))
+                }),
+            ),
+    )
+    .await;
+    let carol =
+        json!({ "sub": "sub-carol", "preferred_username": "carol", "email": "carol@example.org" });
+    assert_eq!(
+        sso_callback(&idp, &router, carol.clone()).await,
+        ("/app/".to_string(), (None, Some(user.id)))
+    );
+    let enrollment = crate::totp::start_enrollment(&updated)
+        .await
+        .expect("Enrollment starts");
+    let secret = crate::totp::base32_decode(&enrollment.secret).expect("Valid secret");
+    let now = crate::totp::step_at(chrono::Utc::now().timestamp());
+    crate::totp::confirm_enrollment(&updated, &crate::totp::code_at(&secret, now))
+        .await
+        .expect("Enrollment is confirmed");
+    assert_eq!(
+        sso_callback(&idp, &router, carol).await,
+        ("/app/login".to_string(), (Some(user.id), None))
+    );
+
+    // with the disabled default role new users wait for an administrator
+    settings.default_role = Role::Disabled;
+    let frank =
+        json!({ "sub": "sub-frank", "preferred_username": "frank", "email": "frank@example.com" });
+    assert!(sso_login(&idp, &settings, frank).await.is_err());
+    assert_eq!(
+        User::get_user_by_name("frank")
+            .await
+            .expect("User exists")
+            .role,
+        Role::Disabled
+    );
+}
+
+/// An entry of the mock directory.
+struct MockLdapEntry {
+    dn: &'static str,
+    password: &'static str,
+    attributes: Vec<(&'static str, Vec<&'static str>)>,
+}
+
+/// Split the first BER element of the bytes into its tag, its content and the remaining bytes.
+fn ber_element(bytes: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = bytes.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (length, rest) = if first & 0x80 == 0 {
+        (usize::from(first), rest)
+    } else {
+        let (length, rest) = rest.split_at_checked(usize::from(first & 0x7f))?;
+        let length = length
+            .iter()
+            .fold(0, |length, byte| length << 8 | usize::from(*byte));
+        (length, rest)
+    };
+    let (content, rest) = rest.split_at_checked(length)?;
+    Some((tag, content, rest))
+}
+
+fn ber(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut element = vec![tag];
+    match u8::try_from(content.len()) {
+        Ok(length) if length < 0x80 => element.push(length),
+        _ => {
+            let length = u16::try_from(content.len()).expect("Short content");
+            element.push(0x82);
+            element.extend(length.to_be_bytes());
+        }
+    }
+    element.extend(content);
+    element
+}
+
+/// The value an equality match of the filter compares the attribute with.
+fn ber_equality<'a>(filter: &'a [u8], attribute: &str) -> Option<&'a [u8]> {
+    let (tag, content, rest) = ber_element(filter)?;
+    let found = match tag {
+        // and, or
+        0xa0 | 0xa1 => ber_equality(content, attribute),
+        // equality match
+        0xa3 => {
+            let (_, name, value) = ber_element(content)?;
+            let (_, value, _) = ber_element(value)?;
+            name.eq_ignore_ascii_case(attribute.as_bytes())
+                .then_some(value)
+        }
+        _ => None,
+    };
+    found.or_else(|| ber_equality(rest, attribute))
+}
+
+fn ldap_result(code: u8) -> Vec<u8> {
+    [ber(0x0a, &[code]), ber(0x04, b""), ber(0x04, b"")].concat()
+}
+
+/// Answer the requests of one connection until it is unbound.
+async fn serve_ldap(
+    mut stream: tokio::net::TcpStream,
+    entries: std::sync::Arc<Vec<MockLdapEntry>>,
+) -> Option<()> {
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    let mut bound = false;
+    loop {
+        let mut header = [0_u8; 2];
+        stream.read_exact(&mut header).await.ok()?;
+        let length = if header[1] & 0x80 == 0 {
+            usize::from(header[1])
+        } else {
+            let mut length = vec![0_u8; usize::from(header[1] & 0x7f)];
+            stream.read_exact(&mut length).await.ok()?;
+            length
+                .iter()
+                .fold(0, |length, byte| length << 8 | usize::from(*byte))
+        };
+        let mut message = vec![0_u8; length];
+        stream.read_exact(&mut message).await.ok()?;
+        let (_, _, operation) = ber_element(&message)?;
+        let id = &message[..message.len() - operation.len()];
+        let (tag, content, _) = ber_element(operation)?;
+        let mut responses = Vec::new();
+        match tag {
+            // bind
+            0x60 => {
+                let (_, _, rest) = ber_element(content)?;
+                let (_, name, rest) = ber_element(rest)?;
+                let (_, password, _) = ber_element(rest)?;
+                bound = entries.iter().any(|entry| {
+                    entry.dn.as_bytes() == name && entry.password.as_bytes() == password
+                });
+                responses.push(ber(0x61, &ldap_result(if bound { 0 } else { 49 })));
+            }
+            // search, anonymous searches are refused
+            0x63 if !bound => {
+                responses.push(ber(0x65, &ldap_result(50)));
+            }
+            0x63 => {
+                // skip the base, scope, aliases, size and time limits and types only
+                let mut filter = content;
+                for _ in 0..6 {
+                    filter = ber_element(filter)?.2;
+                }
+                let uid = ber_equality(filter, "uid")?;
+                for entry in entries.iter() {
+                    let matches = entry.attributes.iter().any(|(name, values)| {
+                        *name == "uid" && values.iter().any(|value| value.as_bytes() == uid)
+                    });
+                    if !matches {
+                        continue;
+                    }
+                    let attributes: Vec<u8> = entry
+                        .attributes
+                        .iter()
+                        .flat_map(|(name, values)| {
+                            let values: Vec<u8> = values
+                                .iter()
+                                .flat_map(|value| ber(0x04, value.as_bytes()))
+                                .collect();
+                            ber(
+                                0x30,
+                                &[ber(0x04, name.as_bytes()), ber(0x31, &values)].concat(),
+                            )
+                        })
+                        .collect();
+                    responses.push(ber(
+                        0x64,
+                        &[ber(0x04, entry.dn.as_bytes()), ber(0x30, &attributes)].concat(),
+                    ));
+                }
+                responses.push(ber(0x65, &ldap_result(0)));
+            }
+            // unbind and everything else
+            _ => return Some(()),
+        }
+        for response in responses {
+            let message = ber(0x30, &[id, &response].concat());
+            stream.write_all(&message).await.ok()?;
+        }
+    }
+}
+
+/// A minimal LDAP directory that answers simple binds and searches by `uid`, returns its url.
+async fn start_mock_directory(entries: Vec<MockLdapEntry>) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind the mock directory");
+    let url = format!("ldap://{}", listener.local_addr().expect("Address"));
+    let entries = std::sync::Arc::new(entries);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve_ldap(stream, entries.clone()));
+        }
+    });
+    url
+}
+
+fn ldap_settings(url: &str) -> crate::ldap::LdapSettings {
+    crate::ldap::LdapSettings {
+        url: url.to_string(),
+        starttls: false,
+        bind_dn: None,
+        bind_password: None,
+        base_dn: "ou=people,dc=example,dc=com".to_string(),
+        user_filter: "(&(objectClass=person)(uid={username}))".to_string(),
+        username_attribute: "uid".to_string(),
+        email_attribute: "mail".to_string(),
+        group_attribute: "memberOf".to_string(),
+        admin_group: Some("cn=admins,ou=groups,dc=example,dc=com".to_string()),
+        order: crate::ldap::LdapOrder::LdapFirst,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ldap_login() {
+    use crate::ldap::{LdapEntry, LdapLogin, LdapOrder};
+    use std::collections::HashMap;
+
+    // nothing listens on the discard port, so the directory is unreachable
+    let settings = ldap_settings("ldap://127.0.0.1:9");
+    assert_eq!(
+        crate::ldap::user_filter(&settings, "eve*)(uid=*"),
+        "(&(objectClass=person)(uid=eve\\2a\\29\\28uid=\\2a))"
+    );
+    assert_eq!(LdapOrder::parse("ldap-only"), Some(LdapOrder::LdapOnly));
+    assert_eq!(LdapOrder::parse("ldap"), None);
+
+    let attributes = HashMap::from([
+        ("UID".to_string(), vec!["grace".to_string()]),
+        (
+            "mail".to_string(),
+            vec![String::new(), "grace@example.com".to_string()],
+        ),
+        (
+            "memberof".to_string(),
+            vec!["CN=Admins,OU=Groups,DC=example,DC=com".to_string()],
+        ),
+    ]);
+    let grace = crate::ldap::entry_from_attributes(
+        &settings,
+        "uid=grace,ou=people,dc=example,dc=com",
+        &attributes,
+    )
+    .expect("The entry is complete");
+    assert_eq!(grace.username, "grace");
+    assert_eq!(grace.email, "grace@example.com");
+    assert_eq!(crate::ldap::is_admin(&settings, &grace), Some(true));
+    let no_mail = HashMap::from([("uid".to_string(), vec!["grace".to_string()])]);
+    assert!(crate::ldap::entry_from_attributes(&settings, "uid=grace", &no_mail).is_err());
+
+    let _lock = fresh_db().await;
+
+    // directory users are created on their first login and follow the admin group
+    let user = crate::ldap::provision_user(&settings, &grace)
+        .await
+        .expect("User is created");
+    assert_eq!(user.role, Role::Admin);
+    let demoted = LdapEntry {
+        email: "grace@example.org".to_string(),
+        groups: Vec::new(),
+        ..grace.clone()
+    };
+    let updated = crate::ldap::provision_user(&settings, &demoted)
+        .await
+        .expect("User is updated");
+    assert_eq!(updated.id, user.id);
+    assert_eq!(updated.email, "grace@example.org");
+    assert_eq!(updated.role, Role::Regular);
+    // the random local password of a directory user is not known
+    assert!(
+        crate::auth::check_password("grace", "password")
+            .await
+            .is_err()
+    );
+
+    // without an admin group the roles are managed in pslink
+    let unmanaged = crate::ldap::LdapSettings {
+        admin_group: None,
+        ..settings.clone()
+    };
+    assert_eq!(crate::ldap::is_admin(&unmanaged, &grace), None);
+
+    // an unreachable directory falls back to the local passwords, empty passwords are rejected
+    assert!(
+        crate::ldap::authenticate(&settings, "grace", "secret")
+            .await
+            .expect("The directory is skipped")
+            .is_none()
+    );
+    assert!(
+        crate::ldap::authenticate(&settings, "grace", "")
+            .await
+            .is_err()
+    );
+
+    // the service account searches the entry and the user binds with its password
+    let url = start_mock_directory(vec![
+        MockLdapEntry {
+            dn: "cn=pslink,dc=example,dc=com",
+            password: "service",
+            attributes: Vec::new(),
+        },
+        MockLdapEntry {
+            dn: "uid=ivan,ou=people,dc=example,dc=com",
+            password: "ivan-secret",
+            attributes: vec![
+                ("uid", vec!["ivan"]),
+                ("mail", vec!["ivan@example.com"]),
+                ("memberOf", vec!["cn=admins,ou=groups,dc=example,dc=com"]),
+            ],
+        },
+        MockLdapEntry {
+            dn: "uid=heidi,ou=people,dc=example,dc=com",
+            password: "heidi-secret",
+            attributes: vec![
+                ("uid", vec!["heidi"]),
+                ("mail", vec!["mallory@example.com"]),
+            ],
+        },
+    ])
+    .await;
+    let directory = crate::ldap::LdapSettings {
+        bind_dn: Some("cn=pslink,dc=example,dc=com".to_string()),
+        bind_password: Some("service".to_string()),
+        ..ldap_settings(&url)
+    };
+    let LdapLogin::Authenticated(ivan) = crate::ldap::login(&directory, "ivan", "ivan-secret")
+        .await
+        .expect("The directory is reachable")
+    else {
+        panic!("Ivan is authenticated");
+    };
+    assert_eq!(ivan.dn, "uid=ivan,ou=people,dc=example,dc=com");
+    assert_eq!(ivan.email, "ivan@example.com");
+    assert_eq!(crate::ldap::is_admin(&directory, &ivan), Some(true));
+    for (username, password, expected) in [
+        ("ivan", "wrong", LdapLogin::WrongPassword),
+        ("nobody", "secret", LdapLogin::UnknownUser),
+        ("ivan*)(uid=*", "ivan-secret", LdapLogin::UnknownUser),
+    ] {
+        assert_eq!(
+            crate::ldap::login(&directory, username, password)
+                .await
+                .expect("The directory is reachable"),
+            expected
+        );
+    }
+    let user = crate::ldap::authenticate(&directory, "ivan", "ivan-secret")
+        .await
+        .expect("Authenticated")
+        .expect("User is created");
+    assert_eq!((user.username.as_str(), user.role), ("ivan", Role::Admin));
+    assert!(
+        crate::ldap::authenticate(&directory, "ivan", "wrong")
+            .await
+            .is_err()
+    );
+    // a rejected service account falls back to the local passwords
+    let rejected = crate::ldap::LdapSettings {
+        bind_password: Some("wrong".to_string()),
+        ..directory.clone()
+    };
+    assert!(
+        crate::ldap::login(&rejected, "ivan", "ivan-secret")
+            .await
+            .is_err()
+    );
+    assert!(
+        crate::ldap::authenticate(&rejected, "ivan", "ivan-secret")
+            .await
+            .expect("The directory is skipped")
+            .is_none()
+    );
+
+    // an existing account is only linked if it has the email of the entry and only to one entry
+    NewUser::new(
+        "heidi".to_string(),
+        "heidi@example.com".to_string(),
+        "password",
+        &crate::get_secret(),
+    )
+    .expect("Hash")
+    .insert_user()
+    .await
+    .expect("Failed to insert user");
+    let heidi = User::get_user_by_name("heidi").await.expect("User exists");
+    assert!(
+        crate::ldap::authenticate(&directory, "heidi", "heidi-secret")
+            .await
+            .is_err()
+    );
+    assert!(
+        crate::auth::check_password("heidi", "password")
+            .await
+            .is_ok()
+    );
+    let entry = LdapEntry {
+        dn: "uid=heidi,ou=people,dc=example,dc=com".to_string(),
+        username: "heidi".to_string(),
+        email: "Heidi@example.com".to_string(),
+        groups: Vec::new(),
+    };
+    let linked = crate::ldap::provision_user(&directory, &entry)
+        .await
+        .expect("Linked");
+    assert_eq!(linked.id, heidi.id);
+    let moved = LdapEntry {
+        dn: "uid=heidi,ou=staff,dc=example,dc=com".to_string(),
+        ..entry.clone()
+    };
+    assert!(
+        crate::ldap::provision_user(&directory, &moved)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_totp() {
+    use crate::totp::{base32_decode, base32_encode, code_at, step_at};
+
+    // the test vectors of RFC 6238 truncated to six digits
+    let rfc_secret = b"12345678901234567890";
+    assert_eq!(
+        base32_encode(rfc_secret),
+        "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ"
+    );
+    assert_eq!(
+        base32_decode("gezd gnbv gy3t qojq gezd gnbv gy3t qojq"),
+        Some(rfc_secret.to_vec())
+    );
+    assert_eq!(base32_decode("not base32!"), None);
+    assert_eq!(code_at(rfc_secret, step_at(59)), "287082");
+    assert_eq!(code_at(rfc_secret, step_at(1_111_111_109)), "081804");
+    assert_eq!(code_at(rfc_secret, step_at(1_234_567_890)), "005924");
+    assert_eq!(
+        crate::totp::matching_step(rfc_secret, "287082", 89),
+        Some(1)
+    );
+    assert_eq!(crate::totp::matching_step(rfc_secret, "287082", 120), None);
+
+    let _lock = fresh_db().await;
+    NewUser::new(
+        "heidi".to_string(),
+        "heidi@example.com".to_string(),
+        "password",
+        &crate::get_secret(),
+    )
+    .expect("Hash")
+    .insert_user()
+    .await
+    .expect("Failed to insert user");
+    let heidi = User::get_user_by_name("heidi").await.expect("User exists");
+    let as_admin = User {
+        role: Role::Admin,
+        ..heidi.clone()
+    };
+
+    // administrators without a second factor have to set it up once it is required
+    assert!(
+        !crate::totp::needs_enrollment(&as_admin)
+            .await
+            .expect("Check")
+    );
+    crate::totp::set_required_for_admins(true)
+        .await
+        .expect("Setting is stored");
+    assert!(
+        crate::totp::needs_enrollment(&as_admin)
+            .await
+            .expect("Check")
+    );
+    assert!(!crate::totp::needs_enrollment(&heidi).await.expect("Check"));
+
+    // the secret is only enabled with a matching code
+    let enrollment = crate::totp::start_enrollment(&heidi)
+        .await
+        .expect("Enrollment starts");
+    assert!(
+        enrollment
+            .uri
+            .starts_with("otpauth://totp/Pslink:heidi?secret=")
+    );
+    let secret = base32_decode(&enrollment.secret).expect("Valid secret");
+    let now = step_at(chrono::Utc::now().timestamp());
+    assert!(
+        crate::totp::confirm_enrollment(&heidi, "000000x")
+            .await
+            .is_err()
+    );
+    let recovery_codes = crate::totp::confirm_enrollment(&heidi, &code_at(&secret, now))
+        .await
+        .expect("Enrollment is confirmed");
+    assert_eq!(recovery_codes.len(), 10);
+    assert!(crate::totp::is_enabled(heidi.id).await.expect("Check"));
+    assert!(
+        !crate::totp::needs_enrollment(&as_admin)
+            .await
+            .expect("Check")
+    );
+    assert!(crate::totp::start_enrollment(&heidi).await.is_err());
+
+    // a code is accepted once, recovery codes once in any spelling
+    assert!(
+        !crate::totp::verify(heidi.id, &code_at(&secret, now))
+            .await
+            .expect("Check")
+    );
+    assert!(
+        crate::totp::verify(heidi.id, &code_at(&secret, now + 1))
+            .await
+            .expect("Check")
+    );
+    assert!(
+        crate::totp::verify(heidi.id, &recovery_codes[0].to_uppercase())
+            .await
+            .expect("Check")
+    );
+    assert!(
+        !crate::totp::verify(heidi.id, &recovery_codes[0])
+            .await
+            .expect("Check")
+    );
+    let status = crate::totp::status(heidi.id).await.expect("Status");
+    assert!(status.enabled && status.required_for_admins);
+    assert_eq!(status.recovery_codes_left, 9);
+
+    crate::totp::disable(&heidi).await.expect("Disabled");
+    assert!(!crate::totp::is_enabled(heidi.id).await.expect("Check"));
+    assert!(
+        !crate::totp::verify(heidi.id, &recovery_codes[1])
+            .await
+            .expect("Check")
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_login_limits() {
+    use crate::login_limits::{ClientIp, LoginLimits, reserve};
+    use pslink_shared::datatypes::LoginEventKind;
+
+    let limits = LoginLimits {
+        max_failures: 3,
+        max_failures_per_ip: 5,
+        backoff_seconds: 0,
+        lockout_minutes: 15,
+        trust_forwarded_for: false,
+        event_days: 90,
+    };
+    assert_eq!(limits.backoff(1), chrono::Duration::zero());
+    let slow = LoginLimits {
+        backoff_seconds: 2,
+        lockout_minutes: 1,
+        ..limits.clone()
+    };
+    assert_eq!(slow.backoff(1), chrono::Duration::seconds(2));
+    assert_eq!(slow.backoff(3), chrono::Duration::seconds(8));
+    assert_eq!(slow.backoff(40), chrono::Duration::minutes(1));
+    // huge lockouts are capped at a year instead of overflowing the dates
+    let endless = LoginLimits {
+        backoff_seconds: u64::MAX,
+        lockout_minutes: u64::MAX,
+        ..limits.clone()
+    };
+    assert_eq!(endless.backoff(40), chrono::Duration::days(365));
+
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(
+        "x-forwarded-for",
+        "203.0.113.7, 10.0.0.1".parse().expect("Valid"),
+    );
+    let peer = Some("10.0.0.1".parse().expect("Valid"));
+    assert_eq!(ClientIp::from_parts(&headers, peer, false).0, peer);
+    assert_eq!(
+        ClientIp::from_parts(&headers, peer, true).0,
+        Some("203.0.113.7".parse().expect("Valid"))
+    );
+
+    let _lock = fresh_db().await;
+    NewUser::new(
+        "ivan".to_string(),
+        "ivan@example.com".to_string(),
+        "password",
+        &crate::get_secret(),
+    )
+    .expect("Hash")
+    .insert_user()
+    .await
+    .expect("Failed to insert user");
+
+    // the answer does not tell whether the username exists
+    let unknown = crate::auth::check_password("nobody", "password")
+        .await
+        .expect_err("Unknown user");
+    let wrong = crate::auth::check_password("ivan", "wrong")
+        .await
+        .expect_err("Wrong password");
+    assert_eq!(unknown.to_string(), wrong.to_string());
+
+    let ip = ClientIp(Some("192.0.2.1".parse().expect("Valid")));
+    for _ in 0..3 {
+        reserve(&limits, "Ivan", ip)
+            .await
+            .expect("Not locked yet")
+            .fail(&limits, "wrong password")
+            .await
+            .expect("Counted");
+    }
+    // usernames are counted case insensitive
+    assert!(reserve(&limits, "ivan", ip).await.is_err());
+    let lockouts = crate::login_limits::lockouts().await.expect("Lockouts");
+    assert_eq!(lockouts.len(), 1);
+    assert!(!lockouts[0].by_ip);
+    assert_eq!(lockouts[0].subject, "ivan");
+    assert_eq!(lockouts[0].failures, 3);
+
+    // a correct password releases the attempt
+    crate::login_limits::reset(false, "IVAN", "admin")
+        .await
+        .expect("Reset");
+    reserve(&limits, "ivan", ip)
+        .await
+        .expect("Unlocked")
+        .release()
+        .await
+        .expect("Released");
+    let counters = |subject: &'static str| async move {
+        query("SELECT failures FROM login_failures WHERE subject = $1")
+            .bind(subject)
+            .fetch_optional(&crate::get_db().await)
+            .await
+            .expect("Counter")
+            .map(|row| row.get::<i64>("failures"))
+    };
+    assert_eq!(counters("ivan").await, None);
+    assert_eq!(counters("192.0.2.1").await, Some(3));
+
+    // a success only resets the username, the address keeps its failures
+    for _ in 0..2 {
+        reserve(&limits, "mallory", ip)
+            .await
+            .expect("Not locked yet")
+            .fail(&limits, "wrong password")
+            .await
+            .expect("Counted");
+    }
+    crate::login_limits::record_success("ivan", ip)
+        .await
+        .expect("Recorded");
+    assert!(reserve(&limits, "ivan", ip).await.is_err());
+    let other_ip = ClientIp(Some("192.0.2.2".parse().expect("Valid")));
+    reserve(&limits, "ivan", other_ip)
+        .await
+        .expect("Other addresses are not locked")
+        .release()
+        .await
+        .expect("Released");
+    crate::login_limits::reset(true, "192.0.2.1", "admin")
+        .await
+        .expect("Reset");
+    reserve(&limits, "ivan", ip)
+        .await
+        .expect("Unlocked")
+        .release()
+        .await
+        .expect("Released");
+
+    let events = crate::login_limits::events(100).await.expect("Events");
+    let count = |kind: LoginEventKind| events.iter().filter(|event| event.kind == kind).count();
+    assert_eq!(count(LoginEventKind::Failure), 5);
+    assert_eq!(count(LoginEventKind::Lockout), 2);
+    assert_eq!(count(LoginEventKind::Blocked), 2);
+    assert_eq!(count(LoginEventKind::Reset), 2);
+    assert_eq!(count(LoginEventKind::Success), 1);
+    assert_eq!(events[0].kind, LoginEventKind::Reset);
+
+    // attempts that are still checked count, so parallel requests cannot try more passwords
+    let mut pending = Vec::new();
+    for _ in 0..3 {
+        pending.push(
+            reserve(&limits, "peggy", other_ip)
+                .await
+                .expect("Within the limit"),
+        );
+    }
+    assert!(reserve(&limits, "peggy", other_ip).await.is_err());
+    for attempt in pending {
+        attempt.release().await.expect("Released");
+    }
+    let lockouts = crate::login_limits::lockouts().await.expect("Lockouts");
+    assert!(lockouts.iter().any(|lockout| lockout.subject == "peggy"));
+    assert!(reserve(&limits, "peggy", other_ip).await.is_err());
+
+    // the audit trail is pruned after the configured days
+    query(
+        "INSERT INTO login_events (created_at, kind, username, ip, detail) VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(chrono::Local::now().naive_utc() - chrono::Duration::days(91))
+    .bind(LoginEventKind::Success.as_str())
+    .bind("ivan")
+    .bind(None::<String>)
+    .bind(None::<String>)
+    .execute(&crate::get_db().await)
+    .await
+    .expect("Old event");
+    assert_eq!(
+        crate::login_limits::prune(&limits).await.expect("Pruned"),
+        1
+    );
+    let keep_forever = LoginLimits {
+        event_days: 0,
+        ..limits.clone()
+    };
+    assert_eq!(
+        crate::login_limits::prune(&keep_forever)
+            .await
+            .expect("Pruned"),
+        0
+    );
+    assert!(
+        crate::login_limits::events(100)
+            .await
+            .expect("Events")
+            .iter()
+            .all(|event| event.created_at
+                > chrono::Local::now().naive_utc() - chrono::Duration::days(1))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invitations() {
+    use pslink_shared::apirequests::users::AcceptInvitation;
+    use pslink_shared::datatypes::Lang;
+
+    let _lock = fresh_db().await;
+    crate::init_public_url(&crate::cli::Protocol::Https, "example.com");
+    NewUser::new(
+        "judy".to_string(),
+        "judy@example.com".to_string(),
+        "password",
+        &crate::get_secret(),
+    )
+    .expect("Hash")
+    .insert_user()
+    .await
+    .expect("Failed to insert user");
+    let judy = User::get_user_by_name("judy").await.expect("User exists");
+
+    assert!(
+        crate::invitations::create_invitation(&judy, None, Role::Disabled, 7)
+            .await
+            .is_err()
+    );
+    assert!(
+        crate::invitations::create_invitation(&judy, None, Role::Regular, u32::MAX)
+            .await
+            .is_err()
+    );
+    let invitation =
+        crate::invitations::create_invitation(&judy, Some(" kim@example.com "), Role::Admin, 7)
+            .await
+            .expect("Created");
+    assert_eq!(
+        invitation.invitation.email.as_deref(),
+        Some("kim@example.com")
+    );
+    let token = invitation
+        .url
+        .strip_prefix("https://example.com/app/invite/")
+        .expect("Invitation url")
+        .to_string();
+    assert!(token.starts_with(&invitation.invitation.hint));
+    let info = crate::invitations::invitation_info(&token)
+        .await
+        .expect("Valid");
+    assert_eq!(info.role, Role::Admin);
+    assert!(
+        crate::invitations::invitation_info("guessed")
+            .await
+            .is_err()
+    );
+
+    let mut request = AcceptInvitation {
+        token: token.clone(),
+        username: "judy".to_string(),
+        email: "kim@example.com".to_string(),
+        password: "secret password".to_string(),
+        language: Lang::DeDE,
+    };
+    // a taken username does not use up the invitation
+    assert!(
+        crate::invitations::accept_invitation(&request)
+            .await
+            .is_err()
+    );
+    request.username = "kim".to_string();
+    let kim = crate::invitations::accept_invitation(&request)
+        .await
+        .expect("Accepted");
+    assert_eq!(kim.role, Role::Admin);
+    assert_eq!(kim.language, Lang::DeDE);
+    crate::auth::check_password("kim", "secret password")
+        .await
+        .expect("The chosen password works");
+
+    // every invitation can be used once
+    request.username = "kim2".to_string();
+    assert!(
+        crate::invitations::accept_invitation(&request)
+            .await
+            .is_err()
+    );
+    assert!(
+        crate::invitations::list_invitations()
+            .await
+            .expect("List")
+            .is_empty()
+    );
+
+    // expired invitations are listed but cannot be used
+    let expired = crate::invitations::create_invitation(&judy, None, Role::Regular, 1)
+        .await
+        .expect("Created");
+    query("UPDATE invitations SET expires_at = $1 WHERE id = $2")
+        .bind(chrono::Local::now().naive_utc() - chrono::Duration::hours(1))
+        .bind(expired.invitation.id)
+        .execute(&crate::get_db().await)
+        .await
+        .expect("Updated");
+    request.token = expired.url.rsplit('/').next().expect("Token").to_string();
+    assert!(
+        crate::invitations::accept_invitation(&request)
+            .await
+            .is_err()
+    );
+    let list = crate::invitations::list_invitations().await.expect("List");
+    assert_eq!(list.len(), 1);
+    assert!(list[0].is_expired(chrono::Local::now().naive_utc()));
+    crate::invitations::delete_invitation(expired.invitation.id)
+        .await
+        .expect("Revoked");
+    assert!(
+        crate::invitations::list_invitations()
+            .await
+            .expect("List")
+            .is_empty()
+    );
+}
+
+/// A minimal SMTP server on a random local port that accepts every email and hands out the raw messages.
+async fn smtp_sink() -> (u16, tokio::sync::mpsc::UnboundedReceiver<String>) {
+    use tokio::io::{AsyncBufReadExt as _, AsyncWriteExt as _, BufReader};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind");
+    let port = listener.local_addr().expect("Local address").port();
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                let (read, mut write) = stream.into_split();
+                let mut lines = BufReader::new(read).lines();
+                write.write_all(b"220 localhost ESMTP\r\n").await.ok();
+                let mut data: Option<String> = None;
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if data.is_some() {
+                        if line == "." {
+                            sender.send(data.take().unwrap_or_default()).ok();
+                            write.write_all(b"250 OK\r\n").await.ok();
+                        } else if let Some(message) = data.as_mut() {
+                            // lines starting with a dot are sent with a second one
+                            message.push_str(line.strip_prefix('.').unwrap_or(&line));
+                            message.push('\n');
+                        }
+                        continue;
+                    }
+                    let command = line.to_uppercase();
+                    if command.starts_with("QUIT") {
+                        write.write_all(b"221 Bye\r\n").await.ok();
+                        break;
+                    }
+                    let answer: &[u8] = if command.starts_with("DATA") {
+                        data = Some(String::new());
+                        b"354 End data with <CR><LF>.<CR><LF>\r\n"
+                    } else if command.starts_with("EHLO") || command.starts_with("HELO") {
+                        b"250 localhost\r\n"
+                    } else {
+                        b"250 OK\r\n"
+                    };
+                    write.write_all(answer).await.ok();
+                }
+            });
+        }
+    });
+    (port, receiver)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_password_reset() {
+    use crate::mail::{SmtpSecurity, SmtpSettings};
+    use pslink_shared::datatypes::Lang;
+
+    // the emails use the translations of the web interface
+    let english =
+        crate::mail::password_reset_email(Lang::EnUS, "leo", "https://example.com/reset", 60);
+    let german =
+        crate::mail::password_reset_email(Lang::DeDE, "leo", "https://example.com/reset", 60);
+    assert_eq!(english.subject, "Reset your pslink password");
+    assert!(english.body.starts_with("Hello leo,"));
+    assert!(english.body.contains("within 60 minutes"));
+    assert!(english.body.contains("\nhttps://example.com/reset\n"));
+    assert!(german.body.starts_with("Hallo leo,"));
+    assert_ne!(english.subject, german.subject);
+
+    let (port, mut inbox) = smtp_sink().await;
+    let settings = SmtpSettings {
+        host: "127.0.0.1".to_string(),
+        port,
+        security: SmtpSecurity::None,
+        username: None,
+        password: None,
+        from: "pslink <pslink@example.com>".to_string(),
+    };
+    crate::mail::send(
+        &settings,
+        "leo@example.com",
+        &crate::mail::test_email(Lang::EnUS),
+    )
+    .await
+    .expect("Sent");
+    let message = inbox.recv().await.expect("Received");
+    assert!(message.contains("Subject: pslink test email"));
+    assert!(message.contains("To: leo@example.com"));
+
+    let _lock = fresh_db().await;
+    crate::init_public_url(&crate::cli::Protocol::Https, "example.com");
+    NewUser::new(
+        "leo".to_string(),
+        "leo@example.com".to_string(),
+        "password",
+        &crate::get_secret(),
+    )
+    .expect("Hash")
+    .insert_user()
+    .await
+    .expect("Failed to insert user");
+
+    // unknown accounts get the same answer but no email
+    crate::password_reset::request_reset(&settings, "nobody")
+        .await
+        .expect("Same answer");
+    crate::password_reset::request_reset(&settings, "LEO@example.com")
+        .await
+        .expect("Requested");
+    let message = inbox.recv().await.expect("Received");
+    assert!(message.contains("To: leo@example.com"));
+    // undo the soft line breaks of quoted-printable
+    let message = message.replace("=\n", "");
+    let token: String = message
+        .split("https://example.com/app/reset-password/")
+        .nth(1)
+        .expect("The email contains the link")
+        .chars()
+        .take_while(char::is_ascii_alphanumeric)
+        .collect();
+    assert_eq!(token.len(), 32);
+
+    // a second request right away does not send another email
+    crate::password_reset::request_reset(&settings, "leo")
+        .await
+        .expect("Requested");
+    assert!(inbox.try_recv().is_err());
+
+    assert!(
+        crate::password_reset::reset_password(&token, "new")
+            .await
+            .is_err()
+    );
+    crate::password_reset::reset_password(&token, "new password")
+        .await
+        .expect("Reset");
+    crate::auth::check_password("leo", "new password")
+        .await
+        .expect("The new password works");
+    assert!(
+        crate::auth::check_password("leo", "password")
+            .await
+            .is_err()
+    );
+    // every link can be used once
+    assert!(
+        crate::password_reset::reset_password(&token, "other password")
+            .await
+            .is_err()
+    );
+
+    // accounts of single sign-on or the directory have no password to reset
+    let db = crate::get_db().await;
+    let oscar = add_user("oscar").await;
+    let nina = add_user("nina").await;
+    query("UPDATE users SET oidc_subject = $1 WHERE id = $2")
+        .bind("oscar-subject")
+        .bind(oscar.id)
+        .execute(&db)
+        .await
+        .expect("Linked");
+    query("UPDATE users SET ldap_dn = $1 WHERE id = $2")
+        .bind("uid=nina,ou=people,dc=example,dc=com")
+        .bind(nina.id)
+        .execute(&db)
+        .await
+        .expect("Linked");
+    for name in ["oscar", "nina@example.com"] {
+        crate::password_reset::request_reset(&settings, name)
+            .await
+            .expect("Same answer");
+    }
+    let resets: i64 =
+        query("SELECT count(*) AS number FROM password_resets WHERE user_id IN ($1, $2)")
+            .bind(oscar.id)
+            .bind(nina.id)
+            .fetch_one(&db)
+            .await
+            .expect("Count")
+            .get("number");
+    assert_eq!(resets, 0);
+
+    // a link is not accepted once the account was linked
+    let mia = add_user("mia").await;
+    crate::password_reset::request_reset(&settings, "mia")
+        .await
+        .expect("Requested");
+    let message = inbox.recv().await.expect("Received").replace("=\n", "");
+    assert!(message.contains("To: mia@example.com"));
+    let token: String = message
+        .split("https://example.com/app/reset-password/")
+        .nth(1)
+        .expect("The email contains the link")
+        .chars()
+        .take_while(char::is_ascii_alphanumeric)
+        .collect();
+    query("UPDATE users SET oidc_subject = $1 WHERE id = $2")
+        .bind("mia-subject")
+        .bind(mia.id)
+        .execute(&db)
+        .await
+        .expect("Linked");
+    assert!(
+        crate::password_reset::reset_password(&token, "new password")
+            .await
+            .is_err()
+    );
+    crate::auth::check_password("mia", "password")
+        .await
+        .expect("The password is unchanged");
+}
+
+/// The number of recorded clicks.
+async fn count_clicks() -> i64 {
+    query("SELECT count(*) AS number FROM clicks")
+        .fetch_one(&crate::get_db().await)
+        .await
+        .expect("Count")
+        .get("number")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_click_queue() {
+    use crate::click_queue::{ClickQueue, MAX_BATCH_SIZE, run};
+    use std::time::Duration;
+
+    let _lock = fresh_db().await;
+    let author = add_user("mallory").await;
+    let link = add_link("queue", &author).await;
+
+    // a full queue drops the click instead of waiting
+    let (queue, receiver) = ClickQueue::new(2);
+    assert!(queue.record(NewClick::new(link.id)));
+    assert!(queue.record(NewClick::new(link.id)));
+    assert!(!queue.record(NewClick::new(link.id)));
+    assert_eq!(queue.usage(), (2, 2));
+    // nothing is written before the interval, the shutdown writes the queued clicks
+    tokio::spawn(run(receiver, Duration::from_secs(3600)));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(count_clicks().await, 0);
+    queue.shutdown().await;
+    assert_eq!(count_clicks().await, 2);
+    assert!(!queue.record(NewClick::new(link.id)));
+
+    // a full batch is written right away, the rest at the shutdown
+    let (queue, receiver) = ClickQueue::new(MAX_BATCH_SIZE * 2);
+    for _ in 0..=MAX_BATCH_SIZE {
+        assert!(queue.record(NewClick::new(link.id)));
+    }
+    tokio::spawn(run(receiver, Duration::from_secs(3600)));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(count_clicks().await, 2 + MAX_BATCH_SIZE as i64);
+    queue.shutdown().await;
+    assert_eq!(count_clicks().await, 3 + MAX_BATCH_SIZE as i64);
+
+    // without a shutdown the clicks are written after the interval
+    let (queue, receiver) = ClickQueue::new(10);
+    tokio::spawn(run(receiver, Duration::from_millis(100)));
+    assert!(queue.record(NewClick::new(link.id)));
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(count_clicks().await, 4 + MAX_BATCH_SIZE as i64);
+    queue.shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_click_export_ownership() {
+    use axum::http::StatusCode;
+    use pslink_shared::apirequests::links::{ClickExportFormat, ClickExportQuery};
+
+    let _lock = fresh_db().await;
+    let alice = add_user("alice").await;
+    let bob = add_user("bob").await;
+    let admin = add_user("admin").await;
+    admin
+        .clone()
+        .toggle_admin()
+        .await
+        .expect("Failed to toggle");
+    let admin = User::get_user(admin.id).await.expect("User exists");
+    let own = add_link("alice-link", &alice).await;
+    let other = add_link("bob-link", &bob).await;
+    let quoted = NewClick::new(own.id).with_request_details(Some("a,\"b\"".to_string()), None);
+    NewClick::insert_clicks(vec![NewClick::new(own.id), NewClick::new(other.id), quoted])
+        .await
+        .expect("Failed to insert clicks");
+
+    // the links of other users are refused even together with an own link
+    assert!(
+        crate::click_export::ensure_may_export(&alice, &[own.id])
+            .await
+            .is_ok()
+    );
+    assert_eq!(
+        crate::click_export::ensure_may_export(&alice, &[own.id, other.id])
+            .await
+            .map_err(|(status, _)| status),
+        Err(StatusCode::FORBIDDEN)
+    );
+    assert_eq!(
+        crate::click_export::ensure_may_export(&alice, &[other.id + 100])
+            .await
+            .map_err(|(status, _)| status),
+        Err(StatusCode::NOT_FOUND)
+    );
+    assert!(
+        crate::click_export::ensure_may_export(&admin, &[own.id, other.id])
+            .await
+            .is_ok()
+    );
+
+    // without link ids only the own links are exported
+    let query = ClickExportQuery {
+        format: ClickExportFormat::Csv,
+        ..ClickExportQuery::default()
+    };
+    let mut csv = Vec::new();
+    crate::click_export::write_export(&mut csv, &query, &[], alice.id)
+        .await
+        .expect("Export");
+    let csv = String::from_utf8(csv).expect("Utf8");
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv.starts_with("link_id,code,created_at,referrer,device\n"));
+    assert!(csv.contains("alice-link"));
+    assert!(!csv.contains("bob-link"));
+    // commas and quotes in a field are quoted
+    let referrers = csv::Reader::from_reader(csv.as_bytes())
+        .records()
+        .map(|record| record.expect("Record")[3].to_string())
+        .collect::<Vec<_>>();
+    assert!(referrers.contains(&"a,\"b\"".to_string()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_link_import() {
+    use crate::link_import::{import, parse};
+    use pslink_shared::apirequests::links::{
+        ImportConflictPolicy, ImportRowAction, LinkImportFormat,
+    };
+
+    let _lock = fresh_db().await;
+    let alice = add_user("alice").await;
+    let bob = add_user("bob").await;
+    add_link("taken", &bob).await;
+    let actions = |report: &pslink_shared::apirequests::links::LinkImportReport| {
+        report
+            .rows
+            .iter()
+            .map(|row| row.action.clone())
+            .collect::<Vec<_>>()
+    };
+
+    // a dry run only reports
+    let links = parse(
+        LinkImportFormat::Csv,
+        "title,target,code\nNew,https://example.com/new,new\n",
+    )
+    .expect("Parse");
+    let report = import(&alice, links.clone(), ImportConflictPolicy::Skip, true)
+        .await
+        .expect("Import");
+    assert_eq!(actions(&report), vec![ImportRowAction::Create]);
+    assert!(!report.applied);
+    assert!(Link::get_link_by_code("new").await.is_err());
+
+    // codes that only differ in case are duplicates and nothing is written
+    let duplicates = parse(
+        LinkImportFormat::Json,
+        r#"[{"title": "A", "target": "https://a.example", "code": "Foo"},
+            {"title": "B", "target": "https://b.example", "code": "foo"}]"#,
+    )
+    .expect("Parse");
+    let report = import(&alice, duplicates, ImportConflictPolicy::Skip, false)
+        .await
+        .expect("Import");
+    assert_eq!(
+        actions(&report),
+        vec![ImportRowAction::Create, ImportRowAction::Invalid]
+    );
+    assert!(!report.applied);
+    assert!(Link::get_link_by_code("foo").await.is_err());
+
+    let conflict = parse(
+        LinkImportFormat::Csv,
+        "title,target,code\nChanged,https://example.com/changed,TAKEN\n",
+    )
+    .expect("Parse");
+
+    // existing links are kept
+    let report = import(&alice, conflict.clone(), ImportConflictPolicy::Skip, false)
+        .await
+        .expect("Import");
+    assert_eq!(actions(&report), vec![ImportRowAction::Skip]);
+    assert!(report.applied);
+    let taken = Link::get_link_by_code("taken").await.expect("Link exists");
+    assert_eq!(taken.target, "https://example.com");
+
+    // only the author or an admin may overwrite a link and the author stays the same
+    let report = import(
+        &alice,
+        conflict.clone(),
+        ImportConflictPolicy::Overwrite,
+        false,
+    )
+    .await
+    .expect("Import");
+    assert_eq!(actions(&report), vec![ImportRowAction::Invalid]);
+    assert!(!report.applied);
+    let report = import(
+        &bob,
+        conflict.clone(),
+        ImportConflictPolicy::Overwrite,
+        false,
+    )
+    .await
+    .expect("Import");
+    assert_eq!(actions(&report), vec![ImportRowAction::Overwrite]);
+    let taken = Link::get_link_by_code("taken").await.expect("Link exists");
+    assert_eq!(taken.target, "https://example.com/changed");
+    assert_eq!(taken.author, bob.id);
+
+    // renamed codes avoid the database and the other rows regardless of case
+    let mut renamed = conflict;
+    renamed.extend(
+        parse(
+            LinkImportFormat::Csv,
+            "title,target,code\nSecond,https://example.com/second,taken-2\n",
+        )
+        .expect("Parse"),
+    );
+    let report = import(&alice, renamed, ImportConflictPolicy::Rename, false)
+        .await
+        .expect("Import");
+    assert_eq!(
+        actions(&report),
+        vec![
+            ImportRowAction::Rename("TAKEN-3".to_string()),
+            ImportRowAction::Create
+        ]
+    );
+    assert!(report.applied);
+    let copy = Link::get_link_by_code("taken-3")
+        .await
+        .expect("Link exists");
+    assert_eq!(copy.author, alice.id);
+    assert_eq!(copy.title, "Changed");
+    assert!(Link::get_link_by_code("taken-2").await.is_ok());
+
+    // only administrators may set the author
+    let authored = parse(
+        LinkImportFormat::Csv,
+        "title,target,code,author\nOther,https://example.com/other,other,bob\n",
+    )
+    .expect("Parse");
+    let report = import(&alice, authored, ImportConflictPolicy::Skip, true)
+        .await
+        .expect("Import");
+    assert_eq!(actions(&report), vec![ImportRowAction::Invalid]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_foreign_import() {
+    use crate::foreign_import::{ForeignFormat, import};
+
+    let _lock = fresh_db().await;
+    let admin = add_user("admin").await;
+    let timestamp = |date: &str| {
+        chrono::NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S")
+            .expect("Date")
+            .and_utc()
+            .timestamp()
+    };
+    let clicks = |code: &'static str| async move {
+        let link = Link::get_link_by_code(code).await.expect("Link exists");
+        query("SELECT created_at, referrer FROM clicks WHERE link = $1 ORDER BY created_at")
+            .bind(link.id)
+            .fetch_all(&crate::get_db().await)
+            .await
+            .expect("Clicks")
+            .iter()
+            .map(|row| {
+                (
+                    row.get::<chrono::NaiveDateTime>("created_at").to_string(),
+                    row.get::<Option<String>>("referrer"),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // the clicks of the log keep their time and referrer host, invalid codes are skipped
+    let summary = import(
+        ForeignFormat::Yourls,
+        include_str!("../fixtures/yourls.sql"),
+        admin.id,
+    )
+    .await
+    .expect("Import");
+    assert_eq!(summary.imported, 2);
+    assert_eq!(summary.clicks, 5);
+    assert_eq!(summary.skipped.len(), 1);
+    assert_eq!(summary.skipped[0].0, "bad code");
+    let docs = Link::get_link_by_code("docs").await.expect("Link exists");
+    assert_eq!(docs.title, "The docs; 'part' one");
+    assert_eq!(docs.target, "https://example.com/docs?a=1;b=2");
+    assert_eq!(docs.author, admin.id);
+    assert_eq!(
+        docs.created_at.unix_timestamp(),
+        timestamp("2023-04-01 10:00:00")
+    );
+    assert_eq!(
+        clicks("docs").await,
+        vec![
+            (
+                "2023-04-02 08:00:00".to_string(),
+                Some("www.google.com".to_string())
+            ),
+            ("2023-04-03 09:30:00".to_string(), None),
+        ]
+    );
+    // without a log the clicks are recorded when the link was created
+    let old = Link::get_link_by_code("old").await.expect("Link exists");
+    assert_eq!(old.title, "old");
+    assert_eq!(
+        clicks("old").await,
+        vec![("2022-01-01 00:00:00".to_string(), None); 3]
+    );
+
+    // existing codes are conflicts
+    let summary = import(
+        ForeignFormat::Polr,
+        include_str!("../fixtures/polr.sql"),
+        admin.id,
+    )
+    .await
+    .expect("Import");
+    assert_eq!(summary.imported, 1);
+    assert_eq!(summary.conflicts, vec!["docs".to_string()]);
+    assert_eq!(summary.skipped.len(), 1);
+    assert_eq!(summary.skipped[0].0, "nourl");
+    assert_eq!(
+        clicks("polr1").await,
+        vec![
+            (
+                "2021-06-02 07:00:00".to_string(),
+                Some("news.example.org".to_string())
+            ),
+            ("2021-06-03 07:00:00".to_string(), None),
+        ]
+    );
+    let docs = Link::get_link_by_code("docs").await.expect("Link exists");
+    assert_eq!(docs.target, "https://example.com/docs?a=1;b=2");
+
+    // dates with offsets are stored in UTC
+    let summary = import(
+        ForeignFormat::Shlink,
+        include_str!("../fixtures/shlink.json"),
+        admin.id,
+    )
+    .await
+    .expect("Import");
+    assert_eq!((summary.imported, summary.clicks), (2, 5));
+    let shlink = Link::get_link_by_code("sh1").await.expect("Link exists");
+    assert_eq!(shlink.title, "Shlink link");
+    assert_eq!(
+        shlink.created_at.unix_timestamp(),
+        timestamp("2024-02-03 09:20:30")
+    );
+    assert_eq!(clicks("sh1").await.len(), 4);
+    let shlink = Link::get_link_by_code("sh2").await.expect("Link exists");
+    assert_eq!(shlink.title, "sh2");
+
+    let summary = import(
+        ForeignFormat::Kutt,
+        include_str!("../fixtures/kutt.json"),
+        admin.id,
+    )
+    .await
+    .expect("Import");
+    assert_eq!((summary.imported, summary.clicks), (2, 2));
+    let kutt = Link::get_link_by_code("kutt1").await.expect("Link exists");
+    assert_eq!(kutt.title, "Kutt link");
+    assert_eq!(kutt.target, "https://example.com/kutt");
+    assert_eq!(
+        kutt.created_at.unix_timestamp(),
+        timestamp("2024-03-01 12:00:00")
+    );
+    assert_eq!(
+        Link::get_link_by_code("kutt2")
+            .await
+            .expect("Link exists")
+            .title,
+        "kutt2"
+    );
+
+    // codes that only differ in case are one link, the later ones are conflicts
+    let dump = r#"{"data": [
+        {"address": "Dup", "target": "https://example.com/first"},
+        {"address": "dup", "target": "https://example.com/second"}
+    ]}"#;
+    let summary = import(ForeignFormat::Kutt, dump, admin.id)
+        .await
+        .expect("Import");
+    assert_eq!(summary.imported, 1);
+    assert_eq!(summary.conflicts, vec!["dup".to_string()]);
+    assert_eq!(
+        Link::get_link_by_code("DUP")
+            .await
+            .expect("Link exists")
+            .target,
+        "https://example.com/first"
+    );
+
+    // a file of the wrong format is an error
+    assert!(
+        import(
+            ForeignFormat::Kutt,
+            include_str!("../fixtures/polr.sql"),
+            admin.id
+        )
+        .await
+        .is_err()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_data_export_round_trip() {
+    use crate::data_export::{export, restore};
+    use pslink_shared::qr::{QrShape, QrStyle};
+
+    let _lock = fresh_db().await;
+    let alice = add_user("alice").await;
+    let admin = add_user("admin").await;
+    admin.toggle_admin().await.expect("Failed to toggle");
+    let docs = add_link("docs", &alice).await;
+    let styled = add_link("styled", &alice).await;
+    crate::qr_images::save_link_style(
+        styled.id,
+        &QrStyle {
+            shape: QrShape::Circle,
+            foreground: "#336699".to_string(),
+            ..QrStyle::default()
+        },
+    )
+    .await
+    .expect("Style");
+    NewClick::insert_clicks(vec![
+        NewClick::new(docs.id),
+        NewClick {
+            referrer: Some("example.org".to_string()),
+            device: Some(DeviceClass::Mobile),
+            ..NewClick::new(styled.id)
+        },
+    ])
+    .await
+    .expect("Failed to insert clicks");
+
+    // credentials and links to other accounts are restored as well
+    crate::init_public_url(&crate::cli::Protocol::Https, "example.com");
+    query("UPDATE users SET oidc_subject = $1 WHERE id = $2")
+        .bind("alice-subject")
+        .bind(alice.id)
+        .execute(&crate::get_db().await)
+        .await
+        .expect("Subject");
+    let admin = User::get_user_by_name("admin").await.expect("User exists");
+    let enrollment = crate::totp::start_enrollment(&admin)
+        .await
+        .expect("Enrollment starts");
+    let secret = crate::totp::base32_decode(&enrollment.secret).expect("Valid secret");
+    let now = crate::totp::step_at(chrono::Utc::now().timestamp());
+    let recovery_codes =
+        crate::totp::confirm_enrollment(&admin, &crate::totp::code_at(&secret, now))
+            .await
+            .expect("Enrollment is confirmed");
+    crate::totp::set_required_for_admins(true)
+        .await
+        .expect("Setting is stored");
+    let api_token = crate::api_tokens::create_token(&alice, "ci", ApiTokenScope::Write, None)
+        .await
+        .expect("Api token");
+    crate::share::create_token(docs.id, Some(7))
+        .await
+        .expect("Share token");
+    let invitation = crate::invitations::create_invitation(&admin, None, Role::Regular, 7)
+        .await
+        .expect("Invitation");
+    let invitation_token = invitation
+        .url
+        .strip_prefix("https://example.com/app/invite/")
+        .expect("Invitation url")
+        .to_string();
+
+    let before = export(true).await.expect("Export");
+    assert_eq!((before.users.len(), before.links.len()), (2, 2));
+    assert_eq!(before.clicks.len(), 2);
+    assert_eq!(
+        (
+            before.api_tokens.len(),
+            before.share_tokens.len(),
+            before.invitations.len()
+        ),
+        (1, 1, 1)
+    );
+    assert_eq!(
+        before.users[1]
+            .totp
+            .as_ref()
+            .map(|totp| totp.recovery_codes.len()),
+        Some(10)
+    );
+
+    // only an empty database can be restored into
+    assert!(restore(&before).await.is_err());
+
+    reset_db().await;
+    let summary = restore(&before).await.expect("Restore");
+    assert_eq!((summary.users, summary.links, summary.clicks), (2, 2, 2));
+    assert_eq!(summary.tokens, 3);
+    assert!(summary.without_password.is_empty());
+    let after = export(true).await.expect("Export");
+    assert_eq!(
+        after,
+        pslink_shared::apirequests::data_export::DataExport {
+            exported_at: after.exported_at,
+            ..before.clone()
+        }
+    );
+    // the passwords, second factors and tokens still work and new rows get new ids
+    let restored = crate::auth::check_password("alice", "password")
+        .await
+        .expect("Password");
+    let new = add_link("new", &restored).await;
+    assert!(new.id > styled.id);
+    let subject: Option<String> = query("SELECT oidc_subject FROM users WHERE id = $1")
+        .bind(alice.id)
+        .fetch_one(&crate::get_db().await)
+        .await
+        .expect("Subject")
+        .get("oidc_subject");
+    assert_eq!(subject.as_deref(), Some("alice-subject"));
+    assert!(crate::totp::is_enabled(admin.id).await.expect("Check"));
+    assert!(crate::totp::required_for_admins().await.expect("Check"));
+    assert!(
+        crate::totp::verify(admin.id, &recovery_codes[0])
+            .await
+            .expect("Verify")
+    );
+    let (owner, _) = crate::api_tokens::authenticate(&api_token.secret)
+        .await
+        .expect("Authenticate")
+        .expect("Token is valid");
+    assert_eq!(owner.id, alice.id);
+    assert_eq!(
+        crate::share::list_tokens(docs.id)
+            .await
+            .expect("Tokens")
+            .len(),
+        1
+    );
+    assert!(
+        crate::invitations::invitation_info(&invitation_token)
+            .await
+            .is_ok()
+    );
+
+    // without passwords the users have to set a new one and no credentials are exported
+    let without = export(false).await.expect("Export");
+    assert!(without.users.iter().all(|user| user.totp.is_none()));
+    assert!(without.api_tokens.is_empty() && without.invitations.is_empty());
+    assert_eq!(without.share_tokens.len(), 1);
+    reset_db().await;
+    let summary = restore(&without).await.expect("Restore");
+    assert_eq!(summary.without_password, vec!["alice", "admin"]);
+    assert!(!crate::totp::is_enabled(admin.id).await.expect("Check"));
+
+    // exports of the first version are still restored
+    let mut first_version = serde_json::to_value(&without).expect("Serialize");
+    first_version["version"] = 1.into();
+    for key in ["api_tokens", "share_tokens", "invitations", "settings"] {
+        first_version.as_object_mut().expect("Object").remove(key);
+    }
+    let first_version: pslink_shared::apirequests::data_export::DataExport =
+        serde_json::from_value(first_version).expect("Version 1");
+    reset_db().await;
+    let summary = restore(&first_version).await.expect("Restore");
+    assert_eq!((summary.users, summary.tokens), (2, 0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_backups() {
+    use crate::backup::{BackupSettings, create_backup, prune};
+    use std::io::Read as _;
+
+    let _lock = fresh_db().await;
+    add_user("alice").await;
+    let directory = std::env::temp_dir().join(format!("pslink-backup-test-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).expect("Directory");
+    let settings = BackupSettings {
+        directory: directory.clone(),
+        keep: 2,
+        gzip: true,
+    };
+
+    // the backup is a compressed sqlite database that only the owner can read
+    let backup = create_backup(&settings).await.expect("Backup");
+    let mut database = Vec::new();
+    flate2::read::GzDecoder::new(std::fs::File::open(&backup).expect("Open"))
+        .read_to_end(&mut database)
+        .expect("Decompress");
+    assert!(database.starts_with(b"SQLite format 3\0"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        let mode = std::fs::metadata(&backup)
+            .expect("Metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    let names = || {
+        let mut names: Vec<String> = std::fs::read_dir(&directory)
+            .expect("Read directory")
+            .map(|entry| {
+                entry
+                    .expect("Entry")
+                    .file_name()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+        names.sort();
+        names
+    };
+    // no uncompressed snapshot is left behind
+    assert_eq!(names().len(), 1);
+
+    // only the newest backups are kept, other files are not touched
+    for name in [
+        "pslink-20200101-000000.db",
+        "pslink-20200102-000000.db.gz",
+        "pslink-20200103-000000.db",
+        "notes.txt",
+    ] {
+        std::fs::write(directory.join(name), b"old").expect("Write");
+    }
+    prune(&settings).expect("Prune");
+    let backup_name = backup.file_name().unwrap().to_string_lossy().to_string();
+    assert_eq!(
+        names(),
+        vec![
+            "notes.txt".to_string(),
+            "pslink-20200103-000000.db".to_string(),
+            backup_name.clone()
+        ]
+    );
+    // at least one backup is always kept
+    prune(&BackupSettings {
+        keep: 0,
+        ..settings
+    })
+    .expect("Prune");
+    assert_eq!(names(), vec!["notes.txt".to_string(), backup_name]);
+
+    std::fs::remove_dir_all(&directory).ok();
+}
@@ -0,0 +1,49 @@
+//! Manage the personal API tokens for scripted access.
+//!
+//! Tokens can only be managed after a login, a token cannot create or revoke tokens.
+
+use dioxus::prelude::*;
+#[cfg(feature = "server")]
+use pslink_shared::apirequests::users::Role;
+use pslink_shared::datatypes::{ApiToken, ApiTokenScope, NewApiToken};
+
+/// Create a token for the logged in user, it expires after `valid_days` days if given.
+///
+/// The returned secret is only shown this once.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if nobody is logged in, the name is empty or access to the database fails.
+#[server(CreateApiToken, endpoint = "create_api_token")]
+pub async fn create_api_token(
+    name: String,
+    scope: ApiTokenScope,
+    valid_days: Option<u32>,
+) -> Result<NewApiToken, ServerFnError> {
+    let user = crate::auth::get_session_user().await?;
+    crate::api_tokens::create_token(&user, &name, scope, valid_days.filter(|days| *days > 0)).await
+}
+
+/// List the tokens of the logged in user, administrators see the tokens of all users.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if nobody is logged in or access to the database fails.
+#[server(ListApiTokens, endpoint = "list_api_tokens")]
+pub async fn list_api_tokens() -> Result<Vec<ApiToken>, ServerFnError> {
+    let user = crate::auth::get_session_user().await?;
+    let owner = (user.role != Role::Admin).then_some(user.id);
+    crate::api_tokens::list_tokens(owner).await
+}
+
+/// Revoke a token, it cannot be used afterwards. Administrators may revoke the tokens of all users.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user does not own the token or access to the database fails.
+#[server(RevokeApiToken, endpoint = "revoke_api_token")]
+pub async fn revoke_api_token(token_id: i64) -> Result<(), ServerFnError> {
+    let user = crate::auth::get_session_user().await?;
+    let owner = crate::api_tokens::token_owner(token_id).await?;
+    if user.role != Role::Admin && owner != user.id {
+        return Err(ServerFnError::new("Only the owner can revoke an API token"));
+    }
+    crate::api_tokens::delete_token(token_id).await
+}
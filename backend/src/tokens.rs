@@ -0,0 +1,20 @@
+//! Random secrets and their hashes for api tokens, invitations, password resets and share links.
+//!
+//! The secrets are long random strings, so a fast SHA-256 hash is enough to store them.
+use sha2::{Digest as _, Sha256};
+
+/// A random string of `length` letters and digits.
+pub(crate) fn random_token(length: usize) -> String {
+    rand::Rng::sample_iter(rand::thread_rng(), &rand::distributions::Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+/// The SHA-256 hash of a secret as lowercase hex, this is what is stored in the database.
+pub(crate) fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
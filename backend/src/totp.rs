@@ -0,0 +1,492 @@
+//! Two-factor authentication with time-based one-time passwords (RFC 6238).
+//!
+//! The secret is shown once as `otpauth://` uri and qr-code and is enabled after the first code
+//! generated with it is confirmed. The server needs the secret to check the codes so it is stored as
+//! is, the recovery codes are only stored as SHA-256 hash like the API tokens. A code is accepted one
+//! step before and after the current time and every step only once.
+//!
+//! A login with a correct password of a user with two-factor authentication only stores a
+//! [`PendingLogin`] in the session, the user is logged in after the code was checked.
+use dioxus::logger::tracing::info;
+use dioxus::prelude::ServerFnError;
+use hmac::{Hmac, Mac as _};
+use pslink_shared::{
+    apirequests::users::{LoginResult, Role, TotpEnrollment, TotpStatus},
+    datatypes::User,
+};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+
+use crate::auth::Session;
+use crate::login_limits::ClientIp;
+use crate::models::UserDbOperations as _;
+use crate::storage::query;
+use crate::tokens::hash_token;
+
+/// The name of the instance in the authenticator apps.
+const ISSUER: &str = "Pslink";
+/// The seconds a code is valid.
+const STEP_SECONDS: i64 = 30;
+/// The number of digits of a code.
+const DIGITS: usize = 6;
+/// The length of a secret in bytes, 160 bits as recommended for HMAC-SHA1.
+const SECRET_LENGTH: usize = 20;
+/// The number of recovery codes that are generated at once.
+const RECOVERY_CODES: usize = 10;
+/// The characters of the recovery codes, without the ones that are easily confused.
+const RECOVERY_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+/// The session key of a login that waits for the second factor.
+const PENDING_KEY: &str = "totp_pending";
+/// The seconds a password stays valid while waiting for the second factor.
+const PENDING_SECONDS: i64 = 300;
+/// The number of wrong codes after which the password has to be entered again.
+const MAX_ATTEMPTS: u32 = 5;
+/// The name of the setting that requires two-factor authentication for administrators.
+const ADMIN_REQUIRED_SETTING: &str = "totp_required_for_admins";
+
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encode bytes as base32 without padding as the authenticator apps expect it.
+#[must_use]
+pub(crate) fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(char::from(
+                BASE32_ALPHABET[((buffer >> bits) & 31) as usize],
+            ));
+        }
+    }
+    if bits > 0 {
+        encoded.push(char::from(
+            BASE32_ALPHABET[((buffer << (5 - bits)) & 31) as usize],
+        ));
+    }
+    encoded
+}
+
+/// Decode base32, padding, spaces and lowercase letters are accepted.
+#[must_use]
+pub(crate) fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(encoded.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in encoded.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| char::from(a) == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push(((buffer >> bits) & 0xff) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+/// The code of a time step (RFC 4226 with HMAC-SHA1).
+#[must_use]
+pub(crate) fn code_at(secret: &[u8], step: i64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    let offset = usize::from(hash[hash.len() - 1] & 0x0f);
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS as u32),
+        width = DIGITS
+    )
+}
+
+/// The time step of a unix timestamp.
+#[must_use]
+pub(crate) const fn step_at(timestamp: i64) -> i64 {
+    timestamp.div_euclid(STEP_SECONDS)
+}
+
+/// Find the time step around `timestamp` the code belongs to.
+#[must_use]
+pub(crate) fn matching_step(secret: &[u8], code: &str, timestamp: i64) -> Option<i64> {
+    if code.len() != DIGITS || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let current = step_at(timestamp);
+    (current - 1..=current + 1).find(|step| code_at(secret, *step) == code)
+}
+
+/// The uri the authenticator apps read from the qr-code.
+#[must_use]
+pub(crate) fn otpauth_uri(account: &str, secret: &str) -> String {
+    let label: String = format!("{ISSUER}:{account}")
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b':' | b'@' => {
+                char::from(b).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect();
+    format!(
+        "otpauth://totp/{label}?secret={secret}&issuer={ISSUER}&algorithm=SHA1&digits={DIGITS}&period={STEP_SECONDS}"
+    )
+}
+
+/// Codes are entered with spaces or dashes and recovery codes in any case.
+fn normalize(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn now() -> chrono::NaiveDateTime {
+    chrono::Local::now().naive_utc()
+}
+
+/// Whether the user has confirmed two-factor authentication.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn is_enabled(user_id: i64) -> Result<bool, ServerFnError> {
+    let db = crate::get_db().await;
+    let row = query("SELECT enabled_at FROM user_totp WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_optional(&db)
+        .await?;
+    Ok(row.is_some_and(|row| {
+        row.get::<Option<chrono::NaiveDateTime>>("enabled_at")
+            .is_some()
+    }))
+}
+
+/// Whether administrators have to use two-factor authentication.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn required_for_admins() -> Result<bool, ServerFnError> {
+    let db = crate::get_db().await;
+    let row = query("SELECT value FROM instance_settings WHERE name = $1")
+        .bind(ADMIN_REQUIRED_SETTING)
+        .fetch_optional(&db)
+        .await?;
+    Ok(row.is_some_and(|row| row.get::<String>("value") == "true"))
+}
+
+/// Require two-factor authentication for all administrators or stop requiring it.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn set_required_for_admins(required: bool) -> Result<(), ServerFnError> {
+    let db = crate::get_db().await;
+    query(
+        "INSERT INTO instance_settings (name, value) VALUES ($1, $2)
+        ON CONFLICT (name) DO UPDATE SET value = excluded.value",
+    )
+    .bind(ADMIN_REQUIRED_SETTING)
+    .bind(required.to_string())
+    .execute(&db)
+    .await?;
+    Ok(())
+}
+
+/// Whether the user has to set up two-factor authentication before logging in.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn needs_enrollment(user: &User) -> Result<bool, ServerFnError> {
+    Ok(user.role == Role::Admin && required_for_admins().await? && !is_enabled(user.id).await?)
+}
+
+/// The settings of the user for the settings page.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn status(user_id: i64) -> Result<TotpStatus, ServerFnError> {
+    let db = crate::get_db().await;
+    let left: i64 = query(
+        "SELECT COUNT(*) AS left_codes FROM totp_recovery_codes WHERE owner = $1 AND used_at IS NULL",
+    )
+    .bind(user_id)
+    .fetch_one(&db)
+    .await?
+    .get("left_codes");
+    Ok(TotpStatus {
+        enabled: is_enabled(user_id).await?,
+        recovery_codes_left: usize::try_from(left).unwrap_or_default(),
+        required_for_admins: required_for_admins().await?,
+    })
+}
+
+/// Generate a new secret for the user, it replaces an earlier secret that was not confirmed.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if two-factor authentication is already enabled or the database cannot be acessed.
+pub(crate) async fn start_enrollment(user: &User) -> Result<TotpEnrollment, ServerFnError> {
+    if is_enabled(user.id).await? {
+        return Err(ServerFnError::new(
+            "Two-factor authentication is already enabled",
+        ));
+    }
+    let bytes: [u8; SECRET_LENGTH] = rand::Rng::r#gen(&mut rand::thread_rng());
+    let secret = base32_encode(&bytes);
+    let db = crate::get_db().await;
+    query(
+        "INSERT INTO user_totp (user_id, secret) VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET secret = excluded.secret, enabled_at = NULL, last_step = NULL",
+    )
+    .bind(user.id)
+    .bind(&secret)
+    .execute(&db)
+    .await?;
+    Ok(TotpEnrollment {
+        uri: otpauth_uri(&user.username, &secret),
+        secret,
+    })
+}
+
+/// Accept a code of the secret if its time step was not used before.
+async fn check_code(user_id: i64, code: &str, confirmed: bool) -> Result<bool, ServerFnError> {
+    let db = crate::get_db().await;
+    let Some(row) = query("SELECT secret, enabled_at FROM user_totp WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_optional(&db)
+        .await?
+    else {
+        return Ok(false);
+    };
+    if row
+        .get::<Option<chrono::NaiveDateTime>>("enabled_at")
+        .is_some()
+        != confirmed
+    {
+        return Ok(false);
+    }
+    let secret = base32_decode(&row.get::<String>("secret"))
+        .ok_or_else(|| ServerFnError::new("The stored secret is invalid"))?;
+    let Some(step) = matching_step(&secret, &normalize(code), chrono::Utc::now().timestamp())
+    else {
+        return Ok(false);
+    };
+    // the condition makes the check atomic, a code cannot be used twice at the same time
+    let updated = query(
+        "UPDATE user_totp SET last_step = $1
+        WHERE user_id = $2 AND (last_step IS NULL OR last_step < $1)",
+    )
+    .bind(step)
+    .bind(user_id)
+    .execute(&db)
+    .await?;
+    Ok(updated == 1)
+}
+
+/// Replace the recovery codes of the user with new ones, they are only returned this once.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn generate_recovery_codes(user_id: i64) -> Result<Vec<String>, ServerFnError> {
+    let mut rng = rand::thread_rng();
+    let codes: Vec<String> = (0..RECOVERY_CODES)
+        .map(|_| {
+            let chars: String = (0..10)
+                .map(|_| {
+                    char::from(
+                        RECOVERY_ALPHABET
+                            [rand::Rng::gen_range(&mut rng, 0..RECOVERY_ALPHABET.len())],
+                    )
+                })
+                .collect();
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect();
+    let db = crate::get_db().await;
+    query("DELETE FROM totp_recovery_codes WHERE owner = $1")
+        .bind(user_id)
+        .execute(&db)
+        .await?;
+    for code in &codes {
+        query("INSERT INTO totp_recovery_codes (owner, code_hash) VALUES ($1, $2)")
+            .bind(user_id)
+            .bind(hash_token(&normalize(code)))
+            .execute(&db)
+            .await?;
+    }
+    Ok(codes)
+}
+
+/// Enable two-factor authentication if the code matches the new secret.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the code is wrong or the database cannot be acessed.
+pub(crate) async fn confirm_enrollment(
+    user: &User,
+    code: &str,
+) -> Result<Vec<String>, ServerFnError> {
+    if !check_code(user.id, code, false).await? {
+        return Err(ServerFnError::new("The code is wrong"));
+    }
+    let db = crate::get_db().await;
+    query("UPDATE user_totp SET enabled_at = $1 WHERE user_id = $2")
+        .bind(now())
+        .bind(user.id)
+        .execute(&db)
+        .await?;
+    info!("{} enabled two-factor authentication", user.username);
+    generate_recovery_codes(user.id).await
+}
+
+/// Check the second factor of a user, a one-time code or an unused recovery code.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn verify(user_id: i64, code: &str) -> Result<bool, ServerFnError> {
+    if check_code(user_id, code, true).await? {
+        return Ok(true);
+    }
+    let db = crate::get_db().await;
+    let used = query(
+        "UPDATE totp_recovery_codes SET used_at = $1
+        WHERE owner = $2 AND code_hash = $3 AND used_at IS NULL",
+    )
+    .bind(now())
+    .bind(user_id)
+    .bind(hash_token(&normalize(code)))
+    .execute(&db)
+    .await?;
+    if used == 1 {
+        info!("The user {} logged in with a recovery code", user_id);
+    }
+    Ok(used == 1)
+}
+
+/// Turn off two-factor authentication and delete the recovery codes.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn disable(user: &User) -> Result<(), ServerFnError> {
+    let db = crate::get_db().await;
+    query("DELETE FROM user_totp WHERE user_id = $1")
+        .bind(user.id)
+        .execute(&db)
+        .await?;
+    query("DELETE FROM totp_recovery_codes WHERE owner = $1")
+        .bind(user.id)
+        .execute(&db)
+        .await?;
+    info!(
+        "Two-factor authentication of {} was disabled",
+        user.username
+    );
+    Ok(())
+}
+
+/// A login whose password was correct and that waits for the second factor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PendingLogin {
+    pub user_id: i64,
+    /// The user has to set up two-factor authentication instead of entering a code.
+    pub enroll: bool,
+    pub attempts: u32,
+    started_at: i64,
+}
+
+/// Log the user in or, if a second factor is needed, remember the pending login in the session.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if the database cannot be acessed.
+pub(crate) async fn begin_login(
+    session: &Session,
+    user: User,
+) -> Result<LoginResult, ServerFnError> {
+    let enroll = match (is_enabled(user.id).await?, needs_enrollment(&user).await?) {
+        (true, _) => false,
+        (false, true) => true,
+        (false, false) => {
+            session.session.remove(PENDING_KEY);
+            session.login_user(user.id);
+            return Ok(LoginResult::LoggedIn(user));
+        }
+    };
+    session.session.set(
+        PENDING_KEY,
+        PendingLogin {
+            user_id: user.id,
+            enroll,
+            attempts: 0,
+            started_at: chrono::Utc::now().timestamp(),
+        },
+    );
+    Ok(if enroll {
+        LoginResult::TotpEnrollmentRequired
+    } else {
+        LoginResult::TotpRequired
+    })
+}
+
+/// The pending login of the session if it did not expire.
+#[must_use]
+pub(crate) fn pending_login(session: &Session) -> Option<PendingLogin> {
+    let pending = session.session.get::<PendingLogin>(PENDING_KEY)?;
+    if chrono::Utc::now().timestamp() - pending.started_at > PENDING_SECONDS {
+        session.session.remove(PENDING_KEY);
+        return None;
+    }
+    Some(pending)
+}
+
+/// Finish a pending login with a one-time or recovery code.
+///
+/// # Errors
+/// fails with [`ServerFnError`] if no login is pending, the code is wrong or the database cannot be acessed.
+pub(crate) async fn finish_login(
+    session: &Session,
+    code: &str,
+    ip: ClientIp,
+) -> Result<User, ServerFnError> {
+    let Some(mut pending) = pending_login(session).filter(|pending| !pending.enroll) else {
+        return Err(ServerFnError::new(
+            "The login expired, enter the password again",
+        ));
+    };
+    let user = User::get_user(pending.user_id).await?;
+    let limits = crate::get_login_limits();
+    let attempt = crate::login_limits::reserve(&limits, &user.username, ip).await?;
+    if verify(user.id, code).await? {
+        attempt.release().await?;
+        session.session.remove(PENDING_KEY);
+        session.login_user(user.id);
+        crate::login_limits::record_success(&user.username, ip).await?;
+        return Ok(user);
+    }
+    // wrong codes count like wrong passwords, the username is locked after too many
+    attempt.fail(&limits, "wrong one-time code").await?;
+    pending.attempts += 1;
+    if pending.attempts >= MAX_ATTEMPTS {
+        info!("Too many wrong codes for the user {}", pending.user_id);
+        session.session.remove(PENDING_KEY);
+        return Err(ServerFnError::new(
+            "Too many wrong codes, enter the password again",
+        ));
+    }
+    session.session.set(PENDING_KEY, pending);
+    Err(ServerFnError::new("The code is wrong"))
+}
+
+/// Finish the login of a user that had to set up two-factor authentication.
+pub(crate) fn finish_enrollment_login(session: &Session, user: &User) {
+    if pending_login(session).is_some_and(|pending| pending.enroll && pending.user_id == user.id) {
+        session.session.remove(PENDING_KEY);
+        session.login_user(user.id);
+    }
+}
@@ -0,0 +1,114 @@
+//! Set up two-factor authentication with an authenticator app.
+//!
+//! Administrators that have to use two-factor authentication but did not set it up yet can do so
+//! during the login, before they are logged in.
+
+use dioxus::prelude::*;
+use pslink_shared::apirequests::users::{TotpEnrollment, TotpStatus};
+#[cfg(feature = "server")]
+use pslink_shared::{apirequests::users::Role, datatypes::User};
+
+/// The logged in user or the user of a login that waits for the setup.
+#[cfg(feature = "server")]
+async fn enrolling_user() -> Result<User, ServerFnError> {
+    use crate::models::UserDbOperations as _;
+    if let Ok(user) = crate::auth::get_session_user().await {
+        return Ok(user);
+    }
+    let session = crate::auth::get_session().await?;
+    let pending = crate::totp::pending_login(&session)
+        .filter(|pending| pending.enroll)
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+    User::get_user(pending.user_id).await
+}
+
+/// Whether the logged in user uses two-factor authentication.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if nobody is logged in or access to the database fails.
+#[server(GetTotpStatus, endpoint = "get_totp_status")]
+pub async fn get_totp_status() -> Result<TotpStatus, ServerFnError> {
+    let user = crate::auth::get_session_user().await?;
+    crate::totp::status(user.id).await
+}
+
+/// Generate a new secret, it is enabled by [`confirm_totp_enrollment`].
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if nobody is logging in or two-factor authentication is already enabled.
+#[server(StartTotpEnrollment, endpoint = "start_totp_enrollment")]
+pub async fn start_totp_enrollment() -> Result<TotpEnrollment, ServerFnError> {
+    let user = enrolling_user().await?;
+    crate::totp::start_enrollment(&user).await
+}
+
+/// Enable two-factor authentication with the first code of the authenticator app.
+///
+/// A pending login is finished. The returned recovery codes are only shown this once.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the code is wrong or access to the database fails.
+#[server(ConfirmTotpEnrollment, endpoint = "confirm_totp_enrollment")]
+pub async fn confirm_totp_enrollment(code: String) -> Result<Vec<String>, ServerFnError> {
+    let user = enrolling_user().await?;
+    let recovery_codes = crate::totp::confirm_enrollment(&user, &code).await?;
+    let session = crate::auth::get_session().await?;
+    crate::totp::finish_enrollment_login(&session, &user);
+    Ok(recovery_codes)
+}
+
+/// Replace the recovery codes, a current code is needed.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the code is wrong or access to the database fails.
+#[server(RegenerateRecoveryCodes, endpoint = "regenerate_recovery_codes")]
+pub async fn regenerate_recovery_codes(code: String) -> Result<Vec<String>, ServerFnError> {
+    let user = crate::auth::get_session_user().await?;
+    if !crate::totp::verify(user.id, &code).await? {
+        return Err(ServerFnError::new("The code is wrong"));
+    }
+    crate::totp::generate_recovery_codes(user.id).await
+}
+
+/// Turn off two-factor authentication, a current code is needed.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the code is wrong, administrators have to use two-factor
+/// authentication or access to the database fails.
+#[server(DisableTotp, endpoint = "disable_totp")]
+pub async fn disable_totp(code: String) -> Result<(), ServerFnError> {
+    let user = crate::auth::get_session_user().await?;
+    if user.role == Role::Admin && crate::totp::required_for_admins().await? {
+        return Err(ServerFnError::new(
+            "Administrators have to use two-factor authentication",
+        ));
+    }
+    if !crate::totp::verify(user.id, &code).await? {
+        return Err(ServerFnError::new("The code is wrong"));
+    }
+    crate::totp::disable(&user).await
+}
+
+/// Require two-factor authentication for all administrators, they have to set it up at their next login.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if the user is no administrator, did not set up two-factor
+/// authentication or access to the database fails.
+#[server(SetTotpRequiredForAdmins, endpoint = "set_totp_required_for_admins")]
+pub async fn set_totp_required_for_admins(required: bool) -> Result<(), ServerFnError> {
+    let user = crate::auth::get_session_user().await?;
+    if user.role != Role::Admin {
+        return Err(ServerFnError::new("Administrator permissions required"));
+    }
+    if required && !crate::totp::is_enabled(user.id).await? {
+        return Err(ServerFnError::new(
+            "Set up two-factor authentication for your own account first",
+        ));
+    }
+    dioxus::logger::tracing::info!(
+        "{} set the two-factor authentication requirement for administrators to {}",
+        user.username,
+        required
+    );
+    crate::totp::set_required_for_admins(required).await
+}
@@ -22,8 +22,6 @@ use pslink_shared::{
     },
     datatypes::Secret,
 };
-#[cfg(feature = "server")]
-use sqlx::Row;
 
 /// Only admins can list all users other users will only see themselves.
 ///
@@ -38,33 +36,10 @@ pub async fn list_users(parameters: UserRequestForm) -> Result<ListWithOwner<Use
         .expect("not authenticated")
         .get_user()
         .expect("User is authenticated");
-    let db = crate::get_db().await;
     match user.role {
         Role::Admin => {
             info!("Admin User {:?}", user.username);
-            let mut querystring = "Select * from users".to_string();
-            querystring.push_str(&generate_filter_users_sql(&parameters.filter));
-            if let Some(order) = parameters.order {
-                querystring.push_str(&generate_order_users_sql(&order));
-            }
-            querystring.push_str(&format!("\n LIMIT {}", parameters.amount));
-
-            let query_result = sqlx::query(&querystring).fetch_all(&db).await;
-            if let Err(e) = &query_result {
-                info!("Query: {:?}", e);
-            }
-            let users: Vec<User> = query_result?
-                .into_iter()
-                .map(|v| User {
-                    id: v.get("id"),
-                    username: v.get("username"),
-                    email: v.get("email"),
-                    password: Secret::new("".to_string()),
-                    role: Role::convert(v.get("role")),
-                    language: Lang::from_str(v.get("language")).expect("Should parse"),
-                })
-                .collect();
-
+            let users = query_users(&parameters).await?;
             info!("Found {} users", users.len());
             trace!("Found {:?} users", users);
 
@@ -81,6 +56,39 @@ pub async fn list_users(parameters: UserRequestForm) -> Result<ListWithOwner<Use
     }
 }
 
+/// Load the users matching the filter of the form.
+///
+/// # Errors
+/// Fails with [`ServerFnError`] if access to the database fails.
+#[cfg(feature = "server")]
+pub(crate) async fn query_users(parameters: &UserRequestForm) -> Result<Vec<User>, ServerFnError> {
+    let db = crate::get_db().await;
+    let mut querystring = "Select * from users".to_string();
+    querystring.push_str(&generate_filter_users_sql(&parameters.filter));
+    if let Some(order) = &parameters.order {
+        querystring.push_str(&generate_order_users_sql(order));
+    }
+    querystring.push_str(&format!("\n LIMIT {}", parameters.amount));
+
+    let query_result = crate::storage::query(querystring).fetch_all(&db).await;
+    if let Err(e) = &query_result {
+        info!("Query: {:?}", e);
+    }
+    let users = query_result?
+        .into_iter()
+        .map(|v| User {
+            id: v.get("id"),
+            username: v.get("username"),
+            email: v.get("email"),
+            password: Secret::new("".to_string()),
+            role: Role::convert(v.get("role")),
+            language: Lang::from_str(v.get("language")).expect("Should parse"),
+        })
+        .collect();
+
+    Ok(users)
+}
+
 /// Generate a filter statement for the SQL-Query according to the parameters...
 ///
 /// Todo: this function only naively protects agains SQL-injections use better variants.
@@ -91,19 +99,23 @@ fn generate_filter_users_sql(filters: &EnumMap<UserOverviewColumns, Filter>) ->
         .iter()
         .filter_map(|(column, sieve)| {
             // avoid sql injections
-            let sieve: String = sieve.chars().filter(|x| x.is_alphanumeric()).collect();
+            let sieve: String = sieve
+                .chars()
+                .filter(|x| x.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase();
             if sieve.is_empty() {
                 None
             } else {
                 Some(match column {
                     UserOverviewColumns::Id => {
-                        format!("\n id LIKE '%{}%'", sieve)
+                        format!("\n CAST(id AS TEXT) LIKE '%{}%'", sieve)
                     }
                     UserOverviewColumns::Username => {
-                        format!("\n username LIKE '%{}%'", sieve)
+                        format!("\n LOWER(username) LIKE '%{}%'", sieve)
                     }
                     UserOverviewColumns::Email => {
-                        format!("\n email LIKE '%{}%'", sieve)
+                        format!("\n LOWER(email) LIKE '%{}%'", sieve)
                     }
                 })
             }
@@ -147,20 +159,15 @@ fn generate_order_users_sql(order: &Operation<UserOverviewColumns, Ordering>) ->
 /// Create a new user and save it to the database
 ///
 /// # Errors
-/// Fails with [`ServerError`] if access to the database fails, this user does not have permissions or the user already exists.
+/// Fails with [`ServerError`] if access to the database fails, this user does not have permissions, an API token was
+/// used or the user already exists.
 #[server(CreateUser, endpoint = "create_user")]
 pub async fn create_user(data: UserDelta) -> Result<Item<User>, ServerFnError> {
     info!("Creating a User: {:?}", &data);
     if data.edit != EditMode::Create {
         return Err(ServerFnError::new("Wrong Request".to_string()));
     }
-    let auth = crate::auth::get_session().await?;
-    info!("Auth: {:?}", auth);
-    let user = auth
-        .current_user
-        .expect("not authenticated")
-        .get_user()
-        .expect("User is authenticated");
+    let user = crate::auth::get_session_user().await?;
 
     // Require a password on user creation!
     let password = match &data.password {
@@ -198,16 +205,12 @@ pub async fn create_user(data: UserDelta) -> Result<Item<User>, ServerFnError> {
 /// The `user_id` is never changed.
 ///
 /// # Errors
-/// Fails with [`ServerFnError`] if access to the database fails, this user does not have permissions, or the given data is malformed.
+/// Fails with [`ServerFnError`] if access to the database fails, this user does not have permissions, an API token
+/// was used, or the given data is malformed.
 #[server(UpdateUser, endpoint = "update_user")]
 pub async fn update_user(data: UserDelta) -> Result<Item<User>, ServerFnError> {
-    let auth = crate::auth::get_session().await?;
-    info!("Auth: {:?}", auth);
-    let user = auth
-        .current_user
-        .expect("not authenticated")
-        .get_user()
-        .expect("User is authenticated");
+    // API tokens cannot manage users or change credentials, that needs a login with all factors
+    let user = crate::auth::get_session_user().await?;
     if let Some(uid) = data.id {
         let unmodified_user = User::get_user(uid).await?;
         if admin_or_self(&user, unmodified_user.id) {
@@ -260,16 +263,10 @@ fn admin_or_self(user: &User, uid: i64) -> bool {
 
 /// Delete a user from the database.
 /// Only admins can delete users.
-/// The user can not delete itself.
+/// The user can not delete itself and API tokens cannot delete users.
 #[server(DeleteUser, endpoint = "delete_user")]
 pub async fn delete_user(user_id: i64) -> Result<(), ServerFnError> {
-    let auth = crate::auth::get_session().await?;
-    info!("Auth: {:?}", auth);
-    let user = auth
-        .current_user
-        .expect("not authenticated")
-        .get_user()
-        .expect("User is authenticated");
+    let user = crate::auth::get_session_user().await?;
     if user.role == Role::Admin {
         if user.id != user_id {
             User::delete_user(user_id, user.id).await?;
@@ -285,12 +282,7 @@ pub async fn delete_user(user_id: i64) -> Result<(), ServerFnError> {
 /// Set a users language
 #[server(SetUserLanguage, endpoint = "set_user_language")]
 pub async fn set_user_language(language: Lang) -> Result<(), ServerFnError> {
-    let auth = crate::auth::get_session().await?;
-    let user = auth
-        .current_user
-        .expect("not authenticated")
-        .get_user()
-        .expect("User is authenticated");
+    let user = crate::auth::get_session_user().await?;
     let user_id = user.id;
     println!("Setting {}'s language to {:?}", user.username, language);
     let res = user.set_language(language).await?;
@@ -0,0 +1,67 @@
+//! The config file with the url of the instance and the API token.
+use std::{
+    io::Write as _,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context as _, anyhow};
+use serde::{Deserialize, Serialize};
+
+/// The content of the config file, `url` and `token` can be overridden on the command line.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub url: Option<String>,
+    pub token: Option<String>,
+}
+
+/// The default location of the config file, `$XDG_CONFIG_HOME/pslink/cli.toml` or `~/.config/pslink/cli.toml`.
+pub fn default_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("APPDATA"))
+                .map(|home| PathBuf::from(home).join(".config"))
+        })
+        .map(|dir| dir.join("pslink").join("cli.toml"))
+}
+
+impl Config {
+    /// Read the config file, a missing file is an empty config.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => toml::from_str(&content)
+                .with_context(|| format!("Failed to parse the config file {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow!(
+                "Failed to read the config file {}: {e}",
+                path.display()
+            )),
+        }
+    }
+
+    /// Write the config file, it is only readable by the owner as it contains the token.
+    ///
+    /// The permissions are set before the token is written, also if the file already existed.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options
+            .open(path)
+            .with_context(|| format!("Failed to write the config file {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt as _;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(toml::to_string(self)?.as_bytes())
+            .with_context(|| format!("Failed to write the config file {}", path.display()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,469 @@
+//! `pslink-cli` manages the links of a running pslink instance through its REST api.
+//!
+//! The url of the instance and the API token are stored by `pslink-cli login` in a config file,
+//! they can be overridden with `--url` and `--token` or `PSLINK_URL` and `PSLINK_TOKEN`.
+mod config;
+mod output;
+mod tests;
+
+use std::{
+    io::Write as _,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use anyhow::{Context as _, anyhow};
+use clap::{Arg, ArgAction, ArgMatches, Command, command, value_parser};
+use pslink_client::blocking::Client;
+use pslink_shared::{
+    apirequests::{
+        links::{LinkDelta, LinkOverviewColumns, LinkRequestForm},
+        rest::{LinkListQuery, UserListQuery},
+    },
+    datatypes::{Clicks, FullLink, Link, User},
+};
+
+use crate::{
+    config::Config,
+    output::{Format, Table},
+};
+
+fn generate_cli() -> Command {
+    command!()
+        .name("pslink-cli")
+        .about("Manage the links of a pslink instance through its REST api")
+        .subcommand_required(true)
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .help("The config file with the url and the API token")
+                .env("PSLINK_CLI_CONFIG")
+                .value_parser(value_parser!(PathBuf))
+                .global(true),
+        )
+        .arg(
+            Arg::new("url")
+                .long("url")
+                .help("The url of the pslink instance, e.g. https://pslink.example.com")
+                .env("PSLINK_URL")
+                .global(true),
+        )
+        .arg(
+            Arg::new("token")
+                .long("token")
+                .help("The API token, it is created on the users page of pslink")
+                .env("PSLINK_TOKEN")
+                .hide_env_values(true)
+                .global(true),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .short('f')
+                .help("How the results are printed")
+                .value_parser(Format::NAMES)
+                .default_value("table")
+                .global(true),
+        )
+        .subcommand(
+            Command::new("login")
+                .about("Check the url and API token and store them in the config file")
+                .display_order(1),
+        )
+        .subcommand(
+            Command::new("link")
+                .about("Create, list, change and delete links")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("add")
+                        .about("Create a link")
+                        .arg(code_arg())
+                        .arg(Arg::new("target").help("The url the link redirects to").required(true))
+                        .arg(
+                            Arg::new("title")
+                                .long("title")
+                                .help("The description of the link, the code if not given"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("ls")
+                        .about("List the links")
+                        .arg(filter_arg("code", "Only links whose code contains this"))
+                        .arg(filter_arg("title", "Only links whose title contains this"))
+                        .arg(filter_arg("target", "Only links whose target contains this"))
+                        .arg(filter_arg("author", "Only links whose author's username contains this"))
+                        .arg(
+                            Arg::new("order")
+                                .long("order")
+                                .help("The column the links are ordered by")
+                                .value_parser(["code", "title", "target", "author", "clicks"])
+                                .default_value("code"),
+                        )
+                        .arg(
+                            Arg::new("descending")
+                                .long("desc")
+                                .help("Order the links descending")
+                                .action(ArgAction::SetTrue),
+                        )
+                        .arg(amount_arg())
+                        .arg(
+                            Arg::new("offset")
+                                .long("offset")
+                                .help("The number of links that are skipped")
+                                .value_parser(value_parser!(usize)),
+                        ),
+                )
+                .subcommand(Command::new("rm").about("Delete a link and its clicks").arg(code_arg()))
+                .subcommand(
+                    Command::new("edit")
+                        .about("Change the code, title or target of a link")
+                        .arg(code_arg())
+                        .arg(Arg::new("new_code").long("code").help("The new code of the link"))
+                        .arg(Arg::new("title").long("title").help("The new title of the link"))
+                        .arg(Arg::new("target").long("target").help("The new target of the link")),
+                )
+                .subcommand(
+                    Command::new("stats")
+                        .about("Show the clicks of a link by referrer and device")
+                        .arg(code_arg()),
+                )
+                .subcommand(
+                    Command::new("qr")
+                        .about("Save the qr-code of a link as png or svg")
+                        .arg(code_arg())
+                        .arg(
+                            Arg::new("svg")
+                                .long("svg")
+                                .help("Save a svg instead of a png")
+                                .action(ArgAction::SetTrue),
+                        )
+                        .arg(
+                            Arg::new("size")
+                                .long("size")
+                                .help("The width of the png in pixels")
+                                .value_parser(value_parser!(u32)),
+                        )
+                        .arg(
+                            Arg::new("dpi")
+                                .long("dpi")
+                                .help("The resolution stored in the png")
+                                .value_parser(value_parser!(u32)),
+                        )
+                        .arg(
+                            Arg::new("output")
+                                .long("output")
+                                .short('o')
+                                .help("The file the qr-code is saved to, - for stdout, <code>.png if not given")
+                                .value_parser(value_parser!(PathBuf)),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("user")
+                .about("List users")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("ls")
+                        .about("List the users, only administrators see all of them")
+                        .arg(filter_arg("username", "Only users whose username contains this"))
+                        .arg(filter_arg("email", "Only users whose email contains this"))
+                        .arg(amount_arg()),
+                ),
+        )
+}
+
+fn code_arg() -> Arg {
+    Arg::new("code").help("The code of the link").required(true)
+}
+
+fn filter_arg(name: &'static str, help: &'static str) -> Arg {
+    Arg::new(name).long(name).help(help)
+}
+
+fn amount_arg() -> Arg {
+    Arg::new("amount")
+        .long("amount")
+        .short('n')
+        .help("The largest number of results")
+        .value_parser(value_parser!(usize))
+}
+
+fn main() -> ExitCode {
+    match run(&generate_cli().get_matches()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(matches: &ArgMatches) -> anyhow::Result<()> {
+    let config_path = matches
+        .get_one::<PathBuf>("config")
+        .cloned()
+        .or_else(config::default_path)
+        .ok_or_else(|| anyhow!("No config file, set --config or PSLINK_CLI_CONFIG"))?;
+    let config = Config::load(&config_path)?;
+    let url = matches.get_one::<String>("url").cloned().or(config.url);
+    let token = matches.get_one::<String>("token").cloned().or(config.token);
+    let format = Format::parse(matches.get_one::<String>("format").expect("has a default"));
+
+    if let Some(("login", _)) = matches.subcommand() {
+        return login(url, token, &config_path);
+    }
+    let (Some(url), Some(token)) = (url, token) else {
+        return Err(anyhow!(
+            "The url or API token is missing, run pslink-cli login first"
+        ));
+    };
+    let client = Client::with_token(&url, &token)?;
+    match matches.subcommand() {
+        Some(("link", link)) => match link.subcommand() {
+            Some(("add", args)) => add_link(&client, args, format),
+            Some(("ls", args)) => list_links(&client, args, format),
+            Some(("rm", args)) => {
+                let code = arg(args, "code");
+                client.delete_link(code)?;
+                eprintln!("Deleted the link {code}");
+                Ok(())
+            }
+            Some(("edit", args)) => edit_link(&client, args, format),
+            Some(("stats", args)) => link_stats(&client, args, format),
+            Some(("qr", args)) => save_qr(&client, args),
+            _ => unreachable!("a subcommand is required"),
+        },
+        Some(("user", user)) => match user.subcommand() {
+            Some(("ls", args)) => list_users(&client, args, format),
+            _ => unreachable!("a subcommand is required"),
+        },
+        _ => unreachable!("a subcommand is required"),
+    }
+}
+
+fn arg<'a>(args: &'a ArgMatches, name: &str) -> &'a str {
+    args.get_one::<String>(name).map_or("", String::as_str)
+}
+
+fn opt(args: &ArgMatches, name: &str) -> Option<String> {
+    args.get_one::<String>(name).cloned()
+}
+
+fn prompt(question: &str) -> anyhow::Result<String> {
+    print!("{question}: ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+/// Ask for the missing url and token, check them and store them in the config file.
+fn login(url: Option<String>, token: Option<String>, path: &Path) -> anyhow::Result<()> {
+    let url = match url {
+        Some(url) => url,
+        None => prompt("The url of the pslink instance")?,
+    };
+    let token = match token {
+        Some(token) => token,
+        None => rpassword::prompt_password("The API token: ")?
+            .trim()
+            .to_string(),
+    };
+    let user = Client::with_token(&url, &token)?
+        .current_user()
+        .context("The url or API token is not valid")?;
+    Config {
+        url: Some(url.clone()),
+        token: Some(token),
+    }
+    .save(path)?;
+    println!(
+        "Logged in to {url} as {}, the token is stored in {}",
+        user.username,
+        path.display()
+    );
+    Ok(())
+}
+
+fn link_rows(links: &[Link]) -> Table {
+    Table {
+        headers: &["code", "title", "target", "created"],
+        rows: links
+            .iter()
+            .map(|link| {
+                vec![
+                    link.code.clone(),
+                    link.title.clone(),
+                    link.target.clone(),
+                    link.created_at.date().to_string(),
+                ]
+            })
+            .collect(),
+    }
+}
+
+fn add_link(client: &Client, args: &ArgMatches, format: Format) -> anyhow::Result<()> {
+    let code = arg(args, "code").to_string();
+    let link = client.create_link(LinkDelta {
+        title: opt(args, "title").unwrap_or_else(|| code.clone()),
+        target: arg(args, "target").to_string(),
+        code,
+        ..Default::default()
+    })?;
+    output::print(format, &link, || link_rows(std::slice::from_ref(&link)))
+}
+
+fn edit_link(client: &Client, args: &ArgMatches, format: Format) -> anyhow::Result<()> {
+    let code = arg(args, "code");
+    let mut delta = LinkDelta::from(client.get_link(code)?);
+    if let Some(new_code) = opt(args, "new_code") {
+        delta.code = new_code;
+    }
+    if let Some(title) = opt(args, "title") {
+        delta.title = title;
+    }
+    if let Some(target) = opt(args, "target") {
+        delta.target = target;
+    }
+    let link = client.update_link(code, delta)?;
+    output::print(format, &link, || link_rows(std::slice::from_ref(&link)))
+}
+
+fn clicks(clicks: &Clicks) -> i64 {
+    match clicks {
+        Clicks::Count(count) => count.number,
+        Clicks::Extended(statistics) => statistics.total.number,
+    }
+}
+
+fn list_links(client: &Client, args: &ArgMatches, format: Format) -> anyhow::Result<()> {
+    let order = match arg(args, "order") {
+        "title" => LinkOverviewColumns::Description,
+        "target" => LinkOverviewColumns::Target,
+        "author" => LinkOverviewColumns::Author,
+        "clicks" => LinkOverviewColumns::Statistics,
+        _ => LinkOverviewColumns::Code,
+    };
+    let query = LinkListQuery {
+        code: opt(args, "code"),
+        title: opt(args, "title"),
+        target: opt(args, "target"),
+        author: opt(args, "author"),
+        offset: args.get_one::<usize>("offset").copied(),
+        amount: args.get_one::<usize>("amount").copied(),
+        order: Some(order),
+        descending: args.get_flag("descending"),
+    };
+    let links = client.list_links(&LinkRequestForm::from(&query))?;
+    output::print(format, &links, || Table {
+        headers: &["code", "title", "target", "author", "clicks", "created"],
+        rows: links
+            .iter()
+            .map(
+                |FullLink {
+                     link,
+                     user,
+                     clicks: c,
+                 }| {
+                    vec![
+                        link.code.clone(),
+                        link.title.clone(),
+                        link.target.clone(),
+                        user.username.clone(),
+                        clicks(c).to_string(),
+                        link.created_at.date().to_string(),
+                    ]
+                },
+            )
+            .collect(),
+    })
+}
+
+fn link_stats(client: &Client, args: &ArgMatches, format: Format) -> anyhow::Result<()> {
+    let details = client.get_statistics(arg(args, "code"))?;
+    output::print(format, &details, || {
+        let mut rows = vec![vec![
+            "total".to_string(),
+            String::new(),
+            clicks(&details.link.clicks).to_string(),
+        ]];
+        rows.extend(details.referrers.iter().map(|referrer| {
+            vec![
+                "referrer".to_string(),
+                if referrer.name.is_empty() {
+                    "(direct)".to_string()
+                } else {
+                    referrer.name.clone()
+                },
+                referrer.total.number.to_string(),
+            ]
+        }));
+        rows.extend(details.devices.iter().map(|(device, count)| {
+            vec![
+                "device".to_string(),
+                format!("{device:?}"),
+                count.number.to_string(),
+            ]
+        }));
+        Table {
+            headers: &["kind", "name", "clicks"],
+            rows,
+        }
+    })
+}
+
+fn save_qr(client: &Client, args: &ArgMatches) -> anyhow::Result<()> {
+    let code = arg(args, "code");
+    let svg = args.get_flag("svg");
+    let image = if svg {
+        client.qr_svg(code)?.into_bytes()
+    } else {
+        client.qr_png(
+            code,
+            args.get_one::<u32>("size").copied(),
+            args.get_one::<u32>("dpi").copied(),
+        )?
+    };
+    let path = args
+        .get_one::<PathBuf>("output")
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from(format!("{code}.{}", if svg { "svg" } else { "png" })));
+    if path.as_os_str() == "-" {
+        std::io::stdout().write_all(&image)?;
+    } else {
+        std::fs::write(&path, image)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        eprintln!("Saved the qr-code to {}", path.display());
+    }
+    Ok(())
+}
+
+fn list_users(client: &Client, args: &ArgMatches, format: Format) -> anyhow::Result<()> {
+    let users = client.list_users(&UserListQuery {
+        username: opt(args, "username"),
+        email: opt(args, "email"),
+        amount: args.get_one::<usize>("amount").copied(),
+    })?;
+    output::print(format, &users, || Table {
+        headers: &["id", "username", "email", "role"],
+        rows: users
+            .iter()
+            .map(
+                |User {
+                     id,
+                     username,
+                     email,
+                     role,
+                     ..
+                 }| {
+                    vec![
+                        id.to_string(),
+                        username.clone(),
+                        email.clone(),
+                        format!("{role:?}"),
+                    ]
+                },
+            )
+            .collect(),
+    })
+}
@@ -0,0 +1,87 @@
+//! Print the results as aligned table, JSON or CSV.
+use std::io::Write;
+
+use serde::Serialize;
+
+/// The format of the printed results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub const NAMES: [&'static str; 3] = ["table", "json", "csv"];
+
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "json" => Self::Json,
+            "csv" => Self::Csv,
+            _ => Self::Table,
+        }
+    }
+}
+
+/// The rows of a result as they are printed in a table or CSV.
+pub struct Table {
+    pub headers: &'static [&'static str],
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn write_aligned(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let headers: Vec<String> = self.headers.iter().map(|h| h.to_uppercase()).collect();
+        for row in std::iter::once(&headers).chain(&self.rows) {
+            let line: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{cell:width$}"))
+                .collect();
+            writeln!(out, "{}", line.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+
+    fn write_csv(&self, out: &mut impl Write) -> anyhow::Result<()> {
+        let mut writer = csv::Writer::from_writer(out);
+        writer.write_record(self.headers)?;
+        for row in &self.rows {
+            writer.write_record(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Write a result, JSON contains the whole `value` the other formats the rows of `table`.
+pub fn write<T: Serialize>(
+    out: &mut impl Write,
+    format: Format,
+    value: &T,
+    table: impl FnOnce() -> Table,
+) -> anyhow::Result<()> {
+    match format {
+        Format::Json => {
+            writeln!(out, "{}", serde_json::to_string_pretty(value)?)?;
+            Ok(())
+        }
+        Format::Csv => table().write_csv(out),
+        Format::Table => Ok(table().write_aligned(out)?),
+    }
+}
+
+/// Print a result to stdout, see [`write`].
+pub fn print<T: Serialize>(
+    format: Format,
+    value: &T,
+    table: impl FnOnce() -> Table,
+) -> anyhow::Result<()> {
+    write(&mut std::io::stdout().lock(), format, value, table)
+}
@@ -0,0 +1,90 @@
+#![cfg(test)]
+use crate::{
+    config::Config,
+    output::{Format, Table, write},
+};
+
+fn table() -> Table {
+    Table {
+        headers: &["code", "title"],
+        rows: vec![
+            vec!["a".to_string(), "Über, \"quoted\"".to_string()],
+            vec!["long-code".to_string(), String::new()],
+        ],
+    }
+}
+
+fn written(format: Format, value: &serde_json::Value) -> String {
+    let mut out = Vec::new();
+    write(&mut out, format, value, table).expect("Written");
+    String::from_utf8(out).expect("Utf-8")
+}
+
+#[test]
+fn test_output_formats() {
+    assert_eq!(Format::parse("csv"), Format::Csv);
+    assert_eq!(Format::parse("json"), Format::Json);
+    assert_eq!(Format::parse("table"), Format::Table);
+
+    let value = serde_json::json!([{ "code": "a" }]);
+    // the columns are aligned by characters, not bytes, and lines end without spaces
+    assert_eq!(
+        written(Format::Table, &value),
+        "CODE       TITLE\na          Über, \"quoted\"\nlong-code\n"
+    );
+    assert_eq!(
+        written(Format::Csv, &value),
+        "code,title\na,\"Über, \"\"quoted\"\"\"\nlong-code,\n"
+    );
+    // json contains the whole value and does not build the table
+    let mut out = Vec::new();
+    write(&mut out, Format::Json, &value, || {
+        panic!("The table is not needed for json")
+    })
+    .expect("Written");
+    assert_eq!(
+        String::from_utf8(out).expect("Utf-8"),
+        "[\n  {\n    \"code\": \"a\"\n  }\n]\n"
+    );
+}
+
+#[test]
+fn test_config_file() {
+    let directory = std::env::temp_dir().join(format!("pslink-cli-test-{}", std::process::id()));
+    let path = directory.join("pslink").join("cli.toml");
+
+    // a missing file is an empty config
+    let empty = Config::load(&path).expect("Missing file");
+    assert!(empty.url.is_none() && empty.token.is_none());
+
+    let config = Config {
+        url: Some("https://pslink.example.com".to_string()),
+        token: Some("pslink_secret".to_string()),
+    };
+    config.save(&path).expect("Saved");
+    let loaded = Config::load(&path).expect("Loaded");
+    assert_eq!(loaded.url, config.url);
+    assert_eq!(loaded.token, config.token);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        let mode = |path: &std::path::Path| {
+            std::fs::metadata(path)
+                .expect("Metadata")
+                .permissions()
+                .mode()
+                & 0o777
+        };
+        assert_eq!(mode(&path), 0o600);
+        // an existing file that others could read is restricted before the token is written
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644))
+            .expect("Permissions");
+        config.save(&path).expect("Saved");
+        assert_eq!(mode(&path), 0o600);
+    }
+
+    std::fs::write(&path, "url = ").expect("Write");
+    assert!(Config::load(&path).is_err());
+    std::fs::remove_dir_all(&directory).expect("Cleanup");
+}
@@ -0,0 +1,189 @@
+//! A client that waits for the responses, for programs without an async runtime.
+//!
+//! It runs the async [`crate::Client`] on its own tokio runtime, so it must not be used from
+//! within an async runtime.
+use std::sync::Arc;
+
+use pslink_shared::{
+    apirequests::{
+        links::{LinkDelta, LinkRequestForm, StatisticsPeriod},
+        rest::UserListQuery,
+        users::UserDelta,
+    },
+    datatypes::{FullLink, Link, LinkClicks, LinkDetails, User, WeekCount},
+};
+use tokio::runtime::Runtime;
+
+use crate::{Auth, Error};
+
+/// The blocking version of [`crate::Client`], the methods are documented there.
+#[derive(Clone, Debug)]
+pub struct Client {
+    inner: crate::Client,
+    runtime: Arc<Runtime>,
+}
+
+fn runtime() -> Result<Arc<Runtime>, Error> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map(Arc::new)
+        .map_err(Error::Runtime)
+}
+
+impl Client {
+    /// Create a client for the pslink server at `base_url`.
+    ///
+    /// # Errors
+    /// Fails with [`Error::InvalidUrl`] if the url is not an absolute http(s) url.
+    pub fn new(base_url: &str, auth: Auth) -> Result<Self, Error> {
+        Ok(Self {
+            inner: crate::Client::new(base_url, auth)?,
+            runtime: runtime()?,
+        })
+    }
+
+    /// Create a client that authenticates with a personal API token.
+    ///
+    /// # Errors
+    /// Fails with [`Error::InvalidUrl`] if the url is not an absolute http(s) url.
+    pub fn with_token(base_url: &str, token: &str) -> Result<Self, Error> {
+        Self::new(base_url, Auth::Token(token.to_string()))
+    }
+
+    /// Log in with username and password, the client keeps the session cookie.
+    ///
+    /// # Errors
+    /// Fails with [`Error::Unauthorized`] if the username or password is wrong.
+    pub fn login(base_url: &str, username: &str, password: &str) -> Result<Self, Error> {
+        let runtime = runtime()?;
+        let inner = runtime.block_on(crate::Client::login(base_url, username, password))?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// # Errors
+    /// See [`crate::Client::login_with_code`].
+    pub fn login_with_code(
+        base_url: &str,
+        username: &str,
+        password: &str,
+        code: Option<&str>,
+    ) -> Result<Self, Error> {
+        let runtime = runtime()?;
+        let inner = runtime.block_on(crate::Client::login_with_code(
+            base_url, username, password, code,
+        ))?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// # Errors
+    /// See [`crate::Client::logout`].
+    pub fn logout(&self) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.logout())
+    }
+
+    /// # Errors
+    /// See [`crate::Client::list_links`].
+    pub fn list_links(&self, form: &LinkRequestForm) -> Result<Vec<FullLink>, Error> {
+        self.runtime.block_on(self.inner.list_links(form))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::get_link`].
+    pub fn get_link(&self, code: &str) -> Result<Link, Error> {
+        self.runtime.block_on(self.inner.get_link(code))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::create_link`].
+    pub fn create_link(&self, link: LinkDelta) -> Result<Link, Error> {
+        self.runtime.block_on(self.inner.create_link(link))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::update_link`].
+    pub fn update_link(&self, code: &str, link: LinkDelta) -> Result<Link, Error> {
+        self.runtime.block_on(self.inner.update_link(code, link))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::delete_link`].
+    pub fn delete_link(&self, code: &str) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.delete_link(code))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::get_statistics`].
+    pub fn get_statistics(&self, code: &str) -> Result<LinkDetails, Error> {
+        self.runtime.block_on(self.inner.get_statistics(code))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::instance_statistics`].
+    pub fn instance_statistics(&self) -> Result<Vec<WeekCount>, Error> {
+        self.runtime.block_on(self.inner.instance_statistics())
+    }
+
+    /// # Errors
+    /// See [`crate::Client::top_links`].
+    pub fn top_links(
+        &self,
+        period: StatisticsPeriod,
+        amount: i64,
+    ) -> Result<Vec<LinkClicks>, Error> {
+        self.runtime.block_on(self.inner.top_links(period, amount))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::qr_png`].
+    pub fn qr_png(
+        &self,
+        code: &str,
+        size: Option<u32>,
+        dpi: Option<u32>,
+    ) -> Result<Vec<u8>, Error> {
+        self.runtime.block_on(self.inner.qr_png(code, size, dpi))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::qr_svg`].
+    pub fn qr_svg(&self, code: &str) -> Result<String, Error> {
+        self.runtime.block_on(self.inner.qr_svg(code))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::list_users`].
+    pub fn list_users(&self, query: &UserListQuery) -> Result<Vec<User>, Error> {
+        self.runtime.block_on(self.inner.list_users(query))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::current_user`].
+    pub fn current_user(&self) -> Result<User, Error> {
+        self.runtime.block_on(self.inner.current_user())
+    }
+
+    /// # Errors
+    /// See [`crate::Client::get_user`].
+    pub fn get_user(&self, id: i64) -> Result<User, Error> {
+        self.runtime.block_on(self.inner.get_user(id))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::create_user`].
+    pub fn create_user(&self, user: UserDelta) -> Result<User, Error> {
+        self.runtime.block_on(self.inner.create_user(user))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::update_user`].
+    pub fn update_user(&self, id: i64, user: UserDelta) -> Result<User, Error> {
+        self.runtime.block_on(self.inner.update_user(id, user))
+    }
+
+    /// # Errors
+    /// See [`crate::Client::delete_user`].
+    pub fn delete_user(&self, id: i64) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.delete_user(id))
+    }
+}
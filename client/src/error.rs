@@ -0,0 +1,94 @@
+//! The errors of the client.
+use std::fmt::Display;
+
+use pslink_shared::apirequests::general::Message;
+
+/// Why a request failed.
+#[derive(Debug)]
+pub enum Error {
+    /// The base url cannot be used, it has to be an absolute http(s) url.
+    InvalidUrl(String),
+    /// The request could not be sent or the response could not be read.
+    Transport(reqwest::Error),
+    /// The token or login is missing, invalid or expired (401).
+    Unauthorized(String),
+    /// The token or its owner may not do this (403).
+    Forbidden(String),
+    /// The link or user does not exist (404).
+    NotFound(String),
+    /// The code or username is already used (409).
+    Conflict(String),
+    /// The server rejected the request as invalid (400 or 422).
+    Invalid(String),
+    /// Any other error status, e.g. if the database of the server failed.
+    Server { status: u16, message: String },
+    /// The blocking client could not start its runtime.
+    Runtime(std::io::Error),
+}
+
+impl Error {
+    /// Read the error message of a response with an error status.
+    pub(crate) async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        // the api answers with a json message, the rejections of malformed requests are plain text
+        let message = serde_json::from_str::<Message>(&body)
+            .map(|m| m.message)
+            .unwrap_or(body);
+        match status.as_u16() {
+            401 => Self::Unauthorized(message),
+            403 => Self::Forbidden(message),
+            404 => Self::NotFound(message),
+            409 => Self::Conflict(message),
+            400 | 422 => Self::Invalid(message),
+            status => Self::Server { status, message },
+        }
+    }
+
+    /// The http status code of the response if the server answered.
+    #[must_use]
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::InvalidUrl(_) | Self::Runtime(_) => None,
+            Self::Transport(e) => e.status().map(|s| s.as_u16()),
+            Self::Unauthorized(_) => Some(401),
+            Self::Forbidden(_) => Some(403),
+            Self::NotFound(_) => Some(404),
+            Self::Conflict(_) => Some(409),
+            Self::Invalid(_) => Some(422),
+            Self::Server { status, .. } => Some(*status),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidUrl(e) => write!(f, "Invalid url: {e}"),
+            Self::Transport(e) => write!(f, "Request failed: {e}"),
+            Self::Unauthorized(message) => write!(f, "Not authenticated: {message}"),
+            Self::Forbidden(message) => write!(f, "Forbidden: {message}"),
+            Self::NotFound(message) => write!(f, "Not found: {message}"),
+            Self::Conflict(message) => write!(f, "Conflict: {message}"),
+            Self::Invalid(message) => write!(f, "Invalid request: {message}"),
+            Self::Server { status, message } => write!(f, "Server error {status}: {message}"),
+            Self::Runtime(e) => write!(f, "Failed to start the runtime: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Transport(e) => Some(e),
+            Self::Runtime(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Transport(e)
+    }
+}
@@ -0,0 +1,335 @@
+//! A typed client for the REST api of pslink.
+//!
+//! The requests and responses are the types of [`pslink_shared`]. Authenticate with a personal API
+//! token that is created on the users page of pslink, with a session cookie or by logging in:
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), pslink_client::Error> {
+//! use pslink_client::Client;
+//! use pslink_shared::apirequests::links::LinkDelta;
+//!
+//! let client = Client::with_token("https://pslink.example.com", "pslink_...")?;
+//! let link = client
+//!     .create_link(LinkDelta {
+//!         title: "Pslink".to_string(),
+//!         target: "https://github.com/enaut/pslink".to_string(),
+//!         code: "pslink".to_string(),
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//! let png = client.qr_png(&link.code, Some(512), Some(300)).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With the `blocking` feature [`blocking::Client`] offers the same methods without async.
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod error;
+
+pub use error::Error;
+pub use pslink_shared;
+
+use pslink_shared::{
+    apirequests::{
+        links::{LinkDelta, LinkRequestForm, StatisticsPeriod},
+        rest::{LinkListQuery, PREFIX, TopLinksQuery, UserListQuery},
+        users::{LoginUser, UserDelta},
+    },
+    datatypes::{FullLink, Link, LinkClicks, LinkDetails, User, WeekCount},
+};
+use reqwest::{Method, RequestBuilder, Url, header};
+use serde::de::DeserializeOwned;
+
+/// How the client authenticates.
+#[derive(Clone, Debug)]
+pub enum Auth {
+    /// A personal API token, it is sent as `Authorization: Bearer <token>`.
+    Token(String),
+    /// The value of a `Cookie` header with the session cookie of a login.
+    Cookie(String),
+    /// The cookies of [`Client::login`] that the client stores itself.
+    Login,
+}
+
+/// A client of one pslink server.
+#[derive(Clone, Debug)]
+pub struct Client {
+    http: reqwest::Client,
+    /// The url of the REST api, e.g. `https://pslink.example.com/api/v1`.
+    base: Url,
+    auth: Auth,
+}
+
+impl Client {
+    /// Create a client for the pslink server at `base_url`.
+    ///
+    /// # Errors
+    /// Fails with [`Error::InvalidUrl`] if the url is not an absolute http(s) url.
+    pub fn new(base_url: &str, auth: Auth) -> Result<Self, Error> {
+        let http = reqwest::Client::builder()
+            .cookie_store(matches!(auth, Auth::Login))
+            .build()?;
+        let mut base = Url::parse(base_url).map_err(|e| Error::InvalidUrl(e.to_string()))?;
+        base.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(base_url.to_string()))?
+            .pop_if_empty()
+            .extend(PREFIX.split('/').filter(|s| !s.is_empty()));
+        Ok(Self { http, base, auth })
+    }
+
+    /// Create a client that authenticates with a personal API token.
+    ///
+    /// # Errors
+    /// Fails with [`Error::InvalidUrl`] if the url is not an absolute http(s) url.
+    pub fn with_token(base_url: &str, token: &str) -> Result<Self, Error> {
+        Self::new(base_url, Auth::Token(token.to_string()))
+    }
+
+    /// Log in with username and password, the client keeps the session cookie.
+    ///
+    /// # Errors
+    /// Fails with [`Error::Unauthorized`] if the username or password is wrong.
+    pub async fn login(base_url: &str, username: &str, password: &str) -> Result<Self, Error> {
+        Self::login_with_code(base_url, username, password, None).await
+    }
+
+    /// Log in to an account with two-factor authentication, `code` is the one-time code of the
+    /// authenticator app or a recovery code.
+    ///
+    /// # Errors
+    /// Fails with [`Error::Unauthorized`] if the username, password or code is wrong.
+    pub async fn login_with_code(
+        base_url: &str,
+        username: &str,
+        password: &str,
+        code: Option<&str>,
+    ) -> Result<Self, Error> {
+        let client = Self::new(base_url, Auth::Login)?;
+        let _: User = client
+            .json(client.request(Method::POST, &["session"]).json(&LoginUser {
+                username: username.to_string(),
+                password: password.to_string(),
+                totp: code.map(str::to_string),
+            }))
+            .await?;
+        Ok(client)
+    }
+
+    /// End the session of [`Client::login`] or [`Auth::Cookie`].
+    ///
+    /// # Errors
+    /// Fails with [`Error`] if the server cannot be reached.
+    pub async fn logout(&self) -> Result<(), Error> {
+        self.send(self.request(Method::DELETE, &["session"]))
+            .await
+            .map(drop)
+    }
+
+    fn url(&self, segments: &[&str]) -> Url {
+        let mut url = self.base.clone();
+        url.path_segments_mut()
+            .expect("The base url was checked in Client::new")
+            .extend(segments);
+        url
+    }
+
+    fn request(&self, method: Method, segments: &[&str]) -> RequestBuilder {
+        let request = self.http.request(method, self.url(segments));
+        match &self.auth {
+            Auth::Token(token) => request.bearer_auth(token),
+            Auth::Cookie(cookie) => request.header(header::COOKIE, cookie),
+            Auth::Login => request,
+        }
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<reqwest::Response, Error> {
+        let response = request.send().await?;
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(Error::from_response(response).await)
+        }
+    }
+
+    async fn json<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, Error> {
+        Ok(self.send(request).await?.json().await?)
+    }
+
+    /// List the links matching the filters of the form. The statistics filter is not supported.
+    ///
+    /// # Errors
+    /// Fails with [`Error`] if the request fails.
+    pub async fn list_links(&self, form: &LinkRequestForm) -> Result<Vec<FullLink>, Error> {
+        self.json(
+            self.request(Method::GET, &["links"])
+                .query(&LinkListQuery::from(form)),
+        )
+        .await
+    }
+
+    /// Get a link by its code.
+    ///
+    /// # Errors
+    /// Fails with [`Error::NotFound`] if the link does not exist.
+    pub async fn get_link(&self, code: &str) -> Result<Link, Error> {
+        self.json(self.request(Method::GET, &["links", code])).await
+    }
+
+    /// Create a link, the author is the user of the token or login.
+    ///
+    /// # Errors
+    /// Fails with [`Error::Conflict`] if the code is used and [`Error::Invalid`] if the code is not allowed.
+    pub async fn create_link(&self, link: LinkDelta) -> Result<Link, Error> {
+        self.json(self.request(Method::POST, &["links"]).json(&link))
+            .await
+    }
+
+    /// Change the code, title and target of the link with the code `code`.
+    ///
+    /// # Errors
+    /// Fails with [`Error::Forbidden`] if the user is neither the author nor an administrator.
+    pub async fn update_link(&self, code: &str, link: LinkDelta) -> Result<Link, Error> {
+        self.json(self.request(Method::PUT, &["links", code]).json(&link))
+            .await
+    }
+
+    /// Delete a link and its clicks.
+    ///
+    /// # Errors
+    /// Fails with [`Error::Forbidden`] if the user is neither the author nor an administrator.
+    pub async fn delete_link(&self, code: &str) -> Result<(), Error> {
+        self.send(self.request(Method::DELETE, &["links", code]))
+            .await
+            .map(drop)
+    }
+
+    /// The clicks of a link per day, referrer, device, weekday and hour.
+    ///
+    /// # Errors
+    /// Fails with [`Error::NotFound`] if the link does not exist.
+    pub async fn get_statistics(&self, code: &str) -> Result<LinkDetails, Error> {
+        self.json(self.request(Method::GET, &["links", code, "stats"]))
+            .await
+    }
+
+    /// The clicks on all links per week during the last year.
+    ///
+    /// # Errors
+    /// Fails with [`Error`] if the request fails.
+    pub async fn instance_statistics(&self) -> Result<Vec<WeekCount>, Error> {
+        self.json(self.request(Method::GET, &["stats", "clicks"]))
+            .await
+    }
+
+    /// The `amount` (at most 100) most clicked links of the period.
+    ///
+    /// # Errors
+    /// Fails with [`Error`] if the request fails.
+    pub async fn top_links(
+        &self,
+        period: StatisticsPeriod,
+        amount: i64,
+    ) -> Result<Vec<LinkClicks>, Error> {
+        self.json(
+            self.request(Method::GET, &["stats", "top-links"])
+                .query(&TopLinksQuery {
+                    period,
+                    amount: Some(amount),
+                }),
+        )
+        .await
+    }
+
+    async fn qr(&self, code: &str, query: &[(&str, String)]) -> Result<Vec<u8>, Error> {
+        let response = self
+            .send(
+                self.request(Method::GET, &["links", code, "qr"])
+                    .query(query),
+            )
+            .await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// The qr-code of a link as png, `size` is the width in pixels and `dpi` the stored resolution.
+    ///
+    /// # Errors
+    /// Fails with [`Error::NotFound`] if the link does not exist.
+    pub async fn qr_png(
+        &self,
+        code: &str,
+        size: Option<u32>,
+        dpi: Option<u32>,
+    ) -> Result<Vec<u8>, Error> {
+        let mut query = vec![("format", "png".to_string())];
+        query.extend(size.map(|size| ("size", size.to_string())));
+        query.extend(dpi.map(|dpi| ("dpi", dpi.to_string())));
+        self.qr(code, &query).await
+    }
+
+    /// The qr-code of a link as svg.
+    ///
+    /// # Errors
+    /// Fails with [`Error::NotFound`] if the link does not exist.
+    pub async fn qr_svg(&self, code: &str) -> Result<String, Error> {
+        let svg = self.qr(code, &[("format", "svg".to_string())]).await?;
+        Ok(String::from_utf8_lossy(&svg).into_owned())
+    }
+
+    /// List the users, users that are not administrators only get themselves.
+    ///
+    /// # Errors
+    /// Fails with [`Error`] if the request fails.
+    pub async fn list_users(&self, query: &UserListQuery) -> Result<Vec<User>, Error> {
+        self.json(self.request(Method::GET, &["users"]).query(query))
+            .await
+    }
+
+    /// The user of the token or login.
+    ///
+    /// # Errors
+    /// Fails with [`Error::Unauthorized`] if the token or login is not valid.
+    pub async fn current_user(&self) -> Result<User, Error> {
+        self.json(self.request(Method::GET, &["users", "me"])).await
+    }
+
+    /// Get a user by id.
+    ///
+    /// # Errors
+    /// Fails with [`Error::NotFound`] if the user does not exist.
+    pub async fn get_user(&self, id: i64) -> Result<User, Error> {
+        self.json(self.request(Method::GET, &["users", &id.to_string()]))
+            .await
+    }
+
+    /// Create a regular user, only administrators may.
+    ///
+    /// # Errors
+    /// Fails with [`Error::Conflict`] if the username is used and [`Error::Forbidden`] for other users.
+    pub async fn create_user(&self, user: UserDelta) -> Result<User, Error> {
+        self.json(self.request(Method::POST, &["users"]).json(&user))
+            .await
+    }
+
+    /// Change a user, the password only if one is given and the role only by administrators.
+    ///
+    /// # Errors
+    /// Fails with [`Error::Forbidden`] if a user that is not an administrator changes someone else.
+    pub async fn update_user(&self, id: i64, user: UserDelta) -> Result<User, Error> {
+        self.json(
+            self.request(Method::PUT, &["users", &id.to_string()])
+                .json(&user),
+        )
+        .await
+    }
+
+    /// Delete a user, their links are given to the administrator.
+    ///
+    /// # Errors
+    /// Fails with [`Error::Forbidden`] if the user is not an administrator.
+    pub async fn delete_user(&self, id: i64) -> Result<(), Error> {
+        self.send(self.request(Method::DELETE, &["users", &id.to_string()]))
+            .await
+            .map(drop)
+    }
+}
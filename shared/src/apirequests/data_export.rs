@@ -0,0 +1,228 @@
+//! The versioned JSON export of all data, it can be restored with `pslink restore`.
+//!
+//! Unlike a copy of the database file the export does not depend on the schema of a specific
+//! version of pslink. Newer versions have to be able to restore all older export versions.
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::{apirequests::users::Role, qr::QrStyle};
+
+/// The request of a JSON export by an administrator.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct DataExportRequest {
+    /// The data download secret of the server.
+    pub secret: String,
+    /// Export the password hashes, second factors, API tokens and invitations, otherwise every user has to
+    /// set a new password after a restore.
+    pub include_passwords: bool,
+}
+
+/// A user in the export.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct ExportedUser {
+    pub id: i64,
+    pub username: String,
+    pub email: String,
+    pub role: Role,
+    pub language: String,
+    /// The password hash, only present if it was explicitly requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// The subject of the single sign-on account the user is linked to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oidc_subject: Option<String>,
+    /// The DN of the directory entry the user is linked to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ldap_dn: Option<String>,
+    /// The second factor, only present with the password.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub totp: Option<ExportedTotp>,
+}
+
+/// The authenticator app secret of a user.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct ExportedTotp {
+    pub secret: String,
+    /// `None` while the setup is not confirmed.
+    #[serde(default)]
+    pub enabled_at: Option<NaiveDateTime>,
+    /// The last time step a code was used for, codes cannot be used twice.
+    #[serde(default)]
+    pub last_step: Option<i64>,
+    #[serde(default)]
+    pub recovery_codes: Vec<ExportedRecoveryCode>,
+}
+
+/// A hashed recovery code of the second factor.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct ExportedRecoveryCode {
+    pub code_hash: String,
+    #[serde(default)]
+    pub used_at: Option<NaiveDateTime>,
+}
+
+/// A link in the export.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct ExportedLink {
+    pub id: i64,
+    pub title: String,
+    pub target: String,
+    pub code: String,
+    /// The id of the user that owns the link.
+    pub author: i64,
+    pub created_at: NaiveDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qr_style: Option<QrStyle>,
+}
+
+/// A click in the export.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct ExportedClick {
+    /// The id of the clicked link.
+    pub link: i64,
+    pub created_at: NaiveDateTime,
+    #[serde(default)]
+    pub referrer: Option<String>,
+    #[serde(default)]
+    pub device: Option<String>,
+}
+
+/// A hashed API token, only exported with the passwords.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct ExportedApiToken {
+    /// The id of the user that owns the token.
+    pub owner: i64,
+    pub name: String,
+    pub scope: String,
+    pub token_hash: String,
+    pub hint: String,
+    pub created_at: NaiveDateTime,
+    #[serde(default)]
+    pub expires_at: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub last_used_at: Option<NaiveDateTime>,
+}
+
+/// The nonce of a public statistics page, the token is signed again with the secret of the server.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct ExportedShareToken {
+    /// The id of the shared link.
+    pub link: i64,
+    pub nonce: String,
+    pub created_at: NaiveDateTime,
+    #[serde(default)]
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// A hashed invitation, only exported with the passwords.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct ExportedInvitation {
+    /// The id of the administrator that invited.
+    pub created_by: i64,
+    pub token_hash: String,
+    pub hint: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    pub role: Role,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    #[serde(default)]
+    pub accepted_at: Option<NaiveDateTime>,
+    /// The id of the user that was created with the invitation.
+    #[serde(default)]
+    pub accepted_by: Option<i64>,
+}
+
+/// All data of a pslink instance.
+///
+/// Pslink has no tags yet, the `tags` list is part of the format so that later versions can fill it
+/// without a new format version.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct DataExport {
+    pub version: u32,
+    pub exported_at: NaiveDateTime,
+    pub users: Vec<ExportedUser>,
+    pub links: Vec<ExportedLink>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub clicks: Vec<ExportedClick>,
+    #[serde(default)]
+    pub api_tokens: Vec<ExportedApiToken>,
+    #[serde(default)]
+    pub share_tokens: Vec<ExportedShareToken>,
+    #[serde(default)]
+    pub invitations: Vec<ExportedInvitation>,
+    /// The settings of the instance like whether administrators need a second factor.
+    #[serde(default)]
+    pub settings: BTreeMap<String, String>,
+}
+
+impl DataExport {
+    /// The version of the format that is written by this version of pslink.
+    ///
+    /// Version 2 added the second factors, the linked accounts, the tokens, the invitations and the settings.
+    pub const VERSION: u32 = 2;
+
+    /// Check that the export can be restored by this version of pslink and that all references are valid.
+    ///
+    /// # Errors
+    /// Returns a description of the first problem that was found.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.version == 0 || self.version > Self::VERSION {
+            return Err(format!(
+                "Export version {} is not supported, this version of pslink restores versions 1 to {}",
+                self.version,
+                Self::VERSION
+            ));
+        }
+        let users: std::collections::HashSet<i64> = self.users.iter().map(|user| user.id).collect();
+        if let Some(link) = self.links.iter().find(|link| !users.contains(&link.author)) {
+            return Err(format!(
+                "The author {} of the link {} is not in the export",
+                link.author, link.code
+            ));
+        }
+        let unknown_user = self
+            .api_tokens
+            .iter()
+            .map(|token| token.owner)
+            .chain(
+                self.invitations
+                    .iter()
+                    .map(|invitation| invitation.created_by),
+            )
+            .chain(
+                self.invitations
+                    .iter()
+                    .filter_map(|invitation| invitation.accepted_by),
+            )
+            .find(|user| !users.contains(user));
+        if let Some(user) = unknown_user {
+            return Err(format!("The user {user} of a token is not in the export"));
+        }
+        let links: std::collections::HashSet<i64> = self.links.iter().map(|link| link.id).collect();
+        if let Some(token) = self
+            .share_tokens
+            .iter()
+            .find(|token| !links.contains(&token.link))
+        {
+            return Err(format!(
+                "The link {} of a share token is not in the export",
+                token.link
+            ));
+        }
+        if let Some(click) = self
+            .clicks
+            .iter()
+            .find(|click| !links.contains(&click.link))
+        {
+            return Err(format!(
+                "The link {} of a click is not in the export",
+                click.link
+            ));
+        }
+        Ok(())
+    }
+}
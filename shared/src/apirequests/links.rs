@@ -2,6 +2,7 @@
 
 use enum_map::{Enum, EnumMap};
 use serde::{Deserialize, Serialize};
+use strum_macros::{AsRefStr, EnumIter, EnumString};
 
 use crate::datatypes::{FullLink, Link};
 
@@ -30,6 +31,7 @@ impl Default for LinkRequestForm {
 /// The Struct that is responsible for creating and editing links.
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct LinkDelta {
+    #[serde(default)]
     pub edit: EditMode,
     pub id: Option<i64>,
     pub title: String,
@@ -107,11 +109,414 @@ pub struct StatisticsRequest {
     pub link_id: i64,
 }
 
+/// The timespan aggregated statistics are calculated for
+#[derive(
+    Clone,
+    Copy,
+    Deserialize,
+    Serialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    EnumString,
+    AsRefStr,
+)]
+pub enum StatisticsPeriod {
+    Week,
+    #[default]
+    Month,
+    Quarter,
+    Year,
+    All,
+}
+
+impl StatisticsPeriod {
+    /// The number of days the period reaches into the past, `None` if the whole history is included.
+    #[must_use]
+    pub const fn days(self) -> Option<u32> {
+        match self {
+            Self::Week => Some(7),
+            Self::Month => Some(30),
+            Self::Quarter => Some(91),
+            Self::Year => Some(365),
+            Self::All => None,
+        }
+    }
+}
+
+/// The file format of a click export
+#[derive(
+    Clone,
+    Copy,
+    Deserialize,
+    Serialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    EnumString,
+    AsRefStr,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ClickExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ClickExportFormat {
+    #[must_use]
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Json => "application/json",
+        }
+    }
+}
+
+/// How the exported clicks are grouped, `Click` exports every single click.
+#[derive(
+    Clone,
+    Copy,
+    Deserialize,
+    Serialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    EnumString,
+    AsRefStr,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ClickAggregation {
+    #[default]
+    Click,
+    Day,
+    Week,
+    Month,
+}
+
+/// The query parameters of a click export.
+///
+/// `links` is a comma separated list of link ids, if it is empty all links of the user are exported.
+/// `from` and `to` limit the export to the clicks between the two days (both included).
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq, Default)]
+pub struct ClickExportQuery {
+    #[serde(default)]
+    pub links: String,
+    #[serde(default)]
+    pub format: ClickExportFormat,
+    #[serde(default)]
+    pub aggregation: ClickAggregation,
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+}
+
+impl ClickExportQuery {
+    /// The path of the export route on the server.
+    pub const PATH: &'static str = "/export/clicks";
+
+    /// The ids of the requested links, invalid entries are ignored.
+    #[must_use]
+    pub fn link_ids(&self) -> Vec<i64> {
+        self.links
+            .split(',')
+            .filter_map(|id| id.trim().parse().ok())
+            .collect()
+    }
+
+    /// The url to download the export.
+    #[must_use]
+    pub fn url(&self) -> String {
+        let mut url = format!(
+            "{}?format={}&aggregation={}",
+            Self::PATH,
+            self.format.as_ref(),
+            self.aggregation.as_ref()
+        );
+        if !self.links.is_empty() {
+            url.push_str(&format!("&links={}", self.links));
+        }
+        if let Some(from) = self.from {
+            url.push_str(&format!("&from={from}"));
+        }
+        if let Some(to) = self.to {
+            url.push_str(&format!("&to={to}"));
+        }
+        url
+    }
+}
+
+/// The file format of a link import
+#[derive(
+    Clone,
+    Copy,
+    Deserialize,
+    Serialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    EnumString,
+    AsRefStr,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum LinkImportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl LinkImportFormat {
+    /// Guess the format from the name of an uploaded file.
+    #[must_use]
+    pub fn from_filename(filename: &str) -> Self {
+        if filename.to_ascii_lowercase().ends_with(".json") {
+            Self::Json
+        } else {
+            Self::Csv
+        }
+    }
+}
+
+/// What happens to imported links whose code already exists
+#[derive(
+    Clone,
+    Copy,
+    Deserialize,
+    Serialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    EnumString,
+    AsRefStr,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ImportConflictPolicy {
+    /// Keep the existing link and ignore the imported one.
+    #[default]
+    Skip,
+    /// Replace the title and target of the existing link.
+    Overwrite,
+    /// Import the link with a new code like `code-2`.
+    Rename,
+}
+
+/// A bulk import of links.
+///
+/// `data` is the content of the uploaded file: a CSV file with the columns `title`, `target`, `code`
+/// and optionally `tags` and `author`, or a JSON array of objects with the same fields.
+/// With `dry_run` nothing is changed and only the report is returned.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct LinkImportRequest {
+    pub format: LinkImportFormat,
+    pub data: String,
+    pub policy: ImportConflictPolicy,
+    pub dry_run: bool,
+}
+
+/// What the import does with a single row
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub enum ImportRowAction {
+    Create,
+    Overwrite,
+    /// The link is created with the contained code.
+    Rename(String),
+    Skip,
+    /// The row has errors, nothing is imported when any row is invalid.
+    Invalid,
+}
+
+/// The result of the validation of a single imported row.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct ImportRowReport {
+    /// The line in the CSV file or the index in the JSON array, starting with 1.
+    pub row: usize,
+    pub code: String,
+    pub title: String,
+    pub target: String,
+    pub action: ImportRowAction,
+    pub messages: Vec<String>,
+}
+
+/// The report of a link import, `applied` is true if the links were written to the database.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct LinkImportReport {
+    pub rows: Vec<ImportRowReport>,
+    pub applied: bool,
+}
+
+impl LinkImportReport {
+    /// True if at least one row cannot be imported.
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.rows
+            .iter()
+            .any(|row| row.action == ImportRowAction::Invalid)
+    }
+}
+
+/// An operation that is applied to many links at once.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub enum BulkLinkOperation {
+    Delete,
+    /// Make the user with this username the author of the links.
+    Transfer(String),
+}
+
+/// Apply an operation to the links with the given ids.
+///
+/// Either all links are changed or none: if one of the links does not exist or may not be edited by
+/// the user nothing is changed.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct BulkLinkRequest {
+    pub links: Vec<i64>,
+    pub operation: BulkLinkOperation,
+}
+
+/// The reasons why a code cannot be used for a link, empty if the code is valid.
+///
+/// Codes are used as the path of the short url, so only letters, digits, `-`, `_` and `.` are
+/// allowed and the paths used by pslink itself are reserved.
+#[must_use]
+pub fn code_problems(code: &str) -> Vec<&'static str> {
+    const RESERVED: [&str; 4] = ["app", "qr", "stats", "export"];
+    let mut problems = Vec::new();
+    if code.is_empty() {
+        problems.push("The code is empty");
+    }
+    if code.len() > 100 {
+        problems.push("The code is longer than 100 characters");
+    }
+    if !code
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        problems.push("The code may only contain letters, digits, '-', '_' and '.'");
+    }
+    if RESERVED.contains(&code.to_lowercase().as_str()) {
+        problems.push("The code is reserved by pslink");
+    }
+    problems
+}
+
+/// The paper size of a printable qr-code sheet
+#[derive(
+    Clone,
+    Copy,
+    Deserialize,
+    Serialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    EnumString,
+    AsRefStr,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum PaperSize {
+    #[default]
+    A4,
+    Letter,
+}
+
+impl PaperSize {
+    /// The width and height of the paper in points (1/72 inch).
+    #[must_use]
+    pub const fn dimensions(self) -> (f64, f64) {
+        match self {
+            Self::A4 => (595.28, 841.89),
+            Self::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+/// The query parameters of a printable pdf sheet with the qr-codes of several links.
+///
+/// `links` is a comma separated list of link ids, the codes are placed in a grid of `rows` and
+/// `columns` with a page margin of `margin` millimeters.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct QrSheetQuery {
+    pub links: String,
+    pub paper: PaperSize,
+    pub rows: u32,
+    pub columns: u32,
+    pub margin: u32,
+}
+
+impl Default for QrSheetQuery {
+    fn default() -> Self {
+        Self {
+            links: String::new(),
+            paper: PaperSize::A4,
+            rows: 5,
+            columns: 4,
+            margin: 10,
+        }
+    }
+}
+
+impl QrSheetQuery {
+    /// The path of the sheet route on the server.
+    pub const PATH: &'static str = "/export/qr-sheet";
+    /// The largest number of rows or columns.
+    pub const MAX_CELLS: u32 = 20;
+    /// The largest page margin in millimeters.
+    pub const MAX_MARGIN: u32 = 50;
+
+    /// The ids of the requested links, invalid entries are ignored.
+    #[must_use]
+    pub fn link_ids(&self) -> Vec<i64> {
+        self.links
+            .split(',')
+            .filter_map(|id| id.trim().parse().ok())
+            .collect()
+    }
+
+    /// The url to download the sheet.
+    #[must_use]
+    pub fn url(&self) -> String {
+        format!(
+            "{}?links={}&paper={}&rows={}&columns={}&margin={}",
+            Self::PATH,
+            self.links,
+            self.paper.as_ref(),
+            self.rows,
+            self.columns,
+            self.margin
+        )
+    }
+}
+
 /// A struct to request a qr-code from the server
+///
+/// `link_id` is the code of the link, `size` the width of the image in pixels (points for pdf and eps).
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub struct QrCodeRequest {
     pub link_id: String,
     pub format: QrCodeFormat,
+    #[serde(default)]
+    pub size: Option<u32>,
+    /// The resolution that is stored in png images.
+    #[serde(default)]
+    pub dpi: Option<u32>,
+    /// Print the title and the short url below the code of pdf and eps files.
+    #[serde(default)]
+    pub label: bool,
 }
 
 /// The response to a qr-request
@@ -120,9 +525,55 @@ pub struct SvgQrCodeResponse {
     pub svg: String,
 }
 
-/// Available formats of qr-codes
+/// The response to a qr-request in the requested format
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub enum QrCodeResponse {
+    Svg(SvgQrCodeResponse),
+    Png(Vec<u8>),
+    Pdf(Vec<u8>),
+    Eps(String),
+}
+
+/// Available formats of qr-codes
+#[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub enum QrCodeFormat {
     Svg,
     Png,
+    Pdf,
+    Eps,
+}
+
+impl QrCodeFormat {
+    /// The file extension of the format.
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+            Self::Pdf => "pdf",
+            Self::Eps => "eps",
+        }
+    }
+
+    /// Find the format of a file extension.
+    #[must_use]
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "svg" => Some(Self::Svg),
+            "png" => Some(Self::Png),
+            "pdf" => Some(Self::Pdf),
+            "eps" => Some(Self::Eps),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Svg => "image/svg+xml",
+            Self::Png => "image/png",
+            Self::Pdf => "application/pdf",
+            Self::Eps => "application/postscript",
+        }
+    }
 }
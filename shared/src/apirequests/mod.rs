@@ -1,4 +1,6 @@
 //! This module contains the structs for api requests.
+pub mod data_export;
 pub mod general;
 pub mod links;
+pub mod rest;
 pub mod users;
@@ -0,0 +1,120 @@
+//! The query parameters of the versioned REST api under [`PREFIX`].
+//!
+//! The request and response bodies are the types of the other modules, errors are returned as
+//! [`super::general::Message`] with a matching http status code.
+use enum_map::EnumMap;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    general::{Filter, Operation, Ordering},
+    links::{LinkOverviewColumns, LinkRequestForm, StatisticsPeriod},
+    users::{UserOverviewColumns, UserRequestForm},
+};
+
+/// The path all routes of the REST api start with.
+pub const PREFIX: &str = "/api/v1";
+
+/// The largest number of entries returned by one list request.
+pub const MAX_AMOUNT: usize = 1000;
+
+fn filter(sieve: Option<&String>) -> Filter {
+    Filter {
+        sieve: sieve.cloned().unwrap_or_default(),
+    }
+}
+
+/// The query parameters of `GET /api/v1/links`, the filters match case insensitive parts of the columns.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq, Eq)]
+pub struct LinkListQuery {
+    pub code: Option<String>,
+    pub title: Option<String>,
+    pub target: Option<String>,
+    /// The username of the author.
+    pub author: Option<String>,
+    pub offset: Option<usize>,
+    /// At most [`MAX_AMOUNT`] links, 100 if not given.
+    pub amount: Option<usize>,
+    /// The column the links are ordered by, the code if not given.
+    pub order: Option<LinkOverviewColumns>,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+impl From<&LinkRequestForm> for LinkListQuery {
+    /// Only the code, title, target and author filters can be used in a query.
+    fn from(form: &LinkRequestForm) -> Self {
+        let sieve = |column: LinkOverviewColumns| {
+            Some(form.filter[column].sieve.clone()).filter(|s| !s.is_empty())
+        };
+        Self {
+            code: sieve(LinkOverviewColumns::Code),
+            title: sieve(LinkOverviewColumns::Description),
+            target: sieve(LinkOverviewColumns::Target),
+            author: sieve(LinkOverviewColumns::Author),
+            offset: Some(form.offset),
+            amount: Some(form.amount),
+            order: form.order.as_ref().map(|order| order.column.clone()),
+            descending: form
+                .order
+                .as_ref()
+                .is_some_and(|order| order.value == Ordering::Descending),
+        }
+    }
+}
+
+impl From<&LinkListQuery> for LinkRequestForm {
+    fn from(query: &LinkListQuery) -> Self {
+        let mut filter = EnumMap::default();
+        filter[LinkOverviewColumns::Code] = self::filter(query.code.as_ref());
+        filter[LinkOverviewColumns::Description] = self::filter(query.title.as_ref());
+        filter[LinkOverviewColumns::Target] = self::filter(query.target.as_ref());
+        filter[LinkOverviewColumns::Author] = self::filter(query.author.as_ref());
+        Self {
+            filter,
+            order: Some(Operation {
+                column: query.order.clone().unwrap_or(LinkOverviewColumns::Code),
+                value: if query.descending {
+                    Ordering::Descending
+                } else {
+                    Ordering::Ascending
+                },
+            }),
+            offset: query.offset.unwrap_or_default(),
+            amount: query.amount.unwrap_or(100).clamp(1, MAX_AMOUNT),
+        }
+    }
+}
+
+/// The query parameters of `GET /api/v1/users`, only administrators see other users than themselves.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq, Eq)]
+pub struct UserListQuery {
+    pub username: Option<String>,
+    pub email: Option<String>,
+    /// At most [`MAX_AMOUNT`] users, 100 if not given.
+    pub amount: Option<usize>,
+}
+
+impl From<&UserListQuery> for UserRequestForm {
+    fn from(query: &UserListQuery) -> Self {
+        let mut filter = EnumMap::default();
+        filter[UserOverviewColumns::Username] = self::filter(query.username.as_ref());
+        filter[UserOverviewColumns::Email] = self::filter(query.email.as_ref());
+        Self {
+            filter,
+            order: Some(Operation {
+                column: UserOverviewColumns::Id,
+                value: Ordering::Ascending,
+            }),
+            amount: query.amount.unwrap_or(100).clamp(1, MAX_AMOUNT),
+        }
+    }
+}
+
+/// The query parameters of `GET /api/v1/stats/top-links`.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq, Eq)]
+pub struct TopLinksQuery {
+    #[serde(default)]
+    pub period: StatisticsPeriod,
+    /// At most 100 links, 10 if not given.
+    pub amount: Option<i64>,
+}
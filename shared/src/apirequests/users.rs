@@ -3,7 +3,7 @@
 use enum_map::{Enum, EnumMap};
 use serde::{Deserialize, Serialize};
 
-use crate::datatypes::User;
+use crate::datatypes::{Lang, User};
 
 use super::general::{EditMode, Filter, Operation, Ordering};
 
@@ -33,11 +33,65 @@ impl Default for UserRequestForm {
 pub struct LoginUser {
     pub username: String,
     pub password: String,
+    /// The one-time code of the authenticator app or a recovery code if two-factor authentication is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub totp: Option<String>,
+}
+
+/// The answer to a login with username and password.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub enum LoginResult {
+    /// The user is logged in.
+    LoggedIn(User),
+    /// The password is correct, the login is finished with a one-time code.
+    TotpRequired,
+    /// The password is correct, but two-factor authentication has to be set up before the login is finished.
+    TotpEnrollmentRequired,
+}
+
+/// A new authenticator secret that is enabled once a code generated with it is confirmed.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct TotpEnrollment {
+    /// The base32 secret for apps that cannot scan the qr-code.
+    pub secret: String,
+    /// The `otpauth://` uri that is shown as qr-code.
+    pub uri: String,
+}
+
+/// The two-factor authentication settings of the logged in user.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct TotpStatus {
+    pub enabled: bool,
+    /// The number of unused recovery codes.
+    pub recovery_codes_left: usize,
+    /// Whether administrators have to use two-factor authentication.
+    pub required_for_admins: bool,
+}
+
+/// What the public invitation page shows before the account is created.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct InvitationInfo {
+    /// The email address the form is pre-filled with.
+    pub email: Option<String>,
+    pub role: Role,
+    pub expires_at: chrono::NaiveDateTime,
+}
+
+/// The account a new user creates with an invitation.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AcceptInvitation {
+    /// The token of the invitation url.
+    pub token: String,
+    pub username: String,
+    pub email: String,
+    pub password: String,
+    pub language: Lang,
 }
 
 /// The Struct that is responsible for creating and editing users.
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct UserDelta {
+    #[serde(default)]
     pub edit: EditMode,
     pub id: Option<i64>,
     pub username: String,
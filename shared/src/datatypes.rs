@@ -120,6 +120,107 @@ pub struct Statistics {
     pub values: Vec<WeekCount>,
 }
 
+/// The number of links created in one month
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MonthCount {
+    pub month: NaiveDate,
+    pub total: Count,
+}
+
+/// A link together with the number of clicks in a period
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LinkClicks {
+    pub link: Link,
+    pub clicks: Count,
+}
+
+/// The activity of an author in a period
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AuthorActivity {
+    pub user_id: i64,
+    pub username: String,
+    pub links_created: Count,
+    pub clicks: Count,
+}
+
+/// A link that was not clicked for a long time and might be removed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StaleLink {
+    pub link: Link,
+    pub author: String,
+    pub last_click: Option<chrono::NaiveDateTime>,
+}
+
+/// The number of clicks on one day
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DayCount {
+    pub date: NaiveDate,
+    pub total: Count,
+}
+
+/// A number of clicks sharing the same property e.g. the same referrer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct NamedCount {
+    pub name: String,
+    pub total: Count,
+}
+
+/// The kind of device a click came from, it is guessed from the user agent.
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, EnumIter, EnumString, AsRefStr,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum DeviceClass {
+    Desktop,
+    Mobile,
+    Tablet,
+    Bot,
+    Unknown,
+}
+
+impl DeviceClass {
+    /// Guess the device class from a user agent string.
+    #[must_use]
+    pub fn from_user_agent(user_agent: &str) -> Self {
+        let agent = user_agent.to_lowercase();
+        if [
+            "bot", "crawler", "spider", "curl", "wget", "python", "preview",
+        ]
+        .iter()
+        .any(|pattern| agent.contains(pattern))
+        {
+            Self::Bot
+        } else if agent.contains("ipad")
+            || agent.contains("tablet")
+            || (agent.contains("android") && !agent.contains("mobi"))
+        {
+            Self::Tablet
+        } else if ["mobi", "iphone", "android"]
+            .iter()
+            .any(|pattern| agent.contains(pattern))
+        {
+            Self::Mobile
+        } else if agent.is_empty() {
+            Self::Unknown
+        } else {
+            Self::Desktop
+        }
+    }
+}
+
+/// Detailed statistics of a single link
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LinkDetails {
+    pub link: FullLink,
+    /// The clicks per day during the last year
+    pub daily: Vec<DayCount>,
+    /// The hosts of the most common referrers, clicks without a referrer are named `""`
+    pub referrers: Vec<NamedCount>,
+    pub devices: Vec<(DeviceClass, Count)>,
+    /// The clicks by day of week (0 = sunday) and hour of the day (UTC)
+    pub heatmap: Vec<Vec<i64>>,
+}
+
 /// Every time a short url is clicked record it for statistical evaluation.
 #[derive(Serialize, Debug)]
 pub struct Click {
@@ -128,6 +229,23 @@ pub struct Click {
     pub created_at: chrono::NaiveDateTime,
 }
 
+/// Counters of the running server, they are reset on every restart.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ServerMetrics {
+    /// Clicks that were written to the database
+    pub clicks_recorded: u64,
+    /// Clicks that were not recorded because the click queue was full
+    pub clicks_dropped: u64,
+    /// Clicks that were lost because writing them to the database failed
+    pub clicks_failed: u64,
+    /// The number of transactions used to write the clicks
+    pub click_batches: u64,
+    /// Clicks currently waiting in the queue
+    pub click_queue_length: usize,
+    /// The maximum number of clicks that can wait in the queue
+    pub click_queue_capacity: usize,
+}
+
 /// The Password: Display, Debug and serialize do not include the Password to prevent leaks of sensible information in logs or similar.
 #[derive(PartialEq, Clone, Deserialize)]
 #[serde(from = "String")]
@@ -232,3 +350,169 @@ pub struct Item<T> {
     pub user: User,
     pub item: T,
 }
+
+/// What a personal API token may do.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiTokenScope {
+    /// Only read links, users and statistics.
+    Read,
+    /// Everything the owner may do, except managing API tokens.
+    Write,
+}
+
+impl ApiTokenScope {
+    /// The name that is stored in the database.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+        }
+    }
+
+    /// Parse the name stored in the database, unknown names only allow reading.
+    #[must_use]
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "write" => Self::Write,
+            _ => Self::Read,
+        }
+    }
+}
+
+/// A personal token for scripted access, it acts as its owner with the given scope.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ApiToken {
+    pub id: i64,
+    pub owner: i64,
+    pub owner_name: String,
+    pub name: String,
+    pub scope: ApiTokenScope,
+    /// The first characters of the token to tell the tokens apart, the token itself is only stored hashed.
+    pub hint: String,
+    pub created_at: chrono::NaiveDateTime,
+    /// After this time the token is no longer valid, `None` if it never expires.
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+}
+
+/// A newly created API token, the `secret` is only shown this once.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct NewApiToken {
+    pub token: ApiToken,
+    /// The value of the `Authorization: Bearer` header.
+    pub secret: String,
+}
+
+/// A token that grants read access to the statistics of a single link without an account.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShareToken {
+    pub id: i64,
+    pub link: i64,
+    /// The signed token as it is used in the public url.
+    pub token: String,
+    pub created_at: chrono::NaiveDateTime,
+    /// After this time the token is no longer valid, `None` if it never expires.
+    pub expires_at: Option<chrono::NaiveDateTime>,
+}
+
+impl ShareToken {
+    /// The path of the public statistics page of this token.
+    #[must_use]
+    pub fn path(&self) -> String {
+        format!("/stats/{}", self.token)
+    }
+}
+
+/// A single-use link that lets a new user create an account.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Invitation {
+    pub id: i64,
+    /// The username of the administrator that created the invitation.
+    pub created_by: String,
+    /// The first characters of the token to tell the invitations apart, the token itself is only stored hashed.
+    pub hint: String,
+    /// The email address the form is pre-filled with.
+    pub email: Option<String>,
+    /// The role of the new account.
+    pub role: Role,
+    pub created_at: chrono::NaiveDateTime,
+    pub expires_at: chrono::NaiveDateTime,
+}
+
+impl Invitation {
+    /// Whether the invitation can no longer be used.
+    #[must_use]
+    pub fn is_expired(&self, now: chrono::NaiveDateTime) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// A newly created invitation, the `url` is only shown this once.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct NewInvitation {
+    pub invitation: Invitation,
+    /// The public url of the page that accepts the invitation.
+    pub url: String,
+}
+
+/// What happened at a login, the events are kept as audit trail.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoginEventKind {
+    Success,
+    Failure,
+    /// A login was refused without checking the password because of earlier failures.
+    Blocked,
+    /// A username or address was locked after too many failures.
+    Lockout,
+    /// An administrator lifted a lockout.
+    Reset,
+}
+
+impl LoginEventKind {
+    /// The name that is stored in the database.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+            Self::Blocked => "blocked",
+            Self::Lockout => "lockout",
+            Self::Reset => "reset",
+        }
+    }
+
+    /// Parse the name stored in the database, unknown names are failures.
+    #[must_use]
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "success" => Self::Success,
+            "blocked" => Self::Blocked,
+            "lockout" => Self::Lockout,
+            "reset" => Self::Reset,
+            _ => Self::Failure,
+        }
+    }
+}
+
+/// An entry of the login audit trail.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LoginEvent {
+    pub id: i64,
+    pub created_at: chrono::NaiveDateTime,
+    pub kind: LoginEventKind,
+    /// The username as it was entered, it does not have to exist.
+    pub username: String,
+    pub ip: Option<String>,
+    pub detail: Option<String>,
+}
+
+/// A username or client address whose logins are refused after too many failures.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LoginLockout {
+    /// `true` if `subject` is a client address, otherwise it is a username.
+    pub by_ip: bool,
+    pub subject: String,
+    pub failures: i64,
+    pub locked_until: chrono::NaiveDateTime,
+}
@@ -2,6 +2,7 @@ use dioxus::prelude::{Asset, asset, manganis};
 
 pub mod apirequests;
 pub mod datatypes;
+pub mod qr;
 mod tests;
 
 pub const BULMA_CSS: Asset = asset!("/assets/styling/bulma.css");
@@ -0,0 +1,274 @@
+//! Render the qr-codes of the short links.
+//!
+//! The same code is used in the browser and on the server so both produce identical images.
+pub mod print;
+
+use base64::Engine as _;
+use fast_qr::{
+    ECL, QRBuilder, QRCode,
+    convert::{Builder, Shape, image::ImageBuilder, svg::SvgBuilder},
+};
+use serde::{Deserialize, Serialize};
+use strum_macros::{AsRefStr, EnumIter, EnumString};
+
+use crate::apirequests::links::QrCodeFormat;
+
+/// The default size of a module (one "pixel" of the qr-code) in png images.
+pub const PIXELS_PER_MODULE: u32 = 8;
+/// The smallest allowed width of a png or svg image in pixels.
+pub const MIN_IMAGE_SIZE: u32 = 64;
+/// The largest allowed width of a png or svg image in pixels.
+pub const MAX_IMAGE_SIZE: u32 = 4096;
+/// The largest allowed quiet zone around the code in modules.
+pub const MAX_MARGIN: usize = 20;
+/// The largest allowed size of a module in pixels.
+pub const MAX_PIXELS_PER_MODULE: u32 = 64;
+
+/// The logo of the instance that can be placed in the center of the qr-code, it is the favicon of the
+/// web interface so replacing the favicon also changes the logo in the qr-codes.
+const LOGO_PNG: &[u8] = include_bytes!("../../../web/assets/favicon.png");
+
+/// How much of the qr-code may be damaged or covered while it is still readable.
+#[derive(
+    Clone,
+    Copy,
+    Deserialize,
+    Serialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    EnumString,
+    AsRefStr,
+)]
+pub enum QrErrorCorrection {
+    /// about 7%
+    #[default]
+    L,
+    /// about 15%
+    M,
+    /// about 25%
+    Q,
+    /// about 30%
+    H,
+}
+
+impl From<QrErrorCorrection> for ECL {
+    fn from(ecl: QrErrorCorrection) -> Self {
+        match ecl {
+            QrErrorCorrection::L => Self::L,
+            QrErrorCorrection::M => Self::M,
+            QrErrorCorrection::Q => Self::Q,
+            QrErrorCorrection::H => Self::H,
+        }
+    }
+}
+
+/// The shape of the single modules of the qr-code.
+#[derive(
+    Clone,
+    Copy,
+    Deserialize,
+    Serialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    EnumString,
+    AsRefStr,
+)]
+pub enum QrShape {
+    #[default]
+    Square,
+    RoundedSquare,
+    Circle,
+    Diamond,
+    Vertical,
+    Horizontal,
+}
+
+impl From<QrShape> for Shape {
+    fn from(shape: QrShape) -> Self {
+        match shape {
+            QrShape::Square => Self::Square,
+            QrShape::RoundedSquare => Self::RoundedSquare,
+            QrShape::Circle => Self::Circle,
+            QrShape::Diamond => Self::Diamond,
+            QrShape::Vertical => Self::Vertical,
+            QrShape::Horizontal => Self::Horizontal,
+        }
+    }
+}
+
+/// The appearance of the qr-code of a link.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct QrStyle {
+    pub error_correction: QrErrorCorrection,
+    pub shape: QrShape,
+    /// The color of the modules as `#rrggbb`
+    pub foreground: String,
+    /// The color of the background as `#rrggbb`
+    pub background: String,
+    /// The quiet zone around the code in modules
+    pub margin: usize,
+    /// The size of a module in png images
+    pub pixels_per_module: u32,
+    /// Place the logo of the instance in the center, this needs the highest error correction.
+    pub logo: bool,
+}
+
+impl Default for QrStyle {
+    fn default() -> Self {
+        Self {
+            error_correction: QrErrorCorrection::L,
+            shape: QrShape::Square,
+            foreground: "#000000".to_string(),
+            background: "#ffffff".to_string(),
+            margin: 4,
+            pixels_per_module: PIXELS_PER_MODULE,
+            logo: false,
+        }
+    }
+}
+
+impl QrStyle {
+    /// The error correction that is actually used, a logo covers parts of the code so it forces [`QrErrorCorrection::H`].
+    #[must_use]
+    pub fn effective_error_correction(&self) -> QrErrorCorrection {
+        if self.logo {
+            QrErrorCorrection::H
+        } else {
+            self.error_correction
+        }
+    }
+
+    /// Limit the values to sensible ranges and replace invalid colors by the defaults.
+    #[must_use]
+    pub fn sanitized(mut self) -> Self {
+        let default = Self::default();
+        if parse_color(&self.foreground).is_none() {
+            self.foreground = default.foreground;
+        }
+        if parse_color(&self.background).is_none() {
+            self.background = default.background;
+        }
+        self.margin = self.margin.min(MAX_MARGIN);
+        self.pixels_per_module = self.pixels_per_module.clamp(1, MAX_PIXELS_PER_MODULE);
+        self
+    }
+}
+
+/// Parse a color in the format `#rrggbb`.
+#[must_use]
+pub fn parse_color(color: &str) -> Option<[u8; 4]> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?, 255])
+}
+
+fn build(url: &str, style: &QrStyle) -> Option<QRCode> {
+    QRBuilder::new(url)
+        .ecl(style.effective_error_correction().into())
+        .build()
+        .ok()
+}
+
+fn configure<B: Builder>(builder: &mut B, style: &QrStyle) {
+    let default = QrStyle::default();
+    builder
+        .shape(style.shape.into())
+        .margin(style.margin.min(MAX_MARGIN))
+        .module_color(
+            parse_color(&style.foreground)
+                .or_else(|| parse_color(&default.foreground))
+                .unwrap_or([0, 0, 0, 255]),
+        )
+        .background_color(
+            parse_color(&style.background)
+                .or_else(|| parse_color(&default.background))
+                .unwrap_or([255, 255, 255, 255]),
+        );
+    if style.logo {
+        let logo = base64::engine::general_purpose::STANDARD.encode(LOGO_PNG);
+        builder.image(format!("data:image/png;base64,{logo}"));
+    }
+}
+
+/// Generate a svg qr-code for a url. If `size` is given the width and height of the image are set,
+/// it is limited to the range of png images.
+#[must_use]
+pub fn svg_from_url(url: &str, style: &QrStyle, size: Option<u32>) -> Option<String> {
+    let qrcode = build(url, style)?;
+    let mut builder = SvgBuilder::default();
+    configure(&mut builder, style);
+    let svg = builder.to_str(&qrcode);
+    let size = size.map(|size| size.clamp(MIN_IMAGE_SIZE, MAX_IMAGE_SIZE));
+    Some(match size {
+        Some(size) => svg.replacen(
+            "<svg ",
+            &format!("<svg width=\"{size}\" height=\"{size}\" "),
+            1,
+        ),
+        None => svg,
+    })
+}
+
+/// Generate a png qr-code for a url.
+///
+/// The image is `size` pixels wide or if no size is given the modules are `pixels_per_module` pixels of the style wide.
+#[must_use]
+pub fn png_from_url(url: &str, style: &QrStyle, size: Option<u32>) -> Option<Vec<u8>> {
+    let qrcode = build(url, style)?;
+    let modules = u32::try_from(qrcode.size + 2 * style.margin.min(MAX_MARGIN)).ok()?;
+    let size = size
+        .unwrap_or(modules * style.pixels_per_module.clamp(1, MAX_PIXELS_PER_MODULE))
+        .clamp(MIN_IMAGE_SIZE, MAX_IMAGE_SIZE);
+    let mut builder = ImageBuilder::default();
+    configure(&mut builder, style);
+    builder
+        .fit_height(size)
+        .fit_width(size)
+        .to_bytes(&qrcode)
+        .ok()
+}
+
+/// Options of a download that do not change the qr-code itself.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct QrRenderOptions {
+    /// The width in pixels for png images or in points for pdf and eps files.
+    pub size: Option<u32>,
+    /// The resolution that is stored in png images.
+    pub dpi: Option<u32>,
+    /// Print the title and the short url below the code, only used for pdf and eps files.
+    pub label: bool,
+}
+
+/// Render the qr-code of a link in any of the available formats.
+#[must_use]
+pub fn render(
+    url: &str,
+    title: &str,
+    style: &QrStyle,
+    format: QrCodeFormat,
+    options: &QrRenderOptions,
+) -> Option<Vec<u8>> {
+    let label = options.label.then_some((title, url));
+    match format {
+        QrCodeFormat::Svg => svg_from_url(url, style, options.size).map(String::into_bytes),
+        QrCodeFormat::Png => png_from_url(url, style, options.size).map(|png| match options.dpi {
+            Some(dpi) => print::set_png_dpi(png, dpi),
+            None => png,
+        }),
+        QrCodeFormat::Pdf => Some(print::pdf(&build(url, style)?, style, options.size, label)),
+        QrCodeFormat::Eps => {
+            Some(print::eps(&build(url, style)?, style, options.size, label).into_bytes())
+        }
+    }
+}
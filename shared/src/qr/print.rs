@@ -0,0 +1,792 @@
+//! Vector versions of the qr-codes for print shops.
+//!
+//! The pdf and eps files are written by hand: they contain the modules in the shape of the style, the
+//! logo if the style has one and text in the builtin Helvetica font, so no font has to be embedded.
+use std::fmt::Write as _;
+use std::sync::OnceLock;
+
+use fast_qr::QRCode;
+
+use super::{LOGO_PNG, MAX_MARGIN, QrShape, QrStyle, build, parse_color};
+use crate::apirequests::links::QrSheetQuery;
+
+/// The default size of a module in points (1/72 inch), about one millimeter.
+pub const POINTS_PER_MODULE: f64 = 3.0;
+/// The smallest allowed width of a vector qr-code in points.
+pub const MIN_VECTOR_SIZE: u32 = 36;
+/// The largest allowed width of a vector qr-code in points.
+pub const MAX_VECTOR_SIZE: u32 = 2000;
+/// The smallest allowed resolution of a png image.
+pub const MIN_DPI: u32 = 72;
+/// The largest allowed resolution of a png image.
+pub const MAX_DPI: u32 = 2400;
+
+/// A filled rectangle in points with the origin in the lower left corner like in pdf and postscript.
+struct Rect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// One step of the outline of a module.
+enum PathOp {
+    Move(f64, f64),
+    Line(f64, f64),
+    /// A cubic bezier curve with two control points and the end point.
+    Curve([f64; 6]),
+    Close,
+}
+
+/// A dark module, rectangles are kept apart because they are much shorter in both formats.
+enum Module {
+    Rect(Rect),
+    Path(Vec<PathOp>),
+}
+
+/// The decoded logo, the pixels are stored row by row from the top.
+struct Logo {
+    width: u32,
+    height: u32,
+    rgb: Vec<u8>,
+    alpha: Vec<u8>,
+}
+
+/// The logo of the instance, `None` if it cannot be decoded.
+fn logo() -> Option<&'static Logo> {
+    static LOGO: OnceLock<Option<Logo>> = OnceLock::new();
+    LOGO.get_or_init(|| {
+        let mut decoder = png::Decoder::new(LOGO_PNG);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().ok()?;
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).ok()?;
+        pixels.truncate(info.buffer_size());
+        let channels = info.color_type.samples();
+        let mut rgb = Vec::with_capacity(pixels.len() / channels * 3);
+        let mut alpha = Vec::with_capacity(pixels.len() / channels);
+        for pixel in pixels.chunks_exact(channels) {
+            match pixel {
+                [gray] => rgb.extend_from_slice(&[*gray; 3]),
+                [gray, a] => {
+                    rgb.extend_from_slice(&[*gray; 3]);
+                    alpha.push(*a);
+                }
+                [r, g, b] => rgb.extend_from_slice(&[*r, *g, *b]),
+                [r, g, b, a] => {
+                    rgb.extend_from_slice(&[*r, *g, *b]);
+                    alpha.push(*a);
+                }
+                _ => return None,
+            }
+        }
+        Some(Logo {
+            width: info.width,
+            height: info.height,
+            rgb,
+            alpha,
+        })
+    })
+    .as_ref()
+}
+
+/// Bytes as hex digits for the `ASCIIHexDecode` filter, ended by `>`.
+fn hex_data(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2 + bytes.len() / 32 + 1);
+    for line in bytes.chunks(32) {
+        for byte in line {
+            let _ = write!(hex, "{byte:02x}");
+        }
+        hex.push('\n');
+    }
+    hex.push('>');
+    hex
+}
+
+/// The approximate distance of the control points of a bezier curve that draws a quarter circle.
+const KAPPA: f64 = 0.552_284_75;
+
+/// A square of `size` points with corners of `radius` points, a circle if the radius is half the size.
+fn rounded_square(x: f64, y: f64, size: f64, radius: f64) -> Vec<PathOp> {
+    let k = radius * KAPPA;
+    let (left, bottom, right, top) = (x, y, x + size, y + size);
+    vec![
+        PathOp::Move(left + radius, bottom),
+        PathOp::Line(right - radius, bottom),
+        PathOp::Curve([
+            right - radius + k,
+            bottom,
+            right,
+            bottom + radius - k,
+            right,
+            bottom + radius,
+        ]),
+        PathOp::Line(right, top - radius),
+        PathOp::Curve([
+            right,
+            top - radius + k,
+            right - radius + k,
+            top,
+            right - radius,
+            top,
+        ]),
+        PathOp::Line(left + radius, top),
+        PathOp::Curve([
+            left + radius - k,
+            top,
+            left,
+            top - radius + k,
+            left,
+            top - radius,
+        ]),
+        PathOp::Line(left, bottom + radius),
+        PathOp::Curve([
+            left,
+            bottom + radius - k,
+            left + radius - k,
+            bottom,
+            left + radius,
+            bottom,
+        ]),
+        PathOp::Close,
+    ]
+}
+
+/// `count` neighbouring dark modules of a row in the shape of the style, `x` and `y` are the lower left
+/// corner of the first module.
+fn row_modules(shape: QrShape, x: f64, y: f64, module: f64, count: usize) -> Vec<Module> {
+    let width = count as f64 * module;
+    match shape {
+        // neighbouring squares and horizontal bars look like one long rectangle
+        QrShape::Square => vec![Module::Rect(Rect {
+            x,
+            y,
+            width,
+            height: module,
+        })],
+        QrShape::Horizontal => vec![Module::Rect(Rect {
+            x,
+            y: y + 0.1 * module,
+            width,
+            height: 0.8 * module,
+        })],
+        _ => (0..count)
+            .map(|i| {
+                let x = x + i as f64 * module;
+                match shape {
+                    QrShape::Vertical => Module::Rect(Rect {
+                        x: x + 0.1 * module,
+                        y,
+                        width: 0.8 * module,
+                        height: module,
+                    }),
+                    QrShape::Circle => Module::Path(rounded_square(x, y, module, module / 2.0)),
+                    QrShape::Diamond => Module::Path(vec![
+                        PathOp::Move(x + module / 2.0, y),
+                        PathOp::Line(x + module, y + module / 2.0),
+                        PathOp::Line(x + module / 2.0, y + module),
+                        PathOp::Line(x, y + module / 2.0),
+                        PathOp::Close,
+                    ]),
+                    _ => Module::Path(rounded_square(x, y, module, module / 4.0)),
+                }
+            })
+            .collect(),
+    }
+}
+
+/// A single line of text, `x` and `y` are the start of the baseline.
+struct Text {
+    x: f64,
+    y: f64,
+    size: f64,
+    text: String,
+}
+
+/// Everything that is drawn for one qr-code.
+pub(crate) struct Drawing {
+    foreground: [f64; 3],
+    background: [f64; 3],
+    area: Rect,
+    modules: Vec<Module>,
+    /// The cleared square in the center and the logo inside of it.
+    logo: Option<(Rect, Rect)>,
+    texts: Vec<Text>,
+}
+
+fn rgb(color: &str, fallback: [u8; 4]) -> [f64; 3] {
+    let [r, g, b, _] = parse_color(color).unwrap_or(fallback);
+    [r, g, b].map(|c| f64::from(c) / 255.0)
+}
+
+/// The font size of the label of a qr-code that is `width` points wide.
+fn label_font_size(width: f64) -> f64 {
+    (width / 16.0).clamp(6.0, 14.0)
+}
+
+/// The height that the label below a qr-code of `width` points needs.
+pub(crate) fn label_height(width: f64) -> f64 {
+    label_font_size(width) * 3.2
+}
+
+/// Shorten a text so it fits into `width` points. Helvetica is narrower than 0.55 em on average.
+fn fit_text(text: &str, width: f64, size: f64) -> String {
+    let max_chars = (width / (size * 0.55)).floor().max(4.0) as usize;
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let mut short: String = text.chars().take(max_chars - 3).collect();
+        short.push_str("...");
+        short
+    }
+}
+
+/// Lay out a qr-code with its lower left corner at `x`, `y` and `width` points wide.
+///
+/// If a label (title and short url) is given it is placed below the code and `y` is the lower left
+/// corner of the label.
+pub(crate) fn layout(
+    qrcode: &QRCode,
+    style: &QrStyle,
+    x: f64,
+    y: f64,
+    width: f64,
+    label: Option<(&str, &str)>,
+) -> Drawing {
+    let margin = style.margin.min(MAX_MARGIN);
+    let modules = qrcode.size + 2 * margin;
+    let module = width / modules as f64;
+    let label_space = if label.is_some() {
+        label_height(width)
+    } else {
+        0.0
+    };
+    let top = y + label_space + width;
+
+    let mut modules = Vec::new();
+    for row in 0..qrcode.size {
+        let mut column = 0;
+        while column < qrcode.size {
+            if !qrcode.data[row * qrcode.size + column].value() {
+                column += 1;
+                continue;
+            }
+            // the dark modules of a row are handled together to keep the files small
+            let start = column;
+            while column < qrcode.size && qrcode.data[row * qrcode.size + column].value() {
+                column += 1;
+            }
+            modules.extend(row_modules(
+                style.shape,
+                x + (start + margin) as f64 * module,
+                top - (row + margin + 1) as f64 * module,
+                module,
+                column - start,
+            ));
+        }
+    }
+
+    // the logo covers a quarter of the width of the code and a module around it is cleared
+    let logo = (style.logo && logo().is_some()).then(|| {
+        let code = qrcode.size as f64 * module;
+        let side = code / 4.0;
+        let center_x = x + width / 2.0;
+        let center_y = top - width / 2.0;
+        let image = Rect {
+            x: center_x - side / 2.0,
+            y: center_y - side / 2.0,
+            width: side,
+            height: side,
+        };
+        let cleared = Rect {
+            x: image.x - module,
+            y: image.y - module,
+            width: side + 2.0 * module,
+            height: side + 2.0 * module,
+        };
+        (cleared, image)
+    });
+
+    let mut texts = Vec::new();
+    if let Some((title, url)) = label {
+        let size = label_font_size(width);
+        let indent = (margin as f64 * module).max(size / 2.0);
+        let line_width = width - 2.0 * indent;
+        texts.push(Text {
+            x: x + indent,
+            y: y + size * 1.8,
+            size,
+            text: fit_text(title, line_width, size),
+        });
+        texts.push(Text {
+            x: x + indent,
+            y: y + size * 0.6,
+            size: size * 0.9,
+            text: fit_text(url, line_width, size * 0.9),
+        });
+    }
+
+    Drawing {
+        foreground: rgb(&style.foreground, [0, 0, 0, 255]),
+        background: rgb(&style.background, [255, 255, 255, 255]),
+        area: Rect {
+            x,
+            y,
+            width,
+            height: width + label_space,
+        },
+        modules,
+        logo,
+        texts,
+    }
+}
+
+/// Encode a text as a string literal of pdf and postscript, characters outside of latin-1 become `?`.
+fn string_literal(text: &str) -> String {
+    let mut literal = String::from("(");
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                literal.push('\\');
+                literal.push(c);
+            }
+            ' '..='~' => literal.push(c),
+            '\u{a0}'..='\u{ff}' => {
+                let _ = write!(literal, "\\{:03o}", u32::from(c));
+            }
+            _ => literal.push('?'),
+        }
+    }
+    literal.push(')');
+    literal
+}
+
+fn color(color: [f64; 3]) -> String {
+    format!("{:.3} {:.3} {:.3}", color[0], color[1], color[2])
+}
+
+fn rect(rect: &Rect) -> String {
+    format!(
+        "{:.2} {:.2} {:.2} {:.2}",
+        rect.x, rect.y, rect.width, rect.height
+    )
+}
+
+impl Drawing {
+    /// The drawing as pdf content stream operators, the font `/F1` has to be Helvetica.
+    pub(crate) fn pdf_operations(&self, out: &mut String) {
+        let _ = writeln!(
+            out,
+            "{} rg\n{} re f",
+            color(self.background),
+            rect(&self.area)
+        );
+        let _ = writeln!(out, "{} rg", color(self.foreground));
+        for module in &self.modules {
+            match module {
+                Module::Rect(module) => {
+                    let _ = writeln!(out, "{} re", rect(module));
+                }
+                Module::Path(path) => {
+                    for op in path {
+                        let _ = match op {
+                            PathOp::Move(x, y) => writeln!(out, "{x:.2} {y:.2} m"),
+                            PathOp::Line(x, y) => writeln!(out, "{x:.2} {y:.2} l"),
+                            PathOp::Curve([x1, y1, x2, y2, x3, y3]) => {
+                                writeln!(out, "{x1:.2} {y1:.2} {x2:.2} {y2:.2} {x3:.2} {y3:.2} c")
+                            }
+                            PathOp::Close => writeln!(out, "h"),
+                        };
+                    }
+                }
+            }
+        }
+        out.push_str("f\n");
+        if let Some((cleared, image)) = &self.logo {
+            let _ = writeln!(
+                out,
+                "{} rg\n{} re f\nq {:.2} 0 0 {:.2} {:.2} {:.2} cm /Logo Do Q",
+                color(self.background),
+                rect(cleared),
+                image.width,
+                image.height,
+                image.x,
+                image.y
+            );
+        }
+        for text in &self.texts {
+            let _ = writeln!(
+                out,
+                "BT /F1 {:.2} Tf {:.2} {:.2} Td {} Tj ET",
+                text.size,
+                text.x,
+                text.y,
+                string_literal(&text.text)
+            );
+        }
+    }
+
+    /// The drawing as postscript operators, the font `/Helvetica-Latin1` has to be defined.
+    fn postscript_operations(&self, out: &mut String) {
+        let _ = writeln!(
+            out,
+            "{} setrgbcolor\n{} rectfill",
+            color(self.background),
+            rect(&self.area)
+        );
+        let _ = writeln!(out, "{} setrgbcolor", color(self.foreground));
+        let mut paths = String::new();
+        for module in &self.modules {
+            match module {
+                Module::Rect(module) => {
+                    let _ = writeln!(out, "{} rectfill", rect(module));
+                }
+                Module::Path(path) => {
+                    for op in path {
+                        let _ = match op {
+                            PathOp::Move(x, y) => writeln!(paths, "{x:.2} {y:.2} moveto"),
+                            PathOp::Line(x, y) => writeln!(paths, "{x:.2} {y:.2} lineto"),
+                            PathOp::Curve([x1, y1, x2, y2, x3, y3]) => writeln!(
+                                paths,
+                                "{x1:.2} {y1:.2} {x2:.2} {y2:.2} {x3:.2} {y3:.2} curveto"
+                            ),
+                            PathOp::Close => writeln!(paths, "closepath"),
+                        };
+                    }
+                }
+            }
+        }
+        if !paths.is_empty() {
+            let _ = write!(out, "newpath\n{paths}fill\n");
+        }
+        if let (Some((cleared, image)), Some(logo)) = (&self.logo, logo()) {
+            // postscript has no transparency, the logo is blended with the background instead
+            let blended: Vec<u8> = logo
+                .rgb
+                .chunks_exact(3)
+                .enumerate()
+                .flat_map(|(i, pixel)| {
+                    let alpha = logo.alpha.get(i).map_or(1.0, |a| f64::from(*a) / 255.0);
+                    [0, 1, 2].map(|c| {
+                        let value = f64::from(pixel[c]) / 255.0 * alpha
+                            + self.background[c] * (1.0 - alpha);
+                        (value * 255.0).round() as u8
+                    })
+                })
+                .collect();
+            let _ = writeln!(
+                out,
+                "{} setrgbcolor\n{} rectfill\ngsave {:.2} {:.2} translate {:.2} {:.2} scale\n{} {} 8 [{} 0 0 -{} 0 {}] currentfile /ASCIIHexDecode filter false 3 colorimage\n{}\ngrestore",
+                color(self.background),
+                rect(cleared),
+                image.x,
+                image.y,
+                image.width,
+                image.height,
+                logo.width,
+                logo.height,
+                logo.width,
+                logo.height,
+                logo.height,
+                hex_data(&blended)
+            );
+        }
+        for text in &self.texts {
+            let _ = writeln!(
+                out,
+                "/Helvetica-Latin1 findfont {:.2} scalefont setfont {:.2} {:.2} moveto {} show",
+                text.size,
+                text.x,
+                text.y,
+                string_literal(&text.text)
+            );
+        }
+    }
+}
+
+/// A page of a pdf document, the sizes are in points.
+pub(crate) struct Page {
+    pub width: f64,
+    pub height: f64,
+    pub content: String,
+    /// Whether the content draws the logo as `/Logo`.
+    pub logo: bool,
+}
+
+/// Write a pdf document with one page per entry.
+pub(crate) fn pdf_document(pages: &[Page]) -> Vec<u8> {
+    let mut pdf: Vec<u8> = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::new();
+    let mut object = |pdf: &mut Vec<u8>, body: &[u8]| {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", offsets.len()).as_bytes());
+        pdf.extend_from_slice(body);
+        pdf.extend_from_slice(b"\nendobj\n");
+    };
+
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", 4 + 2 * i))
+        .collect();
+    object(&mut pdf, b"<< /Type /Catalog /Pages 2 0 R >>");
+    object(
+        &mut pdf,
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .as_bytes(),
+    );
+    object(
+        &mut pdf,
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>",
+    );
+    // the logo and its transparency follow the pages
+    let logo_object = 4 + 2 * pages.len();
+    let logo = logo().filter(|_| pages.iter().any(|page| page.logo));
+    for (i, page) in pages.iter().enumerate() {
+        let images = if page.logo && logo.is_some() {
+            format!(" /XObject << /Logo {logo_object} 0 R >>")
+        } else {
+            String::new()
+        };
+        object(
+            &mut pdf,
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << /F1 3 0 R >>{} >> /Contents {} 0 R >>",
+                page.width,
+                page.height,
+                images,
+                5 + 2 * i
+            )
+            .as_bytes(),
+        );
+        object(
+            &mut pdf,
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                page.content.len(),
+                page.content
+            )
+            .as_bytes(),
+        );
+    }
+
+    if let Some(logo) = logo {
+        let rgb = hex_data(&logo.rgb);
+        let mask = if logo.alpha.is_empty() {
+            String::new()
+        } else {
+            format!(" /SMask {} 0 R", logo_object + 1)
+        };
+        object(
+            &mut pdf,
+            format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8{} /Filter /ASCIIHexDecode /Length {} >>\nstream\n{}\nendstream",
+                logo.width,
+                logo.height,
+                mask,
+                rgb.len(),
+                rgb
+            )
+            .as_bytes(),
+        );
+        if !logo.alpha.is_empty() {
+            let alpha = hex_data(&logo.alpha);
+            object(
+                &mut pdf,
+                format!(
+                    "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /ASCIIHexDecode /Length {} >>\nstream\n{}\nendstream",
+                    logo.width,
+                    logo.height,
+                    alpha.len(),
+                    alpha
+                )
+                .as_bytes(),
+            );
+        }
+    }
+
+    let xref = pdf.len();
+    let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+    for offset in &offsets {
+        let _ = write!(trailer, "{offset:010} 00000 n \n");
+    }
+    let _ = write!(
+        trailer,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        offsets.len() + 1,
+        xref
+    );
+    pdf.extend_from_slice(trailer.as_bytes());
+    pdf
+}
+
+/// The width of a single vector qr-code in points.
+fn vector_width(qrcode: &QRCode, style: &QrStyle, size: Option<u32>) -> f64 {
+    match size {
+        Some(size) => f64::from(size.clamp(MIN_VECTOR_SIZE, MAX_VECTOR_SIZE)),
+        None => (qrcode.size + 2 * style.margin.min(MAX_MARGIN)) as f64 * POINTS_PER_MODULE,
+    }
+}
+
+/// A pdf with a single page that is exactly as large as the qr-code and its label.
+pub(crate) fn pdf(
+    qrcode: &QRCode,
+    style: &QrStyle,
+    size: Option<u32>,
+    label: Option<(&str, &str)>,
+) -> Vec<u8> {
+    let width = vector_width(qrcode, style, size);
+    let drawing = layout(qrcode, style, 0.0, 0.0, width, label);
+    let mut content = String::new();
+    drawing.pdf_operations(&mut content);
+    pdf_document(&[Page {
+        width,
+        height: drawing.area.height,
+        content,
+        logo: drawing.logo.is_some(),
+    }])
+}
+
+/// An encapsulated postscript file of the qr-code and its label.
+pub(crate) fn eps(
+    qrcode: &QRCode,
+    style: &QrStyle,
+    size: Option<u32>,
+    label: Option<(&str, &str)>,
+) -> String {
+    let width = vector_width(qrcode, style, size);
+    let drawing = layout(qrcode, style, 0.0, 0.0, width, label);
+    let mut eps = format!(
+        "%!PS-Adobe-3.0 EPSF-3.0\n%%BoundingBox: 0 0 {} {}\n%%HiResBoundingBox: 0 0 {:.2} {:.2}\n%%EndComments\n",
+        width.ceil(),
+        drawing.area.height.ceil(),
+        width,
+        drawing.area.height
+    );
+    if !drawing.texts.is_empty() {
+        // the standard encoding of postscript has no umlauts
+        eps.push_str(
+            "/Helvetica findfont dup length dict begin\n{1 index /FID ne {def} {pop pop} ifelse} forall\n/Encoding ISOLatin1Encoding def\ncurrentdict end /Helvetica-Latin1 exch definefont pop\n",
+        );
+    }
+    drawing.postscript_operations(&mut eps);
+    eps.push_str("showpage\n%%EOF\n");
+    eps
+}
+
+/// One qr-code of a printable sheet.
+pub struct QrSheetEntry {
+    pub url: String,
+    pub title: String,
+    pub style: QrStyle,
+}
+
+/// A pdf with the labeled qr-codes of several links in a grid, one cell per link.
+///
+/// Light gray lines between the cells help cutting the sheet into stickers.
+#[must_use]
+pub fn pdf_sheet(entries: &[QrSheetEntry], query: &QrSheetQuery) -> Option<Vec<u8>> {
+    const POINTS_PER_MM: f64 = 72.0 / 25.4;
+    let (page_width, page_height) = query.paper.dimensions();
+    let rows = query.rows.clamp(1, QrSheetQuery::MAX_CELLS);
+    let columns = query.columns.clamp(1, QrSheetQuery::MAX_CELLS);
+    let margin = f64::from(query.margin.min(QrSheetQuery::MAX_MARGIN)) * POINTS_PER_MM;
+    let cell_width = (page_width - 2.0 * margin) / f64::from(columns);
+    let cell_height = (page_height - 2.0 * margin) / f64::from(rows);
+    let padding = cell_width.min(cell_height) * 0.05;
+
+    // the largest code that fits into a cell together with its label
+    let mut width = (cell_width - 2.0 * padding).min(cell_height - 2.0 * padding);
+    while width > 1.0 && width + label_height(width) > cell_height - 2.0 * padding {
+        width -= 1.0;
+    }
+
+    let per_page = (rows * columns) as usize;
+    let mut pages = Vec::new();
+    for chunk in entries.chunks(per_page) {
+        let mut content = String::from("0.85 g 0.3 w\n");
+        let mut logo = false;
+        for i in 1..columns {
+            let x = margin + f64::from(i) * cell_width;
+            let _ = writeln!(
+                content,
+                "{x:.2} {margin:.2} m {x:.2} {:.2} l S",
+                page_height - margin
+            );
+        }
+        for i in 1..rows {
+            let y = margin + f64::from(i) * cell_height;
+            let _ = writeln!(
+                content,
+                "{margin:.2} {y:.2} m {:.2} {y:.2} l S",
+                page_width - margin
+            );
+        }
+        for (i, entry) in chunk.iter().enumerate() {
+            let row = (i / columns as usize) as f64;
+            let column = (i % columns as usize) as f64;
+            let qrcode = build(&entry.url, &entry.style)?;
+            let height = width + label_height(width);
+            let drawing = layout(
+                &qrcode,
+                &entry.style,
+                margin + column * cell_width + (cell_width - width) / 2.0,
+                page_height - margin - (row + 1.0) * cell_height + (cell_height - height) / 2.0,
+                width,
+                Some((&entry.title, &entry.url)),
+            );
+            drawing.pdf_operations(&mut content);
+            logo |= drawing.logo.is_some();
+        }
+        pages.push(Page {
+            width: page_width,
+            height: page_height,
+            content,
+            logo,
+        });
+    }
+    Some(pdf_document(&pages))
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Store the resolution in a png image (`pHYs` chunk) so print shops print it in the intended size.
+///
+/// Images that are not valid png files are returned unchanged.
+#[must_use]
+pub fn set_png_dpi(png: Vec<u8>, dpi: u32) -> Vec<u8> {
+    // signature (8 bytes) and the IHDR chunk (25 bytes) always come first
+    const HEADER_END: usize = 33;
+    if png.len() < HEADER_END || png.get(12..16) != Some(b"IHDR".as_slice()) {
+        return png;
+    }
+    let pixels_per_meter = (f64::from(dpi.clamp(MIN_DPI, MAX_DPI)) / 0.0254).round() as u32;
+    let mut chunk = b"pHYs".to_vec();
+    chunk.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    chunk.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    // the unit is meter
+    chunk.push(1);
+
+    let mut result = Vec::with_capacity(png.len() + 21);
+    result.extend_from_slice(&png[..HEADER_END]);
+    result.extend_from_slice(&9_u32.to_be_bytes());
+    result.extend_from_slice(&chunk);
+    result.extend_from_slice(&crc32(&chunk).to_be_bytes());
+    result.extend_from_slice(&png[HEADER_END..]);
+    result
+}
@@ -1,5 +1,5 @@
 #![cfg(test)]
-use crate::datatypes::{Secret, User};
+use crate::datatypes::{DeviceClass, Secret, User};
 
 #[test]
 fn test_type_is_send() {
@@ -23,3 +23,252 @@ fn test_send_with_thread() {
         drop(value);
     });
 }
+
+#[test]
+fn test_device_class_from_user_agent() {
+    let iphone = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+    let android_tablet = "Mozilla/5.0 (Linux; Android 13; SM-X200) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/116.0 Safari/537.36";
+    let firefox = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+    assert_eq!(DeviceClass::from_user_agent(iphone), DeviceClass::Mobile);
+    assert_eq!(
+        DeviceClass::from_user_agent(android_tablet),
+        DeviceClass::Tablet
+    );
+    assert_eq!(DeviceClass::from_user_agent(firefox), DeviceClass::Desktop);
+    assert_eq!(DeviceClass::from_user_agent("curl/8.5.0"), DeviceClass::Bot);
+    assert_eq!(DeviceClass::from_user_agent(""), DeviceClass::Unknown);
+}
+
+#[test]
+fn test_click_export_query_url() {
+    use crate::apirequests::links::{ClickAggregation, ClickExportFormat, ClickExportQuery};
+
+    let query = ClickExportQuery {
+        links: "3,5".to_string(),
+        format: ClickExportFormat::Json,
+        aggregation: ClickAggregation::Week,
+        from: chrono::NaiveDate::from_ymd_opt(2024, 1, 1),
+        to: None,
+    };
+    assert_eq!(query.link_ids(), vec![3, 5]);
+    assert_eq!(
+        query.url(),
+        "/export/clicks?format=json&aggregation=week&links=3,5&from=2024-01-01"
+    );
+    let all_links = ClickExportQuery {
+        links: " 7,,x".to_string(),
+        ..ClickExportQuery::default()
+    };
+    assert_eq!(all_links.link_ids(), vec![7]);
+    assert_eq!(
+        ClickExportQuery::default().url(),
+        "/export/clicks?format=csv&aggregation=click"
+    );
+}
+
+#[test]
+fn test_qr_style() {
+    use crate::qr::{QrErrorCorrection, QrStyle, parse_color};
+
+    assert_eq!(parse_color("#ff8000"), Some([255, 128, 0, 255]));
+    assert_eq!(parse_color("ff8000"), None);
+    assert_eq!(parse_color("#ff80"), None);
+
+    let style = QrStyle {
+        error_correction: QrErrorCorrection::M,
+        logo: true,
+        foreground: "red".to_string(),
+        margin: 1000,
+        ..QrStyle::default()
+    };
+    assert_eq!(style.effective_error_correction(), QrErrorCorrection::H);
+    let style = style.sanitized();
+    assert_eq!(style.foreground, "#000000");
+    assert_eq!(style.margin, crate::qr::MAX_MARGIN);
+
+    // svg sizes are limited like png sizes
+    let svg =
+        crate::qr::svg_from_url("https://example.com", &QrStyle::default(), Some(100_000)).unwrap();
+    assert!(svg.contains("width=\"4096\" height=\"4096\""));
+}
+
+#[test]
+fn test_qr_print_formats() {
+    use crate::apirequests::links::QrCodeFormat;
+    use crate::qr::{QrRenderOptions, QrStyle, render};
+
+    let options = QrRenderOptions {
+        dpi: Some(300),
+        label: true,
+        ..QrRenderOptions::default()
+    };
+    let style = QrStyle::default();
+    let url = "https://example.com/test";
+
+    let pdf = render(url, "Tür (test)", &style, QrCodeFormat::Pdf, &options).unwrap();
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.starts_with("%PDF-1.4"));
+    assert!(text.contains("(T\\374r \\(test\\)) Tj"));
+    // the cross reference table points to the objects
+    let startxref: usize = text
+        .rsplit("startxref\n")
+        .next()
+        .and_then(|rest| rest.lines().next())
+        .and_then(|offset| offset.parse().ok())
+        .unwrap();
+    assert!(pdf[startxref..].starts_with(b"xref"));
+
+    let eps = render(url, "test", &style, QrCodeFormat::Eps, &options).unwrap();
+    assert!(eps.starts_with(b"%!PS-Adobe-3.0 EPSF-3.0"));
+
+    let png = render(url, "test", &style, QrCodeFormat::Png, &options).unwrap();
+    assert_eq!(&png[37..41], b"pHYs");
+    // 300 dpi are 11811 pixels per meter
+    assert_eq!(&png[41..45], &11811_u32.to_be_bytes());
+}
+
+#[test]
+fn test_qr_print_style() {
+    use crate::apirequests::links::QrCodeFormat;
+    use crate::qr::{QrRenderOptions, QrShape, QrStyle, render};
+
+    let options = QrRenderOptions::default();
+    let url = "https://example.com/test";
+
+    let plain = render(url, "", &QrStyle::default(), QrCodeFormat::Pdf, &options).unwrap();
+    let plain = String::from_utf8_lossy(&plain);
+    assert!(plain.contains(" re\n"));
+    assert!(!plain.contains(" c\n"));
+    assert!(!plain.contains("/Logo"));
+
+    let style = QrStyle {
+        shape: QrShape::Circle,
+        logo: true,
+        ..QrStyle::default()
+    };
+    let pdf = render(url, "", &style, QrCodeFormat::Pdf, &options).unwrap();
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains(" c\n"));
+    assert!(text.contains("/XObject << /Logo 6 0 R >>"));
+    assert!(text.contains("/Logo Do"));
+    assert!(text.contains("/SMask 7 0 R"));
+    let startxref: usize = text
+        .rsplit("startxref\n")
+        .next()
+        .and_then(|rest| rest.lines().next())
+        .and_then(|offset| offset.parse().ok())
+        .unwrap();
+    assert!(pdf[startxref..].starts_with(b"xref"));
+
+    let eps = render(url, "", &style, QrCodeFormat::Eps, &options).unwrap();
+    let eps = String::from_utf8_lossy(&eps);
+    assert!(eps.contains(" curveto\n"));
+    assert!(eps.contains("false 3 colorimage"));
+
+    let diamond = QrStyle {
+        shape: QrShape::Diamond,
+        ..QrStyle::default()
+    };
+    let eps = render(url, "", &diamond, QrCodeFormat::Eps, &options).unwrap();
+    let eps = String::from_utf8_lossy(&eps);
+    assert!(eps.contains(" lineto\n"));
+    assert!(!eps.contains("colorimage"));
+}
+
+#[test]
+fn test_qr_sheet_pages() {
+    use crate::apirequests::links::{PaperSize, QrSheetQuery};
+    use crate::qr::{
+        QrStyle,
+        print::{QrSheetEntry, pdf_sheet},
+    };
+
+    let query = QrSheetQuery {
+        links: "1,2,x,3".to_string(),
+        paper: PaperSize::Letter,
+        rows: 1,
+        columns: 2,
+        margin: 10,
+    };
+    assert_eq!(query.link_ids(), vec![1, 2, 3]);
+    let entries: Vec<QrSheetEntry> = query
+        .link_ids()
+        .iter()
+        .map(|id| QrSheetEntry {
+            url: format!("https://example.com/{id}"),
+            title: format!("Link {id}"),
+            style: QrStyle::default(),
+        })
+        .collect();
+    let pdf = pdf_sheet(&entries, &query).unwrap();
+    let pdf = String::from_utf8_lossy(&pdf);
+    assert!(pdf.contains("/Count 2"));
+    assert!(pdf.contains("/MediaBox [0 0 612.00 792.00]"));
+}
+
+#[test]
+fn test_data_export_validation() {
+    use crate::apirequests::data_export::{
+        DataExport, ExportedClick, ExportedLink, ExportedShareToken, ExportedUser,
+    };
+    use crate::apirequests::users::Role;
+
+    let created_at = chrono::NaiveDate::from_ymd_opt(2024, 5, 1)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap();
+    let mut export = DataExport {
+        version: DataExport::VERSION,
+        exported_at: created_at,
+        users: vec![ExportedUser {
+            id: 1,
+            username: "admin".to_string(),
+            email: "admin@example.com".to_string(),
+            role: Role::Admin,
+            language: "en-US".to_string(),
+            password: None,
+            oidc_subject: None,
+            ldap_dn: None,
+            totp: None,
+        }],
+        links: vec![ExportedLink {
+            id: 7,
+            title: "Example".to_string(),
+            target: "https://example.com".to_string(),
+            code: "example".to_string(),
+            author: 1,
+            created_at,
+            qr_style: None,
+        }],
+        tags: Vec::new(),
+        clicks: vec![ExportedClick {
+            link: 7,
+            created_at,
+            referrer: None,
+            device: None,
+        }],
+        api_tokens: Vec::new(),
+        share_tokens: vec![ExportedShareToken {
+            link: 7,
+            nonce: "nonce".to_string(),
+            created_at,
+            expires_at: None,
+        }],
+        invitations: Vec::new(),
+        settings: std::collections::BTreeMap::new(),
+    };
+    assert!(export.validate().is_ok());
+    let json = serde_json::to_string(&export).unwrap();
+    assert!(!json.contains("password"));
+    assert!(!json.contains("totp"));
+    assert_eq!(serde_json::from_str::<DataExport>(&json).unwrap(), export);
+
+    export.clicks[0].link = 8;
+    assert!(export.validate().is_err());
+    export.clicks[0].link = 7;
+    export.share_tokens[0].link = 8;
+    assert!(export.validate().is_err());
+    export.share_tokens[0].link = 7;
+    export.version = DataExport::VERSION + 1;
+    assert!(export.validate().is_err());
+}
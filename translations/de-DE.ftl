@@ -1,28 +1,251 @@
 # Generated translation template by dioxus-i18n-collect
-# Contains 83 translation keys
+# Contains 290 translation keys
 
-# Source: ./ui/src/users/database_export.rs:81
+# Button to create a new API token
+# Source: ./ui/src/users/api_tokens.rs:157
+api-tokens-button-create = Token erstellen
+
+# Button to revoke an API token
+# Source: ./ui/src/users/api_tokens.rs:88
+api-tokens-button-revoke = Widerrufen
+
+# Message after a token was created, it is only shown once
+# Source: ./ui/src/users/api_tokens.rs:32
+# Parameters: $name
+api-tokens-created = Der Token { $name } wurde erstellt. Kopiere ihn jetzt, er wird nicht noch einmal angezeigt:
+
+# Explanation how the API tokens are used
+# Source: ./ui/src/users/api_tokens.rs:26
+api-tokens-description = Skripte und CI-Jobs können in deinem Namen handeln, indem sie einen Token im Header Authorization: Bearer <token> senden. Lese-Tokens dürfen nur Links, Benutzer und Statistiken lesen.
+
+# Column of the date a token expires
+# Source: ./ui/src/users/api_tokens.rs:46
+api-tokens-expires = Läuft ab
+
+# Column of the last time a token was used
+# Source: ./ui/src/users/api_tokens.rs:47
+api-tokens-last-used = Zuletzt benutzt
+
+# Column of the name of a token
+# Source: ./ui/src/users/api_tokens.rs:44
+api-tokens-name = Name
+
+# Displayed if a token never expires or was never used
+# Source: ./ui/src/users/api_tokens.rs:70
+api-tokens-never = nie
+
+# Column of the user a token belongs to
+# Source: ./ui/src/users/api_tokens.rs:42
+api-tokens-owner = Benutzer
+
+# Placeholder of the number of days a token is valid
+# Source: ./ui/src/users/api_tokens.rs:135
+api-tokens-placeholder-days = Gültig für Tage (leer = unbegrenzt)
+
+# Placeholder of the name of a new token
+# Source: ./ui/src/users/api_tokens.rs:108
+api-tokens-placeholder-name = Name des Tokens
+
+# Column of what a token may do
+# Source: ./ui/src/users/api_tokens.rs:45
+api-tokens-scope = Berechtigung
+
+# Scope of a token that may only read
+# Source: ./ui/src/users/api_tokens.rs:63
+api-tokens-scope-read = Lesen
+
+# Scope of a token that may change links and users
+# Source: ./ui/src/users/api_tokens.rs:64
+api-tokens-scope-write = Lesen und schreiben
+
+# Headline of the personal API tokens
+# Source: ./ui/src/users/api_tokens.rs:25
+api-tokens-title = API-Tokens
+
+# Button to clear the selection of links
+# Source: ./ui/src/links/bulk_actions.rs:65
+bulk-actions-button-clear = Auswahl aufheben
+
+# Button to confirm deleting the selected links
+# Source: ./ui/src/links/bulk_actions.rs:104
+# Parameters: $count
+bulk-actions-button-confirm-delete = Wirklich { $count } Links löschen?
+
+# Button to delete the selected links, it asks for a confirmation
+# Source: ./ui/src/links/bulk_actions.rs:111
+bulk-actions-button-delete = Ausgewählte löschen
+
+# Button to download the selected links as CSV
+# Source: ./ui/src/links/bulk_actions.rs:97
+bulk-actions-button-export = Als CSV exportieren
+
+# Button to select all links matching the filter, not only the loaded ones
+# Source: ./ui/src/links/bulk_actions.rs:59
+bulk-actions-button-select-matching = Alle passenden Links auswählen
+
+# Button to make the entered user the author of the selected links
+# Source: ./ui/src/links/bulk_actions.rs:85
+bulk-actions-button-transfer = Übertragen
+
+# Message after the selected links were deleted or transferred
+# Source: ./ui/src/links/bulk_actions.rs:33
+# Parameters: $count
+bulk-actions-done = { $count } Links wurden geändert.
+
+# Label of the username the selected links are transferred to
+# Source: ./ui/src/links/bulk_actions.rs:70
+bulk-actions-label-author = Neuer Autor
+
+# Label with the number of selected links
+# Source: ./ui/src/links/bulk_actions.rs:55
+# Parameters: $count
+bulk-actions-selected = { $count } Links ausgewählt
+
+# Export every single click
+# Source: ./ui/src/links/click_export.rs:17
+click-export-aggregation-click = Jeder Klick
+
+# Export the clicks per day
+# Source: ./ui/src/links/click_export.rs:18
+click-export-aggregation-day = Pro Tag
+
+# Export the clicks per month
+# Source: ./ui/src/links/click_export.rs:20
+click-export-aggregation-month = Pro Monat
+
+# Export the clicks per week
+# Source: ./ui/src/links/click_export.rs:19
+click-export-aggregation-week = Pro Woche
+
+# Button to export the clicks of all own links
+# Source: ./ui/src/links/click_export.rs:102
+click-export-button-all = Klicks aller meiner Links exportieren
+
+# Label of the grouping of the exported clicks
+# Source: ./ui/src/links/click_export.rs:56
+click-export-label-aggregation = Gruppierung
+
+# Label of the file format of the click export
+# Source: ./ui/src/links/click_export.rs:37
+click-export-label-format = Exportformat
+
+# Label of the first day of the click export
+# Source: ./ui/src/links/click_export.rs:75
+click-export-label-from = Von
+
+# Label of the last day of the click export
+# Source: ./ui/src/links/click_export.rs:86
+click-export-label-to = Bis
+
+# Tooltip of the button to export the clicks of one link
+# Source: ./ui/src/links/link_display.rs:146
+click-export-tooltip-link = Klicks dieses Links exportieren
+
+# Headline of the chart with all clicks per week
+# Source: ./ui/src/dashboard.rs:54
+dashboard-clicks-title = Klicks pro Woche auf alle Links
+
+# Source: ./ui/src/dashboard.rs:136
+dashboard-header-clicks = Klicks
+
+# Source: ./ui/src/dashboard.rs:201
+dashboard-header-created = Erstellt
+
+# Source: ./ui/src/dashboard.rs:202
+dashboard-header-last-click = Letzter Klick
+
+# Source: ./ui/src/dashboard.rs:160
+dashboard-header-links-created = Erstellte Links
+
+# Label of the selection how many entries are shown
+# Source: ./ui/src/dashboard.rs:112
+dashboard-label-amount = Einträge
+
+# Label of the period selection
+# Source: ./ui/src/dashboard.rs:93
+dashboard-label-period = Zeitraum
+
+# Label of the number of months without clicks
+# Source: ./ui/src/dashboard.rs:181
+dashboard-label-stale-months = Monate ohne Klicks
+
+# Headline of the chart with the links created per month
+# Source: ./ui/src/dashboard.rs:73
+dashboard-links-per-month-title = Erstellte Links pro Monat
+
+# Source: ./ui/src/dashboard.rs:216
+dashboard-never-clicked = nie
+
+# Source: ./ui/src/dashboard.rs:25
+dashboard-period-all = Gesamter Zeitraum
+
+# Source: ./ui/src/dashboard.rs:22
+dashboard-period-month = Letzte 30 Tage
+
+# Source: ./ui/src/dashboard.rs:23
+dashboard-period-quarter = Letzte 3 Monate
+
+# Source: ./ui/src/dashboard.rs:21
+dashboard-period-week = Letzte 7 Tage
+
+# Source: ./ui/src/dashboard.rs:24
+dashboard-period-year = Letzte 12 Monate
+
+# Headline of the links without clicks
+# Source: ./ui/src/dashboard.rs:178
+# Parameters: $months
+dashboard-stale-links-title = Links ohne Klicks in den letzten {$months} Monaten
+
+# Headline of the most active authors
+# Source: ./ui/src/dashboard.rs:155
+dashboard-top-authors-title = Aktivste Autoren
+
+# Headline of the most clicked links
+# Source: ./ui/src/dashboard.rs:130
+dashboard-top-links-title = Meistgeklickte Links
+
+# Source: ./ui/src/users/database_export.rs:120
 database-export-button-clear = Löschen
 
-# Source: ./ui/src/users/database_export.rs:73
+# Source: ./ui/src/users/database_export.rs:112
 database-export-button-download = 📥 Datenbank herunterladen
 
-# Source: ./ui/src/users/database_export.rs:115
+# Button to save the JSON export
+# Source: ./ui/src/users/database_export.rs:135
+database-export-button-download-json = 📥 JSON herunterladen
+
+# Source: ./ui/src/users/database_export.rs:177
 database-export-button-export = Datenbank exportieren
 
-# Source: ./ui/src/users/database_export.rs:108
+# Button to export all data as versioned JSON
+# Source: ./ui/src/users/database_export.rs:183
+database-export-button-export-json = Als JSON exportieren
+
+# Source: ./ui/src/users/database_export.rs:170
 database-export-button-exporting = Exportiere...
 
-# Source: ./ui/src/users/database_export.rs:28
+# Source: ./ui/src/users/database_export.rs:39
 database-export-error-empty-secret = Bitte geben Sie das Export-Geheimnis ein
 
-# Source: ./ui/src/users/database_export.rs:42
+# Source: ./ui/src/users/database_export.rs:53
 database-export-error-failed = Export fehlgeschlagen
 
-# Source: ./ui/src/users/database_export.rs:99
+# Explanation of the JSON export and pslink restore
+# Source: ./ui/src/users/database_export.rs:196
+database-export-help-json = Der JSON-Export enthält Benutzer, Links und Klicks und kann mit `pslink restore` in eine neue Instanz geladen werden. Ohne Passwort-Hashes braucht jeder Benutzer ein neues Passwort.
+
+# Source: ./ui/src/users/database_export.rs:161
 database-export-help-secret = Geben Sie das in der Umgebungsvariable PSLINK_DATA_DOWNLOAD_SECRET konfigurierte Geheimnis ein
 
-# Source: ./ui/src/users/database_export.rs:88
+# Checkbox to include the password hashes in the JSON export
+# Source: ./ui/src/users/database_export.rs:194
+database-export-include-passwords = Passwort-Hashes, zweite Faktoren, API-Tokens und Einladungen einschließen
+
+# Message that the JSON export can be downloaded
+# Source: ./ui/src/users/database_export.rs:128
+database-export-json-ready = JSON-Export bereit!
+
+# Source: ./ui/src/users/database_export.rs:150
 database-export-label-secret = Export-Geheimnis
 
 # Placeholder for export secret input
@@ -30,152 +253,691 @@ database-export-label-secret = Export-Geheimnis
 # UNUSED: This key is no longer used in the code
 database-export-placeholder-secret = Export-Geheimnis eingeben
 
-# Source: ./ui/src/users/database_export.rs:66
+# Source: ./ui/src/users/database_export.rs:105
 database-export-ready = Datenbank-Export bereit!
 
-# Source: ./ui/src/users/database_export.rs:67
+# Source: ./ui/src/users/database_export.rs:106
 # Parameters: $size
 database-export-size = Größe: {$size} Bytes
 
-# Source: ./ui/src/users/database_export.rs:54
-database-export-subtitle = Exportiere die SQLite Datenbank für Backup-Zwecke
+# Source: ./ui/src/users/database_export.rs:93
+database-export-subtitle = Exportiere einen konsistenten Schnappschuss der SQLite Datenbank für Backup-Zwecke
 
-# Source: ./ui/src/users/database_export.rs:36
+# Source: ./ui/src/users/database_export.rs:47
 database-export-success-message = Datenbank erfolgreich exportiert! Klicken Sie auf den Download-Link unten.
 
-# Source: ./ui/src/users/database_export.rs:53
+# Source: ./ui/src/users/database_export.rs:92
 database-export-title = Datenbank Export
 
-# Source: ./ui/src/navbar.rs:160
+# Source: ./ui/src/navbar.rs:177
 demo-warning = Diese Instanz ist nicht für dauerhaftes Speichern konfiguriert... Die eingegebenen Daten werden nur bis zum Neustart gespeichert.
 
-# Source: ./ui/src/login.rs:112
+# First line of an email
+# Source: ./backend/src/mail.rs:107
+# Parameters: $username
+email-greeting = Hallo { $username },
+
+# Last paragraph of the password reset email
+# Source: ./backend/src/mail.rs:110
+email-reset-ignore = Wenn Sie das nicht angefordert haben, können Sie diese E-Mail ignorieren. Ihr Passwort bleibt unverändert.
+
+# Subject of the email with the password reset link
+# Source: ./backend/src/mail.rs:104
+email-reset-subject = Ihr pslink-Passwort zurücksetzen
+
+# Text of the password reset email before the link
+# Source: ./backend/src/mail.rs:108
+# Parameters: $minutes
+email-reset-text = jemand möchte das Passwort Ihres pslink-Kontos zurücksetzen. Öffnen Sie diesen Link innerhalb von { $minutes } Minuten, um ein neues Passwort zu wählen:
+
+# Subject of the email sent by pslink test-email
+# Source: ./backend/src/mail.rs:119
+email-test-subject = pslink Test-E-Mail
+
+# Text of the email sent by pslink test-email
+# Source: ./backend/src/mail.rs:120
+email-test-text = Diese E-Mail bestätigt, dass pslink mit dem eingestellten SMTP-Server E-Mails versenden kann.
+
+# Source: ./ui/src/login.rs:191
 # Parameters: $error
 failed-login = Benutzername oder passwort waren leider falsch, bitte versuche es erneut.
 
-# Source: ./ui/src/login.rs:40
+# Source: ./ui/src/login.rs:58
 headline-login = Anmelden
 
+# Button to create the account of the invitation
+# Source: ./ui/src/invitation.rs:195
+invitation-button-create = Konto erstellen
+
+# Explanation of the invitation page with the time the invitation expires
+# Source: ./ui/src/invitation.rs:62
+# Parameters: $expires
+invitation-description = Sie wurden zu pslink eingeladen. Wählen Sie Benutzername und Passwort, die Einladung ist bis { $expires } gültig.
+
+# Headline of the page that creates an account with an invitation
+# Source: ./ui/src/invitation.rs:45
+invitation-headline = Konto erstellen
+
+# Message when the invitation was used, revoked or expired
+# Source: ./ui/src/invitation.rs:146
+invitation-invalid = Diese Einladung kann nicht verwendet werden.
+
+# Label of the field that repeats the new password
+# Source: ./ui/src/invitation.rs:105
+invitation-label-password-repeat = Passwort wiederholen
+
+# Message when the repeated password differs
+# Source: ./ui/src/invitation.rs:169
+invitation-password-mismatch = Die Passwörter stimmen nicht überein.
+
+# Button to create a new invitation link
+# Source: ./ui/src/users/invitations.rs:148
+invitations-button-create = Einladung erstellen
+
+# Button to remove an expired invitation
+# Source: ./ui/src/users/invitations.rs:75
+invitations-button-delete = Löschen
+
+# Button to revoke a pending invitation
+# Source: ./ui/src/users/invitations.rs:77
+invitations-button-revoke = Widerrufen
+
+# Message after an invitation was created, the link is only shown once
+# Source: ./ui/src/users/invitations.rs:26
+invitations-created = Senden Sie diesen Link an den neuen Benutzer, er wird nur einmal angezeigt:
+
+# Column of the administrator that created the invitation
+# Source: ./ui/src/users/invitations.rs:38
+invitations-created-by = Erstellt von
+
+# Explanation how invitation links are used
+# Source: ./ui/src/users/invitations.rs:20
+invitations-description = Mit einem Einladungslink erstellt ein neuer Benutzer ein Konto mit eigenem Benutzernamen und Passwort. Jeder Link kann einmal verwendet werden.
+
+# Column of the pre-filled email address
+# Source: ./ui/src/users/invitations.rs:36
+invitations-email = E-Mail
+
+# Tag of an invitation that can no longer be used
+# Source: ./ui/src/users/invitations.rs:62
+invitations-expired = abgelaufen
+
+# Column of the time the invitation expires
+# Source: ./ui/src/users/invitations.rs:39
+invitations-expires = Läuft ab
+
+# Column of the start of the invitation link
+# Source: ./ui/src/users/invitations.rs:35
+invitations-link = Link
+
+# Placeholder of the number of days an invitation is valid
+# Source: ./ui/src/users/invitations.rs:127
+invitations-placeholder-days = Gültige Tage
+
+# Placeholder of the optional email address of an invitation
+# Source: ./ui/src/users/invitations.rs:98
+invitations-placeholder-email = E-Mail (optional)
+
+# Column of the role of the new account
+# Source: ./ui/src/users/invitations.rs:37
+invitations-role = Rolle
+
+# Headline of the invitation links for new users
+# Source: ./ui/src/users/invitations.rs:19
+invitations-title = Einladungen
+
 # The menu entry for language selection
-# Source: ./ui/src/navbar.rs:86
+# Source: ./ui/src/navbar.rs:103
 language = Sprachauswahl
 
+# Link back to the list of links
+# Source: ./ui/src/links/link_detail.rs:50
+link-detail-back = Zurück zur Linkliste
+
+# Headline of the chart with the clicks per day
+# Source: ./ui/src/links/link_detail.rs:140
+link-detail-daily-title = Klicks pro Tag
+
+# Name of the device class
+# Source: ./ui/src/links/link_detail.rs:37
+link-detail-device-bot = Bot
+
+# Name of the device class
+# Source: ./ui/src/links/link_detail.rs:34
+link-detail-device-desktop = Desktop
+
+# Name of the device class
+# Source: ./ui/src/links/link_detail.rs:35
+link-detail-device-mobile = Smartphone
+
+# Name of the device class
+# Source: ./ui/src/links/link_detail.rs:36
+link-detail-device-tablet = Tablet
+
+# Name of the device class
+# Source: ./ui/src/links/link_detail.rs:38
+link-detail-device-unknown = Unbekannt
+
+# Headline of the chart with the device classes
+# Source: ./ui/src/links/link_detail.rs:165
+link-detail-devices-title = Geräte
+
+# Tooltip of the qr code to download it as png
+# Source: ./ui/src/links/link_detail.rs:133
+link-detail-download-qr = QR-Code als PNG herunterladen
+
+# Headline of the heatmap of clicks by weekday and hour
+# Source: ./ui/src/links/link_detail.rs:177
+link-detail-heatmap-title = Klicks nach Wochentag und Uhrzeit (UTC)
+
+# Shown instead of a chart if the link was never clicked
+# Source: ./ui/src/links/link_detail.rs:151
+link-detail-no-clicks = Dieser Link wurde noch nicht angeklickt.
+
+# Name of the clicks without a referrer
+# Source: ./ui/src/links/link_detail.rs:157
+link-detail-referrer-direct = Direkt / unbekannt
+
+# Headline of the chart with the referring websites
+# Source: ./ui/src/links/link_detail.rs:149
+link-detail-referrers-title = Verweisende Seiten
+
+# Label of the complete short url
+# Source: ./ui/src/links/link_detail.rs:105
+link-detail-short-url = Kurzlink
+
+# Label of the number of all clicks of the link
+# Source: ./ui/src/links/link_detail.rs:121
+link-detail-total-clicks = Klicks insgesamt
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:27
+link-detail-weekday-friday = Fr
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:23
+link-detail-weekday-monday = Mo
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:28
+link-detail-weekday-saturday = Sa
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:22
+link-detail-weekday-sunday = So
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:26
+link-detail-weekday-thursday = Do
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:24
+link-detail-weekday-tuesday = Di
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:25
+link-detail-weekday-wednesday = Mi
+
 # Button text for confirming link deletion
-# Source: ./ui/src/links/link_edit.rs:253
+# Source: ./ui/src/links/link_edit.rs:280
 link-edit-button-confirm-delete = Diesen Link wirklich löschen
 
 # Button text for creating a new link
-# Source: ./ui/src/links/link_edit.rs:198
+# Source: ./ui/src/links/link_edit.rs:219
 link-edit-button-create = Link erstellen
 
 # Button text for deleting a link
-# Source: ./ui/src/links/link_edit.rs:209
+# Source: ./ui/src/links/link_edit.rs:230
 link-edit-button-delete = Link löschen
 
 # Button text for modifying a link
-# Source: ./ui/src/links/link_edit.rs:227
+# Source: ./ui/src/links/link_edit.rs:254
 link-edit-button-modify = Link verändern
 
 # Warning message about deleting links
-# Source: ./ui/src/links/link_edit.rs:151
+# Source: ./ui/src/links/link_edit.rs:166
 link-edit-delete-warning = Einen Link zu löschen ist in der Regel nicht empfehlenswert. Es sollten nur Links gelöscht werden, die nirgends veröffentlicht wurden, oder die absichtlich ins Leere führen sollen.
 
 # Label for link code field
-# Source: ./ui/src/links/link_edit.rs:96
+# Source: ./ui/src/links/link_edit.rs:107
 link-edit-field-code = Code
 
 # Label for description field
-# Source: ./ui/src/links/link_edit.rs:52
+# Source: ./ui/src/links/link_edit.rs:63
 link-edit-field-description = Beschreibung
 
 # Label for QR code field
-# Source: ./ui/src/links/link_edit.rs:116
+# Source: ./ui/src/links/link_edit.rs:127
 link-edit-field-qrcode = QR-Code
 
 # Label for link target field
-# Source: ./ui/src/links/link_edit.rs:76
+# Source: ./ui/src/links/link_edit.rs:87
 link-edit-field-target = Ziel der Weiterleitung
 
 # Title for the link editing modal
-# Source: ./ui/src/links/link_edit.rs:40
+# Source: ./ui/src/links/link_edit.rs:51
 link-edit-modal-title = Einen Link editieren
 
 # Placeholder for code input
-# Source: ./ui/src/links/link_edit.rs:102
+# Source: ./ui/src/links/link_edit.rs:113
 link-edit-placeholder-code = Code
 
 # Placeholder for description input
-# Source: ./ui/src/links/link_edit.rs:62
+# Source: ./ui/src/links/link_edit.rs:73
 link-edit-placeholder-description = Beschreibung
 
 # Placeholder for target input
-# Source: ./ui/src/links/link_edit.rs:82
+# Source: ./ui/src/links/link_edit.rs:93
 link-edit-placeholder-target = Ziel der Weiterleitung
 
+# The imported link is created
+# Source: ./ui/src/links/link_import.rs:29
+link-import-action-create = neu
+
+# The imported row has errors
+# Source: ./ui/src/links/link_import.rs:33
+link-import-action-invalid = ungültig
+
+# The imported link overwrites an existing link
+# Source: ./ui/src/links/link_import.rs:30
+link-import-action-overwrite = überschreiben
+
+# The imported link is created with another code
+# Source: ./ui/src/links/link_import.rs:31
+# Parameters: $code
+link-import-action-rename = neu als { $code }
+
+# The imported link is skipped
+# Source: ./ui/src/links/link_import.rs:32
+link-import-action-skip = übersprungen
+
+# Link back to the list of links
+# Source: ./ui/src/links/link_import.rs:69
+link-import-back = Zurück zu den Links
+
+# Button to import the checked links
+# Source: ./ui/src/links/link_import.rs:158
+link-import-button-apply = Importieren
+
+# Button to validate the import without changing anything
+# Source: ./ui/src/links/link_import.rs:151
+link-import-button-check = Prüfen
+
+# Explanation of the columns of the import file
+# Source: ./ui/src/links/link_import.rs:71
+link-import-description = Lade eine CSV-Datei mit den Spalten title, target und code (Administratoren optional author) hoch oder füge sie ein, alternativ ein JSON-Array von Objekten mit denselben Feldern. Der Import wird zuerst geprüft und nur ausgeführt, wenn alle Zeilen gültig sind.
+
+# Message that the import cannot be applied because of invalid rows
+# Source: ./ui/src/links/link_import.rs:170
+link-import-has-errors = Einige Zeilen sind ungültig, korrigiere sie und prüfe erneut.
+
+# Column header of what happens to the row
+# Source: ./ui/src/links/link_import.rs:179
+link-import-header-action = Ergebnis
+
+# Column header of the row number in the import report
+# Source: ./ui/src/links/link_import.rs:175
+link-import-header-row = Zeile
+
+# Label of the file upload of the link import
+# Source: ./ui/src/links/link_import.rs:74
+link-import-label-file = Datei
+
+# Label of the file format of the link import
+# Source: ./ui/src/links/link_import.rs:93
+link-import-label-format = Format
+
+# Label of what happens to links whose code exists
+# Source: ./ui/src/links/link_import.rs:113
+link-import-label-policy = Vorhandene Codes
+
+# Import policy that overwrites links whose code exists
+# Source: ./ui/src/links/link_import.rs:22
+link-import-policy-overwrite = Bestehende Links überschreiben
+
+# Import policy that renames links whose code exists
+# Source: ./ui/src/links/link_import.rs:23
+link-import-policy-rename = Mit neuem Code importieren
+
+# Import policy that skips links whose code exists
+# Source: ./ui/src/links/link_import.rs:21
+link-import-policy-skip = Bestehende Links behalten
+
+# Message after the links were imported
+# Source: ./ui/src/links/link_import.rs:167
+# Parameters: $count
+link-import-success = Der Import von { $count } Zeilen ist abgeschlossen.
+
+# Headline of the link import page
+# Source: ./ui/src/links/link_import.rs:70
+link-import-title = Links importieren
+
+# Button to open the import of many links
+# Source: ./ui/src/links/mod.rs:279
+links-button-import = Links importieren
+
 # Button text to load more links
-# Source: ./ui/src/links/mod.rs:385
+# Source: ./ui/src/links/mod.rs:462
 links-button-load-more = Mehr Links laden
 
-# Source: ./ui/src/links/link_display.rs:68
+# Source: ./ui/src/links/link_display.rs:77
 links-error-not-author = Sie können nur Links editieren, deren Autor Sie sind.
 
 # Text displayed while loading links data
-# Source: ./ui/src/links/mod.rs:391
+# Source: ./ui/src/links/mod.rs:468
 links-loading = Die Links werden geladen
 
 # Text for login link
-# Source: ./ui/src/links/mod.rs:392
+# Source: ./ui/src/links/mod.rs:469
 links-login = Anmelden
 
 # Placeholder text for filter input field
-# Source: ./ui/src/links/mod.rs:272
+# Source: ./ui/src/links/mod.rs:346
 links-table-filter-placeholder = Filtere die Links nach…
 
 # Column header for link code
-# Source: ./ui/src/links/mod.rs:233
+# Source: ./ui/src/links/mod.rs:305
 links-table-header-code = Code
 
 # Column header for description
-# Source: ./ui/src/links/mod.rs:240
+# Source: ./ui/src/links/mod.rs:312
 links-table-header-description = Beschreibung
 
-# Source: ./ui/src/links/mod.rs:261
+# Source: ./ui/src/links/mod.rs:333
 links-table-header-statistics = Statistik
 
 # Column header for link target
-# Source: ./ui/src/links/mod.rs:247
+# Source: ./ui/src/links/mod.rs:319
 links-table-header-target = Ziel des Kurzlinks
 
 # Column header for username
-# Source: ./ui/src/links/mod.rs:254
+# Source: ./ui/src/links/mod.rs:326
 links-table-header-username = Author
 
+# Tooltip of the statistics, a click opens the detail page
+# Source: ./ui/src/links/link_display.rs:123
+links-tooltip-details = Detaillierte Statistik anzeigen
+
+# Tooltip of the checkbox that selects all loaded links
+# Source: ./ui/src/links/mod.rs:288
+links-tooltip-select-all = Alle geladenen Links auswählen
+
 # The menu entry for login
-# Source: ./ui/src/navbar.rs:111
+# Source: ./ui/src/navbar.rs:128
 login = Anmelden
 
+# A login refused because of earlier failures
+# Source: ./ui/src/users/login_lockouts.rs:100
+login-events-blocked = Abgewiesen
+
+# Column of further details
+# Source: ./ui/src/users/login_lockouts.rs:89
+login-events-detail = Details
+
+# A login with wrong credentials
+# Source: ./ui/src/users/login_lockouts.rs:99
+login-events-failure = Fehlgeschlagen
+
+# Column of the client address
+# Source: ./ui/src/users/login_lockouts.rs:88
+login-events-ip = Adresse
+
+# Column of what happened
+# Source: ./ui/src/users/login_lockouts.rs:86
+login-events-kind = Ereignis
+
+# A username or address was locked
+# Source: ./ui/src/users/login_lockouts.rs:101
+login-events-lockout = Gesperrt
+
+# An administrator lifted a lockout
+# Source: ./ui/src/users/login_lockouts.rs:102
+login-events-reset = Entsperrt
+
+# A successful login
+# Source: ./ui/src/users/login_lockouts.rs:98
+login-events-success = Angemeldet
+
+# Column of the time of a login event
+# Source: ./ui/src/users/login_lockouts.rs:85
+login-events-time = Zeit
+
+# Headline of the recent logins and refused logins
+# Source: ./ui/src/users/login_lockouts.rs:79
+login-events-title = Letzte Anmeldungen
+
+# Column of the entered username
+# Source: ./ui/src/users/login_lockouts.rs:87
+login-events-username = Benutzername
+
+# Button to lift a lockout
+# Source: ./ui/src/users/login_lockouts.rs:64
+login-lockouts-button-reset = Entsperren
+
+# Column of the number of failed logins
+# Source: ./ui/src/users/login_lockouts.rs:29
+login-lockouts-failures = Fehlgeschlagene Anmeldungen
+
+# Text when nothing is locked
+# Source: ./ui/src/users/login_lockouts.rs:22
+login-lockouts-none = Kein Benutzername und keine Adresse ist gesperrt.
+
+# Column of the locked username or address
+# Source: ./ui/src/users/login_lockouts.rs:28
+login-lockouts-subject = Benutzername oder Adresse
+
+# Headline of the usernames and addresses locked after failed logins
+# Source: ./ui/src/users/login_lockouts.rs:16
+login-lockouts-title = Gesperrte Anmeldungen
+
+# Column of the time the lockout ends
+# Source: ./ui/src/users/login_lockouts.rs:30
+login-lockouts-until = Gesperrt bis
+
+# Button to log in with the identity provider of the organisation
+# Source: ./ui/src/login.rs:241
+login-single-sign-on = Mit Single Sign-on anmelden
+
 # The menu entry for logout
-# Source: ./ui/src/navbar.rs:106
+# Source: ./ui/src/navbar.rs:123
 logout = Abmelden
 
 # The title of the page
-# Source: ./ui/src/navbar.rs:137
+# Source: ./ui/src/navbar.rs:154
 page-not-found = 404 – Seite nicht gefunden
 
 # The text of the page
-# Source: ./ui/src/navbar.rs:138
+# Source: ./ui/src/navbar.rs:155
 page-not-found-text = Diese Seite konnte nicht gefunden werden.
 
-# Source: ./ui/src/login.rs:69
+# Source: ./ui/src/login.rs:148
 password = Passwort
 
+# Label of the field for the username or email address
+# Source: ./ui/src/login.rs:75
+password-reset-account = Benutzername oder E-Mail
+
+# Button to return from the password reset to the login
+# Source: ./ui/src/login.rs:207
+password-reset-button-back = Zurück zur Anmeldung
+
+# Button to save the new password
+# Source: ./ui/src/password_reset.rs:93
+password-reset-button-save = Passwort speichern
+
+# Button to send the link that resets the password
+# Source: ./ui/src/login.rs:224
+password-reset-button-send = Link senden
+
+# Explanation that a reset link is sent to the email address of the account
+# Source: ./ui/src/login.rs:72
+password-reset-description = Geben Sie Ihren Benutzernamen oder Ihre E-Mail-Adresse ein, wir senden Ihnen einen Link, um ein neues Passwort zu wählen.
+
+# Message after the new password was saved
+# Source: ./ui/src/password_reset.rs:29
+password-reset-done = Ihr neues Passwort ist gespeichert, Sie können sich jetzt damit anmelden.
+
+# Button on the login to reset a forgotten password
+# Source: ./ui/src/login.rs:236
+password-reset-forgot = Passwort vergessen?
+
+# Headline of the page that sets a new password
+# Source: ./ui/src/password_reset.rs:25
+password-reset-headline = Neues Passwort wählen
+
+# Label of the new password
+# Source: ./ui/src/password_reset.rs:32
+password-reset-label-password = Neues Passwort
+
+# Message after a reset link was requested, it does not tell whether the account exists
+# Source: ./ui/src/login.rs:95
+password-reset-sent = Falls das Konto existiert, ist eine E-Mail mit einem Link zum Zurücksetzen des Passworts unterwegs.
+
+# Highest error correction of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:46
+qr-designer-ecl-high = Hoch (30%)
+
+# Lowest error correction of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:43
+qr-designer-ecl-low = Niedrig (7%)
+
+# Medium error correction of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:44
+qr-designer-ecl-medium = Mittel (15%)
+
+# High error correction of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:45
+qr-designer-ecl-quartile = Quartil (25%)
+
+# Label of the background color of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:131
+qr-designer-label-background = Hintergrund
+
+# Label of the error correction of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:80
+qr-designer-label-error-correction = Fehlerkorrektur
+
+# Label of the color of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:119
+qr-designer-label-foreground = Farbe
+
+# Checkbox to place the logo in the center of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:184
+qr-designer-label-logo = Logo in der Mitte
+
+# Label of the quiet zone around the qr-code
+# Source: ./ui/src/links/qr_designer.rs:143
+qr-designer-label-margin = Ruhezone
+
+# Label of the size of a module of the png image
+# Source: ./ui/src/links/qr_designer.rs:158
+qr-designer-label-pixels = Pixel pro Modul
+
+# Label of the shape of the modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:100
+qr-designer-label-shape = Form
+
+# Explanation that the logo forces the highest error correction
+# Source: ./ui/src/links/qr_designer.rs:189
+qr-designer-logo-hint = Das Logo verdeckt einen Teil des Codes, daher wird die höchste Fehlerkorrektur verwendet.
+
+# Circular modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:54
+qr-designer-shape-circle = Kreise
+
+# Diamond shaped modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:55
+qr-designer-shape-diamond = Rauten
+
+# Horizontal modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:57
+qr-designer-shape-horizontal = Waagerechte Balken
+
+# Rounded modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:53
+qr-designer-shape-rounded = Abgerundete Quadrate
+
+# Square modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:52
+qr-designer-shape-square = Quadrate
+
+# Vertical modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:56
+qr-designer-shape-vertical = Senkrechte Balken
+
+# Button to download the qr-code
+# Source: ./ui/src/links/qr_designer.rs:279
+qr-download-button = Herunterladen
+
+# Label of the resolution of the png download
+# Source: ./ui/src/links/qr_designer.rs:250
+qr-download-label-dpi = Auflösung (dpi)
+
+# Label of the file format of the qr-code download
+# Source: ./ui/src/links/qr_designer.rs:230
+qr-download-label-format = Herunterladen als
+
+# Checkbox to print the title and short url below the qr-code
+# Source: ./ui/src/links/qr_designer.rs:272
+qr-download-label-label = Titel und Kurz-URL unter den Code drucken
+
+# Button to download the qr-code sheet of the selected links
+# Source: ./ui/src/links/qr_sheet.rs:94
+# Parameters: $count
+qr-sheet-button = QR-Codes von { $count } ausgewählten Links drucken
+
+# Label of the number of columns of the qr-code sheet
+# Source: ./ui/src/links/qr_sheet.rs:61
+qr-sheet-label-columns = Spalten
+
+# Label of the page margin of the qr-code sheet in millimeters
+# Source: ./ui/src/links/qr_sheet.rs:76
+qr-sheet-label-margin = Rand (mm)
+
+# Label of the paper size of the qr-code sheet
+# Source: ./ui/src/links/qr_sheet.rs:27
+qr-sheet-label-paper = Papier für QR-Bogen
+
+# Label of the number of rows of the qr-code sheet
+# Source: ./ui/src/links/qr_sheet.rs:46
+qr-sheet-label-rows = Zeilen
+
 # The requested route on the 404 page
-# Source: ./ui/src/navbar.rs:139
+# Source: ./ui/src/navbar.rs:156
 # Parameters: $route
 requested-route = Der Pfad, der gesucht wurde: {$route}
 
+# Button to create a new public statistics page
+# Source: ./ui/src/links/share_tokens.rs:91
+share-button-create = Öffentliche Seite erstellen
+
+# Button to revoke a public statistics page
+# Source: ./ui/src/links/share_tokens.rs:51
+share-button-revoke = Widerrufen
+
+# Explanation of the public statistics pages
+# Source: ./ui/src/links/share_tokens.rs:19
+share-description = Alle, die eine dieser Adressen kennen, können die Statistik dieses Links ohne Konto ansehen. Name und E-Mail-Adresse des Autors werden nicht angezeigt.
+
+# Expiry date of a public statistics page
+# Source: ./ui/src/links/share_tokens.rs:38
+# Parameters: $date
+share-expires = Läuft am {$date} ab
+
+# Shown if a public statistics page does not expire
+# Source: ./ui/src/links/share_tokens.rs:39
+share-never-expires = Läuft nie ab
+
+# Placeholder of the number of days a token is valid
+# Source: ./ui/src/links/share_tokens.rs:72
+share-placeholder-days = Gültig für Tage (leer: unbegrenzt)
+
+# Headline of the public statistics pages of a link
+# Source: ./ui/src/links/share_tokens.rs:18
+share-title = Öffentliche Statistikseiten
+
 # The menu entry for links
-# Source: ./ui/src/navbar.rs:69
+# Source: ./ui/src/navbar.rs:86
 short_urls = Kurzlinks
 
 # Displayed as a tooltip when there have been no clicks on this link in the last 12 months.
@@ -188,6 +950,75 @@ tooltip_no_clicks = Dieser Link wurde in den letzen 12 Monaten nicht geklickt. D
 # Parameters: $count
 total_clicks = Klicks insgesamt {$count}
 
+# Button to enable two-factor authentication with the entered code
+# Source: ./ui/src/users/two_factor.rs:68
+totp-button-confirm = Aktivieren
+
+# Button to turn off two-factor authentication
+# Source: ./ui/src/users/two_factor.rs:181
+totp-button-disable = Deaktivieren
+
+# Button after the recovery codes were saved
+# Source: ./ui/src/users/two_factor.rs:22
+totp-button-done = Ich habe die Wiederherstellungscodes gespeichert
+
+# Button to replace the recovery codes
+# Source: ./ui/src/users/two_factor.rs:163
+totp-button-recovery-codes = Neue Wiederherstellungscodes
+
+# Button to set up two-factor authentication
+# Source: ./ui/src/users/two_factor.rs:130
+totp-button-setup = Einrichten
+
+# Button to finish the login with the one-time code
+# Source: ./ui/src/login.rs:197
+totp-button-verify = Bestätigen
+
+# Label of the one-time code field of the login
+# Source: ./ui/src/login.rs:100
+totp-code = Code
+
+# Help that a recovery code can be entered instead of the one-time code
+# Source: ./ui/src/login.rs:117
+totp-code-help = Der Code Ihrer Authenticator-App oder ein Wiederherstellungscode.
+
+# Text when two-factor authentication is not set up
+# Source: ./ui/src/users/two_factor.rs:128
+totp-disabled = Schützen Sie Ihr Konto zusätzlich zum Passwort mit einem Code aus einer Authenticator-App.
+
+# Text when two-factor authentication is enabled with the number of unused recovery codes
+# Source: ./ui/src/users/two_factor.rs:135
+# Parameters: $left
+totp-enabled = Die Zwei-Faktor-Authentifizierung ist aktiv, es sind noch { $left } Wiederherstellungscodes übrig. Geben Sie einen Code ein, um die Wiederherstellungscodes zu ersetzen oder sie zu deaktivieren.
+
+# Placeholder of the one-time code
+# Source: ./ui/src/users/two_factor.rs:50
+totp-placeholder-code = Code
+
+# Explanation that every recovery code can be used once instead of a one-time code
+# Source: ./ui/src/users/two_factor.rs:88
+totp-recovery-codes = Bewahren Sie diese Wiederherstellungscodes sicher auf. Jeder kann einmal statt eines Codes verwendet werden, falls die Authenticator-App verloren geht. Sie werden nur jetzt angezeigt.
+
+# Checkbox to require two-factor authentication for all administrators
+# Source: ./ui/src/users/two_factor.rs:199
+totp-required-for-admins = Zwei-Faktor-Authentifizierung für alle Administratoren verlangen
+
+# How to scan the qr-code with an authenticator app
+# Source: ./ui/src/users/two_factor.rs:29
+totp-setup-description = Scannen Sie den QR-Code mit einer Authenticator-App und geben Sie den angezeigten Code ein.
+
+# Text when an administrator has to set up two-factor authentication before the login
+# Source: ./ui/src/login.rs:62
+totp-setup-required = Administratoren müssen die Zwei-Faktor-Authentifizierung nutzen. Richten Sie sie ein, um die Anmeldung abzuschließen.
+
+# Text before the secret for apps that cannot scan the qr-code
+# Source: ./ui/src/users/two_factor.rs:36
+totp-setup-secret = Oder geben Sie diesen Schlüssel ein:
+
+# Headline of the two-factor authentication settings
+# Source: ./ui/src/users/two_factor.rs:111
+totp-title = Zwei-Faktor-Authentifizierung
+
 # Button text for confirming user deletion
 # Source: ./ui/src/users/user_edit.rs:283
 user-edit-button-confirm-delete = Benutzer wirklich löschen
@@ -253,23 +1084,23 @@ user-edit-role-regular = Regulär
 # Parameters: $username
 user-edit-title = Benutzerdaten
 
-# Source: ./ui/src/login.rs:45
+# Source: ./ui/src/login.rs:84
 username = Benutzername
 
 # The menu entry for users
-# Source: ./ui/src/navbar.rs:72
+# Source: ./ui/src/navbar.rs:89
 users = Benutzer
 
 # Button text to load more users
-# Source: ./ui/src/users/mod.rs:313
+# Source: ./ui/src/users/mod.rs:324
 users-button-load-more = Mehr Benutzer laden…
 
 # Text displayed while loading user data
-# Source: ./ui/src/users/mod.rs:323
+# Source: ./ui/src/users/mod.rs:336
 users-loading = Lade Benutzer…
 
 # Text for login link
-# Source: ./ui/src/users/mod.rs:324
+# Source: ./ui/src/users/mod.rs:337
 users-login = Anmelden
 
 # Source: ./ui/src/users/user_display.rs:56
@@ -285,35 +1116,35 @@ users-role-disabled = Deaktiviert
 users-role-regular = Regulär
 
 # Placeholder text for filter input field
-# Source: ./ui/src/users/mod.rs:233
+# Source: ./ui/src/users/mod.rs:242
 users-table-filter-placeholder = Nutzer filtern nach…
 
 # Column header for email address
-# Source: ./ui/src/users/mod.rs:217
+# Source: ./ui/src/users/mod.rs:226
 users-table-header-email = E-Mail
 
 # Column header for user role
-# Source: ./ui/src/users/mod.rs:219
+# Source: ./ui/src/users/mod.rs:228
 users-table-header-role = Berechtigung
 
 # Column header for user ID
-# Source: ./ui/src/users/mod.rs:203
+# Source: ./ui/src/users/mod.rs:212
 users-table-header-user-id = Nutzernummer
 
 # Column header for username
-# Source: ./ui/src/users/mod.rs:210
+# Source: ./ui/src/users/mod.rs:219
 users-table-header-username = Benutzername
 
 # Welcome message with the username
-# Source: ./ui/src/home.rs:11
+# Source: ./ui/src/home.rs:12
 # Parameters: $username
 welcome = Herzlich willkommen {$username}
 
 # Welcome message for strangers
-# Source: ./ui/src/home.rs:13
+# Source: ./ui/src/home.rs:15
 welcome-stranger = welcome-stranger
 
-# Source: ./ui/src/navbar.rs:93
+# Source: ./ui/src/navbar.rs:110
 # Parameters: $username
 welcome-user = Herzlich Wilkommen {$username}
 
@@ -1,28 +1,251 @@
 # Generated translation template by dioxus-i18n-collect
-# Contains 83 translation keys
+# Contains 290 translation keys
 
-# Source: ./ui/src/users/database_export.rs:81
+# Button to create a new API token
+# Source: ./ui/src/users/api_tokens.rs:157
+api-tokens-button-create = Create token
+
+# Button to revoke an API token
+# Source: ./ui/src/users/api_tokens.rs:88
+api-tokens-button-revoke = Revoke
+
+# Message after a token was created, it is only shown once
+# Source: ./ui/src/users/api_tokens.rs:32
+# Parameters: $name
+api-tokens-created = The token { $name } was created. Copy it now, it is not shown again:
+
+# Explanation how the API tokens are used
+# Source: ./ui/src/users/api_tokens.rs:26
+api-tokens-description = Scripts and CI jobs can act as you by sending a token in the header Authorization: Bearer <token>. Read tokens may only read links, users and statistics.
+
+# Column of the date a token expires
+# Source: ./ui/src/users/api_tokens.rs:46
+api-tokens-expires = Expires
+
+# Column of the last time a token was used
+# Source: ./ui/src/users/api_tokens.rs:47
+api-tokens-last-used = Last used
+
+# Column of the name of a token
+# Source: ./ui/src/users/api_tokens.rs:44
+api-tokens-name = Name
+
+# Displayed if a token never expires or was never used
+# Source: ./ui/src/users/api_tokens.rs:70
+api-tokens-never = never
+
+# Column of the user a token belongs to
+# Source: ./ui/src/users/api_tokens.rs:42
+api-tokens-owner = User
+
+# Placeholder of the number of days a token is valid
+# Source: ./ui/src/users/api_tokens.rs:135
+api-tokens-placeholder-days = Valid for days (empty = forever)
+
+# Placeholder of the name of a new token
+# Source: ./ui/src/users/api_tokens.rs:108
+api-tokens-placeholder-name = Name of the token
+
+# Column of what a token may do
+# Source: ./ui/src/users/api_tokens.rs:45
+api-tokens-scope = Scope
+
+# Scope of a token that may only read
+# Source: ./ui/src/users/api_tokens.rs:63
+api-tokens-scope-read = Read
+
+# Scope of a token that may change links and users
+# Source: ./ui/src/users/api_tokens.rs:64
+api-tokens-scope-write = Read and write
+
+# Headline of the personal API tokens
+# Source: ./ui/src/users/api_tokens.rs:25
+api-tokens-title = API tokens
+
+# Button to clear the selection of links
+# Source: ./ui/src/links/bulk_actions.rs:65
+bulk-actions-button-clear = Clear selection
+
+# Button to confirm deleting the selected links
+# Source: ./ui/src/links/bulk_actions.rs:104
+# Parameters: $count
+bulk-actions-button-confirm-delete = Really delete { $count } links?
+
+# Button to delete the selected links, it asks for a confirmation
+# Source: ./ui/src/links/bulk_actions.rs:111
+bulk-actions-button-delete = Delete selected
+
+# Button to download the selected links as CSV
+# Source: ./ui/src/links/bulk_actions.rs:97
+bulk-actions-button-export = Export as CSV
+
+# Button to select all links matching the filter, not only the loaded ones
+# Source: ./ui/src/links/bulk_actions.rs:59
+bulk-actions-button-select-matching = Select all matching links
+
+# Button to make the entered user the author of the selected links
+# Source: ./ui/src/links/bulk_actions.rs:85
+bulk-actions-button-transfer = Transfer
+
+# Message after the selected links were deleted or transferred
+# Source: ./ui/src/links/bulk_actions.rs:33
+# Parameters: $count
+bulk-actions-done = { $count } links were changed.
+
+# Label of the username the selected links are transferred to
+# Source: ./ui/src/links/bulk_actions.rs:70
+bulk-actions-label-author = New author
+
+# Label with the number of selected links
+# Source: ./ui/src/links/bulk_actions.rs:55
+# Parameters: $count
+bulk-actions-selected = { $count } links selected
+
+# Export every single click
+# Source: ./ui/src/links/click_export.rs:17
+click-export-aggregation-click = Every click
+
+# Export the clicks per day
+# Source: ./ui/src/links/click_export.rs:18
+click-export-aggregation-day = Per day
+
+# Export the clicks per month
+# Source: ./ui/src/links/click_export.rs:20
+click-export-aggregation-month = Per month
+
+# Export the clicks per week
+# Source: ./ui/src/links/click_export.rs:19
+click-export-aggregation-week = Per week
+
+# Button to export the clicks of all own links
+# Source: ./ui/src/links/click_export.rs:102
+click-export-button-all = Export the clicks of all my links
+
+# Label of the grouping of the exported clicks
+# Source: ./ui/src/links/click_export.rs:56
+click-export-label-aggregation = Grouping
+
+# Label of the file format of the click export
+# Source: ./ui/src/links/click_export.rs:37
+click-export-label-format = Export format
+
+# Label of the first day of the click export
+# Source: ./ui/src/links/click_export.rs:75
+click-export-label-from = From
+
+# Label of the last day of the click export
+# Source: ./ui/src/links/click_export.rs:86
+click-export-label-to = To
+
+# Tooltip of the button to export the clicks of one link
+# Source: ./ui/src/links/link_display.rs:146
+click-export-tooltip-link = Export the clicks of this link
+
+# Headline of the chart with all clicks per week
+# Source: ./ui/src/dashboard.rs:54
+dashboard-clicks-title = Clicks per week on all links
+
+# Source: ./ui/src/dashboard.rs:136
+dashboard-header-clicks = Clicks
+
+# Source: ./ui/src/dashboard.rs:201
+dashboard-header-created = Created
+
+# Source: ./ui/src/dashboard.rs:202
+dashboard-header-last-click = Last click
+
+# Source: ./ui/src/dashboard.rs:160
+dashboard-header-links-created = Links created
+
+# Label of the selection how many entries are shown
+# Source: ./ui/src/dashboard.rs:112
+dashboard-label-amount = Entries
+
+# Label of the period selection
+# Source: ./ui/src/dashboard.rs:93
+dashboard-label-period = Period
+
+# Label of the number of months without clicks
+# Source: ./ui/src/dashboard.rs:181
+dashboard-label-stale-months = Months without clicks
+
+# Headline of the chart with the links created per month
+# Source: ./ui/src/dashboard.rs:73
+dashboard-links-per-month-title = Links created per month
+
+# Source: ./ui/src/dashboard.rs:216
+dashboard-never-clicked = never
+
+# Source: ./ui/src/dashboard.rs:25
+dashboard-period-all = All time
+
+# Source: ./ui/src/dashboard.rs:22
+dashboard-period-month = Last 30 days
+
+# Source: ./ui/src/dashboard.rs:23
+dashboard-period-quarter = Last 3 months
+
+# Source: ./ui/src/dashboard.rs:21
+dashboard-period-week = Last 7 days
+
+# Source: ./ui/src/dashboard.rs:24
+dashboard-period-year = Last 12 months
+
+# Headline of the links without clicks
+# Source: ./ui/src/dashboard.rs:178
+# Parameters: $months
+dashboard-stale-links-title = Links without clicks in the last {$months} months
+
+# Headline of the most active authors
+# Source: ./ui/src/dashboard.rs:155
+dashboard-top-authors-title = Most active authors
+
+# Headline of the most clicked links
+# Source: ./ui/src/dashboard.rs:130
+dashboard-top-links-title = Most clicked links
+
+# Source: ./ui/src/users/database_export.rs:120
 database-export-button-clear = Clear
 
-# Source: ./ui/src/users/database_export.rs:73
+# Source: ./ui/src/users/database_export.rs:112
 database-export-button-download = 📥 Download Database
 
-# Source: ./ui/src/users/database_export.rs:115
+# Button to save the JSON export
+# Source: ./ui/src/users/database_export.rs:135
+database-export-button-download-json = 📥 Download JSON
+
+# Source: ./ui/src/users/database_export.rs:177
 database-export-button-export = Export Database
 
-# Source: ./ui/src/users/database_export.rs:108
+# Button to export all data as versioned JSON
+# Source: ./ui/src/users/database_export.rs:183
+database-export-button-export-json = Export as JSON
+
+# Source: ./ui/src/users/database_export.rs:170
 database-export-button-exporting = Exporting...
 
-# Source: ./ui/src/users/database_export.rs:28
+# Source: ./ui/src/users/database_export.rs:39
 database-export-error-empty-secret = Please enter the export secret
 
-# Source: ./ui/src/users/database_export.rs:42
+# Source: ./ui/src/users/database_export.rs:53
 database-export-error-failed = Export failed
 
-# Source: ./ui/src/users/database_export.rs:99
+# Explanation of the JSON export and pslink restore
+# Source: ./ui/src/users/database_export.rs:196
+database-export-help-json = The JSON export contains users, links and clicks and can be loaded into a new instance with `pslink restore`. Without password hashes every user needs a new password.
+
+# Source: ./ui/src/users/database_export.rs:161
 database-export-help-secret = Enter the secret configured in PSLINK_DATA_DOWNLOAD_SECRET environment variable
 
-# Source: ./ui/src/users/database_export.rs:88
+# Checkbox to include the password hashes in the JSON export
+# Source: ./ui/src/users/database_export.rs:194
+database-export-include-passwords = Include password hashes, second factors, API tokens and invitations
+
+# Message that the JSON export can be downloaded
+# Source: ./ui/src/users/database_export.rs:128
+database-export-json-ready = JSON export ready!
+
+# Source: ./ui/src/users/database_export.rs:150
 database-export-label-secret = Export Secret
 
 # Placeholder for export secret input
@@ -30,152 +253,691 @@ database-export-label-secret = Export Secret
 # UNUSED: This key is no longer used in the code
 database-export-placeholder-secret = Enter the export secret
 
-# Source: ./ui/src/users/database_export.rs:66
+# Source: ./ui/src/users/database_export.rs:105
 database-export-ready = Database export ready!
 
-# Source: ./ui/src/users/database_export.rs:67
+# Source: ./ui/src/users/database_export.rs:106
 # Parameters: $size
 database-export-size = Size: {$size} bytes
 
-# Source: ./ui/src/users/database_export.rs:54
-database-export-subtitle = Export the SQLite database for backup purposes
+# Source: ./ui/src/users/database_export.rs:93
+database-export-subtitle = Export a consistent snapshot of the SQLite database for backup purposes
 
-# Source: ./ui/src/users/database_export.rs:36
+# Source: ./ui/src/users/database_export.rs:47
 database-export-success-message = Database exported successfully! Click the download link below.
 
-# Source: ./ui/src/users/database_export.rs:53
+# Source: ./ui/src/users/database_export.rs:92
 database-export-title = Database Export
 
-# Source: ./ui/src/navbar.rs:160
+# Source: ./ui/src/navbar.rs:177
 demo-warning = This instance is not created with a persistent storage. So all the links and useraccounts are lost on restart.
 
-# Source: ./ui/src/login.rs:112
+# First line of an email
+# Source: ./backend/src/mail.rs:107
+# Parameters: $username
+email-greeting = Hello { $username },
+
+# Last paragraph of the password reset email
+# Source: ./backend/src/mail.rs:110
+email-reset-ignore = If you did not ask for this, you can ignore this email. Your password stays unchanged.
+
+# Subject of the email with the password reset link
+# Source: ./backend/src/mail.rs:104
+email-reset-subject = Reset your pslink password
+
+# Text of the password reset email before the link
+# Source: ./backend/src/mail.rs:108
+# Parameters: $minutes
+email-reset-text = somebody asked to reset the password of your pslink account. Open this link within { $minutes } minutes to choose a new password:
+
+# Subject of the email sent by pslink test-email
+# Source: ./backend/src/mail.rs:119
+email-test-subject = pslink test email
+
+# Text of the email sent by pslink test-email
+# Source: ./backend/src/mail.rs:120
+email-test-text = This email confirms that pslink can send emails with the configured SMTP server.
+
+# Source: ./ui/src/login.rs:191
 # Parameters: $error
 failed-login = Username and password did not match please try again.
 
-# Source: ./ui/src/login.rs:40
+# Source: ./ui/src/login.rs:58
 headline-login = Login
 
+# Button to create the account of the invitation
+# Source: ./ui/src/invitation.rs:195
+invitation-button-create = Create account
+
+# Explanation of the invitation page with the time the invitation expires
+# Source: ./ui/src/invitation.rs:62
+# Parameters: $expires
+invitation-description = You were invited to pslink. Choose a username and password, the invitation is valid until { $expires }.
+
+# Headline of the page that creates an account with an invitation
+# Source: ./ui/src/invitation.rs:45
+invitation-headline = Create your account
+
+# Message when the invitation was used, revoked or expired
+# Source: ./ui/src/invitation.rs:146
+invitation-invalid = This invitation cannot be used.
+
+# Label of the field that repeats the new password
+# Source: ./ui/src/invitation.rs:105
+invitation-label-password-repeat = Repeat password
+
+# Message when the repeated password differs
+# Source: ./ui/src/invitation.rs:169
+invitation-password-mismatch = The passwords do not match.
+
+# Button to create a new invitation link
+# Source: ./ui/src/users/invitations.rs:148
+invitations-button-create = Create invitation
+
+# Button to remove an expired invitation
+# Source: ./ui/src/users/invitations.rs:75
+invitations-button-delete = Delete
+
+# Button to revoke a pending invitation
+# Source: ./ui/src/users/invitations.rs:77
+invitations-button-revoke = Revoke
+
+# Message after an invitation was created, the link is only shown once
+# Source: ./ui/src/users/invitations.rs:26
+invitations-created = Send this link to the new user, it is only shown once:
+
+# Column of the administrator that created the invitation
+# Source: ./ui/src/users/invitations.rs:38
+invitations-created-by = Created by
+
+# Explanation how invitation links are used
+# Source: ./ui/src/users/invitations.rs:20
+invitations-description = An invitation link lets a new user create an account with their own username and password. Every link can be used once.
+
+# Column of the pre-filled email address
+# Source: ./ui/src/users/invitations.rs:36
+invitations-email = Email
+
+# Tag of an invitation that can no longer be used
+# Source: ./ui/src/users/invitations.rs:62
+invitations-expired = expired
+
+# Column of the time the invitation expires
+# Source: ./ui/src/users/invitations.rs:39
+invitations-expires = Expires
+
+# Column of the start of the invitation link
+# Source: ./ui/src/users/invitations.rs:35
+invitations-link = Link
+
+# Placeholder of the number of days an invitation is valid
+# Source: ./ui/src/users/invitations.rs:127
+invitations-placeholder-days = Valid days
+
+# Placeholder of the optional email address of an invitation
+# Source: ./ui/src/users/invitations.rs:98
+invitations-placeholder-email = Email (optional)
+
+# Column of the role of the new account
+# Source: ./ui/src/users/invitations.rs:37
+invitations-role = Role
+
+# Headline of the invitation links for new users
+# Source: ./ui/src/users/invitations.rs:19
+invitations-title = Invitations
+
 # The menu entry for language selection
-# Source: ./ui/src/navbar.rs:86
+# Source: ./ui/src/navbar.rs:103
 language = Language Selection
 
+# Link back to the list of links
+# Source: ./ui/src/links/link_detail.rs:50
+link-detail-back = Back to the list of links
+
+# Headline of the chart with the clicks per day
+# Source: ./ui/src/links/link_detail.rs:140
+link-detail-daily-title = Clicks per day
+
+# Name of the device class
+# Source: ./ui/src/links/link_detail.rs:37
+link-detail-device-bot = Bot
+
+# Name of the device class
+# Source: ./ui/src/links/link_detail.rs:34
+link-detail-device-desktop = Desktop
+
+# Name of the device class
+# Source: ./ui/src/links/link_detail.rs:35
+link-detail-device-mobile = Mobile
+
+# Name of the device class
+# Source: ./ui/src/links/link_detail.rs:36
+link-detail-device-tablet = Tablet
+
+# Name of the device class
+# Source: ./ui/src/links/link_detail.rs:38
+link-detail-device-unknown = Unknown
+
+# Headline of the chart with the device classes
+# Source: ./ui/src/links/link_detail.rs:165
+link-detail-devices-title = Devices
+
+# Tooltip of the qr code to download it as png
+# Source: ./ui/src/links/link_detail.rs:133
+link-detail-download-qr = Download the QR code as PNG
+
+# Headline of the heatmap of clicks by weekday and hour
+# Source: ./ui/src/links/link_detail.rs:177
+link-detail-heatmap-title = Clicks by weekday and hour (UTC)
+
+# Shown instead of a chart if the link was never clicked
+# Source: ./ui/src/links/link_detail.rs:151
+link-detail-no-clicks = This link has not been clicked yet.
+
+# Name of the clicks without a referrer
+# Source: ./ui/src/links/link_detail.rs:157
+link-detail-referrer-direct = Direct / unknown
+
+# Headline of the chart with the referring websites
+# Source: ./ui/src/links/link_detail.rs:149
+link-detail-referrers-title = Referrers
+
+# Label of the complete short url
+# Source: ./ui/src/links/link_detail.rs:105
+link-detail-short-url = Short URL
+
+# Label of the number of all clicks of the link
+# Source: ./ui/src/links/link_detail.rs:121
+link-detail-total-clicks = Total clicks
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:27
+link-detail-weekday-friday = Fri
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:23
+link-detail-weekday-monday = Mon
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:28
+link-detail-weekday-saturday = Sat
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:22
+link-detail-weekday-sunday = Sun
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:26
+link-detail-weekday-thursday = Thu
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:24
+link-detail-weekday-tuesday = Tue
+
+# Short name of the weekday
+# Source: ./ui/src/links/link_detail.rs:25
+link-detail-weekday-wednesday = Wed
+
 # Button text for confirming link deletion
-# Source: ./ui/src/links/link_edit.rs:253
+# Source: ./ui/src/links/link_edit.rs:280
 link-edit-button-confirm-delete = Actually delete this link
 
 # Button text for creating a new link
-# Source: ./ui/src/links/link_edit.rs:198
+# Source: ./ui/src/links/link_edit.rs:219
 link-edit-button-create = Create link
 
 # Button text for deleting a link
-# Source: ./ui/src/links/link_edit.rs:209
+# Source: ./ui/src/links/link_edit.rs:230
 link-edit-button-delete = Delete link
 
 # Button text for modifying a link
-# Source: ./ui/src/links/link_edit.rs:227
+# Source: ./ui/src/links/link_edit.rs:254
 link-edit-button-modify = Modify link
 
 # Warning message about deleting links
-# Source: ./ui/src/links/link_edit.rs:151
+# Source: ./ui/src/links/link_edit.rs:166
 link-edit-delete-warning = Deleting a link is generally not recommended. Only delete links that haven't been published anywhere, or that you intentionally want to lead nowhere.
 
 # Label for link code field
-# Source: ./ui/src/links/link_edit.rs:96
+# Source: ./ui/src/links/link_edit.rs:107
 link-edit-field-code = Code
 
 # Label for description field
-# Source: ./ui/src/links/link_edit.rs:52
+# Source: ./ui/src/links/link_edit.rs:63
 link-edit-field-description = Description
 
 # Label for QR code field
-# Source: ./ui/src/links/link_edit.rs:116
+# Source: ./ui/src/links/link_edit.rs:127
 link-edit-field-qrcode = QR Code
 
 # Label for link target field
-# Source: ./ui/src/links/link_edit.rs:76
+# Source: ./ui/src/links/link_edit.rs:87
 link-edit-field-target = Redirect target
 
 # Title for the link editing modal
-# Source: ./ui/src/links/link_edit.rs:40
+# Source: ./ui/src/links/link_edit.rs:51
 link-edit-modal-title = Edit a link
 
 # Placeholder for code input
-# Source: ./ui/src/links/link_edit.rs:102
+# Source: ./ui/src/links/link_edit.rs:113
 link-edit-placeholder-code = Code
 
 # Placeholder for description input
-# Source: ./ui/src/links/link_edit.rs:62
+# Source: ./ui/src/links/link_edit.rs:73
 link-edit-placeholder-description = Description
 
 # Placeholder for target input
-# Source: ./ui/src/links/link_edit.rs:82
+# Source: ./ui/src/links/link_edit.rs:93
 link-edit-placeholder-target = Redirect target
 
+# The imported link is created
+# Source: ./ui/src/links/link_import.rs:29
+link-import-action-create = new
+
+# The imported row has errors
+# Source: ./ui/src/links/link_import.rs:33
+link-import-action-invalid = invalid
+
+# The imported link overwrites an existing link
+# Source: ./ui/src/links/link_import.rs:30
+link-import-action-overwrite = overwrite
+
+# The imported link is created with another code
+# Source: ./ui/src/links/link_import.rs:31
+# Parameters: $code
+link-import-action-rename = new as { $code }
+
+# The imported link is skipped
+# Source: ./ui/src/links/link_import.rs:32
+link-import-action-skip = skipped
+
+# Link back to the list of links
+# Source: ./ui/src/links/link_import.rs:69
+link-import-back = Back to the links
+
+# Button to import the checked links
+# Source: ./ui/src/links/link_import.rs:158
+link-import-button-apply = Import
+
+# Button to validate the import without changing anything
+# Source: ./ui/src/links/link_import.rs:151
+link-import-button-check = Check
+
+# Explanation of the columns of the import file
+# Source: ./ui/src/links/link_import.rs:71
+link-import-description = Upload or paste a CSV file with the columns title, target and code (optionally author for administrators) or a JSON array of objects with the same fields. The import is checked first and only applied when every row is valid.
+
+# Message that the import cannot be applied because of invalid rows
+# Source: ./ui/src/links/link_import.rs:170
+link-import-has-errors = Some rows are invalid, fix them and check again.
+
+# Column header of what happens to the row
+# Source: ./ui/src/links/link_import.rs:179
+link-import-header-action = Result
+
+# Column header of the row number in the import report
+# Source: ./ui/src/links/link_import.rs:175
+link-import-header-row = Row
+
+# Label of the file upload of the link import
+# Source: ./ui/src/links/link_import.rs:74
+link-import-label-file = File
+
+# Label of the file format of the link import
+# Source: ./ui/src/links/link_import.rs:93
+link-import-label-format = Format
+
+# Label of what happens to links whose code exists
+# Source: ./ui/src/links/link_import.rs:113
+link-import-label-policy = Existing codes
+
+# Import policy that overwrites links whose code exists
+# Source: ./ui/src/links/link_import.rs:22
+link-import-policy-overwrite = Overwrite existing links
+
+# Import policy that renames links whose code exists
+# Source: ./ui/src/links/link_import.rs:23
+link-import-policy-rename = Import with a new code
+
+# Import policy that skips links whose code exists
+# Source: ./ui/src/links/link_import.rs:21
+link-import-policy-skip = Keep existing links
+
+# Message after the links were imported
+# Source: ./ui/src/links/link_import.rs:167
+# Parameters: $count
+link-import-success = The import of { $count } rows is done.
+
+# Headline of the link import page
+# Source: ./ui/src/links/link_import.rs:70
+link-import-title = Import links
+
+# Button to open the import of many links
+# Source: ./ui/src/links/mod.rs:279
+links-button-import = Import links
+
 # Button text to load more links
-# Source: ./ui/src/links/mod.rs:385
+# Source: ./ui/src/links/mod.rs:462
 links-button-load-more = Load more links
 
-# Source: ./ui/src/links/link_display.rs:68
+# Source: ./ui/src/links/link_display.rs:77
 links-error-not-author = You can only edit links created by you.
 
 # Text displayed while loading links data
-# Source: ./ui/src/links/mod.rs:391
+# Source: ./ui/src/links/mod.rs:468
 links-loading = Loading links
 
 # Text for login link
-# Source: ./ui/src/links/mod.rs:392
+# Source: ./ui/src/links/mod.rs:469
 links-login = Login
 
 # Placeholder text for filter input field
-# Source: ./ui/src/links/mod.rs:272
+# Source: ./ui/src/links/mod.rs:346
 links-table-filter-placeholder = Filter links by...
 
 # Column header for link code
-# Source: ./ui/src/links/mod.rs:233
+# Source: ./ui/src/links/mod.rs:305
 links-table-header-code = Code
 
 # Column header for description
-# Source: ./ui/src/links/mod.rs:240
+# Source: ./ui/src/links/mod.rs:312
 links-table-header-description = Description
 
-# Source: ./ui/src/links/mod.rs:261
+# Source: ./ui/src/links/mod.rs:333
 links-table-header-statistics = Statistics
 
 # Column header for link target
-# Source: ./ui/src/links/mod.rs:247
+# Source: ./ui/src/links/mod.rs:319
 links-table-header-target = Short link target
 
 # Column header for username
-# Source: ./ui/src/links/mod.rs:254
+# Source: ./ui/src/links/mod.rs:326
 links-table-header-username = Author
 
+# Tooltip of the statistics, a click opens the detail page
+# Source: ./ui/src/links/link_display.rs:123
+links-tooltip-details = Show the detailed statistics
+
+# Tooltip of the checkbox that selects all loaded links
+# Source: ./ui/src/links/mod.rs:288
+links-tooltip-select-all = Select all loaded links
+
 # The menu entry for login
-# Source: ./ui/src/navbar.rs:111
+# Source: ./ui/src/navbar.rs:128
 login = Login
 
+# A login refused because of earlier failures
+# Source: ./ui/src/users/login_lockouts.rs:100
+login-events-blocked = Refused
+
+# Column of further details
+# Source: ./ui/src/users/login_lockouts.rs:89
+login-events-detail = Details
+
+# A login with wrong credentials
+# Source: ./ui/src/users/login_lockouts.rs:99
+login-events-failure = Failed
+
+# Column of the client address
+# Source: ./ui/src/users/login_lockouts.rs:88
+login-events-ip = Address
+
+# Column of what happened
+# Source: ./ui/src/users/login_lockouts.rs:86
+login-events-kind = Event
+
+# A username or address was locked
+# Source: ./ui/src/users/login_lockouts.rs:101
+login-events-lockout = Locked
+
+# An administrator lifted a lockout
+# Source: ./ui/src/users/login_lockouts.rs:102
+login-events-reset = Unlocked
+
+# A successful login
+# Source: ./ui/src/users/login_lockouts.rs:98
+login-events-success = Logged in
+
+# Column of the time of a login event
+# Source: ./ui/src/users/login_lockouts.rs:85
+login-events-time = Time
+
+# Headline of the recent logins and refused logins
+# Source: ./ui/src/users/login_lockouts.rs:79
+login-events-title = Recent logins
+
+# Column of the entered username
+# Source: ./ui/src/users/login_lockouts.rs:87
+login-events-username = Username
+
+# Button to lift a lockout
+# Source: ./ui/src/users/login_lockouts.rs:64
+login-lockouts-button-reset = Unlock
+
+# Column of the number of failed logins
+# Source: ./ui/src/users/login_lockouts.rs:29
+login-lockouts-failures = Failed logins
+
+# Text when nothing is locked
+# Source: ./ui/src/users/login_lockouts.rs:22
+login-lockouts-none = No username or address is locked.
+
+# Column of the locked username or address
+# Source: ./ui/src/users/login_lockouts.rs:28
+login-lockouts-subject = Username or address
+
+# Headline of the usernames and addresses locked after failed logins
+# Source: ./ui/src/users/login_lockouts.rs:16
+login-lockouts-title = Locked logins
+
+# Column of the time the lockout ends
+# Source: ./ui/src/users/login_lockouts.rs:30
+login-lockouts-until = Locked until
+
+# Button to log in with the identity provider of the organisation
+# Source: ./ui/src/login.rs:241
+login-single-sign-on = Log in with single sign-on
+
 # The menu entry for logout
-# Source: ./ui/src/navbar.rs:106
+# Source: ./ui/src/navbar.rs:123
 logout = Logout
 
 # The title of the page
-# Source: ./ui/src/navbar.rs:137
+# Source: ./ui/src/navbar.rs:154
 page-not-found = 404 Page not found
 
 # The text of the page
-# Source: ./ui/src/navbar.rs:138
+# Source: ./ui/src/navbar.rs:155
 page-not-found-text = The requested page was not found.
 
-# Source: ./ui/src/login.rs:69
+# Source: ./ui/src/login.rs:148
 password = Password
 
+# Label of the field for the username or email address
+# Source: ./ui/src/login.rs:75
+password-reset-account = Username or email
+
+# Button to return from the password reset to the login
+# Source: ./ui/src/login.rs:207
+password-reset-button-back = Back to login
+
+# Button to save the new password
+# Source: ./ui/src/password_reset.rs:93
+password-reset-button-save = Save password
+
+# Button to send the link that resets the password
+# Source: ./ui/src/login.rs:224
+password-reset-button-send = Send link
+
+# Explanation that a reset link is sent to the email address of the account
+# Source: ./ui/src/login.rs:72
+password-reset-description = Enter your username or email address, we will send you a link to choose a new password.
+
+# Message after the new password was saved
+# Source: ./ui/src/password_reset.rs:29
+password-reset-done = Your new password is saved, you can log in with it now.
+
+# Button on the login to reset a forgotten password
+# Source: ./ui/src/login.rs:236
+password-reset-forgot = Forgot password?
+
+# Headline of the page that sets a new password
+# Source: ./ui/src/password_reset.rs:25
+password-reset-headline = Choose a new password
+
+# Label of the new password
+# Source: ./ui/src/password_reset.rs:32
+password-reset-label-password = New password
+
+# Message after a reset link was requested, it does not tell whether the account exists
+# Source: ./ui/src/login.rs:95
+password-reset-sent = If the account exists, an email with a link to reset the password is on its way.
+
+# Highest error correction of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:46
+qr-designer-ecl-high = High (30%)
+
+# Lowest error correction of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:43
+qr-designer-ecl-low = Low (7%)
+
+# Medium error correction of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:44
+qr-designer-ecl-medium = Medium (15%)
+
+# High error correction of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:45
+qr-designer-ecl-quartile = Quartile (25%)
+
+# Label of the background color of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:131
+qr-designer-label-background = Background
+
+# Label of the error correction of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:80
+qr-designer-label-error-correction = Error correction
+
+# Label of the color of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:119
+qr-designer-label-foreground = Color
+
+# Checkbox to place the logo in the center of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:184
+qr-designer-label-logo = Logo in the center
+
+# Label of the quiet zone around the qr-code
+# Source: ./ui/src/links/qr_designer.rs:143
+qr-designer-label-margin = Quiet zone
+
+# Label of the size of a module of the png image
+# Source: ./ui/src/links/qr_designer.rs:158
+qr-designer-label-pixels = Pixels per module
+
+# Label of the shape of the modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:100
+qr-designer-label-shape = Shape
+
+# Explanation that the logo forces the highest error correction
+# Source: ./ui/src/links/qr_designer.rs:189
+qr-designer-logo-hint = The logo covers a part of the code, so the highest error correction is used.
+
+# Circular modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:54
+qr-designer-shape-circle = Circles
+
+# Diamond shaped modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:55
+qr-designer-shape-diamond = Diamonds
+
+# Horizontal modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:57
+qr-designer-shape-horizontal = Horizontal bars
+
+# Rounded modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:53
+qr-designer-shape-rounded = Rounded squares
+
+# Square modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:52
+qr-designer-shape-square = Squares
+
+# Vertical modules of the qr-code
+# Source: ./ui/src/links/qr_designer.rs:56
+qr-designer-shape-vertical = Vertical bars
+
+# Button to download the qr-code
+# Source: ./ui/src/links/qr_designer.rs:279
+qr-download-button = Download
+
+# Label of the resolution of the png download
+# Source: ./ui/src/links/qr_designer.rs:250
+qr-download-label-dpi = Resolution (dpi)
+
+# Label of the file format of the qr-code download
+# Source: ./ui/src/links/qr_designer.rs:230
+qr-download-label-format = Download as
+
+# Checkbox to print the title and short url below the qr-code
+# Source: ./ui/src/links/qr_designer.rs:272
+qr-download-label-label = Print title and short URL below the code
+
+# Button to download the qr-code sheet of the selected links
+# Source: ./ui/src/links/qr_sheet.rs:94
+# Parameters: $count
+qr-sheet-button = Print QR codes of { $count } selected links
+
+# Label of the number of columns of the qr-code sheet
+# Source: ./ui/src/links/qr_sheet.rs:61
+qr-sheet-label-columns = Columns
+
+# Label of the page margin of the qr-code sheet in millimeters
+# Source: ./ui/src/links/qr_sheet.rs:76
+qr-sheet-label-margin = Margin (mm)
+
+# Label of the paper size of the qr-code sheet
+# Source: ./ui/src/links/qr_sheet.rs:27
+qr-sheet-label-paper = QR sheet paper
+
+# Label of the number of rows of the qr-code sheet
+# Source: ./ui/src/links/qr_sheet.rs:46
+qr-sheet-label-rows = Rows
+
 # The requested route on the 404 page
-# Source: ./ui/src/navbar.rs:139
+# Source: ./ui/src/navbar.rs:156
 # Parameters: $route
 requested-route = The requested route was {$route}
 
+# Button to create a new public statistics page
+# Source: ./ui/src/links/share_tokens.rs:91
+share-button-create = Create public page
+
+# Button to revoke a public statistics page
+# Source: ./ui/src/links/share_tokens.rs:51
+share-button-revoke = Revoke
+
+# Explanation of the public statistics pages
+# Source: ./ui/src/links/share_tokens.rs:19
+share-description = Everybody who knows one of these addresses can view the statistics of this link without an account. The name and email of the author are not shown.
+
+# Expiry date of a public statistics page
+# Source: ./ui/src/links/share_tokens.rs:38
+# Parameters: $date
+share-expires = Expires on {$date}
+
+# Shown if a public statistics page does not expire
+# Source: ./ui/src/links/share_tokens.rs:39
+share-never-expires = Never expires
+
+# Placeholder of the number of days a token is valid
+# Source: ./ui/src/links/share_tokens.rs:72
+share-placeholder-days = Valid for days (empty: forever)
+
+# Headline of the public statistics pages of a link
+# Source: ./ui/src/links/share_tokens.rs:18
+share-title = Public statistics pages
+
 # The menu entry for links
-# Source: ./ui/src/navbar.rs:69
+# Source: ./ui/src/navbar.rs:86
 short_urls = Short URLs
 
 # Displayed as a tooltip when there have been no clicks on this link in the last 12 months.
@@ -188,6 +950,75 @@ tooltip_no_clicks = This link was not clicked in the last 12 months. Before that
 # Parameters: $count
 total_clicks = Total clicks: {$count}
 
+# Button to enable two-factor authentication with the entered code
+# Source: ./ui/src/users/two_factor.rs:68
+totp-button-confirm = Enable
+
+# Button to turn off two-factor authentication
+# Source: ./ui/src/users/two_factor.rs:181
+totp-button-disable = Disable
+
+# Button after the recovery codes were saved
+# Source: ./ui/src/users/two_factor.rs:22
+totp-button-done = I saved the recovery codes
+
+# Button to replace the recovery codes
+# Source: ./ui/src/users/two_factor.rs:163
+totp-button-recovery-codes = New recovery codes
+
+# Button to set up two-factor authentication
+# Source: ./ui/src/users/two_factor.rs:130
+totp-button-setup = Set up
+
+# Button to finish the login with the one-time code
+# Source: ./ui/src/login.rs:197
+totp-button-verify = Verify
+
+# Label of the one-time code field of the login
+# Source: ./ui/src/login.rs:100
+totp-code = Code
+
+# Help that a recovery code can be entered instead of the one-time code
+# Source: ./ui/src/login.rs:117
+totp-code-help = The code of your authenticator app or a recovery code.
+
+# Text when two-factor authentication is not set up
+# Source: ./ui/src/users/two_factor.rs:128
+totp-disabled = Protect your account with a code of an authenticator app in addition to the password.
+
+# Text when two-factor authentication is enabled with the number of unused recovery codes
+# Source: ./ui/src/users/two_factor.rs:135
+# Parameters: $left
+totp-enabled = Two-factor authentication is enabled, { $left } recovery codes are left. Enter a code to replace the recovery codes or to disable it.
+
+# Placeholder of the one-time code
+# Source: ./ui/src/users/two_factor.rs:50
+totp-placeholder-code = Code
+
+# Explanation that every recovery code can be used once instead of a one-time code
+# Source: ./ui/src/users/two_factor.rs:88
+totp-recovery-codes = Store these recovery codes in a safe place. Each of them can be used once instead of a code if the authenticator app is lost. They are only shown now.
+
+# Checkbox to require two-factor authentication for all administrators
+# Source: ./ui/src/users/two_factor.rs:199
+totp-required-for-admins = Require two-factor authentication for all administrators
+
+# How to scan the qr-code with an authenticator app
+# Source: ./ui/src/users/two_factor.rs:29
+totp-setup-description = Scan the qr-code with an authenticator app and enter the code it shows.
+
+# Text when an administrator has to set up two-factor authentication before the login
+# Source: ./ui/src/login.rs:62
+totp-setup-required = Administrators have to use two-factor authentication. Set it up to finish the login.
+
+# Text before the secret for apps that cannot scan the qr-code
+# Source: ./ui/src/users/two_factor.rs:36
+totp-setup-secret = Or enter this key:
+
+# Headline of the two-factor authentication settings
+# Source: ./ui/src/users/two_factor.rs:111
+totp-title = Two-factor authentication
+
 # Button text for confirming user deletion
 # Source: ./ui/src/users/user_edit.rs:283
 user-edit-button-confirm-delete = Actually delete user
@@ -253,23 +1084,23 @@ user-edit-role-regular = Regular
 # Parameters: $username
 user-edit-title = User data
 
-# Source: ./ui/src/login.rs:45
+# Source: ./ui/src/login.rs:84
 username = Username
 
 # The menu entry for users
-# Source: ./ui/src/navbar.rs:72
+# Source: ./ui/src/navbar.rs:89
 users = User Accounts
 
 # Button text to load more users
-# Source: ./ui/src/users/mod.rs:313
+# Source: ./ui/src/users/mod.rs:324
 users-button-load-more = Load more users...
 
 # Text displayed while loading user data
-# Source: ./ui/src/users/mod.rs:323
+# Source: ./ui/src/users/mod.rs:336
 users-loading = Loading users...
 
 # Text for login link
-# Source: ./ui/src/users/mod.rs:324
+# Source: ./ui/src/users/mod.rs:337
 users-login = Login
 
 # Source: ./ui/src/users/user_display.rs:56
@@ -285,35 +1116,35 @@ users-role-disabled = Disabled
 users-role-regular = Regular
 
 # Placeholder text for filter input field
-# Source: ./ui/src/users/mod.rs:233
+# Source: ./ui/src/users/mod.rs:242
 users-table-filter-placeholder = Filter users by...
 
 # Column header for email address
-# Source: ./ui/src/users/mod.rs:217
+# Source: ./ui/src/users/mod.rs:226
 users-table-header-email = Email
 
 # Column header for user role
-# Source: ./ui/src/users/mod.rs:219
+# Source: ./ui/src/users/mod.rs:228
 users-table-header-role = Permission
 
 # Column header for user ID
-# Source: ./ui/src/users/mod.rs:203
+# Source: ./ui/src/users/mod.rs:212
 users-table-header-user-id = User ID
 
 # Column header for username
-# Source: ./ui/src/users/mod.rs:210
+# Source: ./ui/src/users/mod.rs:219
 users-table-header-username = Username
 
 # Welcome message with the username
-# Source: ./ui/src/home.rs:11
+# Source: ./ui/src/home.rs:12
 # Parameters: $username
 welcome = Welcome {$username}
 
 # Welcome message for strangers
-# Source: ./ui/src/home.rs:13
+# Source: ./ui/src/home.rs:15
 welcome-stranger = Welcome stranger
 
-# Source: ./ui/src/navbar.rs:93
+# Source: ./ui/src/navbar.rs:110
 # Parameters: $username
 welcome-user = Welcome {$username}
 
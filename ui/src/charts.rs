@@ -0,0 +1,297 @@
+//! Svg charts to visualize the statistics.
+use dioxus::prelude::*;
+
+const CHARTS_CSS: Asset = asset!("/assets/styling/charts.css");
+
+const CHART_WIDTH: f64 = 600.0;
+const CHART_HEIGHT: f64 = 200.0;
+/// Space for the labels of the y-axis
+const MARGIN_LEFT: f64 = 40.0;
+/// Space for the labels of the x-axis
+const MARGIN_BOTTOM: f64 = 20.0;
+const MARGIN_TOP: f64 = 10.0;
+
+/// Only show up to this many labels on the x-axis.
+const MAX_X_LABELS: usize = 8;
+
+/// Calculate the y coordinate of a value.
+fn y_position(value: i64, maximum: i64) -> f64 {
+    let plot_height = CHART_HEIGHT - MARGIN_BOTTOM - MARGIN_TOP;
+    CHART_HEIGHT - MARGIN_BOTTOM - value as f64 / maximum.max(1) as f64 * plot_height
+}
+
+/// The indices of the entries whose labels are shown on the x-axis.
+fn label_indices(count: usize) -> Vec<usize> {
+    let step = count.div_ceil(MAX_X_LABELS).max(1);
+    (0..count).step_by(step).collect()
+}
+
+/// Draw the axes and the labels of the maximum and zero.
+#[component]
+fn Axes(maximum: i64) -> Element {
+    let bottom = CHART_HEIGHT - MARGIN_BOTTOM;
+    rsx! {
+        line {
+            x1: "{MARGIN_LEFT}",
+            y1: "{MARGIN_TOP}",
+            x2: "{MARGIN_LEFT}",
+            y2: "{bottom}",
+            class: "chart_axis",
+        }
+        line {
+            x1: "{MARGIN_LEFT}",
+            y1: "{bottom}",
+            x2: "{CHART_WIDTH}",
+            y2: "{bottom}",
+            class: "chart_axis",
+        }
+        text {
+            x: "{MARGIN_LEFT - 4.0}",
+            y: "{MARGIN_TOP + 4.0}",
+            class: "chart_label",
+            text_anchor: "end",
+            "{maximum}"
+        }
+        text {
+            x: "{MARGIN_LEFT - 4.0}",
+            y: "{bottom}",
+            class: "chart_label",
+            text_anchor: "end",
+            "0"
+        }
+    }
+}
+
+/// A line chart with one point for every value. `labels` and `values` need to have the same length.
+#[component]
+pub(crate) fn LineChart(labels: Vec<String>, values: Vec<i64>) -> Element {
+    // hooks have to run on every render, also before there are values
+    let mut hovered = use_signal(|| None::<usize>);
+    if values.is_empty() {
+        return rsx! {};
+    }
+    let maximum = values.iter().copied().max().unwrap_or(0).max(1);
+    let step = (CHART_WIDTH - MARGIN_LEFT) / (values.len().max(2) - 1) as f64;
+    let x_position = |i: usize| MARGIN_LEFT + i as f64 * step;
+
+    let mut points = Vec::new();
+    for (i, value) in values.iter().enumerate() {
+        let command = if i == 0 { "M" } else { "L" };
+        points.push(format!(
+            "{} {:.1} {:.1}",
+            command,
+            x_position(i),
+            y_position(*value, maximum)
+        ));
+    }
+    let line = points.join(" ");
+    let bottom = CHART_HEIGHT - MARGIN_BOTTOM;
+    let area = format!(
+        "{} L {:.1} {:.1} L {:.1} {:.1} Z",
+        line,
+        x_position(values.len() - 1),
+        bottom,
+        MARGIN_LEFT,
+        bottom
+    );
+
+    rsx! {
+        document::Stylesheet { href: CHARTS_CSS }
+        svg {
+            class: "statistics_chart",
+            view_box: "0 0 {CHART_WIDTH} {CHART_HEIGHT}",
+            onmouseleave: move |_| hovered.set(None),
+            Axes { maximum }
+            path { d: area, class: "chart_area" }
+            path { d: line, class: "chart_line" }
+            for i in label_indices(labels.len()) {
+                text {
+                    key: "{i}",
+                    x: "{x_position(i)}",
+                    y: "{CHART_HEIGHT - 4.0}",
+                    class: "chart_label",
+                    text_anchor: "middle",
+                    "{labels[i]}"
+                }
+            }
+            // invisible areas around every point to show its value on hover
+            for i in 0..values.len() {
+                rect {
+                    key: "hover{i}",
+                    x: "{x_position(i) - step / 2.0}",
+                    y: "{MARGIN_TOP}",
+                    width: "{step}",
+                    height: "{CHART_HEIGHT - MARGIN_BOTTOM - MARGIN_TOP}",
+                    class: "chart_hover_area",
+                    onmouseenter: move |_| hovered.set(Some(i)),
+                }
+            }
+            // the hovered index may be left over from a longer series
+            if let Some((i, value)) = hovered().and_then(|i| values.get(i).map(|value| (i, *value))) {
+                circle {
+                    cx: "{x_position(i)}",
+                    cy: "{y_position(value, maximum)}",
+                    r: "3",
+                    class: "chart_marker",
+                }
+                text {
+                    x: "{x_position(i).clamp(MARGIN_LEFT + 40.0, CHART_WIDTH - 40.0)}",
+                    y: "{MARGIN_TOP + 10.0}",
+                    class: "chart_tooltip",
+                    text_anchor: "middle",
+                    {format!("{}: {}", labels.get(i).cloned().unwrap_or_default(), value)}
+                }
+            }
+        }
+    }
+}
+
+/// A bar chart with one bar for every value. `labels` and `values` need to have the same length.
+#[component]
+pub(crate) fn BarChart(labels: Vec<String>, values: Vec<i64>) -> Element {
+    if values.is_empty() {
+        return rsx! {};
+    }
+    let maximum = values.iter().copied().max().unwrap_or(0).max(1);
+    let slot = (CHART_WIDTH - MARGIN_LEFT) / values.len() as f64;
+    let bar_width = (slot * 0.8).max(1.0);
+    let bottom = CHART_HEIGHT - MARGIN_BOTTOM;
+
+    rsx! {
+        document::Stylesheet { href: CHARTS_CSS }
+        svg {
+            class: "statistics_chart",
+            view_box: "0 0 {CHART_WIDTH} {CHART_HEIGHT}",
+            Axes { maximum }
+            for (i, value) in values.iter().copied().enumerate() {
+                rect {
+                    key: "{i}",
+                    x: "{MARGIN_LEFT + i as f64 * slot + (slot - bar_width) / 2.0}",
+                    y: "{y_position(value, maximum)}",
+                    width: "{bar_width}",
+                    height: "{bottom - y_position(value, maximum)}",
+                    class: "chart_bar",
+                }
+            }
+            for i in label_indices(labels.len()) {
+                text {
+                    key: "label{i}",
+                    x: "{MARGIN_LEFT + (i as f64 + 0.5) * slot}",
+                    y: "{CHART_HEIGHT - 4.0}",
+                    class: "chart_label",
+                    text_anchor: "middle",
+                    "{labels[i]}"
+                }
+            }
+        }
+    }
+}
+
+/// The height of one bar in the [`BreakdownChart`].
+const BREAKDOWN_ROW_HEIGHT: f64 = 22.0;
+/// Space for the labels left of the bars in the [`BreakdownChart`].
+const BREAKDOWN_LABEL_WIDTH: f64 = 160.0;
+/// Space for the values right of the bars in the [`BreakdownChart`].
+const BREAKDOWN_VALUE_WIDTH: f64 = 50.0;
+
+/// A horizontal bar chart with one labeled row for every value. `labels` and `values` need to have the same length.
+#[component]
+pub(crate) fn BreakdownChart(labels: Vec<String>, values: Vec<i64>) -> Element {
+    if values.is_empty() {
+        return rsx! {};
+    }
+    let maximum = values.iter().copied().max().unwrap_or(0).max(1);
+    let height = BREAKDOWN_ROW_HEIGHT * values.len() as f64;
+    let bar_space = CHART_WIDTH - BREAKDOWN_LABEL_WIDTH - BREAKDOWN_VALUE_WIDTH;
+
+    rsx! {
+        document::Stylesheet { href: CHARTS_CSS }
+        svg {
+            class: "statistics_chart",
+            view_box: "0 0 {CHART_WIDTH} {height}",
+            for (i, value) in values.iter().copied().enumerate() {
+                g { key: "{i}",
+                    text {
+                        x: "{BREAKDOWN_LABEL_WIDTH - 6.0}",
+                        y: "{(i as f64 + 0.7) * BREAKDOWN_ROW_HEIGHT}",
+                        class: "chart_label",
+                        text_anchor: "end",
+                        "{labels[i]}"
+                    }
+                    rect {
+                        x: "{BREAKDOWN_LABEL_WIDTH}",
+                        y: "{(i as f64 + 0.15) * BREAKDOWN_ROW_HEIGHT}",
+                        width: "{(value as f64 / maximum as f64 * bar_space).max(1.0)}",
+                        height: "{BREAKDOWN_ROW_HEIGHT * 0.7}",
+                        class: "chart_bar",
+                    }
+                    text {
+                        x: "{BREAKDOWN_LABEL_WIDTH + (value as f64 / maximum as f64 * bar_space).max(1.0) + 4.0}",
+                        y: "{(i as f64 + 0.7) * BREAKDOWN_ROW_HEIGHT}",
+                        class: "chart_label",
+                        "{value}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The size of one cell in the [`Heatmap`].
+const HEATMAP_CELL: f64 = 20.0;
+
+/// A grid of cells whose color intensity shows the value.
+///
+/// `values` contains one row for every entry in `row_labels`, each with one value for every entry in `column_labels`.
+#[component]
+pub(crate) fn Heatmap(
+    row_labels: Vec<String>,
+    column_labels: Vec<String>,
+    values: Vec<Vec<i64>>,
+) -> Element {
+    let maximum = values.iter().flatten().copied().max().unwrap_or(0).max(1);
+    let width = MARGIN_LEFT + HEATMAP_CELL * column_labels.len() as f64;
+    let height = MARGIN_BOTTOM + HEATMAP_CELL * row_labels.len() as f64;
+
+    rsx! {
+        document::Stylesheet { href: CHARTS_CSS }
+        svg {
+            class: "statistics_chart",
+            view_box: "0 0 {width} {height}",
+            for (row , label) in row_labels.iter().enumerate() {
+                text {
+                    key: "row{row}",
+                    x: "{MARGIN_LEFT - 4.0}",
+                    y: "{(row as f64 + 0.7) * HEATMAP_CELL}",
+                    class: "chart_label",
+                    text_anchor: "end",
+                    "{label}"
+                }
+            }
+            for (column , label) in column_labels.iter().enumerate() {
+                text {
+                    key: "column{column}",
+                    x: "{MARGIN_LEFT + (column as f64 + 0.5) * HEATMAP_CELL}",
+                    y: "{height - 6.0}",
+                    class: "chart_label",
+                    text_anchor: "middle",
+                    "{label}"
+                }
+            }
+            for (row , cells) in values.iter().enumerate() {
+                for (column , value) in cells.iter().copied().enumerate() {
+                    rect {
+                        key: "{row}-{column}",
+                        x: "{MARGIN_LEFT + column as f64 * HEATMAP_CELL + 1.0}",
+                        y: "{row as f64 * HEATMAP_CELL + 1.0}",
+                        width: "{HEATMAP_CELL - 2.0}",
+                        height: "{HEATMAP_CELL - 2.0}",
+                        class: "chart_heatmap_cell",
+                        fill_opacity: "{0.05 + 0.95 * value as f64 / maximum as f64:.2}",
+                        title { "{value}" }
+                    }
+                }
+            }
+        }
+    }
+}
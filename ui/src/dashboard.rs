@@ -0,0 +1,226 @@
+//! A dashboard with statistics over all links of the instance.
+use std::str::FromStr as _;
+
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+use pslink_shared::apirequests::links::StatisticsPeriod;
+
+use crate::charts::{BarChart, LineChart};
+use crate::navbar::Route;
+
+const PERIODS: [StatisticsPeriod; 5] = [
+    StatisticsPeriod::Week,
+    StatisticsPeriod::Month,
+    StatisticsPeriod::Quarter,
+    StatisticsPeriod::Year,
+    StatisticsPeriod::All,
+];
+
+fn period_label(period: StatisticsPeriod) -> String {
+    match period {
+        StatisticsPeriod::Week => t!("dashboard-period-week"),
+        StatisticsPeriod::Month => t!("dashboard-period-month"),
+        StatisticsPeriod::Quarter => t!("dashboard-period-quarter"),
+        StatisticsPeriod::Year => t!("dashboard-period-year"),
+        StatisticsPeriod::All => t!("dashboard-period-all"),
+    }
+}
+
+#[component]
+pub fn Dashboard() -> Element {
+    let mut period = use_signal(StatisticsPeriod::default);
+    let mut amount = use_signal(|| 10_i64);
+    let mut stale_months = use_signal(|| 12_u32);
+
+    let clicks_per_week =
+        use_resource(|| async move { backend::link_api::get_instance_statistics().await });
+    let links_per_month =
+        use_resource(|| async move { backend::link_api::get_links_per_month().await });
+    let top_links =
+        use_resource(
+            move || async move { backend::link_api::get_top_links(period(), amount()).await },
+        );
+    let top_authors = use_resource(move || async move {
+        backend::link_api::get_top_authors(period(), amount()).await
+    });
+    let stale_links = use_resource(move || async move {
+        backend::link_api::get_stale_links(stale_months(), 100).await
+    });
+
+    rsx! {
+        div { class: "columns",
+            div { class: "column is-half",
+                div { class: "box",
+                    h2 { class: "title is-5", {t!("dashboard-clicks-title")} } // Headline of the chart with all clicks per week
+                    match clicks_per_week() {
+                        Some(Ok(weeks)) => rsx! {
+                            LineChart {
+                                labels: weeks.iter().map(|w| w.full_date.format("%d.%m.").to_string()).collect::<Vec<_>>(),
+                                values: weeks.iter().map(|w| w.total.number).collect::<Vec<_>>(),
+                            }
+                        },
+                        Some(Err(e)) => rsx! {
+                            div { class: "notification is-danger", "{e}" }
+                        },
+                        None => rsx! {
+                            progress { class: "progress is-small is-primary" }
+                        },
+                    }
+                }
+            }
+            div { class: "column is-half",
+                div { class: "box",
+                    h2 { class: "title is-5", {t!("dashboard-links-per-month-title")} } // Headline of the chart with the links created per month
+                    match links_per_month() {
+                        Some(Ok(months)) => rsx! {
+                            BarChart {
+                                labels: months.iter().map(|m| m.month.format("%m/%y").to_string()).collect::<Vec<_>>(),
+                                values: months.iter().map(|m| m.total.number).collect::<Vec<_>>(),
+                            }
+                        },
+                        Some(Err(e)) => rsx! {
+                            div { class: "notification is-danger", "{e}" }
+                        },
+                        None => rsx! {
+                            progress { class: "progress is-small is-primary" }
+                        },
+                    }
+                }
+            }
+        }
+        div { class: "field is-grouped",
+            div { class: "control",
+                label { class: "label", {t!("dashboard-label-period")} } // Label of the period selection
+                div { class: "select is-small",
+                    select {
+                        onchange: move |e| {
+                            if let Ok(new_period) = StatisticsPeriod::from_str(&e.value()) {
+                                period.set(new_period);
+                            }
+                        },
+                        for p in PERIODS {
+                            option {
+                                value: p.as_ref(),
+                                selected: p == period(),
+                                {period_label(p)}
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "control",
+                label { class: "label", {t!("dashboard-label-amount")} } // Label of the selection how many entries are shown
+                div { class: "select is-small",
+                    select {
+                        onchange: move |e| {
+                            if let Ok(new_amount) = e.value().parse() {
+                                amount.set(new_amount);
+                            }
+                        },
+                        for n in [5_i64, 10, 25, 50] {
+                            option { value: "{n}", selected: n == amount(), "{n}" }
+                        }
+                    }
+                }
+            }
+        }
+        div { class: "columns",
+            div { class: "column is-half",
+                div { class: "box",
+                    h2 { class: "title is-5", {t!("dashboard-top-links-title")} } // Headline of the most clicked links
+                    table { class: "table is-striped is-fullwidth is-narrow",
+                        tbody {
+                            tr {
+                                th { {t!("links-table-header-code")} }
+                                th { {t!("links-table-header-description")} }
+                                th { {t!("dashboard-header-clicks")} }
+                            }
+                            if let Some(Ok(list)) = top_links() {
+                                for entry in list {
+                                    tr { key: "{entry.link.id}",
+                                        td {
+                                            Link { to: Route::LinkDetail { id: entry.link.id }, "{entry.link.code}" }
+                                        }
+                                        td { "{entry.link.title}" }
+                                        td { "{entry.clicks.number}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "column is-half",
+                div { class: "box",
+                    h2 { class: "title is-5", {t!("dashboard-top-authors-title")} } // Headline of the most active authors
+                    table { class: "table is-striped is-fullwidth is-narrow",
+                        tbody {
+                            tr {
+                                th { {t!("links-table-header-username")} }
+                                th { {t!("dashboard-header-links-created")} }
+                                th { {t!("dashboard-header-clicks")} }
+                            }
+                            if let Some(Ok(list)) = top_authors() {
+                                for entry in list {
+                                    tr { key: "{entry.user_id}",
+                                        td { "{entry.username}" }
+                                        td { "{entry.links_created.number}" }
+                                        td { "{entry.clicks.number}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        div { class: "box",
+            h2 { class: "title is-5", {t!("dashboard-stale-links-title", months : stale_months())} } // Headline of the links without clicks
+            div { class: "field is-grouped",
+                div { class: "control",
+                    label { class: "label", {t!("dashboard-label-stale-months")} } // Label of the number of months without clicks
+                    input {
+                        class: "input is-small",
+                        r#type: "number",
+                        min: "1",
+                        value: "{stale_months}",
+                        onchange: move |e| {
+                            if let Ok(months) = e.value().parse::<u32>() {
+                                stale_months.set(months.max(1));
+                            }
+                        },
+                    }
+                }
+            }
+            table { class: "table is-striped is-fullwidth is-narrow",
+                tbody {
+                    tr {
+                        th { {t!("links-table-header-code")} }
+                        th { {t!("links-table-header-description")} }
+                        th { {t!("links-table-header-username")} }
+                        th { {t!("dashboard-header-created")} }
+                        th { {t!("dashboard-header-last-click")} }
+                    }
+                    if let Some(Ok(list)) = stale_links() {
+                        for entry in list {
+                            tr { key: "{entry.link.id}",
+                                td {
+                                    Link { to: Route::LinkDetail { id: entry.link.id }, "{entry.link.code}" }
+                                }
+                                td { "{entry.link.title}" }
+                                td { "{entry.author}" }
+                                td { "{entry.link.created_at.date()}" }
+                                td {
+                                    match entry.last_click {
+                                        Some(last_click) => last_click.date().to_string(),
+                                        None => t!("dashboard-never-clicked"),
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
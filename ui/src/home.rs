@@ -1,4 +1,5 @@
 use crate::PslinkContext;
+use crate::dashboard::Dashboard;
 use dioxus::prelude::*;
 use dioxus_i18n::t;
 
@@ -9,6 +10,7 @@ pub fn Home() -> Element {
     rsx! {
         if let Some(user) = user.cloned() {
             h1 { {t!("welcome", username : user.username)} } // Welcome message with the username
+            div { class: "container is-fluid p-4", Dashboard {} }
         } else {
             h1 { {t!("welcome-stranger")} } // Welcome message for strangers
         }
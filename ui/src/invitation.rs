@@ -0,0 +1,203 @@
+//! The public page where an invited user creates an account.
+use dioxus::{logger::tracing::info, prelude::*};
+use dioxus_i18n::{prelude::i18n, t, unic_langid::langid};
+use pslink_shared::{
+    BULMA_CSS,
+    apirequests::users::{AcceptInvitation, LoginResult},
+    datatypes::Lang,
+};
+
+use crate::{PslinkContext, navbar::Route, users::TotpSetup};
+
+const LOGIN_CSS: Asset = asset!("/assets/styling/login.css");
+
+#[component]
+pub fn InvitationScreen(token: String) -> Element {
+    let mut username = use_signal(String::new);
+    let mut email = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut password_repeat = use_signal(String::new);
+    let mut language = use_signal(|| Lang::EnUS);
+    let mut nachricht: Signal<Option<String>> = use_signal(|| None);
+    let mut setup = use_signal(|| false);
+    let PslinkContext { mut user, .. } = use_context::<PslinkContext>();
+    let mut language_selector = i18n();
+    let nav = navigator();
+    let token_for_info = token.clone();
+    let invitation = use_resource(move || {
+        let token = token_for_info.clone();
+        async move {
+            let info = backend::invitation_api::get_invitation(token).await;
+            if let Ok(info) = &info {
+                email.set(info.email.clone().unwrap_or_default());
+            }
+            info
+        }
+    });
+
+    rsx! {
+        document::Stylesheet { href: BULMA_CSS }
+        document::Stylesheet { href: LOGIN_CSS }
+        div { class: "modal is-active",
+            div { class: "modal-background" }
+            div { class: "modal-card",
+                header { class: "modal-card-head",
+                    p { class: "modal-card-title", {t!("invitation-headline")} } // Headline of the page that creates an account with an invitation
+                }
+                div { class: "modal-card-body",
+                    match invitation() {
+                        Some(Ok(_)) if setup() => rsx! {
+                            p { class: "mb-3", {t!("totp-setup-required")} }
+                            TotpSetup {
+                                on_finished: move |_| async move {
+                                    if let Ok(info) = backend::auth_api::get_session_info().await {
+                                        user.set(info.user);
+                                    }
+                                    nav.push(Route::Links {});
+                                },
+                            }
+                        },
+                        Some(Ok(info)) => rsx! {
+                            p { class: "mb-3",
+                                {t!("invitation-description", expires : info.expires_at.format("%Y-%m-%d %H:%M").to_string())} // Explanation of the invitation page with the time the invitation expires
+                            }
+                            div { class: "field",
+                                label { class: "label", {t!("user-edit-label-username")} }
+                                div { class: "control",
+                                    input {
+                                        autofocus: true,
+                                        r#type: "text",
+                                        class: "input",
+                                        autocomplete: "username",
+                                        placeholder: t!("user-edit-placeholder-username"),
+                                        value: "{username}",
+                                        oninput: move |e| username.set(e.value()),
+                                    }
+                                }
+                            }
+                            div { class: "field",
+                                label { class: "label", {t!("user-edit-label-email")} }
+                                div { class: "control",
+                                    input {
+                                        r#type: "email",
+                                        class: "input",
+                                        autocomplete: "email",
+                                        placeholder: t!("user-edit-placeholder-email"),
+                                        value: "{email}",
+                                        oninput: move |e| email.set(e.value()),
+                                    }
+                                }
+                            }
+                            div { class: "field",
+                                label { class: "label", {t!("user-edit-label-password")} }
+                                div { class: "control",
+                                    input {
+                                        r#type: "password",
+                                        class: "input",
+                                        autocomplete: "new-password",
+                                        placeholder: t!("user-edit-placeholder-password"),
+                                        value: "{password}",
+                                        oninput: move |e| password.set(e.value()),
+                                    }
+                                }
+                            }
+                            div { class: "field",
+                                label { class: "label", {t!("invitation-label-password-repeat")} } // Label of the field that repeats the new password
+                                div { class: "control",
+                                    input {
+                                        r#type: "password",
+                                        class: "input",
+                                        autocomplete: "new-password",
+                                        value: "{password_repeat}",
+                                        oninput: move |e| password_repeat.set(e.value()),
+                                    }
+                                }
+                            }
+                            div { class: "field",
+                                label { class: "label", {t!("language")} }
+                                div { class: "control",
+                                    div { class: "select",
+                                        select {
+                                            onchange: move |e| {
+                                                let lang = e.value().parse().unwrap_or(Lang::EnUS);
+                                                language.set(lang);
+                                                language_selector.set_language(match lang {
+                                                    Lang::EnUS => langid!("en-US"),
+                                                    Lang::DeDE => langid!("de-DE"),
+                                                });
+                                            },
+                                            option {
+                                                value: "en-US",
+                                                selected: language() == Lang::EnUS,
+                                                "English"
+                                            }
+                                            option {
+                                                value: "de-DE",
+                                                selected: language() == Lang::DeDE,
+                                                "Deutsch"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                        Some(Err(e)) => rsx! {
+                            div { class: "notification is-danger",
+                                {t!("invitation-invalid")} // Message when the invitation was used, revoked or expired
+                                " "
+                                "{e}"
+                            }
+                        },
+                        None => rsx! {
+                            progress { class: "progress is-small is-primary" }
+                        },
+                    }
+                    if let Some(nachricht) = nachricht() {
+                        div { class: "notification is-danger", {nachricht} }
+                    }
+                }
+                footer { class: "modal-card-foot is-justify-content-flex-end",
+                    if let Some(Ok(_)) = invitation() {
+                        if !setup() {
+                            button {
+                                class: "button is-primary",
+                                disabled: username().trim().is_empty() || password().is_empty(),
+                                onclick: move |_| {
+                                    let token = token.clone();
+                                    async move {
+                                        if password() != password_repeat() {
+                                            nachricht.set(Some(t!("invitation-password-mismatch"))); // Message when the repeated password differs
+                                            return;
+                                        }
+                                        let data = AcceptInvitation {
+                                            token,
+                                            username: username(),
+                                            email: email(),
+                                            password: password(),
+                                            language: language(),
+                                        };
+                                        match backend::invitation_api::accept_invitation(data).await {
+                                            Ok(LoginResult::LoggedIn(u)) => {
+                                                user.set(Some(u));
+                                                nav.push(Route::Links {});
+                                            }
+                                            Ok(_) => {
+                                                nachricht.set(None);
+                                                setup.set(true);
+                                            }
+                                            Err(e) => {
+                                                info!("Failed to accept the invitation: {:?}", e);
+                                                nachricht.set(Some(e.to_string()));
+                                            }
+                                        }
+                                    }
+                                },
+                                {t!("invitation-button-create")} // Button to create the account of the invitation
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,12 @@
 //! This crate contains all shared UI for the workspace.
 
+mod charts;
+mod dashboard;
 pub mod home;
+mod invitation;
 mod links;
 pub mod login;
+mod password_reset;
 pub mod translations;
 mod users;
 use dioxus::signals::Signal;
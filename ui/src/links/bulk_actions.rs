@@ -0,0 +1,131 @@
+//! Delete, transfer or export the selected links at once.
+use std::collections::BTreeSet;
+
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+use pslink_shared::apirequests::links::{BulkLinkOperation, BulkLinkRequest};
+
+use crate::links::download_file;
+
+/// The actions on the selected links.
+///
+/// `on_select_matching` selects all links matching the filter, not only the loaded ones.
+/// `on_change` is called after links were deleted or transferred so the list can be reloaded.
+#[component]
+pub fn BulkActionBar(
+    selected: Signal<BTreeSet<i64>>,
+    on_select_matching: EventHandler<()>,
+    on_change: EventHandler<()>,
+) -> Element {
+    let mut new_author = use_signal(String::new);
+    let mut confirm_delete = use_signal(|| false);
+    let mut message = use_signal(|| None::<Result<String, String>>);
+    let mut running = use_signal(|| false);
+
+    let run = move |operation: BulkLinkOperation| async move {
+        running.set(true);
+        let request = BulkLinkRequest {
+            links: selected().into_iter().collect(),
+            operation,
+        };
+        match backend::link_api::bulk_link_operation(request).await {
+            Ok(count) => {
+                message.set(Some(Ok(t!("bulk-actions-done", count : count))));
+                selected.set(BTreeSet::new());
+                on_change.call(());
+            }
+            Err(e) => message.set(Some(Err(e.to_string()))),
+        }
+        confirm_delete.set(false);
+        running.set(false);
+    };
+    let export = move |_| async move {
+        let links: Vec<i64> = selected().into_iter().collect();
+        match backend::link_api::export_links(links).await {
+            Ok(csv) => download_file(csv.as_bytes(), "text/csv", "links.csv"),
+            Err(e) => message.set(Some(Err(e.to_string()))),
+        }
+    };
+    let nothing_selected = selected().is_empty() || running();
+
+    rsx! {
+        div { class: "field is-grouped is-grouped-multiline p-2",
+            div { class: "control",
+                label { class: "label is-small",
+                    {t!("bulk-actions-selected", count : selected().len())} // Label with the number of selected links
+                }
+                div { class: "buttons has-addons",
+                    button { class: "button is-small", onclick: move |_| on_select_matching.call(()),
+                        {t!("bulk-actions-button-select-matching")} // Button to select all links matching the filter, not only the loaded ones
+                    }
+                    button {
+                        class: "button is-small",
+                        disabled: selected().is_empty(),
+                        onclick: move |_| selected.set(BTreeSet::new()),
+                        {t!("bulk-actions-button-clear")} // Button to clear the selection of links
+                    }
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("bulk-actions-label-author")} } // Label of the username the selected links are transferred to
+                div { class: "field has-addons",
+                    div { class: "control",
+                        input {
+                            class: "input is-small",
+                            r#type: "text",
+                            value: "{new_author}",
+                            oninput: move |e| new_author.set(e.value()),
+                        }
+                    }
+                    div { class: "control",
+                        button {
+                            class: "button is-small is-link",
+                            disabled: nothing_selected || new_author().trim().is_empty(),
+                            onclick: move |_| run(BulkLinkOperation::Transfer(new_author().trim().to_string())),
+                            {t!("bulk-actions-button-transfer")} // Button to make the entered user the author of the selected links
+                        }
+                    }
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", "\u{a0}" }
+                div { class: "buttons",
+                    button {
+                        class: "button is-small",
+                        disabled: nothing_selected,
+                        onclick: export,
+                        {t!("bulk-actions-button-export")} // Button to download the selected links as CSV
+                    }
+                    if confirm_delete() {
+                        button {
+                            class: "button is-small is-danger",
+                            disabled: nothing_selected,
+                            onclick: move |_| run(BulkLinkOperation::Delete),
+                            {t!("bulk-actions-button-confirm-delete", count : selected().len())} // Button to confirm deleting the selected links
+                        }
+                    } else {
+                        button {
+                            class: "button is-small is-danger is-outlined",
+                            disabled: nothing_selected,
+                            onclick: move |_| confirm_delete.set(true),
+                            {t!("bulk-actions-button-delete")} // Button to delete the selected links, it asks for a confirmation
+                        }
+                    }
+                }
+            }
+            match message() {
+                Some(Ok(text)) => rsx! {
+                    div { class: "control",
+                        div { class: "notification is-success is-light p-2", "{text}" }
+                    }
+                },
+                Some(Err(text)) => rsx! {
+                    div { class: "control",
+                        div { class: "notification is-danger is-light p-2", "{text}" }
+                    }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
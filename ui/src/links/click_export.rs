@@ -0,0 +1,107 @@
+use std::str::FromStr as _;
+
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+use pslink_shared::apirequests::links::{ClickAggregation, ClickExportFormat, ClickExportQuery};
+
+const FORMATS: [ClickExportFormat; 2] = [ClickExportFormat::Csv, ClickExportFormat::Json];
+const AGGREGATIONS: [ClickAggregation; 4] = [
+    ClickAggregation::Click,
+    ClickAggregation::Day,
+    ClickAggregation::Week,
+    ClickAggregation::Month,
+];
+
+fn aggregation_label(aggregation: ClickAggregation) -> String {
+    match aggregation {
+        ClickAggregation::Click => t!("click-export-aggregation-click"),
+        ClickAggregation::Day => t!("click-export-aggregation-day"),
+        ClickAggregation::Week => t!("click-export-aggregation-week"),
+        ClickAggregation::Month => t!("click-export-aggregation-month"),
+    }
+}
+
+/// The settings of the click export and a button to export the clicks of all own links.
+///
+/// The settings are also used by the export buttons of the single links.
+#[component]
+pub fn ClickExportBar(export_settings: Signal<ClickExportQuery>) -> Element {
+    let all_links_url = use_memo(move || {
+        let mut query = export_settings();
+        query.links = String::new();
+        query.url()
+    });
+    rsx! {
+        div { class: "field is-grouped is-grouped-multiline p-2",
+            div { class: "control",
+                label { class: "label is-small", {t!("click-export-label-format")} } // Label of the file format of the click export
+                div { class: "select is-small",
+                    select {
+                        onchange: move |e| {
+                            if let Ok(format) = ClickExportFormat::from_str(&e.value()) {
+                                export_settings.write().format = format;
+                            }
+                        },
+                        for format in FORMATS {
+                            option {
+                                value: format.as_ref(),
+                                selected: format == export_settings().format,
+                                {format.as_ref().to_uppercase()}
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("click-export-label-aggregation")} } // Label of the grouping of the exported clicks
+                div { class: "select is-small",
+                    select {
+                        onchange: move |e| {
+                            if let Ok(aggregation) = ClickAggregation::from_str(&e.value()) {
+                                export_settings.write().aggregation = aggregation;
+                            }
+                        },
+                        for aggregation in AGGREGATIONS {
+                            option {
+                                value: aggregation.as_ref(),
+                                selected: aggregation == export_settings().aggregation,
+                                {aggregation_label(aggregation)}
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("click-export-label-from")} } // Label of the first day of the click export
+                input {
+                    class: "input is-small",
+                    r#type: "date",
+                    value: export_settings().from.map(|d| d.to_string()).unwrap_or_default(),
+                    onchange: move |e| {
+                        export_settings.write().from = e.value().parse().ok();
+                    },
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("click-export-label-to")} } // Label of the last day of the click export
+                input {
+                    class: "input is-small",
+                    r#type: "date",
+                    value: export_settings().to.map(|d| d.to_string()).unwrap_or_default(),
+                    onchange: move |e| {
+                        export_settings.write().to = e.value().parse().ok();
+                    },
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", "\u{a0}" }
+                a {
+                    class: "button is-small is-link",
+                    href: all_links_url(),
+                    download: "",
+                    {t!("click-export-button-all")} // Button to export the clicks of all own links
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! A page with the detailed statistics of a single link.
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+use pslink_shared::apirequests::users::Role;
+use pslink_shared::datatypes::{Clicks, DeviceClass, LinkDetails};
+use pslink_shared::qr::QrStyle;
+
+use crate::PslinkContext;
+use crate::charts::{BreakdownChart, Heatmap, LineChart};
+use crate::links::share_tokens::ShareTokens;
+use crate::links::{
+    generate_blob_url_from_png, generate_png_qr_from_url, generate_svg_qr_from_url,
+    generate_url_for_code,
+};
+use crate::navbar::Route;
+
+/// The order of the days in the heatmap, the statistics start with sunday.
+const WEEKDAY_ORDER: [usize; 7] = [1, 2, 3, 4, 5, 6, 0];
+
+fn weekday_label(weekday: usize) -> String {
+    match weekday {
+        0 => t!("link-detail-weekday-sunday"),
+        1 => t!("link-detail-weekday-monday"),
+        2 => t!("link-detail-weekday-tuesday"),
+        3 => t!("link-detail-weekday-wednesday"),
+        4 => t!("link-detail-weekday-thursday"),
+        5 => t!("link-detail-weekday-friday"),
+        _ => t!("link-detail-weekday-saturday"),
+    }
+}
+
+fn device_label(device: DeviceClass) -> String {
+    match device {
+        DeviceClass::Desktop => t!("link-detail-device-desktop"),
+        DeviceClass::Mobile => t!("link-detail-device-mobile"),
+        DeviceClass::Tablet => t!("link-detail-device-tablet"),
+        DeviceClass::Bot => t!("link-detail-device-bot"),
+        DeviceClass::Unknown => t!("link-detail-device-unknown"),
+    }
+}
+
+#[component]
+pub fn LinkDetail(id: i64) -> Element {
+    let details =
+        use_resource(move || async move { backend::link_api::get_link_details(id).await });
+    let qr_style = use_resource(move || async move { backend::qr_api::get_qr_style(id).await });
+
+    rsx! {
+        div { class: "container is-fluid p-4",
+            Link { to: Route::Links {}, {t!("link-detail-back")} } // Link back to the list of links
+            match (details(), qr_style()) {
+                (Some(Ok(details)), Some(qr_style)) => rsx! {
+                    DetailView { details, qr_style: qr_style.unwrap_or_default() }
+                },
+                (Some(Err(e)), _) => rsx! {
+                    div { class: "notification is-danger", "{e}" }
+                },
+                _ => rsx! {
+                    progress { class: "progress is-small is-primary" }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn DetailView(details: LinkDetails, qr_style: QrStyle) -> Element {
+    let PslinkContext { user, hostname } = use_context::<PslinkContext>();
+    let link = details.link.link.clone();
+    let url = generate_url_for_code(&link.code, &hostname());
+    let qr_svg = generate_svg_qr_from_url(&url, &qr_style);
+    let mut filename = url
+        .replace("https://", "")
+        .replace("/", "_")
+        .replace(":", "_");
+    filename.push_str(".png");
+    let png_url = use_memo({
+        let url = url.clone();
+        let qr_style = qr_style.clone();
+        move || generate_blob_url_from_png(generate_png_qr_from_url(&url, &qr_style))
+    });
+    let may_share = user().is_some_and(|u| u.role == Role::Admin || u.id == link.author);
+    let total = match &details.link.clicks {
+        Clicks::Count(count) => count.number,
+        Clicks::Extended(statistics) => statistics.total.number,
+    };
+
+    let heatmap: Vec<Vec<i64>> = WEEKDAY_ORDER
+        .iter()
+        .map(|day| details.heatmap.get(*day).cloned().unwrap_or_default())
+        .collect();
+
+    rsx! {
+        h1 { class: "title", "{link.title}" }
+        div { class: "columns",
+            div { class: "column",
+                div { class: "box",
+                    table { class: "table is-fullwidth",
+                        tbody {
+                            tr {
+                                th { {t!("links-table-header-code")} }
+                                td { "{link.code}" }
+                            }
+                            tr {
+                                th { {t!("link-detail-short-url")} } // Label of the complete short url
+                                td { "{url}" }
+                            }
+                            tr {
+                                th { {t!("links-table-header-target")} }
+                                td { style: "word-wrap:anywhere;", "{link.target}" }
+                            }
+                            tr {
+                                th { {t!("links-table-header-username")} }
+                                td { "{details.link.user.username}" }
+                            }
+                            tr {
+                                th { {t!("dashboard-header-created")} }
+                                td { "{link.created_at.date()}" }
+                            }
+                            tr {
+                                th { {t!("link-detail-total-clicks")} } // Label of the number of all clicks of the link
+                                td { "{total}" }
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "column is-narrow",
+                div { class: "box", style: "width: 200px;",
+                    a {
+                        href: png_url(),
+                        download: filename,
+                        title: t!("link-detail-download-qr"), // Tooltip of the qr code to download it as png
+                        dangerous_inner_html: qr_svg,
+                    }
+                }
+            }
+        }
+        div { class: "box",
+            h2 { class: "title is-5", {t!("link-detail-daily-title")} } // Headline of the chart with the clicks per day
+            LineChart {
+                labels: details.daily.iter().map(|d| d.date.format("%d.%m.%y").to_string()).collect::<Vec<_>>(),
+                values: details.daily.iter().map(|d| d.total.number).collect::<Vec<_>>(),
+            }
+        }
+        div { class: "columns",
+            div { class: "column is-half",
+                div { class: "box",
+                    h2 { class: "title is-5", {t!("link-detail-referrers-title")} } // Headline of the chart with the referring websites
+                    if details.referrers.is_empty() {
+                        p { {t!("link-detail-no-clicks")} } // Shown instead of a chart if the link was never clicked
+                    }
+                    BreakdownChart {
+                        labels: details
+                            .referrers
+                            .iter()
+                            .map(|r| if r.name.is_empty() { t!("link-detail-referrer-direct") } else { r.name.clone() })
+                            .collect::<Vec<_>>(),
+                        values: details.referrers.iter().map(|r| r.total.number).collect::<Vec<_>>(),
+                    }
+                }
+            }
+            div { class: "column is-half",
+                div { class: "box",
+                    h2 { class: "title is-5", {t!("link-detail-devices-title")} } // Headline of the chart with the device classes
+                    if details.devices.is_empty() {
+                        p { {t!("link-detail-no-clicks")} }
+                    }
+                    BreakdownChart {
+                        labels: details.devices.iter().map(|(device, _)| device_label(*device)).collect::<Vec<_>>(),
+                        values: details.devices.iter().map(|(_, count)| count.number).collect::<Vec<_>>(),
+                    }
+                }
+            }
+        }
+        div { class: "box",
+            h2 { class: "title is-5", {t!("link-detail-heatmap-title")} } // Headline of the heatmap of clicks by weekday and hour
+            Heatmap {
+                row_labels: WEEKDAY_ORDER.iter().map(|day| weekday_label(*day)).collect::<Vec<_>>(),
+                column_labels: (0..24).map(|hour| format!("{hour}")).collect::<Vec<_>>(),
+                values: heatmap,
+            }
+        }
+        if may_share {
+            ShareTokens { link_id: link.id }
+        }
+    }
+}
@@ -1,10 +1,14 @@
+use std::collections::BTreeSet;
+
 use dioxus::logger::tracing::info;
 use dioxus::prelude::*;
 use dioxus_i18n::t;
 use indexmap::IndexMap;
 use pslink_shared::apirequests::general::EditMode;
+use pslink_shared::apirequests::links::ClickExportQuery;
 use pslink_shared::apirequests::users::Role;
 use pslink_shared::datatypes::{Clicks, Count, FullLink};
+use pslink_shared::qr::QrStyle;
 
 use crate::PslinkContext;
 use crate::links::EditDialog;
@@ -12,7 +16,9 @@ use crate::links::OptionEditDialog as _;
 use crate::links::generate_svg_qr_from_url;
 use crate::links::generate_url_for_code;
 use crate::links::stats::Stats;
+use crate::navbar::Route;
 const TRASH_SVG: Asset = asset!("/assets/trash.svg");
+const DOWNLOAD_SVG: Asset = asset!("/assets/download.svg");
 const VANISHING_MESSAGE: Asset = asset!("/assets/styling/vanishing_message.css");
 
 #[component]
@@ -21,10 +27,13 @@ pub fn LinkDisplay(
     links: Signal<IndexMap<String, FullLink>>,
     link_stats: Signal<IndexMap<String, Clicks>>,
     link_signal: Signal<Option<EditDialog>>,
+    export_settings: Signal<ClickExportQuery>,
+    selected: Signal<BTreeSet<i64>>,
 ) -> Element {
     let ll = use_memo(move || links().get(&current_code).cloned());
     let mut nachricht: Signal<Option<String>> = use_signal(move || None);
     let PslinkContext { user, hostname } = use_context::<PslinkContext>();
+    let nav = navigator();
     let mut timer = use_resource(move || {
         let delay = std::time::Duration::from_secs(3);
         let mut nachricht = nachricht.clone();
@@ -37,7 +46,7 @@ pub fn LinkDisplay(
         if let Some(link_data) = ll() {
             let code = link_data.link.code.clone();
             let url = generate_url_for_code(&code, &hostname());
-            generate_svg_qr_from_url(&url)
+            generate_svg_qr_from_url(&url, &QrStyle::default())
         } else {
             String::new()
         }
@@ -83,6 +92,22 @@ pub fn LinkDisplay(
                     }
                 },
 
+                td { onclick: move |e: Event<MouseData>| e.stop_propagation(),
+                    input {
+                        r#type: "checkbox",
+                        checked: selected().contains(&link_data.link.id),
+                        onchange: {
+                            let link_id = link_data.link.id;
+                            move |e: Event<FormData>| {
+                                if e.checked() {
+                                    selected.write().insert(link_id);
+                                } else {
+                                    selected.write().remove(&link_id);
+                                }
+                            }
+                        },
+                    }
+                }
                 td { "{link_data.link.code}" }
                 td { "{link_data.link.title}" }
                 td {
@@ -94,10 +119,36 @@ pub fn LinkDisplay(
                 }
                 td { "{link_data.user.username}" }
                 td {
+                    class: "cursor-pointer",
+                    title: t!("links-tooltip-details"), // Tooltip of the statistics, a click opens the detail page
+                    onclick: {
+                        let link_id = link_data.link.id;
+                        move |e: Event<MouseData>| {
+                            e.stop_propagation();
+                            nav.push(Route::LinkDetail { id: link_id });
+                        }
+                    },
                     Stats {
                         clicks: stats() }
                 }
                 td { class: "table_qr", dangerous_inner_html: qr_code_svg }
+                td {
+                    if user()
+                        .is_some_and(|u| u.role == Role::Admin || u.id == link_data.link.author)
+                    {
+                        a {
+                            href: ClickExportQuery {
+                                links: link_data.link.id.to_string(),
+                                ..export_settings()
+                            }
+                                .url(),
+                            download: "",
+                            title: t!("click-export-tooltip-link"), // Tooltip of the button to export the clicks of one link
+                            onclick: move |e: Event<MouseData>| e.stop_propagation(),
+                            img { src: DOWNLOAD_SVG, class: "downloadicon" }
+                        }
+                    }
+                }
                 td {
                     onclick: {
                         let link_data_clone = link_data.clone();
@@ -7,7 +7,10 @@ use pslink_shared::{apirequests::general::EditMode, datatypes::FullLink};
 
 use crate::{
     PslinkContext,
-    links::{EditDialog, OptionEditDialog as _},
+    links::{
+        EditDialog, OptionEditDialog as _,
+        qr_designer::{QrDesigner, QrDownload},
+    },
 };
 
 #[component]
@@ -21,6 +24,14 @@ pub fn LinkEdit(
             edit_link.set(None);
         }
     };
+    let link_id = use_memo(move || edit_link().and_then(|dialog| dialog.link_delta.id));
+    let _style_loader = use_resource(move || async move {
+        if let Some(id) = link_id() {
+            if let Ok(style) = backend::qr_api::get_qr_style(id).await {
+                edit_link.update_qr_style(style, &hostname.peek());
+            }
+        }
+    });
     let mut description_field: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
     let _focus_grabber = use_resource(move || async move {
         if description_field().is_some() {
@@ -124,6 +135,10 @@ pub fn LinkEdit(
                                             download: png_qr_url.1.clone(),
                                         }
                                     }
+                                    div { class: "field ml-3",
+                                        QrDesigner { edit_link }
+                                        QrDownload { edit_link }
+                                    }
                                 }
                             }
                         }
@@ -188,7 +203,13 @@ fn Buttons(
                                     if let Some(dialog) = edit_link() {
                                         let link_delta = dialog.link_delta;
                                         info!("Link delta: {:?}", link_delta);
-                                        let _res = backend::link_api::create_link(link_delta).await;
+                                        if let Ok(created) = backend::link_api::create_link(link_delta).await {
+                                            let _res = backend::qr_api::save_qr_style(
+                                                    created.item.id,
+                                                    dialog.qr_style,
+                                                )
+                                                .await;
+                                        }
                                         links.set(IndexMap::new());
                                         edit_link.set(None);
                                     }
@@ -217,7 +238,13 @@ fn Buttons(
                                     if let Some(dialog) = edit_link() {
                                         let link_delta = dialog.link_delta;
                                         info!("Link delta: {:?}", link_delta);
-                                        let _res = backend::link_api::save_link(link_delta).await;
+                                        if let Ok(saved) = backend::link_api::save_link(link_delta).await {
+                                            let _res = backend::qr_api::save_qr_style(
+                                                    saved.item.id,
+                                                    dialog.qr_style,
+                                                )
+                                                .await;
+                                        }
                                         links.set(IndexMap::new());
                                         edit_link.set(None);
                                     }
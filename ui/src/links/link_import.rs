@@ -0,0 +1,207 @@
+//! Import many links from a CSV or JSON file, checked in a dry run before anything is written.
+use std::str::FromStr as _;
+
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+use pslink_shared::apirequests::links::{
+    ImportConflictPolicy, ImportRowAction, LinkImportFormat, LinkImportReport, LinkImportRequest,
+};
+
+use crate::navbar::Route;
+
+const FORMATS: [LinkImportFormat; 2] = [LinkImportFormat::Csv, LinkImportFormat::Json];
+const POLICIES: [ImportConflictPolicy; 3] = [
+    ImportConflictPolicy::Skip,
+    ImportConflictPolicy::Overwrite,
+    ImportConflictPolicy::Rename,
+];
+
+fn policy_label(policy: ImportConflictPolicy) -> String {
+    match policy {
+        ImportConflictPolicy::Skip => t!("link-import-policy-skip"),
+        ImportConflictPolicy::Overwrite => t!("link-import-policy-overwrite"),
+        ImportConflictPolicy::Rename => t!("link-import-policy-rename"),
+    }
+}
+
+fn action_label(action: &ImportRowAction) -> (String, &'static str) {
+    match action {
+        ImportRowAction::Create => (t!("link-import-action-create"), "is-success"),
+        ImportRowAction::Overwrite => (t!("link-import-action-overwrite"), "is-warning"),
+        ImportRowAction::Rename(code) => (t!("link-import-action-rename", code : code), "is-info"),
+        ImportRowAction::Skip => (t!("link-import-action-skip"), "is-light"),
+        ImportRowAction::Invalid => (t!("link-import-action-invalid"), "is-danger"),
+    }
+}
+
+#[component]
+pub fn LinkImport() -> Element {
+    let mut format = use_signal(LinkImportFormat::default);
+    let mut policy = use_signal(ImportConflictPolicy::default);
+    let mut data = use_signal(String::new);
+    let mut report = use_signal(|| None::<LinkImportReport>);
+    let mut error = use_signal(|| None::<String>);
+    let mut running = use_signal(|| false);
+
+    let run_import = move |dry_run: bool| async move {
+        running.set(true);
+        error.set(None);
+        let request = LinkImportRequest {
+            format: format(),
+            data: data(),
+            policy: policy(),
+            dry_run,
+        };
+        match backend::link_api::import_links(request).await {
+            Ok(result) => report.set(Some(result)),
+            Err(e) => {
+                report.set(None);
+                error.set(Some(e.to_string()));
+            }
+        }
+        running.set(false);
+    };
+    // the import is only possible after a successful check of the current file and settings
+    let checked = report().is_some_and(|report| !report.applied && !report.has_errors());
+
+    rsx! {
+        div { class: "container is-fluid p-4",
+            Link { to: Route::Links {}, {t!("link-import-back")} } // Link back to the list of links
+            h1 { class: "title", {t!("link-import-title")} } // Headline of the link import page
+            p { class: "mb-3", {t!("link-import-description")} } // Explanation of the columns of the import file
+            div { class: "field is-grouped is-grouped-multiline",
+                div { class: "control",
+                    label { class: "label is-small", {t!("link-import-label-file")} } // Label of the file upload of the link import
+                    input {
+                        class: "input is-small",
+                        r#type: "file",
+                        accept: ".csv,.json",
+                        onchange: move |e| async move {
+                            if let Some(engine) = e.files() {
+                                if let Some(name) = engine.files().first() {
+                                    format.set(LinkImportFormat::from_filename(name));
+                                    if let Some(content) = engine.read_file_to_string(name).await {
+                                        data.set(content);
+                                        report.set(None);
+                                    }
+                                }
+                            }
+                        },
+                    }
+                }
+                div { class: "control",
+                    label { class: "label is-small", {t!("link-import-label-format")} } // Label of the file format of the link import
+                    div { class: "select is-small",
+                        select {
+                            onchange: move |e| {
+                                if let Ok(new_format) = LinkImportFormat::from_str(&e.value()) {
+                                    format.set(new_format);
+                                    report.set(None);
+                                }
+                            },
+                            for option_format in FORMATS {
+                                option {
+                                    value: option_format.as_ref(),
+                                    selected: option_format == format(),
+                                    {option_format.as_ref().to_uppercase()}
+                                }
+                            }
+                        }
+                    }
+                }
+                div { class: "control",
+                    label { class: "label is-small", {t!("link-import-label-policy")} } // Label of what happens to links whose code exists
+                    div { class: "select is-small",
+                        select {
+                            onchange: move |e| {
+                                if let Ok(new_policy) = ImportConflictPolicy::from_str(&e.value()) {
+                                    policy.set(new_policy);
+                                    report.set(None);
+                                }
+                            },
+                            for option_policy in POLICIES {
+                                option {
+                                    value: option_policy.as_ref(),
+                                    selected: option_policy == policy(),
+                                    {policy_label(option_policy)}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "field",
+                textarea {
+                    class: "textarea is-family-monospace is-small",
+                    rows: "8",
+                    placeholder: "title,target,code\nExample,https://example.com,example",
+                    value: "{data}",
+                    oninput: move |e| {
+                        data.set(e.value());
+                        report.set(None);
+                    },
+                }
+            }
+            div { class: "buttons",
+                button {
+                    class: "button is-info",
+                    class: if running() { "is-loading" },
+                    disabled: data().trim().is_empty(),
+                    onclick: move |_| run_import(true),
+                    {t!("link-import-button-check")} // Button to validate the import without changing anything
+                }
+                button {
+                    class: "button is-success",
+                    class: if running() { "is-loading" },
+                    disabled: !checked,
+                    onclick: move |_| run_import(false),
+                    {t!("link-import-button-apply")} // Button to import the checked links
+                }
+            }
+            if let Some(msg) = error() {
+                div { class: "notification is-danger", "{msg}" }
+            }
+            if let Some(report) = report() {
+                if report.applied {
+                    div { class: "notification is-success",
+                        {t!("link-import-success", count : report.rows.len())} // Message after the links were imported
+                    }
+                } else if report.has_errors() {
+                    div { class: "notification is-danger", {t!("link-import-has-errors")} } // Message that the import cannot be applied because of invalid rows
+                }
+                table { class: "table is-fullwidth is-narrow is-striped",
+                    thead {
+                        tr {
+                            th { {t!("link-import-header-row")} } // Column header of the row number in the import report
+                            th { {t!("links-table-header-code")} }
+                            th { {t!("links-table-header-description")} }
+                            th { {t!("links-table-header-target")} }
+                            th { {t!("link-import-header-action")} } // Column header of what happens to the row
+                        }
+                    }
+                    tbody {
+                        for row in report.rows {
+                            tr { key: "{row.row}",
+                                td { "{row.row}" }
+                                td { "{row.code}" }
+                                td { "{row.title}" }
+                                td { style: "word-wrap:anywhere;", "{row.target}" }
+                                td {
+                                    {
+                                        let (label, color) = action_label(&row.action);
+                                        rsx! {
+                                            span { class: "tag {color}", "{label}" }
+                                        }
+                                    }
+                                    for message in row.messages {
+                                        p { class: "help", "{message}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,30 +1,41 @@
+mod bulk_actions;
+mod click_export;
+mod link_detail;
 mod link_display;
 mod link_edit;
+mod link_import;
 mod new_link_button;
+mod qr_designer;
+mod qr_sheet;
+mod share_tokens;
 mod stats;
 
+use std::collections::BTreeSet;
+
 use dioxus::{
     logger::tracing::{info, trace},
     prelude::*,
 };
 use dioxus_i18n::t;
-use fast_qr::{
-    QRBuilder,
-    convert::{Builder as _, Shape, image::ImageBuilder, svg::SvgBuilder},
-};
 use indexmap::IndexMap;
 use pslink_shared::{
     apirequests::{
         general::{EditMode, Filter, Operation, Ordering},
-        links::{LinkDelta, LinkOverviewColumns, LinkRequestForm},
+        links::{ClickExportQuery, LinkDelta, LinkOverviewColumns, LinkRequestForm},
     },
     datatypes::FullLink,
+    qr::QrStyle,
 };
 use web_sys::wasm_bindgen::JsValue;
 
+use crate::links::bulk_actions::BulkActionBar;
+use crate::links::click_export::ClickExportBar;
+pub use crate::links::link_detail::LinkDetail;
 use crate::links::link_display::LinkDisplay;
 use crate::links::link_edit::LinkEdit;
+pub use crate::links::link_import::LinkImport;
 use crate::links::new_link_button::NewLinkButton;
+use crate::links::qr_sheet::QrSheetBar;
 use crate::{PslinkContext, navbar::Route};
 
 const LINKS_CSS: Asset = asset!("/assets/styling/lists.css");
@@ -60,6 +71,7 @@ struct EditDialog {
     link_delta: LinkDelta,
     qr: String,
     png_qr_url: Option<(String, String)>,
+    qr_style: QrStyle,
 }
 
 trait OptionEditDialog {
@@ -77,6 +89,7 @@ trait OptionEditDialog {
     fn update_title(&mut self, title: String);
     fn update_target(&mut self, target: String);
     fn set_edit_mode(&mut self, edit_mode: EditMode);
+    fn update_qr_style(&mut self, qr_style: QrStyle, host: &str);
 }
 
 impl OptionEditDialog for Signal<Option<EditDialog>> {
@@ -91,14 +104,10 @@ impl OptionEditDialog for Signal<Option<EditDialog>> {
         host: &str,
     ) {
         let url = generate_url_for_code(&code, host);
-        let mut filename = url
-            .replace("https://", "")
-            .replace("/", "_")
-            .replace(":", "_");
-        filename.push_str(".png");
+        let filename = format!("{}.png", qr_filename(&url));
         info!("the filename is: {}", filename);
-        let qr_string = generate_svg_qr_from_url(&url);
         if let Some(mut dialog) = self() {
+            let qr_string = generate_svg_qr_from_url(&url, &dialog.qr_style);
             dialog.link_delta = LinkDelta {
                 id,
                 author: None,
@@ -110,6 +119,8 @@ impl OptionEditDialog for Signal<Option<EditDialog>> {
             dialog.qr = qr_string;
             self.set(Some(dialog));
         } else {
+            let qr_style = QrStyle::default();
+            let qr_string = generate_svg_qr_from_url(&url, &qr_style);
             self.set(Some(EditDialog {
                 link_delta: LinkDelta {
                     id,
@@ -121,9 +132,10 @@ impl OptionEditDialog for Signal<Option<EditDialog>> {
                 },
                 qr: qr_string,
                 png_qr_url: Some((
-                    generate_blob_url_from_png(generate_png_qr_from_url(&url)),
+                    generate_blob_url_from_png(generate_png_qr_from_url(&url, &qr_style)),
                     filename,
                 )),
+                qr_style,
             }))
         }
     }
@@ -131,7 +143,7 @@ impl OptionEditDialog for Signal<Option<EditDialog>> {
         trace!("Updating code to: {}", code);
         if let Some(mut dialog) = self() {
             let url = generate_url_for_code(&code, host);
-            dialog.qr = generate_svg_qr_from_url(&url);
+            dialog.qr = generate_svg_qr_from_url(&url, &dialog.qr_style);
             dialog.link_delta.code = code.to_string();
             self.set(Some(dialog));
         }
@@ -159,6 +171,25 @@ impl OptionEditDialog for Signal<Option<EditDialog>> {
             self.set(Some(dialog));
         };
     }
+
+    fn update_qr_style(&mut self, qr_style: QrStyle, host: &str) {
+        trace!("Updating qr style to: {:?}", &qr_style);
+        // peek so loading the stored style does not subscribe to the dialog
+        let dialog = self.peek().clone();
+        if let Some(mut dialog) = dialog {
+            let url = generate_url_for_code(&dialog.link_delta.code, host);
+            dialog.qr = generate_svg_qr_from_url(&url, &qr_style);
+            if let Some((old_url, filename)) = dialog.png_qr_url.take() {
+                web_sys::Url::revoke_object_url(&old_url).ok();
+                dialog.png_qr_url = Some((
+                    generate_blob_url_from_png(generate_png_qr_from_url(&url, &qr_style)),
+                    filename,
+                ));
+            }
+            dialog.qr_style = qr_style;
+            self.set(Some(dialog));
+        };
+    }
 }
 
 #[component]
@@ -170,6 +201,8 @@ pub fn Links() -> Element {
     let mut username_filter = use_signal(|| "".to_string());
     let mut order_by = use_signal(|| Option::<Operation<LinkOverviewColumns, Ordering>>::None);
     let edit_link = use_signal(|| None);
+    let export_settings = use_signal(ClickExportQuery::default);
+    let mut selected = use_signal(BTreeSet::<i64>::new);
     let mut links: Signal<IndexMap<String, FullLink>> = use_signal(move || IndexMap::new());
     let link_codes = use_memo(move || links().keys().cloned().collect::<Vec<String>>());
     let mut link_stats = use_signal(|| IndexMap::new());
@@ -192,7 +225,7 @@ pub fn Links() -> Element {
         }
     });
 
-    let _update_filters = use_resource(move || async move {
+    let mut update_filters = use_resource(move || async move {
         let code_filter = code_filter();
         let description_filter = description_filter();
         let target_filter = target_filter();
@@ -222,9 +255,48 @@ pub fn Links() -> Element {
         LinkEdit { edit_link, links }
         if let Some(_user) = user.as_ref() {
             div {
+                ClickExportBar { export_settings }
+                QrSheetBar { selected }
+                BulkActionBar {
+                    selected,
+                    on_select_matching: move |_| {
+                        let form = link_request_form(
+                            code_filter(),
+                            description_filter(),
+                            target_filter(),
+                            username_filter(),
+                        );
+                        spawn(async move {
+                            if let Ok(ids) = backend::link_api::list_matching_link_ids(form).await {
+                                selected.set(ids.into_iter().collect());
+                            }
+                        });
+                    },
+                    on_change: move |_| update_filters.restart(),
+                }
+                div { class: "p-2",
+                    Link { class: "button is-small", to: Route::LinkImport {},
+                        {t!("links-button-import")} // Button to open the import of many links
+                    }
+                }
                 table { class: "table is-bordered is-striped is-hoverable is-fullwidth",
                     tbody {
                         tr {
+                            th {
+                                input {
+                                    r#type: "checkbox",
+                                    title: t!("links-tooltip-select-all"), // Tooltip of the checkbox that selects all loaded links
+                                    checked: !links().is_empty()
+                                        && links().values().all(|link| selected().contains(&link.link.id)),
+                                    onchange: move |e| {
+                                        if e.checked() {
+                                            selected.set(links().values().map(|link| link.link.id).collect());
+                                        } else {
+                                            selected.set(BTreeSet::new());
+                                        }
+                                    },
+                                }
+                            }
                             th {
                                 class: "headlines",
                                 onclick: move |_| {
@@ -263,8 +335,10 @@ pub fn Links() -> Element {
                             }
                             th {}
                             th {}
+                            th {}
                         }
                         tr {
+                            td {}
                             td {
                                 div { class: "control has-icons-left has-icons-right is-small",
                                     input {
@@ -332,6 +406,7 @@ pub fn Links() -> Element {
                             td {}
                             td {}
                             td {}
+                            td {}
                         }
                         if !links().is_empty() {
                             for code in link_codes() {
@@ -341,6 +416,8 @@ pub fn Links() -> Element {
                                     links,
                                     link_stats,
                                     link_signal: edit_link.clone(),
+                                    export_settings,
+                                    selected,
                                 }
                             }
                         }
@@ -396,6 +473,27 @@ pub fn Links() -> Element {
     }
 }
 
+/// A request for the links matching the filters of the columns.
+fn link_request_form(
+    code_filter: String,
+    description_filter: String,
+    target_filter: String,
+    username_filter: String,
+) -> LinkRequestForm {
+    let mut form = LinkRequestForm::default();
+    form.filter[LinkOverviewColumns::Code] = Filter { sieve: code_filter };
+    form.filter[LinkOverviewColumns::Description] = Filter {
+        sieve: description_filter,
+    };
+    form.filter[LinkOverviewColumns::Target] = Filter {
+        sieve: target_filter,
+    };
+    form.filter[LinkOverviewColumns::Author] = Filter {
+        sieve: username_filter,
+    };
+    form
+}
+
 async fn load_links(
     offsett: usize,
     amount: usize,
@@ -406,17 +504,12 @@ async fn load_links(
     order: Option<Operation<LinkOverviewColumns, Ordering>>,
 ) -> IndexMap<String, FullLink> {
     {
-        let mut form = LinkRequestForm::default();
-        form.filter[LinkOverviewColumns::Code] = Filter { sieve: code_filter };
-        form.filter[LinkOverviewColumns::Description] = Filter {
-            sieve: description_filter,
-        };
-        form.filter[LinkOverviewColumns::Target] = Filter {
-            sieve: target_filter,
-        };
-        form.filter[LinkOverviewColumns::Author] = Filter {
-            sieve: username_filter,
-        };
+        let mut form = link_request_form(
+            code_filter,
+            description_filter,
+            target_filter,
+            username_filter,
+        );
         form.order = order;
         form.offset = offsett;
         form.amount = amount;
@@ -437,45 +530,58 @@ pub fn generate_url_for_code(code: &str, host: &str) -> String {
 }
 
 /// generate a svg qr-code for a url
-fn generate_svg_qr_from_url(url: &str) -> String {
-    let qrcode = QRBuilder::new(url).ecl(fast_qr::ECL::L).build();
-    if let Ok(qrcode) = qrcode {
-        let svg = SvgBuilder::default().shape(Shape::Square).to_str(&qrcode);
-        svg
-    } else {
+fn generate_svg_qr_from_url(url: &str, style: &QrStyle) -> String {
+    pslink_shared::qr::svg_from_url(url, style, None).unwrap_or_else(|| {
         info!("Failed to create QR code");
-        "".to_string()
-    }
+        String::new()
+    })
 }
 
 // generate a png qr-code for a url
-fn generate_png_qr_from_url(url: &str) -> Vec<u8> {
-    let qrcode = QRBuilder::new(url).ecl(fast_qr::ECL::L).build();
-
-    if let Ok(qrcode) = qrcode {
-        let png = ImageBuilder::default()
-            .shape(Shape::Square)
-            .fit_height((qrcode.size * 8).try_into().unwrap())
-            .fit_width((qrcode.size * 8).try_into().unwrap())
-            .to_bytes(&qrcode)
-            .expect("Failed to create png");
-        png
-    } else {
+fn generate_png_qr_from_url(url: &str, style: &QrStyle) -> Vec<u8> {
+    pslink_shared::qr::png_from_url(url, style, None).unwrap_or_else(|| {
         info!("Failed to create QR code");
         vec![]
-    }
+    })
 }
 
 fn generate_blob_url_from_png(png: Vec<u8>) -> String {
+    generate_blob_url(&png, "image/png")
+}
+
+fn generate_blob_url(data: &[u8], content_type: &str) -> String {
     let properties = web_sys::BlobPropertyBag::new();
-    properties.set_type("image/png");
+    properties.set_type(content_type);
 
-    let png_jsarray: JsValue = web_sys::js_sys::Uint8Array::from(&png[..]).into();
+    let jsarray: JsValue = web_sys::js_sys::Uint8Array::from(data).into();
     // the buffer has to be an array of arrays
-    let png_buffer: web_sys::js_sys::Array = IntoIterator::into_iter([png_jsarray]).collect();
-    let png_blob =
-        web_sys::Blob::new_with_buffer_source_sequence_and_options(&png_buffer, &properties)
-            .unwrap();
-    let url = web_sys::Url::create_object_url_with_blob(&png_blob).unwrap();
+    let buffer: web_sys::js_sys::Array = IntoIterator::into_iter([jsarray]).collect();
+    let blob =
+        web_sys::Blob::new_with_buffer_source_sequence_and_options(&buffer, &properties).unwrap();
+    let url = web_sys::Url::create_object_url_with_blob(&blob).unwrap();
     url
 }
+
+/// the filename of a downloaded qr-code without the extension
+fn qr_filename(url: &str) -> String {
+    url.replace("https://", "")
+        .replace("/", "_")
+        .replace(":", "_")
+}
+
+/// let the browser save generated data as a file
+fn download_file(data: &[u8], content_type: &str, filename: &str) {
+    use web_sys::wasm_bindgen::JsCast as _;
+
+    let url = generate_blob_url(data, content_type);
+    let anchor = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("a").ok())
+        .and_then(|element| element.dyn_into::<web_sys::HtmlAnchorElement>().ok());
+    if let Some(anchor) = anchor {
+        anchor.set_href(&url);
+        anchor.set_download(filename);
+        anchor.click();
+    }
+    // the url is not revoked as the browser might still be reading it
+}
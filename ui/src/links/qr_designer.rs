@@ -0,0 +1,284 @@
+//! Change the appearance of the qr-code of a link with a live preview.
+use std::str::FromStr as _;
+
+use dioxus::{logger::tracing::info, prelude::*};
+use dioxus_i18n::t;
+use pslink_shared::{
+    apirequests::links::QrCodeFormat,
+    qr::{
+        MAX_MARGIN, MAX_PIXELS_PER_MODULE, QrErrorCorrection, QrRenderOptions, QrShape, QrStyle,
+        print::{MAX_DPI, MIN_DPI},
+    },
+};
+
+use crate::PslinkContext;
+use crate::links::{
+    EditDialog, OptionEditDialog as _, download_file, generate_url_for_code, qr_filename,
+};
+
+const FORMATS: [QrCodeFormat; 4] = [
+    QrCodeFormat::Png,
+    QrCodeFormat::Svg,
+    QrCodeFormat::Pdf,
+    QrCodeFormat::Eps,
+];
+
+const ERROR_CORRECTIONS: [QrErrorCorrection; 4] = [
+    QrErrorCorrection::L,
+    QrErrorCorrection::M,
+    QrErrorCorrection::Q,
+    QrErrorCorrection::H,
+];
+const SHAPES: [QrShape; 6] = [
+    QrShape::Square,
+    QrShape::RoundedSquare,
+    QrShape::Circle,
+    QrShape::Diamond,
+    QrShape::Vertical,
+    QrShape::Horizontal,
+];
+
+fn error_correction_label(ecl: QrErrorCorrection) -> String {
+    match ecl {
+        QrErrorCorrection::L => t!("qr-designer-ecl-low"),
+        QrErrorCorrection::M => t!("qr-designer-ecl-medium"),
+        QrErrorCorrection::Q => t!("qr-designer-ecl-quartile"),
+        QrErrorCorrection::H => t!("qr-designer-ecl-high"),
+    }
+}
+
+fn shape_label(shape: QrShape) -> String {
+    match shape {
+        QrShape::Square => t!("qr-designer-shape-square"),
+        QrShape::RoundedSquare => t!("qr-designer-shape-rounded"),
+        QrShape::Circle => t!("qr-designer-shape-circle"),
+        QrShape::Diamond => t!("qr-designer-shape-diamond"),
+        QrShape::Vertical => t!("qr-designer-shape-vertical"),
+        QrShape::Horizontal => t!("qr-designer-shape-horizontal"),
+    }
+}
+
+/// The settings of the qr-code in the edit dialog, every change updates the preview of the dialog.
+#[component]
+pub fn QrDesigner(edit_link: Signal<Option<EditDialog>>) -> Element {
+    let PslinkContext { hostname, .. } = use_context::<PslinkContext>();
+    let Some(dialog) = edit_link() else {
+        return rsx! {};
+    };
+    let style = dialog.qr_style;
+    let mut change = move |update: &dyn Fn(&mut QrStyle)| {
+        if let Some(dialog) = edit_link() {
+            let mut style = dialog.qr_style;
+            update(&mut style);
+            edit_link.update_qr_style(style.sanitized(), &hostname());
+        }
+    };
+
+    rsx! {
+        div { class: "field is-grouped is-grouped-multiline",
+            div { class: "control",
+                label { class: "label is-small", {t!("qr-designer-label-error-correction")} } // Label of the error correction of the qr-code
+                div { class: "select is-small",
+                    select {
+                        disabled: style.logo,
+                        onchange: move |e| {
+                            if let Ok(ecl) = QrErrorCorrection::from_str(&e.value()) {
+                                change(&|style| style.error_correction = ecl);
+                            }
+                        },
+                        for ecl in ERROR_CORRECTIONS {
+                            option {
+                                value: ecl.as_ref(),
+                                selected: ecl == style.effective_error_correction(),
+                                {error_correction_label(ecl)}
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("qr-designer-label-shape")} } // Label of the shape of the modules of the qr-code
+                div { class: "select is-small",
+                    select {
+                        onchange: move |e| {
+                            if let Ok(shape) = QrShape::from_str(&e.value()) {
+                                change(&|style| style.shape = shape);
+                            }
+                        },
+                        for shape in SHAPES {
+                            option {
+                                value: shape.as_ref(),
+                                selected: shape == style.shape,
+                                {shape_label(shape)}
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("qr-designer-label-foreground")} } // Label of the color of the qr-code
+                input {
+                    class: "input is-small",
+                    r#type: "color",
+                    value: "{style.foreground}",
+                    onchange: move |e| {
+                        let color = e.value();
+                        change(&|style| style.foreground.clone_from(&color));
+                    },
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("qr-designer-label-background")} } // Label of the background color of the qr-code
+                input {
+                    class: "input is-small",
+                    r#type: "color",
+                    value: "{style.background}",
+                    onchange: move |e| {
+                        let color = e.value();
+                        change(&|style| style.background.clone_from(&color));
+                    },
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("qr-designer-label-margin")} } // Label of the quiet zone around the qr-code
+                input {
+                    class: "input is-small",
+                    r#type: "number",
+                    min: "0",
+                    max: "{MAX_MARGIN}",
+                    value: "{style.margin}",
+                    onchange: move |e| {
+                        if let Ok(margin) = e.value().parse::<usize>() {
+                            change(&|style| style.margin = margin);
+                        }
+                    },
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("qr-designer-label-pixels")} } // Label of the size of a module of the png image
+                input {
+                    class: "input is-small",
+                    r#type: "number",
+                    min: "1",
+                    max: "{MAX_PIXELS_PER_MODULE}",
+                    value: "{style.pixels_per_module}",
+                    onchange: move |e| {
+                        if let Ok(pixels) = e.value().parse::<u32>() {
+                            change(&|style| style.pixels_per_module = pixels);
+                        }
+                    },
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", "\u{a0}" }
+                label { class: "checkbox",
+                    input {
+                        r#type: "checkbox",
+                        checked: style.logo,
+                        onchange: move |e| {
+                            let logo = e.checked();
+                            change(&|style| style.logo = logo);
+                        },
+                    }
+                    " "
+                    {t!("qr-designer-label-logo")} // Checkbox to place the logo in the center of the qr-code
+                }
+            }
+        }
+        if style.logo {
+            p { class: "help", {t!("qr-designer-logo-hint")} } // Explanation that the logo forces the highest error correction
+        }
+    }
+}
+
+/// Download the qr-code of the edit dialog in any format, the files are generated in the browser.
+#[component]
+pub fn QrDownload(edit_link: Signal<Option<EditDialog>>) -> Element {
+    let PslinkContext { hostname, .. } = use_context::<PslinkContext>();
+    let mut format = use_signal(|| QrCodeFormat::Png);
+    let mut dpi = use_signal(|| None::<u32>);
+    let mut label = use_signal(|| false);
+    let download = move |_: Event<MouseData>| {
+        let Some(dialog) = edit_link() else {
+            return;
+        };
+        let url = generate_url_for_code(&dialog.link_delta.code, &hostname());
+        let options = QrRenderOptions {
+            size: None,
+            dpi: dpi(),
+            label: label(),
+        };
+        match pslink_shared::qr::render(
+            &url,
+            &dialog.link_delta.title,
+            &dialog.qr_style,
+            format(),
+            &options,
+        ) {
+            Some(data) => download_file(
+                &data,
+                format().content_type(),
+                &format!("{}.{}", qr_filename(&url), format().extension()),
+            ),
+            None => info!("Failed to create QR code"),
+        }
+    };
+
+    rsx! {
+        div { class: "field is-grouped is-grouped-multiline",
+            div { class: "control",
+                label { class: "label is-small", {t!("qr-download-label-format")} } // Label of the file format of the qr-code download
+                div { class: "select is-small",
+                    select {
+                        onchange: move |e| {
+                            if let Some(new_format) = QrCodeFormat::from_extension(&e.value()) {
+                                format.set(new_format);
+                            }
+                        },
+                        for option_format in FORMATS {
+                            option {
+                                value: option_format.extension(),
+                                selected: option_format == format(),
+                                {option_format.extension().to_uppercase()}
+                            }
+                        }
+                    }
+                }
+            }
+            if format() == QrCodeFormat::Png {
+                div { class: "control",
+                    label { class: "label is-small", {t!("qr-download-label-dpi")} } // Label of the resolution of the png download
+                    input {
+                        class: "input is-small",
+                        r#type: "number",
+                        min: "{MIN_DPI}",
+                        max: "{MAX_DPI}",
+                        placeholder: "300",
+                        value: dpi().map(|d| d.to_string()).unwrap_or_default(),
+                        onchange: move |e| dpi.set(e.value().parse().ok()),
+                    }
+                }
+            }
+            if matches!(format(), QrCodeFormat::Pdf | QrCodeFormat::Eps) {
+                div { class: "control",
+                    label { class: "label is-small", "\u{a0}" }
+                    label { class: "checkbox",
+                        input {
+                            r#type: "checkbox",
+                            checked: label(),
+                            onchange: move |e| label.set(e.checked()),
+                        }
+                        " "
+                        {t!("qr-download-label-label")} // Checkbox to print the title and short url below the qr-code
+                    }
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", "\u{a0}" }
+                button { class: "button is-small is-link", onclick: download,
+                    {t!("qr-download-button")} // Button to download the qr-code
+                }
+            }
+        }
+    }
+}
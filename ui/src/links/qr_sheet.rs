@@ -0,0 +1,107 @@
+//! Print the qr-codes of the selected links on a pdf sheet.
+use std::collections::BTreeSet;
+use std::str::FromStr as _;
+
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+use pslink_shared::apirequests::links::{PaperSize, QrSheetQuery};
+
+const PAPER_SIZES: [PaperSize; 2] = [PaperSize::A4, PaperSize::Letter];
+
+/// The layout of the sheet and a button to download it, the sheet is generated on the server.
+#[component]
+pub fn QrSheetBar(selected: Signal<BTreeSet<i64>>) -> Element {
+    let mut sheet = use_signal(QrSheetQuery::default);
+    let sheet_url = use_memo(move || {
+        let ids: Vec<String> = selected().iter().map(ToString::to_string).collect();
+        QrSheetQuery {
+            links: ids.join(","),
+            ..sheet()
+        }
+        .url()
+    });
+
+    rsx! {
+        div { class: "field is-grouped is-grouped-multiline p-2",
+            div { class: "control",
+                label { class: "label is-small", {t!("qr-sheet-label-paper")} } // Label of the paper size of the qr-code sheet
+                div { class: "select is-small",
+                    select {
+                        onchange: move |e| {
+                            if let Ok(paper) = PaperSize::from_str(&e.value()) {
+                                sheet.write().paper = paper;
+                            }
+                        },
+                        for paper in PAPER_SIZES {
+                            option {
+                                value: paper.as_ref(),
+                                selected: paper == sheet().paper,
+                                {paper.as_ref().to_uppercase()}
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("qr-sheet-label-rows")} } // Label of the number of rows of the qr-code sheet
+                input {
+                    class: "input is-small",
+                    r#type: "number",
+                    min: "1",
+                    max: QrSheetQuery::MAX_CELLS.to_string(),
+                    value: "{sheet().rows}",
+                    onchange: move |e| {
+                        if let Ok(value) = e.value().parse::<u32>() {
+                            sheet.write().rows = value.clamp(1, QrSheetQuery::MAX_CELLS);
+                        }
+                    },
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("qr-sheet-label-columns")} } // Label of the number of columns of the qr-code sheet
+                input {
+                    class: "input is-small",
+                    r#type: "number",
+                    min: "1",
+                    max: QrSheetQuery::MAX_CELLS.to_string(),
+                    value: "{sheet().columns}",
+                    onchange: move |e| {
+                        if let Ok(value) = e.value().parse::<u32>() {
+                            sheet.write().columns = value.clamp(1, QrSheetQuery::MAX_CELLS);
+                        }
+                    },
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", {t!("qr-sheet-label-margin")} } // Label of the page margin of the qr-code sheet in millimeters
+                input {
+                    class: "input is-small",
+                    r#type: "number",
+                    min: "0",
+                    max: QrSheetQuery::MAX_MARGIN.to_string(),
+                    value: "{sheet().margin}",
+                    onchange: move |e| {
+                        if let Ok(value) = e.value().parse::<u32>() {
+                            sheet.write().margin = value.min(QrSheetQuery::MAX_MARGIN);
+                        }
+                    },
+                }
+            }
+            div { class: "control",
+                label { class: "label is-small", "\u{a0}" }
+                if selected().is_empty() {
+                    button { class: "button is-small is-link", disabled: true,
+                        {t!("qr-sheet-button", count : 0)} // Button to download the qr-code sheet of the selected links
+                    }
+                } else {
+                    a {
+                        class: "button is-small is-link",
+                        href: sheet_url(),
+                        download: "",
+                        {t!("qr-sheet-button", count : selected().len())}
+                    }
+                }
+            }
+        }
+    }
+}
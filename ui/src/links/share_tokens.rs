@@ -0,0 +1,97 @@
+//! Share the statistics of a link with people without an account.
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+
+use crate::PslinkContext;
+use crate::links::generate_url_for_code;
+
+#[component]
+pub fn ShareTokens(link_id: i64) -> Element {
+    let PslinkContext { hostname, .. } = use_context::<PslinkContext>();
+    let mut valid_days = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+    let mut tokens =
+        use_resource(move || async move { backend::share_api::list_share_tokens(link_id).await });
+
+    rsx! {
+        div { class: "box",
+            h2 { class: "title is-5", {t!("share-title")} } // Headline of the public statistics pages of a link
+            p { class: "mb-3", {t!("share-description")} } // Explanation of the public statistics pages
+            if let Some(msg) = error() {
+                div { class: "notification is-danger", "{msg}" }
+            }
+            match tokens() {
+                Some(Ok(list)) => rsx! {
+                    table { class: "table is-fullwidth is-narrow",
+                        tbody {
+                            for token in list {
+                                tr { key: "{token.id}",
+                                    td { style: "word-wrap:anywhere;",
+                                        a {
+                                            href: token.path(),
+                                            target: "_blank",
+                                            {generate_url_for_code(token.path().trim_start_matches('/'), &hostname())}
+                                        }
+                                    }
+                                    td {
+                                        match token.expires_at {
+                                            Some(expires_at) => t!("share-expires", date : expires_at.date().to_string()),
+                                            None => t!("share-never-expires"),
+                                        }
+                                    }
+                                    td {
+                                        button {
+                                            class: "button is-small is-danger is-outlined",
+                                            onclick: move |_| async move {
+                                                match backend::share_api::revoke_share_token(token.id).await {
+                                                    Ok(()) => tokens.restart(),
+                                                    Err(e) => error.set(Some(e.to_string())),
+                                                }
+                                            },
+                                            {t!("share-button-revoke")} // Button to revoke a public statistics page
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! {
+                    div { class: "notification is-danger", "{e}" }
+                },
+                None => rsx! {
+                    progress { class: "progress is-small is-primary" }
+                },
+            }
+            div { class: "field is-grouped",
+                div { class: "control",
+                    input {
+                        class: "input is-small",
+                        r#type: "number",
+                        min: "1",
+                        placeholder: t!("share-placeholder-days"), // Placeholder of the number of days a token is valid
+                        value: "{valid_days}",
+                        oninput: move |e| valid_days.set(e.value()),
+                    }
+                }
+                div { class: "control",
+                    button {
+                        class: "button is-small is-primary",
+                        onclick: move |_| async move {
+                            let days = valid_days().trim().parse::<u32>().ok();
+                            match backend::share_api::create_share_token(link_id, days).await {
+                                Ok(_) => {
+                                    error.set(None);
+                                    valid_days.set(String::new());
+                                    tokens.restart();
+                                }
+                                Err(e) => error.set(Some(e.to_string())),
+                            }
+                        },
+                        {t!("share-button-create")} // Button to create a new public statistics page
+                    }
+                }
+            }
+        }
+    }
+}
@@ -3,12 +3,23 @@ use dioxus::{
     prelude::*,
 };
 use dioxus_i18n::t;
+use pslink_shared::apirequests::users::LoginResult;
 use std::rc::Rc;
 
-use crate::{PslinkContext, navbar::Route};
+use crate::{PslinkContext, navbar::Route, users::TotpSetup};
 
 const LOGIN_CSS: Asset = asset!("/assets/styling/login.css");
 
+/// The login asks for the password first and then for the second factor if the user has one.
+#[derive(Clone, Copy, PartialEq)]
+enum LoginStep {
+    Password,
+    Code,
+    Setup,
+    /// Ask for a link to reset a forgotten password.
+    Forgot,
+}
+
 #[component]
 pub fn LoginScreen() -> Element {
     let mut username = use_signal(|| "".to_string());
@@ -18,6 +29,22 @@ pub fn LoginScreen() -> Element {
     let mut username_field: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
     let mut password_field: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
     let mut nachricht: Signal<Option<String>> = use_signal(|| None);
+    let mut step = use_signal(|| LoginStep::Password);
+    let mut code = use_signal(|| "".to_string());
+    let mut reset_sent = use_signal(|| false);
+    let single_sign_on =
+        use_resource(|| async { backend::auth_api::single_sign_on_enabled().await });
+    let password_reset =
+        use_resource(|| async { backend::auth_api::password_reset_enabled().await });
+
+    // a single sign-on redirects here if the login waits for the second factor
+    let _pending = use_resource(move || async move {
+        match backend::auth_api::pending_second_factor().await {
+            Ok(Some(LoginResult::TotpRequired)) => step.set(LoginStep::Code),
+            Ok(Some(LoginResult::TotpEnrollmentRequired)) => step.set(LoginStep::Setup),
+            _ => {}
+        }
+    });
 
     let _focus_grabber = use_resource(move || async move {
         if let Some(field) = username_field().as_ref() {
@@ -40,48 +67,110 @@ pub fn LoginScreen() -> Element {
                     p { class: "modal-card-title", {t!("headline-login")} }
                 }
                 div { class: "modal-card-body",
-                    div { class: "field is-horizontal is-wider",
-                        div { class: "field-label is-normal",
-                            label { class: "label", {t!("username")} }
+                    if step() == LoginStep::Setup {
+                        p { class: "mb-3", {t!("totp-setup-required")} } // Text when an administrator has to set up two-factor authentication before the login
+                        TotpSetup {
+                            on_finished: move |_| async move {
+                                if let Ok(info) = backend::auth_api::get_session_info().await {
+                                    user.set(info.user);
+                                }
+                                nav.push(Route::Links {});
+                            },
                         }
-                        div { class: "field-body",
-                            div { class: "field",
-                                p { class: "control",
-                                    input {
-                                        autofocus: true,
-                                        onmounted: move |e| {
-                                            username_field.set(Some(e.data()));
-                                        },
-                                        r#type: "text",
-                                        class: "input",
-                                        placeholder: t!("username"),
-                                        value: "{username}",
-                                        oninput: move |e| {
-                                            username.set(e.value());
-                                        },
+                    } else if step() == LoginStep::Forgot {
+                        p { class: "mb-3", {t!("password-reset-description")} } // Explanation that a reset link is sent to the email address of the account
+                        div { class: "field is-horizontal is-wider",
+                            div { class: "field-label is-normal",
+                                label { class: "label", {t!("password-reset-account")} } // Label of the field for the username or email address
+                            }
+                            div { class: "field-body",
+                                div { class: "field",
+                                    p { class: "control",
+                                        input {
+                                            autofocus: true,
+                                            r#type: "text",
+                                            class: "input",
+                                            autocomplete: "username",
+                                            value: "{username}",
+                                            oninput: move |e| {
+                                                username.set(e.value());
+                                            },
+                                        }
                                     }
                                 }
                             }
                         }
-                    }
-                    div { class: "field is-horizontal is-wider",
-                        div { class: "field-label is-normal",
-                            label { class: "label", {t!("password")} }
+                        if reset_sent() {
+                            div { class: "notification is-success", {t!("password-reset-sent")} } // Message after a reset link was requested, it does not tell whether the account exists
+                        }
+                    } else if step() == LoginStep::Code {
+                        div { class: "field is-horizontal is-wider",
+                            div { class: "field-label is-normal",
+                                label { class: "label", {t!("totp-code")} } // Label of the one-time code field of the login
+                            }
+                            div { class: "field-body",
+                                div { class: "field",
+                                    p { class: "control",
+                                        input {
+                                            autofocus: true,
+                                            r#type: "text",
+                                            class: "input",
+                                            autocomplete: "one-time-code",
+                                            placeholder: t!("totp-placeholder-code"),
+                                            value: "{code}",
+                                            oninput: move |e| {
+                                                code.set(e.value());
+                                            },
+                                        }
+                                    }
+                                    p { class: "help", {t!("totp-code-help")} } // Help that a recovery code can be entered instead of the one-time code
+                                }
+                            }
+                        }
+                    } else {
+                        div { class: "field is-horizontal is-wider",
+                            div { class: "field-label is-normal",
+                                label { class: "label", {t!("username")} }
+                            }
+                            div { class: "field-body",
+                                div { class: "field",
+                                    p { class: "control",
+                                        input {
+                                            autofocus: true,
+                                            onmounted: move |e| {
+                                                username_field.set(Some(e.data()));
+                                            },
+                                            r#type: "text",
+                                            class: "input",
+                                            placeholder: t!("username"),
+                                            value: "{username}",
+                                            oninput: move |e| {
+                                                username.set(e.value());
+                                            },
+                                        }
+                                    }
+                                }
+                            }
                         }
-                        div { class: "field-body",
-                            div { class: "field",
-                                p { class: "control",
-                                    input {
-                                        r#type: "password",
-                                        class: "input",
-                                        placeholder: t!("password"),
-                                        value: "{password}",
-                                        oninput: move |e| {
-                                            password.set(e.value());
-                                        },
-                                        onmounted: move |e| {
-                                            password_field.set(Some(e.data()));
-                                        },
+                        div { class: "field is-horizontal is-wider",
+                            div { class: "field-label is-normal",
+                                label { class: "label", {t!("password")} }
+                            }
+                            div { class: "field-body",
+                                div { class: "field",
+                                    p { class: "control",
+                                        input {
+                                            r#type: "password",
+                                            class: "input",
+                                            placeholder: t!("password"),
+                                            value: "{password}",
+                                            oninput: move |e| {
+                                                password.set(e.value());
+                                            },
+                                            onmounted: move |e| {
+                                                password_field.set(Some(e.data()));
+                                            },
+                                        }
                                     }
                                 }
                             }
@@ -98,12 +187,11 @@ pub fn LoginScreen() -> Element {
                 }
                 footer { class: "modal-card-foot is-justify-content-flex-end",
                     div { class: "buttons",
-                        button {
-                            class: "button is-primary",
-                            onclick: move |_| {
-                                info!("Login button clicked with username: {}", username());
-                                async move {
-                                    match backend::auth_api::login(username(), password()).await {
+                        if step() == LoginStep::Code {
+                            button {
+                                class: "button is-primary",
+                                onclick: move |_| async move {
+                                    match backend::auth_api::login_totp(code()).await {
                                         Ok(u) => {
                                             user.set(Some(u));
                                             nav.push(Route::Links {});
@@ -111,12 +199,85 @@ pub fn LoginScreen() -> Element {
                                         Err(e) => {
                                             let fehlernachricht = t!("failed-login", error : e.to_string());
                                             nachricht.set(Some(fehlernachricht));
-                                            info!("Failed to login: {:?}", e);
+                                            info!("Failed to verify the code: {:?}", e);
+                                        }
+                                    }
+                                },
+                                {t!("totp-button-verify")} // Button to finish the login with the one-time code
+                            }
+                        }
+                        if step() == LoginStep::Forgot {
+                            button {
+                                class: "button",
+                                onclick: move |_| {
+                                    nachricht.set(None);
+                                    step.set(LoginStep::Password);
+                                },
+                                {t!("password-reset-button-back")} // Button to return from the password reset to the login
+                            }
+                            button {
+                                class: "button is-primary",
+                                disabled: username().trim().is_empty() || reset_sent(),
+                                onclick: move |_| async move {
+                                    match backend::auth_api::request_password_reset(username()).await {
+                                        Ok(()) => {
+                                            nachricht.set(None);
+                                            reset_sent.set(true);
+                                        }
+                                        Err(e) => {
+                                            info!("Failed to request a password reset: {:?}", e);
+                                            nachricht.set(Some(e.to_string()));
                                         }
                                     }
+                                },
+                                {t!("password-reset-button-send")} // Button to send the link that resets the password
+                            }
+                        }
+                        if step() == LoginStep::Password {
+                            if let Some(Ok(true)) = password_reset() {
+                                button {
+                                    class: "button is-text",
+                                    onclick: move |_| {
+                                        nachricht.set(None);
+                                        reset_sent.set(false);
+                                        step.set(LoginStep::Forgot);
+                                    },
+                                    {t!("password-reset-forgot")} // Button on the login to reset a forgotten password
                                 }
-                            },
-                            {t!("login")}
+                            }
+                            if let Some(Ok(true)) = single_sign_on() {
+                                a { class: "button is-link is-outlined", href: "/auth/oidc/login",
+                                    {t!("login-single-sign-on")} // Button to log in with the identity provider of the organisation
+                                }
+                            }
+                            button {
+                                class: "button is-primary",
+                                onclick: move |_| {
+                                    info!("Login button clicked with username: {}", username());
+                                    async move {
+                                        match backend::auth_api::login(username(), password()).await {
+                                            Ok(LoginResult::LoggedIn(u)) => {
+                                                user.set(Some(u));
+                                                nav.push(Route::Links {});
+                                            }
+                                            Ok(LoginResult::TotpRequired) => {
+                                                nachricht.set(None);
+                                                step.set(LoginStep::Code);
+                                            }
+                                            Ok(LoginResult::TotpEnrollmentRequired) => {
+                                                nachricht.set(None);
+                                                step.set(LoginStep::Setup);
+                                            }
+                                            Err(e) => {
+                                                let fehlernachricht = t!("failed-login", error : e.to_string());
+                                                nachricht.set(Some(fehlernachricht));
+                                                info!("Failed to login: {:?}", e);
+                                            }
+                                        }
+                                    }
+                                },
+                                {t!("login")}
+                            }
                         }
                     }
                 }
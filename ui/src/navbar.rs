@@ -1,4 +1,12 @@
-use crate::{PslinkContext, home::Home, links::Links, login::LoginScreen, users::Users};
+use crate::{
+    PslinkContext,
+    home::Home,
+    invitation::InvitationScreen,
+    links::{LinkDetail, LinkImport, Links},
+    login::LoginScreen,
+    password_reset::PasswordResetScreen,
+    users::Users,
+};
 use backend::{auth_api::get_session_info, user_api::set_user_language};
 use dioxus::{logger::tracing::info, prelude::*};
 use dioxus_i18n::{prelude::i18n, t, unic_langid::langid};
@@ -9,11 +17,20 @@ const NAVBAR_CSS: Asset = asset!("/assets/styling/navbar.css");
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
 pub enum Route {
+    // the invitation and password reset pages are public, they are shown without the navbar that redirects to the login
+    #[route("/invite/:token")]
+    InvitationScreen { token: String },
+    #[route("/reset-password/:token")]
+    PasswordResetScreen { token: String },
     #[layout(WebNavbar)]
     #[route("/login")]
     LoginScreen {},
     #[route("/links")]
     Links {},
+    #[route("/links/import")]
+    LinkImport {},
+    #[route("/link/:id")]
+    LinkDetail { id: i64 },
     #[route("/users")]
     Users {},
     #[route("/")]
@@ -0,0 +1,100 @@
+//! The public page of the emailed link that sets a new password.
+use dioxus::{logger::tracing::info, prelude::*};
+use dioxus_i18n::t;
+use pslink_shared::BULMA_CSS;
+
+use crate::navbar::Route;
+
+const LOGIN_CSS: Asset = asset!("/assets/styling/login.css");
+
+#[component]
+pub fn PasswordResetScreen(token: String) -> Element {
+    let mut password = use_signal(String::new);
+    let mut password_repeat = use_signal(String::new);
+    let mut nachricht: Signal<Option<String>> = use_signal(|| None);
+    let mut done = use_signal(|| false);
+    let nav = navigator();
+
+    rsx! {
+        document::Stylesheet { href: BULMA_CSS }
+        document::Stylesheet { href: LOGIN_CSS }
+        div { class: "modal is-active",
+            div { class: "modal-background" }
+            div { class: "modal-card",
+                header { class: "modal-card-head",
+                    p { class: "modal-card-title", {t!("password-reset-headline")} } // Headline of the page that sets a new password
+                }
+                div { class: "modal-card-body",
+                    if done() {
+                        div { class: "notification is-success", {t!("password-reset-done")} } // Message after the new password was saved
+                    } else {
+                        div { class: "field",
+                            label { class: "label", {t!("password-reset-label-password")} } // Label of the new password
+                            div { class: "control",
+                                input {
+                                    autofocus: true,
+                                    r#type: "password",
+                                    class: "input",
+                                    autocomplete: "new-password",
+                                    value: "{password}",
+                                    oninput: move |e| password.set(e.value()),
+                                }
+                            }
+                        }
+                        div { class: "field",
+                            label { class: "label", {t!("invitation-label-password-repeat")} }
+                            div { class: "control",
+                                input {
+                                    r#type: "password",
+                                    class: "input",
+                                    autocomplete: "new-password",
+                                    value: "{password_repeat}",
+                                    oninput: move |e| password_repeat.set(e.value()),
+                                }
+                            }
+                        }
+                    }
+                    if let Some(nachricht) = nachricht() {
+                        div { class: "notification is-danger", {nachricht} }
+                    }
+                }
+                footer { class: "modal-card-foot is-justify-content-flex-end",
+                    if done() {
+                        button {
+                            class: "button is-primary",
+                            onclick: move |_| {
+                                nav.push(Route::LoginScreen {});
+                            },
+                            {t!("login")}
+                        }
+                    } else {
+                        button {
+                            class: "button is-primary",
+                            disabled: password().is_empty(),
+                            onclick: move |_| {
+                                let token = token.clone();
+                                async move {
+                                    if password() != password_repeat() {
+                                        nachricht.set(Some(t!("invitation-password-mismatch")));
+                                        return;
+                                    }
+                                    match backend::auth_api::reset_password(token, password()).await {
+                                        Ok(()) => {
+                                            nachricht.set(None);
+                                            done.set(true);
+                                        }
+                                        Err(e) => {
+                                            info!("Failed to reset the password: {:?}", e);
+                                            nachricht.set(Some(e.to_string()));
+                                        }
+                                    }
+                                }
+                            },
+                            {t!("password-reset-button-save")} // Button to save the new password
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
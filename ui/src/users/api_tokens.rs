@@ -0,0 +1,163 @@
+//! Personal API tokens for scripts and CI jobs.
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+use pslink_shared::{
+    apirequests::users::Role,
+    datatypes::{ApiTokenScope, NewApiToken},
+};
+
+use crate::PslinkContext;
+
+#[component]
+pub fn ApiTokens() -> Element {
+    let PslinkContext { user, .. } = use_context::<PslinkContext>();
+    let is_admin = user().is_some_and(|user| user.role == Role::Admin);
+    let mut name = use_signal(String::new);
+    let mut scope = use_signal(|| ApiTokenScope::Read);
+    let mut valid_days = use_signal(String::new);
+    let mut created = use_signal(|| None::<NewApiToken>);
+    let mut error = use_signal(|| None::<String>);
+    let mut tokens =
+        use_resource(move || async move { backend::token_api::list_api_tokens().await });
+
+    rsx! {
+        div { class: "box m-3",
+            h2 { class: "title is-5", {t!("api-tokens-title")} } // Headline of the personal API tokens
+            p { class: "mb-3", {t!("api-tokens-description")} } // Explanation how the API tokens are used
+            if let Some(msg) = error() {
+                div { class: "notification is-danger", "{msg}" }
+            }
+            if let Some(new_token) = created() {
+                div { class: "notification is-success",
+                    p { {t!("api-tokens-created", name : new_token.token.name)} } // Message after a token was created, it is only shown once
+                    pre { "{new_token.secret}" }
+                }
+            }
+            match tokens() {
+                Some(Ok(list)) => rsx! {
+                    table { class: "table is-fullwidth is-narrow",
+                        thead {
+                            tr {
+                                if is_admin {
+                                    th { {t!("api-tokens-owner")} } // Column of the user a token belongs to
+                                }
+                                th { {t!("api-tokens-name")} } // Column of the name of a token
+                                th { {t!("api-tokens-scope")} } // Column of what a token may do
+                                th { {t!("api-tokens-expires")} } // Column of the date a token expires
+                                th { {t!("api-tokens-last-used")} } // Column of the last time a token was used
+                                th {}
+                            }
+                        }
+                        tbody {
+                            for token in list {
+                                tr { key: "{token.id}",
+                                    if is_admin {
+                                        td { "{token.owner_name}" }
+                                    }
+                                    td {
+                                        "{token.name} "
+                                        code { "{token.hint}…" }
+                                    }
+                                    td {
+                                        match token.scope {
+                                            ApiTokenScope::Read => t!("api-tokens-scope-read"),
+                                            ApiTokenScope::Write => t!("api-tokens-scope-write"),
+                                        }
+                                    }
+                                    td {
+                                        match token.expires_at {
+                                            Some(expires_at) => expires_at.date().to_string(),
+                                            None => t!("api-tokens-never"),
+                                        }
+                                    }
+                                    td {
+                                        match token.last_used_at {
+                                            Some(last_used_at) => last_used_at.format("%Y-%m-%d %H:%M").to_string(),
+                                            None => t!("api-tokens-never"),
+                                        }
+                                    }
+                                    td {
+                                        button {
+                                            class: "button is-small is-danger is-outlined",
+                                            onclick: move |_| async move {
+                                                match backend::token_api::revoke_api_token(token.id).await {
+                                                    Ok(()) => tokens.restart(),
+                                                    Err(e) => error.set(Some(e.to_string())),
+                                                }
+                                            },
+                                            {t!("api-tokens-button-revoke")} // Button to revoke an API token
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! {
+                    div { class: "notification is-danger", "{e}" }
+                },
+                None => rsx! {
+                    progress { class: "progress is-small is-primary" }
+                },
+            }
+            div { class: "field is-grouped",
+                div { class: "control",
+                    input {
+                        class: "input is-small",
+                        r#type: "text",
+                        placeholder: t!("api-tokens-placeholder-name"), // Placeholder of the name of a new token
+                        value: "{name}",
+                        oninput: move |e| name.set(e.value()),
+                    }
+                }
+                div { class: "control",
+                    div { class: "select is-small",
+                        select {
+                            onchange: move |e| scope.set(ApiTokenScope::parse(&e.value())),
+                            option {
+                                value: ApiTokenScope::Read.as_str(),
+                                selected: scope() == ApiTokenScope::Read,
+                                {t!("api-tokens-scope-read")} // Scope of a token that may only read
+                            }
+                            option {
+                                value: ApiTokenScope::Write.as_str(),
+                                selected: scope() == ApiTokenScope::Write,
+                                {t!("api-tokens-scope-write")} // Scope of a token that may change links and users
+                            }
+                        }
+                    }
+                }
+                div { class: "control",
+                    input {
+                        class: "input is-small",
+                        r#type: "number",
+                        min: "1",
+                        placeholder: t!("api-tokens-placeholder-days"), // Placeholder of the number of days a token is valid
+                        value: "{valid_days}",
+                        oninput: move |e| valid_days.set(e.value()),
+                    }
+                }
+                div { class: "control",
+                    button {
+                        class: "button is-small is-primary",
+                        disabled: name().trim().is_empty(),
+                        onclick: move |_| async move {
+                            let days = valid_days().trim().parse::<u32>().ok();
+                            match backend::token_api::create_api_token(name(), scope(), days).await {
+                                Ok(new_token) => {
+                                    error.set(None);
+                                    created.set(Some(new_token));
+                                    name.set(String::new());
+                                    valid_days.set(String::new());
+                                    tokens.restart();
+                                }
+                                Err(e) => error.set(Some(e.to_string())),
+                            }
+                        },
+                        {t!("api-tokens-button-create")} // Button to create a new API token
+                    }
+                }
+            }
+        }
+    }
+}
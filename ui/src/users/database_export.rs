@@ -2,6 +2,15 @@
 
 use dioxus::prelude::*;
 use dioxus_i18n::t;
+use pslink_shared::apirequests::data_export::DataExportRequest;
+
+fn create_json_url(data: &str) -> String {
+    use base64::prelude::*;
+    format!(
+        "data:application/json;base64,{}",
+        BASE64_STANDARD.encode(data.as_bytes())
+    )
+}
 
 #[component]
 pub fn DatabaseExport() -> Element {
@@ -10,6 +19,8 @@ pub fn DatabaseExport() -> Element {
     let mut error_message = use_signal(|| Option::<String>::None);
     let mut success_message = use_signal(|| Option::<String>::None);
     let mut download_data = use_signal(|| Option::<Vec<u8>>::None);
+    let mut include_passwords = use_signal(|| false);
+    let mut json_data = use_signal(|| Option::<String>::None);
 
     let create_download_url = move |data: &[u8]| -> String {
         use base64::prelude::*;
@@ -48,6 +59,34 @@ pub fn DatabaseExport() -> Element {
         is_loading.set(false);
     };
 
+    let export_json = move |_| async move {
+        is_loading.set(true);
+        error_message.set(None);
+        success_message.set(None);
+        json_data.set(None);
+
+        let request = DataExportRequest {
+            secret: secret_input(),
+            include_passwords: include_passwords(),
+        };
+        match backend::export_api::export_data(request).await {
+            Ok(data) => {
+                json_data.set(Some(data));
+                success_message.set(Some(t!("database-export-success-message")));
+                secret_input.set(String::new());
+            }
+            Err(e) => {
+                error_message.set(Some(format!(
+                    "{}: {}",
+                    t!("database-export-error-failed"),
+                    e
+                )));
+            }
+        }
+
+        is_loading.set(false);
+    };
+
     rsx! {
         div { class: "database-export-section container p-4",
             h3 { class: "title is-5", {t!("database-export-title")} }
@@ -84,6 +123,29 @@ pub fn DatabaseExport() -> Element {
                 }
             }
 
+            if let Some(data) = json_data() {
+                div { class: "notification is-success",
+                    p { {t!("database-export-json-ready")} } // Message that the JSON export can be downloaded
+                    p { class: "is-size-7", {t!("database-export-size", size : data.len())} }
+                    div { class: "buttons",
+                        a {
+                            class: "button is-primary",
+                            href: create_json_url(&data),
+                            download: "pslink_export.json",
+                            {t!("database-export-button-download-json")} // Button to save the JSON export
+                        }
+                        button {
+                            class: "button is-light is-small",
+                            onclick: move |_| {
+                                json_data.set(None);
+                                success_message.set(None);
+                            },
+                            {t!("database-export-button-clear")}
+                        }
+                    }
+                }
+            }
+
             div { class: "field",
                 label { class: "label", {t!("database-export-label-secret")} }
                 div { class: "control",
@@ -114,8 +176,24 @@ pub fn DatabaseExport() -> Element {
                             disabled: secret_input().trim().is_empty(),
                             {t!("database-export-button-export")}
                         }
+                        button {
+                            class: "button is-info ml-2",
+                            onclick: export_json,
+                            disabled: secret_input().trim().is_empty(),
+                            {t!("database-export-button-export-json")} // Button to export all data as versioned JSON
+                        }
+                    }
+                }
+                label { class: "checkbox mt-2",
+                    input {
+                        r#type: "checkbox",
+                        checked: include_passwords(),
+                        onchange: move |e| include_passwords.set(e.checked()),
                     }
+                    " "
+                    {t!("database-export-include-passwords")} // Checkbox to include the password hashes in the JSON export
                 }
+                p { class: "help", {t!("database-export-help-json")} } // Explanation of the JSON export and pslink restore
             }
         }
     }
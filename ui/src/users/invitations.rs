@@ -0,0 +1,154 @@
+//! Invitation links for new users, only administrators see them.
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+use pslink_shared::{apirequests::users::Role, datatypes::NewInvitation};
+
+#[component]
+pub fn Invitations() -> Element {
+    let mut email = use_signal(String::new);
+    let mut role = use_signal(|| Role::Regular);
+    let mut valid_days = use_signal(|| "7".to_string());
+    let mut created = use_signal(|| None::<NewInvitation>);
+    let mut error = use_signal(|| None::<String>);
+    let mut invitations =
+        use_resource(move || async move { backend::invitation_api::list_invitations().await });
+    let now = chrono::Utc::now().naive_utc();
+
+    rsx! {
+        div { class: "box m-3",
+            h2 { class: "title is-5", {t!("invitations-title")} } // Headline of the invitation links for new users
+            p { class: "mb-3", {t!("invitations-description")} } // Explanation how invitation links are used
+            if let Some(msg) = error() {
+                div { class: "notification is-danger", "{msg}" }
+            }
+            if let Some(new_invitation) = created() {
+                div { class: "notification is-success",
+                    p { {t!("invitations-created")} } // Message after an invitation was created, the link is only shown once
+                    pre { "{new_invitation.url}" }
+                }
+            }
+            match invitations() {
+                Some(Ok(list)) => rsx! {
+                    table { class: "table is-fullwidth is-narrow",
+                        thead {
+                            tr {
+                                th { {t!("invitations-link")} } // Column of the start of the invitation link
+                                th { {t!("invitations-email")} } // Column of the pre-filled email address
+                                th { {t!("invitations-role")} } // Column of the role of the new account
+                                th { {t!("invitations-created-by")} } // Column of the administrator that created the invitation
+                                th { {t!("invitations-expires")} } // Column of the time the invitation expires
+                                th {}
+                            }
+                        }
+                        tbody {
+                            for invitation in list {
+                                tr { key: "{invitation.id}",
+                                    td {
+                                        code { "{invitation.hint}…" }
+                                    }
+                                    td { {invitation.email.clone().unwrap_or_default()} }
+                                    td {
+                                        if invitation.role == Role::Admin {
+                                            {t!("user-edit-role-admin")}
+                                        } else {
+                                            {t!("user-edit-role-regular")}
+                                        }
+                                    }
+                                    td { "{invitation.created_by}" }
+                                    td {
+                                        {invitation.expires_at.format("%Y-%m-%d %H:%M").to_string()}
+                                        if invitation.is_expired(now) {
+                                            " "
+                                            span { class: "tag is-warning", {t!("invitations-expired")} } // Tag of an invitation that can no longer be used
+                                        }
+                                    }
+                                    td {
+                                        button {
+                                            class: "button is-small is-danger is-outlined",
+                                            onclick: move |_| async move {
+                                                match backend::invitation_api::revoke_invitation(invitation.id).await {
+                                                    Ok(()) => invitations.restart(),
+                                                    Err(e) => error.set(Some(e.to_string())),
+                                                }
+                                            },
+                                            if invitation.is_expired(now) {
+                                                {t!("invitations-button-delete")} // Button to remove an expired invitation
+                                            } else {
+                                                {t!("invitations-button-revoke")} // Button to revoke a pending invitation
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! {
+                    div { class: "notification is-danger", "{e}" }
+                },
+                None => rsx! {
+                    progress { class: "progress is-small is-primary" }
+                },
+            }
+            div { class: "field is-grouped",
+                div { class: "control",
+                    input {
+                        class: "input is-small",
+                        r#type: "email",
+                        placeholder: t!("invitations-placeholder-email"), // Placeholder of the optional email address of an invitation
+                        value: "{email}",
+                        oninput: move |e| email.set(e.value()),
+                    }
+                }
+                div { class: "control",
+                    div { class: "select is-small",
+                        select {
+                            onchange: move |e| {
+                                role.set(Role::convert(e.value().parse::<i64>().unwrap_or(1)));
+                            },
+                            option {
+                                value: Role::Regular.to_i64(),
+                                selected: role() == Role::Regular,
+                                {t!("user-edit-role-regular")}
+                            }
+                            option {
+                                value: Role::Admin.to_i64(),
+                                selected: role() == Role::Admin,
+                                {t!("user-edit-role-admin")}
+                            }
+                        }
+                    }
+                }
+                div { class: "control",
+                    input {
+                        class: "input is-small",
+                        r#type: "number",
+                        min: "1",
+                        placeholder: t!("invitations-placeholder-days"), // Placeholder of the number of days an invitation is valid
+                        value: "{valid_days}",
+                        oninput: move |e| valid_days.set(e.value()),
+                    }
+                }
+                div { class: "control",
+                    button {
+                        class: "button is-small is-primary",
+                        onclick: move |_| async move {
+                            let days = valid_days().trim().parse::<u32>().unwrap_or(7);
+                            let address = Some(email()).filter(|address| !address.trim().is_empty());
+                            match backend::invitation_api::create_invitation(address, role(), days).await {
+                                Ok(new_invitation) => {
+                                    error.set(None);
+                                    created.set(Some(new_invitation));
+                                    email.set(String::new());
+                                    invitations.restart();
+                                }
+                                Err(e) => error.set(Some(e.to_string())),
+                            }
+                        },
+                        {t!("invitations-button-create")} // Button to create a new invitation link
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Lockouts after too many failed logins and the login audit trail for administrators.
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+use pslink_shared::datatypes::LoginEventKind;
+
+#[component]
+pub fn LoginLockouts() -> Element {
+    let mut error = use_signal(|| None::<String>);
+    let mut lockouts =
+        use_resource(move || async move { backend::auth_api::list_login_lockouts().await });
+    let mut events =
+        use_resource(move || async move { backend::auth_api::list_login_events().await });
+
+    rsx! {
+        div { class: "box m-3",
+            h2 { class: "title is-5", {t!("login-lockouts-title")} } // Headline of the usernames and addresses locked after failed logins
+            if let Some(msg) = error() {
+                div { class: "notification is-danger", "{msg}" }
+            }
+            match lockouts() {
+                Some(Ok(list)) if list.is_empty() => rsx! {
+                    p { class: "mb-3", {t!("login-lockouts-none")} } // Text when nothing is locked
+                },
+                Some(Ok(list)) => rsx! {
+                    table { class: "table is-fullwidth is-narrow",
+                        thead {
+                            tr {
+                                th { {t!("login-lockouts-subject")} } // Column of the locked username or address
+                                th { {t!("login-lockouts-failures")} } // Column of the number of failed logins
+                                th { {t!("login-lockouts-until")} } // Column of the time the lockout ends
+                                th {}
+                            }
+                        }
+                        tbody {
+                            for lockout in list {
+                                tr { key: "{lockout.by_ip}-{lockout.subject}",
+                                    td {
+                                        if lockout.by_ip {
+                                            code { "{lockout.subject}" }
+                                        } else {
+                                            "{lockout.subject}"
+                                        }
+                                    }
+                                    td { "{lockout.failures}" }
+                                    td { {lockout.locked_until.format("%Y-%m-%d %H:%M").to_string()} }
+                                    td {
+                                        button {
+                                            class: "button is-small is-warning is-outlined",
+                                            onclick: move |_| {
+                                                let subject = lockout.subject.clone();
+                                                async move {
+                                                    match backend::auth_api::reset_login_lockout(lockout.by_ip, subject)
+                                                        .await
+                                                    {
+                                                        Ok(()) => {
+                                                            error.set(None);
+                                                            lockouts.restart();
+                                                            events.restart();
+                                                        }
+                                                        Err(e) => error.set(Some(e.to_string())),
+                                                    }
+                                                }
+                                            },
+                                            {t!("login-lockouts-button-reset")} // Button to lift a lockout
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! {
+                    div { class: "notification is-danger", "{e}" }
+                },
+                None => rsx! {
+                    progress { class: "progress is-small is-primary" }
+                },
+            }
+            h3 { class: "title is-6", {t!("login-events-title")} } // Headline of the recent logins and refused logins
+            match events() {
+                Some(Ok(list)) => rsx! {
+                    table { class: "table is-fullwidth is-narrow is-striped",
+                        thead {
+                            tr {
+                                th { {t!("login-events-time")} } // Column of the time of a login event
+                                th { {t!("login-events-kind")} } // Column of what happened
+                                th { {t!("login-events-username")} } // Column of the entered username
+                                th { {t!("login-events-ip")} } // Column of the client address
+                                th { {t!("login-events-detail")} } // Column of further details
+                            }
+                        }
+                        tbody {
+                            for event in list {
+                                tr { key: "{event.id}",
+                                    td { {event.created_at.format("%Y-%m-%d %H:%M:%S").to_string()} }
+                                    td {
+                                        match event.kind {
+                                            LoginEventKind::Success => t!("login-events-success"),
+                                            LoginEventKind::Failure => t!("login-events-failure"),
+                                            LoginEventKind::Blocked => t!("login-events-blocked"),
+                                            LoginEventKind::Lockout => t!("login-events-lockout"),
+                                            LoginEventKind::Reset => t!("login-events-reset"),
+                                        }
+                                    }
+                                    td { "{event.username}" }
+                                    td { {event.ip.unwrap_or_default()} }
+                                    td { {event.detail.unwrap_or_default()} }
+                                }
+                            }
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! {
+                    div { class: "notification is-danger", "{e}" }
+                },
+                None => rsx! {
+                    progress { class: "progress is-small is-primary" }
+                },
+            }
+        }
+    }
+}
@@ -1,13 +1,20 @@
+mod api_tokens;
 mod database_export;
+mod invitations;
+mod login_lockouts;
 mod new_user_button;
+mod two_factor;
 mod user_display;
 mod user_edit;
 
 use dioxus::{logger::tracing::info, prelude::*};
 use dioxus_i18n::t;
 
+use api_tokens::ApiTokens;
 use database_export::DatabaseExport;
 use indexmap::IndexMap;
+use invitations::Invitations;
+use login_lockouts::LoginLockouts;
 use new_user_button::NewUserButton;
 use pslink_shared::{
     apirequests::{
@@ -16,6 +23,8 @@ use pslink_shared::{
     },
     datatypes::User,
 };
+pub(crate) use two_factor::TotpSetup;
+use two_factor::TwoFactor;
 use user_display::UserDisplay;
 use user_edit::UserEdit;
 
@@ -306,12 +315,16 @@ pub fn Users() -> Element {
                     }
                 }
                 if let Some(current_user) = user.as_ref() {
+                    TwoFactor {}
+                    ApiTokens {}
                     if current_user.role == Role::Admin {
                         NewUserButton { edit_dialog_signal }
                         a { class: "loadmore button",
                             img { src: RELOAD_SVG, class: "reloadicon" }
                             {t!("users-button-load-more")} // Button text to load more users
                         }
+                        Invitations {}
+                        LoginLockouts {}
                         hr {}
                         DatabaseExport {}
                     }
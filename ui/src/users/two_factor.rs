@@ -0,0 +1,212 @@
+//! Two-factor authentication with an authenticator app.
+use dioxus::prelude::*;
+use dioxus_i18n::t;
+use pslink_shared::{apirequests::users::Role, qr::QrStyle};
+
+use crate::PslinkContext;
+
+/// Show a new secret as qr-code and enable it once a code is confirmed.
+///
+/// Afterwards the recovery codes are shown until `on_finished` is called by the user.
+#[component]
+pub fn TotpSetup(on_finished: EventHandler<()>) -> Element {
+    let enrollment = use_resource(|| async { backend::totp_api::start_totp_enrollment().await });
+    let mut code = use_signal(String::new);
+    let mut recovery_codes = use_signal(|| None::<Vec<String>>);
+    let mut error = use_signal(|| None::<String>);
+
+    if let Some(codes) = recovery_codes() {
+        return rsx! {
+            RecoveryCodes { codes }
+            button { class: "button is-primary", onclick: move |_| on_finished.call(()),
+                {t!("totp-button-done")} // Button after the recovery codes were saved
+            }
+        };
+    }
+    rsx! {
+        match enrollment() {
+            Some(Ok(enrollment)) => rsx! {
+                p { class: "mb-3", {t!("totp-setup-description")} } // How to scan the qr-code with an authenticator app
+                div {
+                    class: "mb-3",
+                    dangerous_inner_html: pslink_shared::qr::svg_from_url(&enrollment.uri, &QrStyle::default(), Some(200))
+                        .unwrap_or_default(),
+                }
+                p { class: "mb-3",
+                    {t!("totp-setup-secret")} // Text before the secret for apps that cannot scan the qr-code
+                    " "
+                    code { "{enrollment.secret}" }
+                }
+                if let Some(msg) = error() {
+                    div { class: "notification is-danger", "{msg}" }
+                }
+                div { class: "field is-grouped",
+                    div { class: "control",
+                        input {
+                            class: "input",
+                            r#type: "text",
+                            inputmode: "numeric",
+                            autocomplete: "one-time-code",
+                            placeholder: t!("totp-placeholder-code"), // Placeholder of the one-time code
+                            value: "{code}",
+                            oninput: move |e| code.set(e.value()),
+                        }
+                    }
+                    div { class: "control",
+                        button {
+                            class: "button is-primary",
+                            disabled: code().trim().is_empty(),
+                            onclick: move |_| async move {
+                                match backend::totp_api::confirm_totp_enrollment(code()).await {
+                                    Ok(codes) => {
+                                        error.set(None);
+                                        recovery_codes.set(Some(codes));
+                                    }
+                                    Err(e) => error.set(Some(e.to_string())),
+                                }
+                            },
+                            {t!("totp-button-confirm")} // Button to enable two-factor authentication with the entered code
+                        }
+                    }
+                }
+            },
+            Some(Err(e)) => rsx! {
+                div { class: "notification is-danger", "{e}" }
+            },
+            None => rsx! {
+                progress { class: "progress is-small is-primary" }
+            },
+        }
+    }
+}
+
+/// The recovery codes, they are only shown once.
+#[component]
+fn RecoveryCodes(codes: Vec<String>) -> Element {
+    rsx! {
+        div { class: "notification is-warning",
+            p { {t!("totp-recovery-codes")} } // Explanation that every recovery code can be used once instead of a one-time code
+            pre {
+                for code in codes {
+                    "{code}\n"
+                }
+            }
+        }
+    }
+}
+
+/// The two-factor authentication settings of the logged in user.
+#[component]
+pub fn TwoFactor() -> Element {
+    let PslinkContext { user, .. } = use_context::<PslinkContext>();
+    let is_admin = user().is_some_and(|user| user.role == Role::Admin);
+    let mut status = use_resource(|| async { backend::totp_api::get_totp_status().await });
+    let mut setup = use_signal(|| false);
+    let mut code = use_signal(String::new);
+    let mut recovery_codes = use_signal(|| None::<Vec<String>>);
+    let mut error = use_signal(|| None::<String>);
+
+    rsx! {
+        div { class: "box m-3",
+            h2 { class: "title is-5", {t!("totp-title")} } // Headline of the two-factor authentication settings
+            if let Some(msg) = error() {
+                div { class: "notification is-danger", "{msg}" }
+            }
+            if let Some(codes) = recovery_codes() {
+                RecoveryCodes { codes }
+            }
+            match status() {
+                Some(Ok(current)) if setup() && !current.enabled => rsx! {
+                    TotpSetup {
+                        on_finished: move |_| {
+                            setup.set(false);
+                            status.restart();
+                        },
+                    }
+                },
+                Some(Ok(current)) if !current.enabled => rsx! {
+                    p { class: "mb-3", {t!("totp-disabled")} } // Text when two-factor authentication is not set up
+                    button { class: "button is-primary", onclick: move |_| setup.set(true),
+                        {t!("totp-button-setup")} // Button to set up two-factor authentication
+                    }
+                },
+                Some(Ok(current)) => rsx! {
+                    p { class: "mb-3",
+                        {t!("totp-enabled", left : current.recovery_codes_left)} // Text when two-factor authentication is enabled with the number of unused recovery codes
+                    }
+                    div { class: "field is-grouped",
+                        div { class: "control",
+                            input {
+                                class: "input is-small",
+                                r#type: "text",
+                                autocomplete: "one-time-code",
+                                placeholder: t!("totp-placeholder-code"),
+                                value: "{code}",
+                                oninput: move |e| code.set(e.value()),
+                            }
+                        }
+                        div { class: "control",
+                            button {
+                                class: "button is-small",
+                                disabled: code().trim().is_empty(),
+                                onclick: move |_| async move {
+                                    match backend::totp_api::regenerate_recovery_codes(code()).await {
+                                        Ok(codes) => {
+                                            error.set(None);
+                                            code.set(String::new());
+                                            recovery_codes.set(Some(codes));
+                                            status.restart();
+                                        }
+                                        Err(e) => error.set(Some(e.to_string())),
+                                    }
+                                },
+                                {t!("totp-button-recovery-codes")} // Button to replace the recovery codes
+                            }
+                        }
+                        div { class: "control",
+                            button {
+                                class: "button is-small is-danger is-outlined",
+                                disabled: code().trim().is_empty(),
+                                onclick: move |_| async move {
+                                    match backend::totp_api::disable_totp(code()).await {
+                                        Ok(()) => {
+                                            error.set(None);
+                                            code.set(String::new());
+                                            recovery_codes.set(None);
+                                            status.restart();
+                                        }
+                                        Err(e) => error.set(Some(e.to_string())),
+                                    }
+                                },
+                                {t!("totp-button-disable")} // Button to turn off two-factor authentication
+                            }
+                        }
+                    }
+                    if is_admin {
+                        label { class: "checkbox",
+                            input {
+                                r#type: "checkbox",
+                                checked: current.required_for_admins,
+                                onchange: move |e| async move {
+                                    match backend::totp_api::set_totp_required_for_admins(e.checked()).await {
+                                        Ok(()) => error.set(None),
+                                        Err(e) => error.set(Some(e.to_string())),
+                                    }
+                                    status.restart();
+                                },
+                            }
+                            " "
+                            {t!("totp-required-for-admins")} // Checkbox to require two-factor authentication for all administrators
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! {
+                    div { class: "notification is-danger", "{e}" }
+                },
+                None => rsx! {
+                    progress { class: "progress is-small is-primary" }
+                },
+            }
+        }
+    }
+}